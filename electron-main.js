@@ -9,12 +9,14 @@ const { autoUpdater } = require('electron-updater');
 const { registerDialogChannels } = require('./src/main/ipc/channels/dialogs');
 const { createPrefsStore } = require('./src/main/services/prefsStore');
 const { createLogger } = require('./src/main/services/logger');
+const { applyPortableMode } = require('./src/main/services/portableMode');
 const { createCliArgsHandler } = require('./src/main/services/cliArgsHandler');
 const { loadHashManager } = require('./src/main/services/hashManagerLoader');
 const { createDefaultResourcesService } = require('./src/main/services/defaultResources');
 const { createShutdownCleanup } = require('./src/main/services/shutdownCleanup');
 const { createAutoUpdaterService } = require('./src/main/services/autoUpdaterService');
 const { createModelInspectLaunchService } = require('./src/main/services/modelInspectLaunch');
+const { createFileOpenLaunchService } = require('./src/main/services/fileOpenLaunch');
 const { createMainWindowService } = require('./src/main/window/mainWindowService');
 const { registerAppLifecycleHandlers } = require('./src/main/services/appLifecycle');
 const { registerLocalFileProtocol, runStartupTasks } = require('./src/main/services/startup');
@@ -23,6 +25,7 @@ const { registerAppInfoChannels } = require('./src/main/ipc/channels/appInfo');
 const { registerWindowChannels } = require('./src/main/ipc/channels/window');
 const { registerMiscChannels } = require('./src/main/ipc/channels/misc');
 const { registerContextMenuChannels } = require('./src/main/ipc/channels/contextMenu');
+const { registerFileAssociationChannels } = require('./src/main/ipc/channels/fileAssociations');
 const { registerToolsChannels } = require('./src/main/ipc/channels/tools');
 const { registerInteropChannels } = require('./src/main/ipc/channels/interop');
 const { registerHashChannels } = require('./src/main/ipc/channels/hashes');
@@ -79,12 +82,20 @@ const setQuitState = ({ isQuitting: nextIsQuitting, isShuttingDown: nextIsShutti
   if (typeof nextIsShuttingDown === 'boolean') isShuttingDown = nextIsShuttingDown;
 };
 
+const portableMode = applyPortableMode({ app, fs, path, processRef: process });
+
 const { logToFile, initLogDirectory, logDir: LOG_DIR, logFile: LOG_FILE } = createLogger({ app, fs, path });
 initLogDirectory();
+if (portableMode.portable) {
+  logToFile(`Portable mode: data stored in ${portableMode.dataDir}`, 'INFO');
+}
 
 const {
   setupAutoUpdater,
   checkUpdatesViaGitHubAPI,
+  getUpdateSettings,
+  setUpdateChannel,
+  setBackgroundCheck,
   setUpdateWindow,
   getUpdateWindow,
   getCachedUpdateInfo,
@@ -95,6 +106,9 @@ const {
   processRef: process,
   https,
   logToFile,
+  // The prefs store is created further down; these only run after startup.
+  loadPrefs: () => loadPrefs(),
+  savePrefs: (prefs) => savePrefs(prefs),
 });
 
 const modelInspectLaunch = createModelInspectLaunchService({
@@ -104,6 +118,13 @@ const modelInspectLaunch = createModelInspectLaunchService({
 });
 modelInspectLaunch.initFromStartupArgs(process.argv);
 
+const fileOpenLaunch = createFileOpenLaunchService({
+  fs,
+  logToFile,
+  getMainWindow: () => getUpdateWindow(),
+});
+fileOpenLaunch.initFromStartupArgs(process.argv);
+
 let hashManager;
 
 const { handleCommandLineArgs } = createCliArgsHandler({
@@ -134,6 +155,7 @@ if (!gotTheLock) {
     }
 
     modelInspectLaunch.handleSecondInstanceArgs(commandLine);
+    fileOpenLaunch.handleSecondInstanceArgs(commandLine);
 
     try {
       updateCheckWindow?.webContents?.send('interop:check-now');
@@ -155,6 +177,7 @@ registerAppInfoChannels({
   LOG_DIR,
   LOG_FILE,
   logToFile,
+  portableMode,
 });
 
 logToFile('='.repeat(80), 'INFO');
@@ -172,10 +195,16 @@ const { ensureRitobinCli, ensureDefaultAssets, ensureDefaultCursors } = createDe
   logToFile,
 });
 
-const { clearTextureCacheOnQuit, cleanupMeiFolders } = createShutdownCleanup({
+const {
+  clearTextureCacheOnQuit,
+  cleanupMeiFolders,
+  cleanupOrphanTempFiles,
+  cleanupSessionTempFiles,
+} = createShutdownCleanup({
   fs,
   path,
   logToFile,
+  loadNativeAddon: tryLoadNativeWadIndexer,
 });
 
 logToFile(`Version: ${app.getVersion()}`, 'INFO');
@@ -256,6 +285,16 @@ const { refreshContextMenuIfStale } = registerContextMenuChannels({
   logToFile,
 });
 
+const { refreshFileAssociationsIfStale } = registerFileAssociationChannels({
+  ipcMain,
+  exec,
+  app,
+  path,
+  processRef: process,
+  isDev,
+  logToFile,
+});
+
 
 registerToolsChannels({
   ipcMain,
@@ -270,6 +309,7 @@ app.whenReady().then(() => {
   logToFile('APP: whenReady triggered - initializing application', 'INFO');
 
   registerLocalFileProtocol({ protocol, path, fs, logToFile });
+  cleanupOrphanTempFiles();
 
   runStartupTasks({
     app,
@@ -286,6 +326,8 @@ app.whenReady().then(() => {
   });
 
   modelInspectLaunch.flushPendingOnReady();
+  fileOpenLaunch.flushPendingOnReady();
+  refreshFileAssociationsIfStale();
 });
 
 registerAppLifecycleHandlers({
@@ -296,6 +338,7 @@ registerAppLifecycleHandlers({
   createWindow,
   clearTextureCacheOnQuit,
   cleanupMeiFolders,
+  cleanupSessionTempFiles,
   clearSavedBinPaths,
   getQuitState,
   setQuitState,
@@ -404,6 +447,9 @@ registerUpdateChannels({
   autoUpdater,
   logToFile,
   checkUpdatesViaGitHubAPI,
+  getUpdateSettings,
+  setUpdateChannel,
+  setBackgroundCheck,
   shell,
   getCachedUpdateInfo,
 });
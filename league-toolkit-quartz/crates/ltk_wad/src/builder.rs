@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{WriteBytesExt, LE};
@@ -78,13 +79,33 @@ impl WadBuilder {
             .sorted_by_key(|chunk| chunk.path)
             .collect::<Vec<_>>();
 
-        let mut final_chunks = Vec::new();
+        let mut final_chunks: Vec<WadChunk> = Vec::new();
+        // Chunks with identical data and compression share one data region, like the
+        // game's own WADs. Keyed by content hash, compression and size; the first
+        // chunk in path hash order owns the region, so output stays deterministic.
+        let mut written: HashMap<(u128, Option<u8>, usize), usize> = HashMap::new();
 
         for chunk in ordered_chunks {
             let mut cursor = Cursor::new(Vec::new());
             provide_chunk_data(chunk.path, &mut cursor)?;
 
             let chunk_data_size = cursor.get_ref().len();
+            let key = (
+                xxh3::xxh3_128(cursor.get_ref()),
+                chunk.force_compression.map(u8::from),
+                chunk_data_size,
+            );
+            if let Some(&owner) = written.get(&key) {
+                let shared = final_chunks[owner];
+                final_chunks.push(WadChunk {
+                    path_hash: chunk.path,
+                    is_duplicated: true,
+                    ..shared
+                });
+                continue;
+            }
+            written.insert(key, final_chunks.len());
+
             let (compressed_data, compression) =
                 Self::compress_chunk_data(cursor.get_ref(), chunk.force_compression)?;
             let compressed_data_size = compressed_data.len();
@@ -277,4 +298,50 @@ mod tests {
         assert_eq!(chunk.uncompressed_size, 100);
         assert_eq!(chunk.compression_type, WadChunkCompression::Zstd);
     }
+
+    fn build_shared_wad() -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        WadBuilder::default()
+            .with_chunk(WadChunkBuilder::default().with_path("shared1"))
+            .with_chunk(WadChunkBuilder::default().with_path("shared2"))
+            .with_chunk(
+                WadChunkBuilder::default()
+                    .with_path("uncompressed")
+                    .with_force_compression(WadChunkCompression::None),
+            )
+            .with_chunk(WadChunkBuilder::default().with_path("other"))
+            .build_to_writer(&mut cursor, |path, cursor| {
+                if path == xxh64::xxh64(b"other", 0) {
+                    cursor.write_all(&[0xBB; 100])?;
+                } else {
+                    cursor.write_all(&[0xAA; 100])?;
+                }
+                Ok(())
+            })
+            .expect("Failed to build WAD");
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_wad_builder_shares_identical_chunks() {
+        let data = build_shared_wad();
+        assert_eq!(data, build_shared_wad(), "output is not deterministic");
+
+        let mut wad = Wad::mount(Cursor::new(data)).expect("Failed to mount WAD");
+        let chunk = |wad: &Wad<_>, path: &[u8]| *wad.chunks().get(xxh64::xxh64(path, 0)).unwrap();
+        let shared1 = chunk(&wad, b"shared1");
+        let shared2 = chunk(&wad, b"shared2");
+        let uncompressed = chunk(&wad, b"uncompressed");
+        let other = chunk(&wad, b"other");
+
+        assert_eq!(shared1.data_offset, shared2.data_offset);
+        assert_ne!(uncompressed.data_offset, shared1.data_offset);
+        assert_eq!(uncompressed.compression_type, WadChunkCompression::None);
+        assert_ne!(other.data_offset, shared1.data_offset);
+
+        for (chunk, byte) in [(shared1, 0xAA), (shared2, 0xAA), (uncompressed, 0xAA), (other, 0xBB)] {
+            let data = wad.load_chunk_decompressed(&chunk).expect("Failed to decompress chunk");
+            assert_eq!(&data[..], &[byte; 100][..]);
+        }
+    }
 }
@@ -6,10 +6,10 @@ use indexmap::IndexMap;
 use ltk_meta::property::{values, BinProperty, PropertyValueEnum};
 use ltk_meta::{BinObject, PropertyValueEnum as PVE};
 
-use crate::utils::{fnv1a_32, read_bin, write_bin};
+use crate::utils::{fnv1a_lower, read_bin, write_bin};
 
 fn h(name: &str) -> u32 {
-    fnv1a_32(name)
+    fnv1a_lower(name)
 }
 
 fn make_prop(name_hash: u32, value: PropertyValueEnum) -> BinProperty {
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use quartz_core::checkpoints;
+
+fn mb(bytes: u64) -> f64 {
+    bytes as f64 / 1_048_576.0
+}
+
+pub fn create(project: &Path, label: Option<String>) -> Result<(), String> {
+    let created = checkpoints::create(project, label)?;
+    let c = &created.checkpoint;
+    eprintln!(
+        "OK: checkpoint {} ({} files, {:.1} MB, {} new objects)",
+        c.id,
+        c.file_count,
+        mb(c.total_size),
+        created.new_objects
+    );
+    // The id goes to stdout so scripts can capture it.
+    println!("{}", c.id);
+    Ok(())
+}
+
+pub fn list(project: &Path) -> Result<(), String> {
+    for c in checkpoints::list(project)? {
+        println!(
+            "{}\t{} files\t{:.1} MB\t{}",
+            c.id,
+            c.file_count,
+            mb(c.total_size),
+            c.label.as_deref().unwrap_or("")
+        );
+    }
+    Ok(())
+}
+
+pub fn restore(project: &Path, id: &str, link: bool) -> Result<(), String> {
+    let r = checkpoints::restore(project, id, link)?;
+    eprintln!(
+        "OK: restored checkpoint {} ({} files, {} linked, {} removed)",
+        id, r.restored_count, r.linked_count, r.removed_count
+    );
+    if let Some(reason) = &r.link_fallback {
+        eprintln!("NOTE: {}", reason);
+    }
+    Ok(())
+}
+
+pub fn verify(project: &Path, id: &str) -> Result<(), String> {
+    let damaged = checkpoints::verify(project, id)?;
+    if !damaged.is_empty() {
+        return Err(format!("{} damaged file(s) in checkpoint {}: {}", damaged.len(), id, damaged.join(", ")));
+    }
+    eprintln!("OK: checkpoint {} is intact", id);
+    Ok(())
+}
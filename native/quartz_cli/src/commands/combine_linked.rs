@@ -2,15 +2,10 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use xxhash_rust::xxh64::xxh64;
+use quartz_core::hash::xxhash_path;
 
 use crate::utils::{find_root_dir, read_bin, write_bin};
 
-/// Compute xxhash64 of a path (same as WADHasher.raw_to_hex).
-fn wad_hash(s: &str) -> u64 {
-    xxh64(s.as_bytes(), 0)
-}
-
 /// Extract champion name from path like "/characters/akali/skins/..."
 fn detect_champ_name(path: &Path) -> Option<String> {
     let posix = path.to_string_lossy().replace('\\', "/").to_lowercase();
@@ -97,7 +92,7 @@ pub fn run(bin_path: &Path) -> Result<(), String> {
 
         // Fallback: compute xxhash64
         if hashed_name.is_none() {
-            let computed = format!("{:016x}.bin", wad_hash(&normalized_link));
+            let computed = format!("{:016x}.bin", xxhash_path(&normalized_link));
             candidates.push((root_dir.join(&computed), "hash-computed"));
             hashed_name = Some(computed);
         }
@@ -4,7 +4,7 @@ use std::path::Path;
 use ltk_meta::property::{values, BinProperty, PropertyValueEnum};
 use ltk_meta::Bin;
 use rayon::prelude::*;
-use crate::utils::{fnv1a_32, read_bin, write_bin};
+use crate::utils::{fnv1a_lower, read_bin, write_bin};
 
 fn parse_skin_info(path: &Path) -> (String, u32) {
     let s = path.to_string_lossy().replace('\\', "/").to_lowercase();
@@ -51,9 +51,9 @@ pub fn run(source_bin_path: &Path) -> Result<(), String> {
         .len();
     let source_bin = read_bin(source_bin_path)?;
 
-    let scdp_type = fnv1a_32("SkinCharacterDataProperties");
-    let rr_type = fnv1a_32("ResourceResolver");
-    let mrr_field = fnv1a_32("mResourceResolver");
+    let scdp_type = fnv1a_lower("SkinCharacterDataProperties");
+    let rr_type = fnv1a_lower("ResourceResolver");
+    let mrr_field = fnv1a_lower("mResourceResolver");
 
     let base_scdp_hash = source_bin
         .objects
@@ -79,16 +79,12 @@ pub fn run(source_bin_path: &Path) -> Result<(), String> {
 
     let targets: Vec<u32> = (0u32..100u32)
         .filter(|&target_idx| target_idx != source_skin_idx)
-        .filter_map(|target_idx| {
+        .filter(|target_idx| {
             let out_path = out_dir.join(format!("skin{}.bin", target_idx));
             if out_path.exists() {
-                match out_path.metadata() {
-                    Ok(meta) if meta.len() != source_size => None,
-                    Ok(_) => Some(target_idx),
-                    Err(_) => None,
-                }
+                matches!(out_path.metadata(), Ok(meta) if meta.len() == source_size)
             } else {
-                Some(target_idx)
+                true
             }
         })
         .collect();
@@ -100,13 +96,13 @@ pub fn run(source_bin_path: &Path) -> Result<(), String> {
             let mut bin = source_bin.clone();
 
             let new_scdp_path = format!("characters/{}/skins/skin{}", champ, target_idx);
-            let new_scdp_hash = fnv1a_32(&new_scdp_path);
+            let new_scdp_hash = fnv1a_lower(&new_scdp_path);
             replace_object_key(&mut bin, base_scdp_hash, new_scdp_hash)?;
 
             let mut new_rr_hash = None;
             if let Some(rr_old_hash) = base_rr_hash {
                 let new_rr_link = format!("Characters/{}/Skins/Skin{}/Resources", champ, target_idx);
-                let rr_hash = fnv1a_32(&new_rr_link.to_lowercase());
+                let rr_hash = fnv1a_lower(&new_rr_link.to_lowercase());
                 replace_object_key(&mut bin, rr_old_hash, rr_hash)?;
                 new_rr_hash = Some(rr_hash);
             }
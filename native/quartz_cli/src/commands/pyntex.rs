@@ -10,10 +10,13 @@ use ltk_meta::{property::PropertyValueEnum, Bin};
 use ltk_ritobin::hashes::HashMapProvider;
 use ltk_ritobin::writer::write_with_hashes;
 
+use quartz_core::hash::{parse_hex_file_name, xxhash_path};
+use quartz_core::paths;
+
 use crate::hashes::{default_hash_dir, load_bin_hashes};
 
 fn normalize_rel_path(p: &str) -> String {
-    p.replace('\\', "/").trim_start_matches('/').to_ascii_lowercase()
+    paths::normalize_rel_path(p).to_ascii_lowercase()
 }
 
 fn is_probable_game_path(path: &str) -> bool {
@@ -21,23 +24,8 @@ fn is_probable_game_path(path: &str) -> bool {
     p.starts_with("assets/") || p.starts_with("data/")
 }
 
-fn xxhash_path(s: &str) -> u64 {
-    xxhash_rust::xxh64::xxh64(s.as_bytes(), 0)
-}
-
-fn parse_hex_stem(path: &str) -> Option<u64> {
-    if path.contains('/') {
-        return None;
-    }
-    let stem = path.split('.').next().unwrap_or(path);
-    if stem.len() != 16 || !stem.bytes().all(|b| b.is_ascii_hexdigit()) {
-        return None;
-    }
-    u64::from_str_radix(stem, 16).ok()
-}
-
 fn unified_hash(path: &str) -> u64 {
-    if let Some(h) = parse_hex_stem(path) {
+    if let Some(h) = parse_hex_file_name(path) {
         h
     } else {
         xxhash_path(path)
@@ -177,7 +165,7 @@ fn analyze_dir(dir: &Path) -> Result<(Vec<String>, Vec<String>), String> {
 
     let hashes = default_hash_dir()
         .map(|d| load_bin_hashes(&d))
-        .unwrap_or_else(HashMapProvider::new);
+        .unwrap_or_default();
 
     let mut full_files = Vec::new();
     walk_files(dir, &mut full_files)?;
@@ -0,0 +1,83 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_meta::Bin;
+use quartz_core::paths::{collect_files, rename_retrying, write_retrying};
+use quartz_core::repath::{replace_prefix, rewrite_bin};
+
+/// Move every file under `dir` whose relative path starts with `from` to the
+/// `to` prefix, and rewrite matching asset paths inside every .bin.
+pub fn run(dir: &Path, from: &str, to: &str) -> Result<(), String> {
+    let from = from.replace('\\', "/");
+    let to = to.replace('\\', "/");
+    if from.is_empty() {
+        return Err("Repath prefix must not be empty".to_string());
+    }
+    let map = |p: &str| replace_prefix(&from, &to, &p.replace('\\', "/"));
+
+    let files = collect_files(dir)?;
+    let mut bins_changed = 0usize;
+    let mut paths_rewritten = 0u32;
+    let mut failed = 0usize;
+    for (rel, path) in &files {
+        if !rel.to_ascii_lowercase().ends_with(".bin") {
+            continue;
+        }
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if !data.starts_with(b"PROP") && !data.starts_with(b"PTCH") {
+            continue;
+        }
+        let mut bin = match Bin::from_reader(&mut Cursor::new(&data)) {
+            Ok(b) => b,
+            Err(e) => {
+                failed += 1;
+                eprintln!("Error: {} ({})", path.display(), e);
+                continue;
+            }
+        };
+        let n = rewrite_bin(&mut bin, &map);
+        if n == 0 {
+            continue;
+        }
+        let mut out = Cursor::new(Vec::new());
+        bin.to_writer(&mut out)
+            .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+        write_retrying(path, out.get_ref())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        bins_changed += 1;
+        paths_rewritten += n;
+    }
+
+    let mut moved = 0usize;
+    for (rel, path) in &files {
+        let Some(new_rel) = map(rel) else { continue };
+        let dst = dir.join(&new_rel);
+        if dst.exists() {
+            eprintln!("Skip: {} already exists", dst.display());
+            continue;
+        }
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        rename_retrying(path, &dst)
+            .map_err(|e| format!("Failed to move {} to {}: {}", path.display(), dst.display(), e))?;
+        moved += 1;
+    }
+
+    eprintln!(
+        "DONE: repath {} -> {} in {} | bins changed={}, paths rewritten={}, files moved={}, failed={}",
+        from,
+        to,
+        dir.display(),
+        bins_changed,
+        paths_rewritten,
+        moved,
+        failed
+    );
+    if failed > 0 {
+        return Err(format!("{} bin(s) could not be parsed", failed));
+    }
+    Ok(())
+}
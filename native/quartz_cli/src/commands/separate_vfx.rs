@@ -4,7 +4,7 @@ use std::path::Path;
 
 use ltk_meta::BinObject;
 
-use crate::utils::{fnv1a_32, find_root_dir, read_bin, write_bin};
+use crate::utils::{fnv1a_lower, find_root_dir, read_bin, write_bin};
 
 fn detect_champ_name(path: &Path) -> Option<String> {
     let posix = path.to_string_lossy().replace('\\', "/").to_lowercase();
@@ -20,7 +20,7 @@ pub fn run(bin_path: &Path) -> Result<(), String> {
 
     eprintln!("--- CONTENT-BASED VFX SEPARATION ---");
 
-    let vfx_type_hash = fnv1a_32("VfxSystemDefinitionData");
+    let vfx_type_hash = fnv1a_lower("VfxSystemDefinitionData");
     eprintln!("  VFX Type Hash: {:08x} (int: {})", vfx_type_hash, vfx_type_hash);
 
     let mut all_vfx_entries: Vec<(u32, BinObject)> = Vec::new();
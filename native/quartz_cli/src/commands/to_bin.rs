@@ -1,28 +1,20 @@
-use std::fs::{self, File};
-use std::io::BufWriter;
+use std::fs;
 use std::path::Path;
 use std::time::Instant;
 
-use ltk_ritobin::parse;
+use quartz_core::ritobin::{text_to_bin, write_bin};
 
 pub fn run(py_path: &Path) -> Result<(), String> {
     let text = fs::read_to_string(py_path)
         .map_err(|e| format!("Failed to read {}: {}", py_path.display(), e))?;
 
     let start = Instant::now();
-    let file_ast = parse(&text)
-        .map_err(|e| format!("Failed to parse py: {}", e))?;
-    let tree = file_ast.to_bin_tree();
+    let tree = text_to_bin(&text)?;
     let parse_time = start.elapsed();
 
     let bin_path = py_path.with_extension("bin");
-    let out_file = File::create(&bin_path)
-        .map_err(|e| format!("Failed to create {}: {}", bin_path.display(), e))?;
-
     let start = Instant::now();
-    let mut writer = BufWriter::new(out_file);
-    tree.to_writer(&mut writer)
-        .map_err(|e| format!("Failed to write bin: {}", e))?;
+    write_bin(&bin_path, &tree)?;
     let write_time = start.elapsed();
 
     eprintln!(
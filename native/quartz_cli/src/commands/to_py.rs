@@ -1,21 +1,14 @@
-use std::fs::{self, File};
-use std::io::BufReader;
+use std::fs;
 use std::path::Path;
 use std::time::Instant;
 
-use ltk_meta::Bin;
-use ltk_ritobin::writer::write_with_hashes;
+use quartz_core::ritobin::{bin_to_text, read_bin};
 
 use crate::hashes::load_bin_hashes;
 
 pub fn run(bin_path: &Path, hash_dir: Option<&Path>) -> Result<(), String> {
-    let file = File::open(bin_path)
-        .map_err(|e| format!("Failed to open {}: {}", bin_path.display(), e))?;
-    let mut reader = BufReader::new(file);
-
     let start = Instant::now();
-    let tree = Bin::from_reader(&mut reader)
-        .map_err(|e| format!("Failed to parse bin: {}", e))?;
+    let tree = read_bin(bin_path)?;
     let parse_time = start.elapsed();
 
     let hashes = match hash_dir {
@@ -24,8 +17,7 @@ pub fn run(bin_path: &Path, hash_dir: Option<&Path>) -> Result<(), String> {
     };
 
     let start = Instant::now();
-    let output = write_with_hashes(&tree, &hashes)
-        .map_err(|e| format!("Failed to write text: {}", e))?;
+    let output = bin_to_text(&tree, &hashes)?;
     let write_time = start.elapsed();
 
     let py_path = bin_path.with_extension("py");
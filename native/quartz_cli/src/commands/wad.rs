@@ -3,161 +3,34 @@ use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
 
-use heed::types::{Bytes, Str};
-use heed::{Database, EnvOpenOptions};
 use ltk_file::LeagueFileKind;
 use ltk_wad::{Wad, WadBuilder, WadChunkBuilder};
-
-fn normalize_rel_path(v: &str) -> String {
-    v.replace('\\', "/").trim_start_matches('/').to_string()
-}
-
-fn is_safe_relative_path(path: &str) -> bool {
-    let p = Path::new(path);
-    if p.is_absolute() {
-        return false;
-    }
-    for comp in p.components() {
-        use std::path::Component;
-        match comp {
-            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return false,
-            _ => {}
-        }
-    }
-    true
-}
-
-fn parse_hash_text_file(path: &Path, hash_len: usize) -> HashMap<u64, String> {
-    let mut out = HashMap::new();
-    let Ok(content) = fs::read_to_string(path) else {
-        return out;
-    };
-    for line in content.lines() {
-        let l = line.trim();
-        if l.is_empty() || l.starts_with('#') || l.len() <= hash_len + 1 {
-            continue;
-        }
-        let h = &l[..hash_len];
-        let p = l[hash_len + 1..].trim();
-        if let Ok(v) = u64::from_str_radix(h, 16) {
-            out.entry(v).or_insert_with(|| p.to_string());
-        }
-    }
-    out
-}
+use quartz_core::hash::{parse_hash_text_file, parse_hex_file_name, xxhash_path};
+use quartz_core::hash_db;
+use quartz_core::paths::{is_safe_relative_path, normalize_rel_path};
+use quartz_core::resolver::{HashSource, LayeredResolver, LmdbResolver, CUSTOM_HASH_FILE};
+use quartz_core::scan::{scan_bin_game_hashes, scan_skn_bin_hashes};
 
 fn load_extracted_hashes(hash_dir: &Path) -> HashMap<u64, String> {
     parse_hash_text_file(&hash_dir.join("hashes.extracted.txt"), 16)
 }
 
-fn parse_hash_entries(path: &Path, hash_len: usize) -> Vec<([u8; 8], String)> {
-    let mut out = Vec::new();
-    let Ok(content) = fs::read_to_string(path) else {
-        return out;
-    };
-    for line in content.lines() {
-        let l = line.trim();
-        if l.is_empty() || l.starts_with('#') || l.len() <= hash_len + 1 {
-            continue;
-        }
-        let h = &l[..hash_len];
-        let p = l[hash_len + 1..].trim_end_matches('\r');
-        if let Ok(v) = u64::from_str_radix(h, 16) {
-            out.push((v.to_be_bytes(), p.to_string()));
-        }
-    }
-    out
-}
-
-fn lmdb_dir(hash_dir: &Path) -> PathBuf {
-    hash_dir.join("hashes.lmdb")
-}
-
-fn build_hash_db(hash_dir: &Path) -> Result<(), String> {
-    let db_dir = lmdb_dir(hash_dir);
-    let sources: &[(&str, usize)] = &[
-        ("hashes.game.txt", 16),
-        ("hashes.lcu.txt", 16),
-        ("hashes.extracted.txt", 16),
-    ];
-
-    let db_mtime: Option<SystemTime> = fs::metadata(db_dir.join("data.mdb"))
-        .and_then(|m| m.modified())
-        .ok();
-
-    let needs_rebuild = !db_dir.exists()
-        || sources.iter().any(|(name, _)| {
-            let source_mtime = fs::metadata(hash_dir.join(name))
-                .and_then(|m| m.modified())
-                .ok();
-            match (db_mtime, source_mtime) {
-                (Some(db), Some(src)) => src > db,
-                (None, Some(_)) => true,
-                _ => false,
-            }
-        });
-
-    if !needs_rebuild {
-        return Ok(());
+pub fn build_hash_db(hash_dir: &Path) -> Result<(), String> {
+    let rebuilt = hash_db::build(hash_dir)?;
+    if !rebuilt.is_empty() {
+        eprintln!("[WAD] LMDB rebuilt: {}", rebuilt.join(", "));
     }
-
-    if db_dir.exists() {
-        fs::remove_dir_all(&db_dir)
-            .map_err(|e| format!("Failed to remove {}: {}", db_dir.display(), e))?;
-    }
-    fs::create_dir_all(&db_dir)
-        .map_err(|e| format!("Failed to create {}: {}", db_dir.display(), e))?;
-
-    let env = unsafe {
-        EnvOpenOptions::new()
-            .map_size(512 * 1024 * 1024)
-            .max_dbs(1)
-            .open(&db_dir)
-    }
-    .map_err(|e| format!("Failed to open LMDB {}: {}", db_dir.display(), e))?;
-
-    let mut wtxn = env
-        .write_txn()
-        .map_err(|e| format!("Failed to start LMDB write transaction: {}", e))?;
-    let db: Database<Bytes, Str> = env
-        .create_database(&mut wtxn, None)
-        .map_err(|e| format!("Failed to create LMDB database: {}", e))?;
-
-    let mut entries: Vec<([u8; 8], String)> = Vec::with_capacity(2_000_000);
-    for (name, hash_len) in sources {
-        entries.extend(parse_hash_entries(&hash_dir.join(name), *hash_len));
-    }
-    entries.sort_unstable_by_key(|(k, _)| *k);
-    entries.dedup_by_key(|(k, _)| *k);
-
-    for (key, value) in &entries {
-        db.put(&mut wtxn, key.as_slice(), value.as_str())
-            .map_err(|e| format!("Failed LMDB put: {}", e))?;
-    }
-
-    wtxn.commit()
-        .map_err(|e| format!("Failed LMDB commit: {}", e))?;
-    eprintln!("[WAD] LMDB rebuilt: {} entries", entries.len());
     Ok(())
 }
 
-fn open_hash_db(hash_dir: &Path) -> Result<heed::Env, String> {
-    let db_dir = lmdb_dir(hash_dir);
-    if !db_dir.exists() {
-        return Err(format!(
+fn open_hash_db(hash_dir: &Path) -> Result<hash_db::HashDb, String> {
+    hash_db::open(hash_dir)?.ok_or_else(|| {
+        format!(
             "LMDB not found at {} (build failed or hash sources missing)",
-            db_dir.display()
-        ));
-    }
-    unsafe {
-        EnvOpenOptions::new()
-            .map_size(512 * 1024 * 1024)
-            .max_dbs(1)
-            .open(&db_dir)
-    }
-    .map_err(|e| format!("Failed to open LMDB {}: {}", db_dir.display(), e))
+            hash_db::lmdb_dir(hash_dir).display()
+        )
+    })
 }
 
 fn default_unpack_output(wad_path: &Path) -> PathBuf {
@@ -203,121 +76,6 @@ pub fn default_pack_output(input_dir: &Path) -> PathBuf {
     }
 }
 
-fn parse_hex_name_from_root(rel: &str) -> Option<u64> {
-    if rel.contains('/') {
-        return None;
-    }
-    let stem = rel.split('.').next().unwrap_or(rel);
-    if stem.len() != 16 || !stem.bytes().all(|b| b.is_ascii_hexdigit()) {
-        return None;
-    }
-    u64::from_str_radix(stem, 16).ok()
-}
-
-const PATH_PREFIXES: &[&[u8]] = &[
-    b"assets/",
-    b"data/",
-    b"maps/",
-    b"levels/",
-    b"clientstates/",
-    b"ux/",
-    b"uiautoatlas/",
-];
-
-fn xxhash_path(s: &str) -> u64 {
-    xxhash_rust::xxh64::xxh64(s.as_bytes(), 0)
-}
-
-fn fnv1a_lower(s: &str) -> u32 {
-    let mut h: u32 = 0x811c9dc5;
-    for b in s.bytes().map(|b| b.to_ascii_lowercase()) {
-        h ^= b as u32;
-        h = h.wrapping_mul(0x01000193);
-    }
-    h
-}
-
-fn scan_bin_game_hashes(data: &[u8]) -> Vec<(u64, String)> {
-    if data.len() < 4 {
-        return vec![];
-    }
-    if &data[..4] != b"PROP" && &data[..4] != b"PTCH" {
-        return vec![];
-    }
-    let mut results = Vec::new();
-    let mut i = 0usize;
-    while i + 2 <= data.len() {
-        let len = u16::from_le_bytes([data[i], data[i + 1]]) as usize;
-        if (8..=300).contains(&len) {
-            if let Some(slice) = data.get(i + 2..i + 2 + len) {
-                if let Ok(s) = std::str::from_utf8(slice) {
-                    let lb = s.as_bytes();
-                    let is_path = s.contains('/')
-                        && s.is_ascii()
-                        && PATH_PREFIXES
-                            .iter()
-                            .any(|p| lb.len() >= p.len() && lb[..p.len()].eq_ignore_ascii_case(p));
-                    if is_path {
-                        let lower = s.to_ascii_lowercase();
-                        results.push((xxhash_path(&lower), lower.clone()));
-                        if lower.ends_with(".dds") {
-                            let slash = lower.rfind('/').map(|i| i + 1).unwrap_or(0);
-                            let dir = &lower[..slash];
-                            let fname = &lower[slash..];
-                            let v2x = format!("{}2x_{}", dir, fname);
-                            let v4x = format!("{}4x_{}", dir, fname);
-                            results.push((xxhash_path(&v2x), v2x));
-                            results.push((xxhash_path(&v4x), v4x));
-                        }
-                        if lower.ends_with(".bin") {
-                            let py = format!("{}.py", &lower[..lower.len() - 4]);
-                            results.push((xxhash_path(&py), py));
-                        }
-                        i += 2 + len;
-                        continue;
-                    }
-                }
-            }
-        }
-        i += 1;
-    }
-    results
-}
-
-fn scan_skn_bin_hashes(data: &[u8]) -> Vec<(u32, String)> {
-    if data.len() < 12 {
-        return vec![];
-    }
-    let magic = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-    if magic != 0x00112233 {
-        return vec![];
-    }
-    let major = u16::from_le_bytes([data[4], data[5]]);
-    if major == 0 {
-        return vec![];
-    }
-    let range_count = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
-    if range_count == 0 || range_count > 256 {
-        return vec![];
-    }
-    let mut results = Vec::with_capacity(range_count);
-    let mut pos = 12usize;
-    for _ in 0..range_count {
-        if pos + 80 > data.len() {
-            break;
-        }
-        let name_bytes = &data[pos..pos + 64];
-        let null_pos = name_bytes.iter().position(|&b| b == 0).unwrap_or(64);
-        if let Ok(name) = std::str::from_utf8(&name_bytes[..null_pos]) {
-            if !name.is_empty() {
-                results.push((fnv1a_lower(name), name.to_string()));
-            }
-        }
-        pos += 80;
-    }
-    results
-}
-
 pub fn extract_hashes(wad_path: &Path, hash_dir: &Path) -> Result<(), String> {
     eprintln!("[WAD] Extracting hashes from {}", wad_path.display());
     eprintln!("[WAD] Hash output dir: {}", hash_dir.display());
@@ -346,8 +104,7 @@ pub fn extract_hashes(wad_path: &Path, hash_dir: &Path) -> Result<(), String> {
         for (k, v) in scan_skn_bin_hashes(&data) {
             bin_hashes.entry(k).or_insert(v);
         }
-        if total_chunks > 0 {
-            let pct = ((idx + 1) * 100) / total_chunks;
+        if let Some(pct) = ((idx + 1) * 100).checked_div(total_chunks) {
             while next_progress_step <= 100 && pct >= next_progress_step {
                 eprintln!(
                     "[HASH] Progress {:>3}% ({}/{})",
@@ -434,14 +191,13 @@ pub fn unpack(wad_path: &Path, output_dir: Option<&Path>, hash_dir: Option<&Path
             open_hash_db(hash_dir)?
         }
     };
-    let extracted_resolver = load_extracted_hashes(hash_dir);
-    let rtxn = env
-        .read_txn()
-        .map_err(|e| format!("Failed to start LMDB read transaction: {}", e))?;
-    let db: Database<Bytes, Str> = env
-        .open_database(&rtxn, None)
-        .map_err(|e| format!("Failed to open LMDB database: {}", e))?
-        .ok_or_else(|| "LMDB database missing".to_string())?;
+    let custom = parse_hash_text_file(&hash_dir.join(CUSTOM_HASH_FILE), 16);
+    let lmdb = LmdbResolver::new(&env);
+    let extracted = load_extracted_hashes(hash_dir);
+    let resolver = LayeredResolver::new()
+        .layer(HashSource::Custom, &custom)
+        .layer(HashSource::Lmdb, &lmdb)
+        .layer(HashSource::Table, &extracted);
 
     let file = fs::File::open(wad_path)
         .map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
@@ -459,17 +215,11 @@ pub fn unpack(wad_path: &Path, output_dir: Option<&Path>, hash_dir: Option<&Path
     let mut skip_write_failed = 0u32;
     let mut hashed_files: HashMap<String, String> = HashMap::new();
 
-    for (idx, chunk) in chunks.into_iter().enumerate() {
+    let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+    let names = resolver.resolve_names(&hashes);
+
+    for (idx, (chunk, resolved)) in chunks.into_iter().zip(names).enumerate() {
         let path_hash = chunk.path_hash();
-        let resolved = if let Some(v) = extracted_resolver.get(&path_hash) {
-            v.clone()
-        } else {
-            let key = path_hash.to_be_bytes();
-            db.get(&rtxn, key.as_slice())
-                .map_err(|e| format!("LMDB lookup failed for {:016x}: {}", path_hash, e))?
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| format!("{:016x}", path_hash))
-        };
         let mut rel = normalize_rel_path(&resolved);
         if !is_safe_relative_path(&rel) {
             skipped += 1;
@@ -539,8 +289,7 @@ pub fn unpack(wad_path: &Path, output_dir: Option<&Path>, hash_dir: Option<&Path
             }
         }
 
-        if total_chunks > 0 {
-            let pct = ((idx + 1) * 100) / total_chunks;
+        if let Some(pct) = ((idx + 1) * 100).checked_div(total_chunks) {
             while next_progress_step <= 100 && pct >= next_progress_step {
                 eprintln!(
                     "[WAD] Progress {:>3}% ({}/{}) extracted={} skipped={}",
@@ -631,7 +380,7 @@ pub fn pack_dir_to_wad(input_dir: &Path, output_wad: Option<&Path>) -> Result<()
                 .to_string_lossy()
                 .replace('\\', "/");
 
-            let hash = if let Some(v) = parse_hex_name_from_root(&rel) {
+            let hash = if let Some(v) = parse_hex_file_name(&rel) {
                 v
             } else {
                 xxhash_path(&rel.to_ascii_lowercase())
@@ -644,7 +393,9 @@ pub fn pack_dir_to_wad(input_dir: &Path, output_wad: Option<&Path>) -> Result<()
         return Err(format!("No files found in {}", input_dir.display()));
     }
 
-    files.sort_by_key(|(h, _)| *h);
+    // Path breaks hash ties, so the chunk kept on a collision doesn't depend on
+    // directory listing order and the same folder always packs identically.
+    files.sort();
 
     let mut index: HashMap<u64, PathBuf> = HashMap::new();
     let mut builder = WadBuilder::default();
@@ -683,8 +434,7 @@ pub fn pack_dir_to_wad(input_dir: &Path, output_wad: Option<&Path>) -> Result<()
 
             let current_written = written.get() + 1;
             written.set(current_written);
-            if total_chunks > 0 {
-                let pct = (current_written * 100) / total_chunks;
+            if let Some(pct) = (current_written * 100).checked_div(total_chunks) {
                 while next_progress_step.get() <= 100 && pct >= next_progress_step.get() {
                     eprintln!(
                         "[WAD] Pack Progress {:>3}% ({}/{})",
@@ -7,19 +7,48 @@ mod utils;
 extern "C" {}
 
 use std::env;
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read};
 use std::path::{Path, PathBuf};
 use std::process;
 
 use hashes::default_hash_dir;
 
+/// Exit with `code`, first waiting for Enter when run from a console window
+/// (double-click / file association) so the message stays readable. Scripts and
+/// CI pipelines have no terminal on stdin and exit right away.
 fn pause_and_exit(code: i32) -> ! {
-    eprintln!();
-    eprintln!("Press Enter to close...");
-    let _ = io::stdin().read(&mut [0u8]);
+    if io::stdin().is_terminal() {
+        eprintln!();
+        eprintln!("Press Enter to close...");
+        let _ = io::stdin().read(&mut [0u8]);
+    }
     process::exit(code);
 }
 
+/// Value of `--name <value>` anywhere in the arguments.
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// Arguments after the command, without `--hash-dir <dir>` and `--flag`s.
+fn positionals(args: &[String]) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut iter = args.iter().skip(2);
+    while let Some(a) = iter.next() {
+        if a == "--hash-dir" {
+            iter.next();
+        } else if !a.starts_with("--") {
+            out.push(a.as_str());
+        }
+    }
+    out
+}
+
+/// `--hash-dir <dir>` when given, else the default hash directory.
+fn hash_dir_arg(args: &[String]) -> Option<PathBuf> {
+    option_value(args, "--hash-dir").map(PathBuf::from).or_else(default_hash_dir)
+}
+
 fn print_usage() {
     eprintln!("quartz_cli - League of Legends bin/py/texture converter");
     eprintln!();
@@ -52,9 +81,17 @@ fn print_usage() {
     eprintln!("  quartz_cli extract-unpack-wad <file.wad|file.wad.client> [output_dir]  Extract hashes, then unpack");
     eprintln!("  quartz_cli unpack-wad    <file.wad|file.wad.client> [output_dir]  Unpack WAD using available hashes");
     eprintln!("  quartz_cli pack-wad      <folder> [output.wad.client]  Pack folder into .wad.client");
+    eprintln!("  quartz_cli build-hash-db              Build hashes.lmdb from the hash lists (if outdated)");
+    eprintln!("  quartz_cli repath        <folder> <from-prefix> <to-prefix>  Move assets and rewrite bin paths");
+    eprintln!("  quartz_cli checkpoint    <project> [label]  Snapshot the project's content folder");
+    eprintln!("  quartz_cli list-checkpoints <project>  List checkpoints, newest first");
+    eprintln!("  quartz_cli restore-checkpoint <project> <id> [--link]  Restore a checkpoint");
+    eprintln!("  quartz_cli verify-checkpoint <project> <id>  Check a checkpoint's stored files");
     eprintln!();
     eprintln!("Options:");
     eprintln!("  --hash-dir <dir>  Custom hash directory (default: %APPDATA%/FrogTools/hashes/)");
+    eprintln!();
+    eprintln!("Exits with status 1 on failure; only waits for Enter when run from a console.");
 }
 
 fn main() {
@@ -421,7 +458,7 @@ fn main() {
                 eprintln!("Error: folder not found: {}", path.display());
                 pause_and_exit(1);
             }
-            let hash_dir = default_hash_dir();
+            let hash_dir = hash_dir_arg(&args);
             if let Err(e) = commands::ritobin_dir::bin_to_py_dir(path, hash_dir.as_deref()) {
                 eprintln!("Error: {}", e);
                 pause_and_exit(1);
@@ -452,7 +489,7 @@ fn main() {
                 eprintln!("Error: file not found: {}", path.display());
                 pause_and_exit(1);
             }
-            let Some(hash_dir) = default_hash_dir() else {
+            let Some(hash_dir) = hash_dir_arg(&args) else {
                 eprintln!("Error: could not resolve default hash directory");
                 pause_and_exit(1);
             };
@@ -471,7 +508,7 @@ fn main() {
                 eprintln!("Error: folder not found: {}", path.display());
                 pause_and_exit(1);
             }
-            let Some(hash_dir) = default_hash_dir() else {
+            let Some(hash_dir) = hash_dir_arg(&args) else {
                 eprintln!("Error: could not resolve default hash directory");
                 pause_and_exit(1);
             };
@@ -520,7 +557,7 @@ fn main() {
                 eprintln!("Error: file not found: {}", wad_path.display());
                 pause_and_exit(1);
             }
-            let Some(hash_dir) = default_hash_dir() else {
+            let Some(hash_dir) = hash_dir_arg(&args) else {
                 eprintln!("Error: could not resolve default hash directory");
                 pause_and_exit(1);
             };
@@ -544,7 +581,7 @@ fn main() {
             } else {
                 None
             };
-            let hash_dir = default_hash_dir();
+            let hash_dir = hash_dir_arg(&args);
             if let Err(e) = commands::wad::unpack(wad_path, output_dir, hash_dir.as_deref()) {
                 eprintln!("Error: {}", e);
                 pause_and_exit(1);
@@ -565,7 +602,7 @@ fn main() {
             } else {
                 None
             };
-            let Some(hash_dir) = default_hash_dir() else {
+            let Some(hash_dir) = hash_dir_arg(&args) else {
                 eprintln!("Error: could not resolve default hash directory");
                 pause_and_exit(1);
             };
@@ -594,6 +631,53 @@ fn main() {
                 pause_and_exit(1);
             }
         }
+        "build-hash-db" => {
+            let Some(hash_dir) = hash_dir_arg(&args) else {
+                eprintln!("Error: could not resolve default hash directory");
+                pause_and_exit(1);
+            };
+            if let Err(e) = commands::wad::build_hash_db(&hash_dir) {
+                eprintln!("Error: {}", e);
+                pause_and_exit(1);
+            }
+            eprintln!("OK: hash database up to date in {}", hash_dir.display());
+        }
+        "repath" => {
+            let pos = positionals(&args);
+            let [dir, from, to] = pos[..] else {
+                eprintln!("Error: usage: repath <folder> <from-prefix> <to-prefix>");
+                pause_and_exit(1);
+            };
+            let dir = Path::new(dir);
+            if !dir.is_dir() {
+                eprintln!("Error: folder not found: {}", dir.display());
+                pause_and_exit(1);
+            }
+            if let Err(e) = commands::repath::run(dir, from, to) {
+                eprintln!("Error: {}", e);
+                pause_and_exit(1);
+            }
+        }
+        "checkpoint" | "list-checkpoints" | "restore-checkpoint" | "verify-checkpoint" => {
+            let pos = positionals(&args);
+            let Some(project) = pos.first().map(Path::new) else {
+                eprintln!("Error: missing project folder");
+                pause_and_exit(1);
+            };
+            let result = match args[1].as_str() {
+                "checkpoint" => commands::checkpoint::create(project, pos.get(1).map(|l| l.to_string())),
+                "list-checkpoints" => commands::checkpoint::list(project),
+                cmd => match pos.get(1) {
+                    Some(id) if cmd == "verify-checkpoint" => commands::checkpoint::verify(project, id),
+                    Some(id) => commands::checkpoint::restore(project, id, args.iter().any(|a| a == "--link")),
+                    None => Err("missing checkpoint id".to_string()),
+                },
+            };
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                pause_and_exit(1);
+            }
+        }
         "help" | "--help" | "-h" => print_usage(),
         other => {
             eprintln!("Unknown command: {}", other);
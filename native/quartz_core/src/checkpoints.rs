@@ -0,0 +1,525 @@
+//! Snapshots of a project's `content/` folder. File data lives once in a
+//! content-addressed object store (`.quartz/objects/{xx}/{xxh3}`) shared by every
+//! checkpoint; a checkpoint is just a manifest of relative path -> object in
+//! `.quartz/checkpoints/{id}.json`, so files that didn't change cost nothing.
+//!
+//! A restore assembles the checkpoint's files next to `content/` and swaps the
+//! folder in only once every file is in place, so a failed restore leaves the
+//! project untouched. Files are copied from the store by default. With `link`,
+//! they are cloned instead (FICLONE on btrfs/XFS, clonefile on APFS, block
+//! cloning on ReFS and Dev Drive volumes), which makes restoring a multi-GB
+//! project near-instant while each file still gets its own blocks on the first
+//! write, so editing a restored file never touches the store. Where cloning isn't possible (NTFS
+//! among them) the file is copied and the restore says why. Hard links are not
+//! used: an in-place edit of a linked file would rewrite the stored object.
+//!
+//! Objects are checked against their xxh3 name before they are reused, so a
+//! damaged object is rewritten instead of spreading. Restores only check sizes,
+//! keeping them independent of the project's size; `verify` hashes every object
+//! of a checkpoint on demand.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+
+use crate::paths::{collect_files, is_safe_relative_path, normalize_rel_path, rename_retrying, swap_dir_in};
+
+const CHECKPOINTS_DIR: &str = "checkpoints";
+const OBJECTS_DIR: &str = "objects";
+/// Where a restore assembles the new `content/` before swapping it in.
+const RESTORE_STAGING_DIR: &str = "content.restore";
+
+#[derive(Serialize, Deserialize)]
+struct CheckpointFile {
+    /// xxh3 of the file data, 16-digit hex; also the object name.
+    object: String,
+    size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    /// Unix milliseconds.
+    created_at: i64,
+    files: BTreeMap<String, CheckpointFile>,
+}
+
+pub struct CheckpointInfo {
+    pub id: String,
+    pub label: Option<String>,
+    /// Unix milliseconds.
+    pub created_at: i64,
+    pub file_count: u32,
+    pub total_size: u64,
+}
+
+pub struct Created {
+    pub checkpoint: CheckpointInfo,
+    /// Objects written to the store; files already stored by an earlier checkpoint aren't counted.
+    pub new_objects: u32,
+}
+
+pub struct Restored {
+    pub restored_count: u32,
+    /// Restored files that were cloned rather than copied.
+    pub linked_count: u32,
+    /// Why a `link` restore copied files instead of cloning them, if it did.
+    pub link_fallback: Option<String>,
+    /// Files not in the checkpoint that were removed from `content/`.
+    pub removed_count: u32,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+fn quartz_dir(project: &Path) -> PathBuf {
+    project.join(".quartz")
+}
+
+fn object_path(project: &Path, object: &str) -> PathBuf {
+    quartz_dir(project).join(OBJECTS_DIR).join(&object[..2]).join(object)
+}
+
+/// True when the object at `path` still hashes to its name. The file is
+/// streamed, so large objects are never held in memory.
+fn object_intact(path: &Path, object: &str) -> bool {
+    let Ok(mut file) = fs::File::open(path) else { return false };
+    let mut hasher = Xxh3::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(_) => return false,
+        }
+    }
+    format!("{:016x}", hasher.digest()) == object
+}
+
+fn valid_object_name(object: &str) -> bool {
+    object.len() == 16 && object.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn manifest_path(project: &Path, id: &str) -> PathBuf {
+    quartz_dir(project).join(CHECKPOINTS_DIR).join(format!("{}.json", id))
+}
+
+fn info(manifest: &Manifest) -> CheckpointInfo {
+    CheckpointInfo {
+        id: manifest.id.clone(),
+        label: manifest.label.clone(),
+        created_at: manifest.created_at,
+        file_count: manifest.files.len() as u32,
+        total_size: manifest.files.values().map(|f| f.size).sum(),
+    }
+}
+
+fn read_manifest(project: &Path, id: &str) -> Result<Manifest, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid checkpoint id: {}", id));
+    }
+    let path = manifest_path(project, id);
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Snapshot the project's `content/` folder into a new checkpoint.
+pub fn create(project: &Path, label: Option<String>) -> Result<Created, String> {
+    let content = project.join("content");
+    if !content.is_dir() {
+        return Err(format!("Project has no content folder: {}", project.display()));
+    }
+    let mut files = BTreeMap::new();
+    let mut new_objects = 0u32;
+    for (rel, path) in collect_files(&content)? {
+        let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let object = format!("{:016x}", xxh3_64(&data));
+        let dst = object_path(project, &object);
+        let stored = fs::metadata(&dst).is_ok_and(|m| m.len() == data.len() as u64);
+        if !stored || !object_intact(&dst, &object) {
+            if let Some(parent) = dst.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            // Written under a temp name so a crash never leaves a truncated object behind.
+            let tmp = dst.with_extension("tmp");
+            fs::write(&tmp, &data).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+            rename_retrying(&tmp, &dst).map_err(|e| format!("Failed to write {}: {}", dst.display(), e))?;
+            new_objects += 1;
+        }
+        files.insert(rel, CheckpointFile { object, size: data.len() as u64 });
+    }
+
+    let manifest = write_manifest(project, label, now_ms(), files)?;
+    Ok(Created { checkpoint: info(&manifest), new_objects })
+}
+
+/// Save a new manifest. The id is its creation time; checkpoints made in the
+/// same millisecond get a `-{n}` suffix, and an existing manifest is never
+/// overwritten.
+fn write_manifest(
+    project: &Path,
+    label: Option<String>,
+    created_at: i64,
+    files: BTreeMap<String, CheckpointFile>,
+) -> Result<Manifest, String> {
+    let dir = quartz_dir(project).join(CHECKPOINTS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let mut manifest = Manifest { id: created_at.to_string(), label, created_at, files };
+    for n in 1u32.. {
+        let path = manifest_path(project, &manifest.id);
+        let json = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(json.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                return Ok(manifest);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => manifest.id = format!("{}-{}", created_at, n),
+            Err(e) => return Err(format!("Failed to write {}: {}", path.display(), e)),
+        }
+    }
+    unreachable!("checkpoint id suffixes exhausted")
+}
+
+/// Checkpoints of a project, newest first.
+pub fn list(project: &Path) -> Result<Vec<CheckpointInfo>, String> {
+    let dir = quartz_dir(project).join(CHECKPOINTS_DIR);
+    let Ok(entries) = fs::read_dir(&dir) else { return Ok(Vec::new()) };
+    let mut out: Vec<CheckpointInfo> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let id = name.strip_suffix(".json")?;
+            read_manifest(project, id).ok().map(|m| info(&m))
+        })
+        .collect();
+    out.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+    Ok(out)
+}
+
+/// Make `dst` a copy-on-write clone of `src`; fails where the file system
+/// can't share extents between the two.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn clone_file(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let from = fs::File::open(src)?;
+    let to = fs::File::create(dst)?;
+    // SAFETY: both descriptors are open for the duration of the call.
+    if unsafe { libc::ioctl(to.as_raw_fd(), libc::FICLONE, from.as_raw_fd()) } == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    drop(to);
+    let _ = fs::remove_file(dst);
+    Err(err)
+}
+
+#[cfg(target_os = "macos")]
+fn clone_file(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let from = CString::new(src.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    let to = CString::new(dst.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    // SAFETY: both paths are valid NUL-terminated strings.
+    if unsafe { libc::clonefile(from.as_ptr(), to.as_ptr(), 0) } == 0 {
+        return Ok(());
+    }
+    Err(io::Error::last_os_error())
+}
+
+/// ReFS block cloning: `dst` is sized to match and its extents are pointed at
+/// `src`'s, a cluster-aligned region at a time.
+#[cfg(windows)]
+fn clone_file(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+
+    const FSCTL_GET_INTEGRITY_INFORMATION: u32 = 0x0009_027C;
+    const FSCTL_DUPLICATE_EXTENTS_TO_FILE: u32 = 0x0009_8344;
+    /// Regions per call stay well below the 4 GB limit on `ByteCount`.
+    const MAX_REGION: u64 = 1 << 30;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct IntegrityInformation {
+        checksum_algorithm: u16,
+        reserved: u16,
+        flags: u32,
+        checksum_chunk_size_in_bytes: u32,
+        cluster_size_in_bytes: u32,
+    }
+
+    #[repr(C)]
+    struct DuplicateExtentsData {
+        file_handle: *mut c_void,
+        source_file_offset: i64,
+        target_file_offset: i64,
+        byte_count: i64,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn DeviceIoControl(
+            device: *mut c_void,
+            code: u32,
+            in_buffer: *const c_void,
+            in_size: u32,
+            out_buffer: *mut c_void,
+            out_size: u32,
+            returned: *mut u32,
+            overlapped: *mut c_void,
+        ) -> i32;
+    }
+
+    let from = fs::File::open(src)?;
+    let len = from.metadata()?.len();
+    let mut integrity = IntegrityInformation::default();
+    let mut returned = 0u32;
+    // SAFETY: the handle is open and the output buffer matches the FSCTL's layout.
+    let ok = unsafe {
+        DeviceIoControl(
+            from.as_raw_handle(),
+            FSCTL_GET_INTEGRITY_INFORMATION,
+            std::ptr::null(),
+            0,
+            &mut integrity as *mut IntegrityInformation as *mut c_void,
+            std::mem::size_of::<IntegrityInformation>() as u32,
+            &mut returned,
+            std::ptr::null_mut(),
+        )
+    };
+    // Only ReFS answers this; NTFS fails it before anything is created.
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let cluster = u64::from(integrity.cluster_size_in_bytes.max(1));
+
+    let to = fs::File::create(dst)?;
+    let result = (|| {
+        to.set_len(len)?;
+        let mut offset = 0u64;
+        while offset < len {
+            // The last region is rounded up to a whole cluster, as the FSCTL requires.
+            let count = (len - offset).min(MAX_REGION).div_ceil(cluster) * cluster;
+            let data = DuplicateExtentsData {
+                file_handle: from.as_raw_handle(),
+                source_file_offset: offset as i64,
+                target_file_offset: offset as i64,
+                byte_count: count as i64,
+            };
+            // SAFETY: both handles are open and `data` matches DUPLICATE_EXTENTS_DATA.
+            let ok = unsafe {
+                DeviceIoControl(
+                    to.as_raw_handle(),
+                    FSCTL_DUPLICATE_EXTENTS_TO_FILE,
+                    &data as *const DuplicateExtentsData as *const c_void,
+                    std::mem::size_of::<DuplicateExtentsData>() as u32,
+                    std::ptr::null_mut(),
+                    0,
+                    &mut returned,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            offset += count;
+        }
+        Ok(())
+    })();
+    if result.is_err() {
+        drop(to);
+        let _ = fs::remove_file(dst);
+    }
+    result
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", windows)))]
+fn clone_file(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "file cloning isn't supported on this platform"))
+}
+
+/// Put `object` at the new file `dst`, cloned when `link` is set and possible.
+/// Returns whether it was cloned; the first clone failure is kept in `fallback`.
+fn place(object: &Path, dst: &Path, link: bool, fallback: &mut Option<String>) -> Result<bool, String> {
+    if link {
+        match clone_file(object, dst) {
+            Ok(()) => return Ok(true),
+            Err(e) => {
+                fallback.get_or_insert_with(|| format!("Files were copied instead of cloned: {}", e));
+            }
+        }
+    }
+    fs::copy(object, dst).map_err(|e| format!("Failed to restore {}: {}", dst.display(), e))?;
+    Ok(false)
+}
+
+/// Files of checkpoint `id` whose stored object is missing or no longer
+/// hashes to its name, by relative path. Reads every object in full.
+pub fn verify(project: &Path, id: &str) -> Result<Vec<String>, String> {
+    let manifest = read_manifest(project, id)?;
+    Ok(manifest
+        .files
+        .iter()
+        .filter(|(_, file)| {
+            !valid_object_name(&file.object) || !object_intact(&object_path(project, &file.object), &file.object)
+        })
+        .map(|(rel, _)| rel.clone())
+        .collect())
+}
+
+/// Make `content/` match checkpoint `id`. With `link`, files are cloned from
+/// the object store instead of copied (copy fallback per file).
+pub fn restore(project: &Path, id: &str, link: bool) -> Result<Restored, String> {
+    let manifest = read_manifest(project, id)?;
+    let content = project.join("content");
+
+    // Check the whole checkpoint before touching the project.
+    let mut plan = Vec::with_capacity(manifest.files.len());
+    for (rel, file) in &manifest.files {
+        let rel = normalize_rel_path(rel);
+        if !is_safe_relative_path(&rel) {
+            return Err(format!("Unsafe path in checkpoint: {}", rel));
+        }
+        if !valid_object_name(&file.object) {
+            return Err(format!("Checkpoint object for {} is missing or damaged", rel));
+        }
+        let object = object_path(project, &file.object);
+        if !fs::metadata(&object).is_ok_and(|m| m.len() == file.size) {
+            return Err(format!("Checkpoint object for {} is missing or damaged", rel));
+        }
+        plan.push((rel, object));
+    }
+
+    // Build the restored tree next to `content/` and swap it in, so a failure
+    // partway through leaves the project as it was.
+    let staging = project.join(RESTORE_STAGING_DIR);
+    let _ = fs::remove_dir_all(&staging);
+    let (mut restored_count, mut linked_count) = (0u32, 0u32);
+    let mut link_fallback = None;
+    let staged = (|| {
+        for (rel, object) in &plan {
+            let dst = staging.join(rel);
+            if let Some(parent) = dst.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            if place(object, &dst, link, &mut link_fallback)? {
+                linked_count += 1;
+            }
+            restored_count += 1;
+        }
+        fs::create_dir_all(&staging).map_err(|e| format!("Failed to create {}: {}", staging.display(), e))
+    })();
+    if let Err(e) = staged {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let mut removed_count = 0u32;
+    if content.is_dir() {
+        let keep: HashSet<String> = manifest.files.keys().map(|r| normalize_rel_path(r)).collect();
+        removed_count = collect_files(&content)?.iter().filter(|(rel, _)| !keep.contains(rel)).count() as u32;
+    }
+    if let Err(e) = swap_dir_in(&staging, &content) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    Ok(Restored { restored_count, linked_count, link_fallback, removed_count })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quartz_core_checkpoints_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("content/data")).unwrap();
+        fs::write(dir.join("content/data/a.bin"), b"original").unwrap();
+        dir
+    }
+
+    fn only_object(project: &Path, created: &Created) -> PathBuf {
+        let manifest = read_manifest(project, &created.checkpoint.id).unwrap();
+        object_path(project, &manifest.files["data/a.bin"].object)
+    }
+
+    #[test]
+    fn test_linked_restore_keeps_store_intact() {
+        let dir = project("linked");
+        let created = create(&dir, None).unwrap();
+        let restored = restore(&dir, &created.checkpoint.id, true).unwrap();
+        assert_eq!(restored.restored_count, 1);
+        // Copying is fine where the file system can't clone, but it has to be explained.
+        assert_eq!(restored.link_fallback.is_some(), restored.linked_count == 0);
+
+        // An in-place rewrite, as the app's bin writers do.
+        let file = dir.join("content/data/a.bin");
+        fs::OpenOptions::new().write(true).open(&file).unwrap().write_all(b"EDITED!!").unwrap();
+        let object = only_object(&dir, &created);
+        assert_eq!(fs::read(&object).unwrap(), b"original");
+
+        restore(&dir, &created.checkpoint.id, true).unwrap();
+        assert_eq!(fs::read(&file).unwrap(), b"original");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_damaged_object_is_rewritten_or_refused() {
+        let dir = project("damaged");
+        let first = create(&dir, None).unwrap();
+        let object = only_object(&dir, &first);
+        fs::write(&object, b"corrupt!").unwrap();
+        assert_eq!(verify(&dir, &first.checkpoint.id).unwrap(), vec!["data/a.bin".to_string()]);
+
+        fs::write(&object, b"short").unwrap();
+        assert!(restore(&dir, &first.checkpoint.id, false).is_err());
+        assert_eq!(fs::read(dir.join("content/data/a.bin")).unwrap(), b"original");
+
+        let second = create(&dir, None).unwrap();
+        assert_eq!(second.new_objects, 1);
+        assert_eq!(fs::read(&object).unwrap(), b"original");
+        assert!(verify(&dir, &first.checkpoint.id).unwrap().is_empty());
+        assert!(restore(&dir, &first.checkpoint.id, false).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_failed_restore_leaves_content_untouched() {
+        let dir = project("failed_restore");
+        let created = create(&dir, None).unwrap();
+        let manifest = read_manifest(&dir, &created.checkpoint.id).unwrap();
+        let object = &manifest.files["data/a.bin"];
+        let file = || CheckpointFile { object: object.object.clone(), size: object.size };
+        // "x" is a file, so "x/y" can't be created under it.
+        let files = BTreeMap::from([("x".to_string(), file()), ("x/y".to_string(), file())]);
+        let broken = write_manifest(&dir, None, now_ms(), files).unwrap();
+        fs::write(dir.join("content/extra.txt"), b"keep me").unwrap();
+
+        assert!(restore(&dir, &broken.id, false).is_err());
+        assert_eq!(fs::read(dir.join("content/data/a.bin")).unwrap(), b"original");
+        assert_eq!(fs::read(dir.join("content/extra.txt")).unwrap(), b"keep me");
+        assert!(!dir.join(RESTORE_STAGING_DIR).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_same_millisecond_checkpoints_get_distinct_ids() {
+        let dir = project("same_ms");
+        let first = write_manifest(&dir, Some("first".into()), 1_700_000_000_000, BTreeMap::new()).unwrap();
+        let second = write_manifest(&dir, Some("second".into()), 1_700_000_000_000, BTreeMap::new()).unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(read_manifest(&dir, &first.id).unwrap().label.as_deref(), Some("first"));
+        assert_eq!(read_manifest(&dir, &second.id).unwrap().label.as_deref(), Some("second"));
+        assert_eq!(list(&dir).unwrap().len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
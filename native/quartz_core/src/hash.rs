@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// WAD path hash: xxh64 of the (already lowercased) path.
+pub fn xxhash_path(s: &str) -> u64 {
+    xxhash_rust::xxh64::xxh64(s.as_bytes(), 0)
+}
+
+/// FNV-1a 32-bit hash of the ASCII-lowercased string, used for bin entry,
+/// type and field names.
+pub fn fnv1a_lower(s: &str) -> u32 {
+    let mut h: u32 = 0x811c9dc5;
+    for b in s.bytes().map(|b| b.to_ascii_lowercase()) {
+        h ^= b as u32;
+        h = h.wrapping_mul(0x01000193);
+    }
+    h
+}
+
+/// A full 16-digit hex path hash, with or without `0x`.
+pub fn parse_hash_hex(s: &str) -> Option<u64> {
+    let raw = s.trim().trim_start_matches("0x").trim_start_matches("0X");
+    if raw.len() != 16 || !raw.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u64::from_str_radix(raw, 16).ok()
+}
+
+/// A hash written as `0x`-prefixed hex, decimal, or bare hex.
+pub fn parse_hash_value(s: &str) -> Option<u64> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        return s.parse::<u64>().ok();
+    }
+    if s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return u64::from_str_radix(s, 16).ok();
+    }
+    None
+}
+
+/// Hash of an unresolved chunk saved under its hex name (`0123456789abcdef.bin`
+/// at the root of an unpacked WAD).
+pub fn parse_hex_file_name(rel: &str) -> Option<u64> {
+    if rel.contains('/') {
+        return None;
+    }
+    let stem = rel.split('.').next().unwrap_or(rel);
+    if stem.len() != 16 || !stem.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u64::from_str_radix(stem, 16).ok()
+}
+
+/// Parse a CDTB-style hash list (`<hex hash> <name>` per line) whose hashes
+/// are `hash_len` hex digits. The first name seen for a hash wins; a missing
+/// file yields an empty map.
+pub fn parse_hash_text_file(path: &Path, hash_len: usize) -> HashMap<u64, String> {
+    let mut out = HashMap::new();
+    let Ok(content) = fs::read_to_string(path) else {
+        return out;
+    };
+    for line in content.lines() {
+        let l = line.trim();
+        if l.is_empty() || l.starts_with('#') || l.len() <= hash_len + 1 {
+            continue;
+        }
+        // Not a char boundary means non-ASCII inside the hash: not a hash line.
+        let (Some(h), Some(p)) = (l.get(..hash_len), l.get(hash_len + 1..)) else {
+            continue;
+        };
+        if let Ok(v) = u64::from_str_radix(h, 16) {
+            let p = p.trim();
+            out.entry(v).or_insert_with(|| p.to_string());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hash_text_file_skips_non_ascii_lines() {
+        let path = std::env::temp_dir().join(format!("quartz_core_hashes_{}.txt", std::process::id()));
+        let lines = [
+            "0123456789abcdef assets/a.dds",
+            "ékkø456789abcdef assets/broken.dds",
+            "0123456789abcdeé assets/broken2.dds",
+            "# comment",
+            "fedcba9876543210 assets/ünïcode.bin",
+            "0123456789abcdef assets/duplicate.dds",
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+        let map = parse_hash_text_file(&path, 16);
+        let _ = fs::remove_file(&path);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&0x0123456789abcdef], "assets/a.dds");
+        assert_eq!(map[&0xfedcba9876543210], "assets/ünïcode.bin");
+    }
+}
@@ -0,0 +1,491 @@
+//! `hashes.lmdb`: the hash lists of a hash dir in one LMDB, so naming a WAD's
+//! chunks is a few point lookups instead of parsing 100+ MB of text.
+//!
+//! Every list has its own named table (`game`, `lcu`, `extracted`, `wwise`), so
+//! tools can resolve from just the lists they care about and tell where two
+//! lists disagree. The unnamed main table is the combined path view (game over
+//! lcu) that plain lookups use; extracted names stay out of it because they
+//! change with every extraction and readers layer the text file below it.
+//! Path keys are u64 as 8-byte big-endian, Wwise keys u32 as 4-byte big-endian.
+//!
+//! `sources.fingerprint` records the size and xxh64 of every list. When only the
+//! extracted or Wwise list changed, a build rewrites just that table; a change
+//! to the game or lcu list, or a DB from before the per-list tables, rebuilds
+//! the DB from scratch.
+//!
+//! LMDB never gives freed pages back to the file system, so a DB that saw many
+//! partial updates keeps its high-water size; `compact` rewrites it without them.
+//!
+//! Several processes (the app, the CLI, other tools) may use one hash dir at a
+//! time. LMDB itself keeps their readers and write transactions apart, but not
+//! the steps that replace the files: a full rebuild or a compaction. Those take
+//! an exclusive lock on `hashes.lmdb.lock` next to the DB, while every open
+//! `HashDb` and every in-place update holds a shared one; whoever has to wait
+//! retries for a while and then gives up with an "in use" error. All of them
+//! open the env with the same options (`open`), since LMDB can't share a file
+//! between envs that disagree on the map size.
+
+use std::collections::HashMap;
+use std::fs::{self, TryLockError};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use heed::types::{Bytes, Str};
+use heed::{CompactionOption, Database, EnvOpenOptions};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::paths::{rename_retrying, write_retrying};
+
+pub const LMDB_DIR_NAME: &str = "hashes.lmdb";
+
+pub const GAME_TABLE: &str = "game";
+pub const LCU_TABLE: &str = "lcu";
+pub const EXTRACTED_TABLE: &str = "extracted";
+pub const WWISE_TABLE: &str = "wwise";
+/// Name the unnamed main table is reported under.
+pub const COMBINED_TABLE: &str = "combined";
+
+pub const GAME_HASH_FILE: &str = "hashes.game.txt";
+pub const LCU_HASH_FILE: &str = "hashes.lcu.txt";
+pub const EXTRACTED_HASH_FILE: &str = "hashes.extracted.txt";
+pub const WWISE_HASH_FILE: &str = "hashes.wwise.txt";
+
+/// Path tables and the list each is built from.
+pub const PATH_TABLES: &[(&str, &str)] = &[
+    (GAME_TABLE, GAME_HASH_FILE),
+    (LCU_TABLE, LCU_HASH_FILE),
+    (EXTRACTED_TABLE, EXTRACTED_HASH_FILE),
+];
+/// Lists merged into the combined view, highest precedence first.
+const COMBINED_FILES: &[&str] = &[GAME_HASH_FILE, LCU_HASH_FILE];
+
+/// Named tables: the path tables plus `wwise`.
+const MAX_TABLES: u32 = 4;
+/// Virtual size only; the OS pages in what is touched.
+const MAP_SIZE: usize = 512 * 1024 * 1024;
+/// Rewritten by every build that changed something.
+pub const FINGERPRINT_FILE: &str = "sources.fingerprint";
+/// In the hash dir rather than the DB dir, which a full rebuild deletes.
+const LOCK_FILE: &str = "hashes.lmdb.lock";
+/// How long to wait for another process to finish a rebuild or let go of the DB.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+const LOCK_POLL: Duration = Duration::from_millis(100);
+/// First fingerprint line; DBs without it predate the per-list tables.
+const LAYOUT: &str = "layout|2";
+/// How long a rebuild or `compact` waits for other users of the env in this
+/// process to let go.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Compacted {
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+/// An open `hashes.lmdb`. Holds a shared lock on the hash dir while it lives,
+/// so no other process rebuilds or compacts the DB underneath it.
+pub struct HashDb {
+    env: heed::Env,
+    _lock: fs::File,
+}
+
+impl HashDb {
+    /// A read transaction that owns its handle on the env, for callers that
+    /// keep one state of the DB across several lookups. heed is built with
+    /// `read-txn-no-tls`, so the thread holding it can still open others.
+    pub fn snapshot(&self) -> heed::Result<heed::RoTxn<'static>> {
+        self.env.clone().static_read_txn()
+    }
+}
+
+impl Deref for HashDb {
+    type Target = heed::Env;
+
+    fn deref(&self) -> &heed::Env {
+        &self.env
+    }
+}
+
+pub fn lmdb_dir(hash_dir: &Path) -> PathBuf {
+    hash_dir.join(LMDB_DIR_NAME)
+}
+
+/// Lock the hash dir against other processes, waiting up to `LOCK_TIMEOUT`.
+fn lock(hash_dir: &Path, exclusive: bool) -> Result<fs::File, String> {
+    let path = hash_dir.join(LOCK_FILE);
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let start = Instant::now();
+    loop {
+        let locked = if exclusive { file.try_lock() } else { file.try_lock_shared() };
+        match locked {
+            Ok(()) => return Ok(file),
+            Err(TryLockError::WouldBlock) if start.elapsed() < LOCK_TIMEOUT => std::thread::sleep(LOCK_POLL),
+            Err(TryLockError::WouldBlock) => {
+                return Err(format!("Hash DB {} is in use by another process", lmdb_dir(hash_dir).display()))
+            }
+            Err(TryLockError::Error(e)) => return Err(format!("Failed to lock {}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// The one set of env options: heed hands back an env already open in this
+/// process only when they match, and LMDB needs every process to agree.
+fn open_env(lmdb_dir: &Path) -> Result<heed::Env, String> {
+    unsafe { EnvOpenOptions::new().map_size(MAP_SIZE).max_dbs(MAX_TABLES).open(lmdb_dir) }
+        .map_err(|e| format!("Failed to open LMDB {}: {}", lmdb_dir.display(), e))
+}
+
+/// Open `hashes.lmdb` in `hash_dir` for reading; `None` when it hasn't been built.
+/// Waits while another process is rebuilding or compacting it.
+pub fn open(hash_dir: &Path) -> Result<Option<HashDb>, String> {
+    let dir = lmdb_dir(hash_dir);
+    if !dir.exists() {
+        return Ok(None);
+    }
+    let lock = lock(hash_dir, false)?;
+    // Checked again under the lock: a rebuild may have been interrupted.
+    if !dir.join("data.mdb").exists() {
+        return Ok(None);
+    }
+    Ok(Some(HashDb { env: open_env(&dir)?, _lock: lock }))
+}
+
+/// Entries of a path hash list (`<16 hex digits> <path>` per line), sorted by
+/// hash. The first name listed for a hash wins.
+fn read_path_entries(path: &Path) -> Vec<(u64, String)> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut out: Vec<(u64, String)> = content
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| {
+            let hash = u64::from_str_radix(l.get(..16)?, 16).ok()?;
+            let name = l.get(17..)?.trim_end_matches('\r');
+            (!name.is_empty()).then(|| (hash, name.to_string()))
+        })
+        .collect();
+    // Stable, so dedup keeps the first name.
+    out.sort_by_key(|(h, _)| *h);
+    out.dedup_by_key(|(h, _)| *h);
+    out
+}
+
+/// Wwise event/bank names (`<hex id> <name>` per line, `0x` optional).
+pub fn read_wwise_hashes(path: &Path) -> HashMap<u32, String> {
+    let mut out = HashMap::new();
+    let Ok(content) = fs::read_to_string(path) else {
+        return out;
+    };
+    for line in content.lines() {
+        let Some((h, name)) = line.trim_end_matches('\r').split_once(' ') else {
+            continue;
+        };
+        if let Ok(id) = u32::from_str_radix(h.trim_start_matches("0x"), 16) {
+            out.insert(id, name.to_string());
+        }
+    }
+    out
+}
+
+fn fingerprint_line(hash_dir: &Path, file: &str) -> String {
+    match fs::read(hash_dir.join(file)) {
+        Ok(data) => format!("{}|{}|{:016x}", file, data.len(), xxh64(&data, 0)),
+        Err(_) => format!("{}|missing", file),
+    }
+}
+
+fn fill<'a, K: AsRef<[u8]>>(
+    db: Database<Bytes, Str>,
+    wtxn: &mut heed::RwTxn,
+    entries: impl IntoIterator<Item = (K, &'a str)>,
+) -> Result<(), String> {
+    for (key, name) in entries {
+        db.put(wtxn, key.as_ref(), name).map_err(|e| format!("Failed LMDB put: {}", e))?;
+    }
+    Ok(())
+}
+
+/// What a build has to do: the fingerprint lines of the lists and whether the
+/// DB must be recreated.
+struct Plan {
+    current: Vec<(&'static str, String)>,
+    full: bool,
+    rebuilt: Vec<&'static str>,
+}
+
+fn plan_build(hash_dir: &Path) -> Plan {
+    let dir = lmdb_dir(hash_dir);
+    let files = PATH_TABLES.iter().map(|(_, f)| *f).chain([WWISE_HASH_FILE]);
+    let current: Vec<(&'static str, String)> = files.map(|f| (f, fingerprint_line(hash_dir, f))).collect();
+    let stored = fs::read_to_string(dir.join(FINGERPRINT_FILE)).unwrap_or_default();
+    let outdated = !dir.join("data.mdb").exists() || stored.lines().next() != Some(LAYOUT);
+    let stored: HashMap<&str, &str> = stored.lines().skip(1).filter_map(|l| Some((l.split('|').next()?, l))).collect();
+    let differs = |file: &str| current.iter().any(|(f, line)| *f == file && stored.get(f) != Some(&line.as_str()));
+    // LMDB keeps the named tables' records in the main table, so the combined
+    // view can't be cleared in place: a change to its lists rebuilds everything.
+    let full = outdated || COMBINED_FILES.iter().any(|f| differs(f));
+    let changed = |file: &str| full || differs(file);
+
+    let mut rebuilt: Vec<&'static str> = PATH_TABLES.iter().filter(|(_, f)| changed(f)).map(|(t, _)| *t).collect();
+    if full {
+        rebuilt.push(COMBINED_TABLE);
+    }
+    if changed(WWISE_HASH_FILE) {
+        rebuilt.push(WWISE_TABLE);
+    }
+    Plan { current, full, rebuilt }
+}
+
+/// Build or update `hashes.lmdb` in `hash_dir` from its hash lists. Returns the
+/// tables that were rewritten; empty when the DB was already up to date.
+/// Updating a table works alongside readers; a rebuild from scratch waits until
+/// no `HashDb` is open, so callers in this process must drop theirs first.
+pub fn build(hash_dir: &Path) -> Result<Vec<&'static str>, String> {
+    let shared = lock(hash_dir, false)?;
+    let mut plan = plan_build(hash_dir);
+    let _lock = if plan.full {
+        // There's no upgrading a shared lock; the DB may have been rebuilt by
+        // someone else in between, so plan again.
+        drop(shared);
+        let exclusive = lock(hash_dir, true)?;
+        plan = plan_build(hash_dir);
+        exclusive
+    } else {
+        shared
+    };
+    let Plan { current, full, rebuilt } = plan;
+    if rebuilt.is_empty() {
+        return Ok(rebuilt);
+    }
+
+    let dir = lmdb_dir(hash_dir);
+    if full && dir.exists() {
+        // heed keeps every env it opened until it is closed, and would hand the
+        // old one, still mapping the deleted file, back for the new DB.
+        if dir.join("data.mdb").exists() && !open_env(&dir)?.prepare_for_closing().wait_timeout(CLOSE_TIMEOUT) {
+            return Err(format!("Hash DB {} is still in use", dir.display()));
+        }
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))?;
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let env = open_env(&dir)?;
+    // Reader slots left behind by crashed processes pin old pages.
+    let _ = env.clear_stale_readers();
+    let mut wtxn = env.write_txn().map_err(|e| format!("Failed to start LMDB write transaction: {}", e))?;
+    let table = |wtxn: &mut heed::RwTxn, name: Option<&str>| {
+        let db = env
+            .create_database::<Bytes, Str>(wtxn, name)
+            .map_err(|e| format!("Failed to create LMDB table {}: {}", name.unwrap_or(COMBINED_TABLE), e))?;
+        // Never the main table: it also holds the named tables' records.
+        if let Some(name) = name {
+            db.clear(wtxn).map_err(|e| format!("Failed to clear LMDB table {}: {}", name, e))?;
+        }
+        Ok::<_, String>(db)
+    };
+
+    let mut lists: HashMap<&str, Vec<(u64, String)>> = HashMap::new();
+    for (name, file) in PATH_TABLES.iter().filter(|(t, _)| rebuilt.contains(t)) {
+        let entries = read_path_entries(&hash_dir.join(file));
+        let db = table(&mut wtxn, Some(name))?;
+        fill(db, &mut wtxn, entries.iter().map(|(h, n)| (h.to_be_bytes(), n.as_str())))?;
+        lists.insert(file, entries);
+    }
+    if full {
+        let mut entries: Vec<&(u64, String)> = COMBINED_FILES.iter().flat_map(|f| &lists[f]).collect();
+        // Stable, so the earlier list wins a collision.
+        entries.sort_by_key(|(h, _)| *h);
+        entries.dedup_by_key(|(h, _)| *h);
+        let db = table(&mut wtxn, None)?;
+        fill(db, &mut wtxn, entries.into_iter().map(|(h, n)| (h.to_be_bytes(), n.as_str())))?;
+    }
+    if rebuilt.contains(&WWISE_TABLE) {
+        let mut entries: Vec<(u32, String)> = read_wwise_hashes(&hash_dir.join(WWISE_HASH_FILE)).into_iter().collect();
+        entries.sort_unstable();
+        let db = table(&mut wtxn, Some(WWISE_TABLE))?;
+        fill(db, &mut wtxn, entries.iter().map(|(id, n)| (id.to_be_bytes(), n.as_str())))?;
+    }
+    wtxn.commit().map_err(|e| format!("Failed LMDB commit: {}", e))?;
+
+    let mut fingerprint = format!("{}\n", LAYOUT);
+    for (_, line) in &current {
+        fingerprint.push_str(line);
+        fingerprint.push('\n');
+    }
+    let path = dir.join(FINGERPRINT_FILE);
+    write_retrying(&path, fingerprint.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(rebuilt)
+}
+
+/// Rewrite `hashes.lmdb` without its free pages (`mdb_env_copy2` with
+/// `MDB_CP_COMPACT`) and swap the copy in. Waits until no `HashDb` is open, so
+/// callers in this process must drop theirs first.
+pub fn compact(hash_dir: &Path) -> Result<Compacted, String> {
+    let _lock = lock(hash_dir, true)?;
+    let dir = lmdb_dir(hash_dir);
+    let data = dir.join("data.mdb");
+    let size_before = fs::metadata(&data).map_err(|e| format!("Failed to read {}: {}", data.display(), e))?.len();
+    let tmp = dir.join("data.mdb.compact");
+    // Left over from an interrupted compaction.
+    let _ = fs::remove_file(&tmp);
+
+    let env = open_env(&dir)?;
+    let copied = env
+        .copy_to_file(&tmp, CompactionOption::Enabled)
+        .map_err(|e| e.to_string())
+        .and_then(|f| f.sync_all().map_err(|e| e.to_string()));
+    // The old file has to be unmapped before it is replaced (Windows), and no
+    // handle may keep reading it afterwards.
+    let closed = env.prepare_for_closing().wait_timeout(CLOSE_TIMEOUT);
+    let swapped = match (copied, closed) {
+        (Err(e), _) => Err(format!("Failed to compact {}: {}", dir.display(), e)),
+        (Ok(()), false) => Err(format!("Hash DB {} is still in use", dir.display())),
+        (Ok(()), true) => rename_retrying(&tmp, &data).map_err(|e| format!("Failed to replace {}: {}", data.display(), e)),
+    };
+    if let Err(e) = swapped {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    let size_after = fs::metadata(&data).map_err(|e| format!("Failed to read {}: {}", data.display(), e))?.len();
+    Ok(Compacted { size_before, size_after })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::{HashResolver, LmdbResolver};
+
+    fn hash_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quartz_core_hash_db_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(GAME_HASH_FILE), "0000000000000001 game/one\n0000000000000002 game/two\n").unwrap();
+        fs::write(dir.join(LCU_HASH_FILE), "0000000000000002 lcu/two\n0000000000000003 lcu/three\n").unwrap();
+        fs::write(dir.join(EXTRACTED_HASH_FILE), "0000000000000004 extracted/four\n").unwrap();
+        dir
+    }
+
+    fn lookup(db: &HashDb, table: Option<&'static str>, hashes: &[u64]) -> Vec<Option<String>> {
+        match table {
+            Some(name) => LmdbResolver::table(db, name).unwrap().lookup(hashes),
+            None => LmdbResolver::new(db).lookup(hashes),
+        }
+    }
+
+    #[test]
+    fn test_build_writes_per_source_tables() {
+        let dir = hash_dir("tables");
+        let rebuilt = build(&dir).unwrap();
+        assert_eq!(rebuilt, [GAME_TABLE, LCU_TABLE, EXTRACTED_TABLE, COMBINED_TABLE, WWISE_TABLE]);
+
+        let fingerprint = fs::read_to_string(lmdb_dir(&dir).join(FINGERPRINT_FILE)).unwrap();
+        assert_eq!(fingerprint.lines().next(), Some(LAYOUT));
+        assert!(fingerprint.contains(&format!("{}|missing", WWISE_HASH_FILE)));
+
+        let db = open(&dir).unwrap().unwrap();
+        let hashes = [1, 2, 3, 4];
+        let s = |n: &str| Some(n.to_string());
+        assert_eq!(lookup(&db, None, &hashes), [s("game/one"), s("game/two"), s("lcu/three"), None]);
+        assert_eq!(lookup(&db, Some(GAME_TABLE), &hashes), [s("game/one"), s("game/two"), None, None]);
+        assert_eq!(lookup(&db, Some(LCU_TABLE), &hashes), [None, s("lcu/two"), s("lcu/three"), None]);
+        assert_eq!(lookup(&db, Some(EXTRACTED_TABLE), &hashes), [None, None, None, s("extracted/four")]);
+        drop(db);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_build_rewrites_only_changed_lists() {
+        let dir = hash_dir("partial");
+        build(&dir).unwrap();
+        assert!(build(&dir).unwrap().is_empty(), "an unchanged dir rebuilds nothing");
+
+        fs::write(dir.join(EXTRACTED_HASH_FILE), "0000000000000005 extracted/five\n").unwrap();
+        assert_eq!(build(&dir).unwrap(), [EXTRACTED_TABLE]);
+        let db = open(&dir).unwrap().unwrap();
+        assert_eq!(lookup(&db, Some(EXTRACTED_TABLE), &[4, 5]), [None, Some("extracted/five".to_string())]);
+        assert_eq!(lookup(&db, None, &[1]), [Some("game/one".to_string())]);
+        drop(db);
+
+        fs::write(dir.join(LCU_HASH_FILE), "0000000000000003 lcu/renamed\n").unwrap();
+        let rebuilt = build(&dir).unwrap();
+        assert!(rebuilt.contains(&COMBINED_TABLE) && rebuilt.contains(&GAME_TABLE));
+        let db = open(&dir).unwrap().unwrap();
+        assert_eq!(lookup(&db, None, &[2, 3]), [Some("game/two".to_string()), Some("lcu/renamed".to_string())]);
+        drop(db);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_build_replaces_db_from_older_layout() {
+        let dir = hash_dir("layout");
+        build(&dir).unwrap();
+        let path = lmdb_dir(&dir).join(FINGERPRINT_FILE);
+        let fingerprint = fs::read_to_string(&path).unwrap();
+        // A fingerprint written before the per-list tables had no layout line.
+        fs::write(&path, fingerprint.lines().skip(1).collect::<Vec<_>>().join("\n")).unwrap();
+
+        assert!(build(&dir).unwrap().contains(&COMBINED_TABLE));
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().next(), Some(LAYOUT));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compact_keeps_entries() {
+        let dir = hash_dir("compact");
+        build(&dir).unwrap();
+        for round in 0..3 {
+            let list: String = (0..2000).map(|i| format!("{:016x} extracted/{}/{}\n", 0x1000 + i, round, i)).collect();
+            fs::write(dir.join(EXTRACTED_HASH_FILE), list).unwrap();
+            build(&dir).unwrap();
+        }
+
+        let compacted = compact(&dir).unwrap();
+        assert!(compacted.size_after <= compacted.size_before);
+        let db = open(&dir).unwrap().unwrap();
+        assert_eq!(lookup(&db, None, &[1]), [Some("game/one".to_string())]);
+        assert_eq!(lookup(&db, Some(EXTRACTED_TABLE), &[0x1000]), [Some("extracted/2/0".to_string())]);
+        drop(db);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_open_db_holds_shared_lock() {
+        let dir = hash_dir("lock");
+        build(&dir).unwrap();
+        let other = fs::File::open(dir.join(LOCK_FILE)).unwrap();
+
+        let db = open(&dir).unwrap().unwrap();
+        let second = open(&dir).unwrap().unwrap();
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)), "an open DB must keep rebuilds out");
+        assert!(other.try_lock_shared().is_ok(), "readers share the lock");
+        other.unlock().unwrap();
+
+        drop((db, second));
+        assert!(other.try_lock().is_ok());
+        other.unlock().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_snapshot_sees_one_state() {
+        let dir = hash_dir("snapshot");
+        build(&dir).unwrap();
+        let db = open(&dir).unwrap().unwrap();
+        let rtxn = db.snapshot().unwrap();
+        let pinned = LmdbResolver::table(&db, EXTRACTED_TABLE).unwrap().in_snapshot(&rtxn);
+
+        fs::write(dir.join(EXTRACTED_HASH_FILE), "0000000000000004 extracted/renamed\n").unwrap();
+        assert_eq!(build(&dir).unwrap(), [EXTRACTED_TABLE]);
+
+        assert_eq!(pinned.lookup(&[4]), [Some("extracted/four".to_string())]);
+        assert_eq!(lookup(&db, Some(EXTRACTED_TABLE), &[4]), [Some("extracted/renamed".to_string())]);
+        drop(rtxn);
+        drop(db);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,14 @@
+//! Helpers shared by the native addon (`wad_indexer`) and `quartz_cli`:
+//! path hashing and hash-list parsing, WAD-relative path handling, hash
+//! discovery in bin/skn chunks, the hash LMDB and layered hash name
+//! resolution, bin <-> ritobin text conversion, bin path rewriting and
+//! project checkpoints.
+
+pub mod checkpoints;
+pub mod hash;
+pub mod hash_db;
+pub mod paths;
+pub mod repath;
+pub mod resolver;
+pub mod ritobin;
+pub mod scan;
@@ -0,0 +1,159 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Forward slashes, no leading slash. Case is left alone.
+pub fn normalize_rel_path(v: &str) -> String {
+    v.replace('\\', "/").trim_start_matches('/').to_string()
+}
+
+/// True when `path` stays inside the directory it is joined onto: not
+/// absolute and without `..`, root or drive-prefix components. Windows forms
+/// (`C:`, a leading `\`, `..\`) are refused on every platform, since archive
+/// entries come from anywhere and are extracted on Windows.
+pub fn is_safe_relative_path(path: &str) -> bool {
+    let p = Path::new(path);
+    if p.is_absolute() || path.starts_with(['/', '\\']) {
+        return false;
+    }
+    let b = path.as_bytes();
+    if b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':' {
+        return false;
+    }
+    if path.split(['/', '\\']).any(|c| c == "..") {
+        return false;
+    }
+    p.components()
+        .all(|c| !matches!(c, Component::ParentDir | Component::RootDir | Component::Prefix(_)))
+}
+
+/// Recursively list files under `dir` as (relative "/"-separated path, absolute path).
+pub fn collect_files(dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut out = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(d) = stack.pop() {
+        let entries = fs::read_dir(&d)
+            .map_err(|e| format!("Failed to read directory {}: {}", d.display(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let p = entry.path();
+            if p.is_dir() {
+                stack.push(p);
+                continue;
+            }
+            let rel = p.strip_prefix(dir)
+                .map_err(|e| format!("Failed to build relative path for {}: {}", p.display(), e))?
+                .to_string_lossy()
+                .replace('\\', "/");
+            out.push((rel, p));
+        }
+    }
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(out)
+}
+
+/// Backoff between attempts when a write hits a transient lock (~400 ms total).
+const LOCK_RETRY_DELAYS_MS: &[u64] = &[10, 25, 50, 100, 200];
+
+/// Errors antivirus scanners and search indexers cause while they briefly hold
+/// a freshly written file: ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION,
+/// ERROR_LOCK_VIOLATION and ERROR_USER_MAPPED_FILE.
+#[cfg(windows)]
+fn is_transient_lock(e: &io::Error) -> bool {
+    matches!(e.raw_os_error(), Some(5 | 32 | 33 | 1224))
+}
+
+#[cfg(not(windows))]
+fn is_transient_lock(_e: &io::Error) -> bool {
+    false
+}
+
+/// Run `op`, retrying with a short bounded backoff while it fails with a
+/// transient lock error, so those don't show up as sporadic skipped files.
+pub fn retry_on_lock<T>(mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut delays = LOCK_RETRY_DELAYS_MS.iter();
+    loop {
+        match op() {
+            Err(e) if is_transient_lock(&e) => match delays.next() {
+                Some(ms) => std::thread::sleep(Duration::from_millis(*ms)),
+                None => return Err(e),
+            },
+            result => return result,
+        }
+    }
+}
+
+pub fn write_retrying(path: &Path, data: &[u8]) -> io::Result<()> {
+    retry_on_lock(|| fs::write(path, data))
+}
+
+pub fn rename_retrying(from: &Path, to: &Path) -> io::Result<()> {
+    retry_on_lock(|| fs::rename(from, to))
+}
+
+/// Replace the folder `dest` with the finished folder `staging`. An existing
+/// `dest` is moved aside first and put back if the swap fails.
+pub fn swap_dir_in(staging: &Path, dest: &Path) -> Result<(), String> {
+    if !dest.exists() {
+        return rename_retrying(staging, dest).map_err(|e| format!("Failed to move {} into place: {}", staging.display(), e));
+    }
+    let mut old_name = dest.file_name().unwrap_or_default().to_os_string();
+    old_name.push(".old");
+    let old = dest.with_file_name(old_name);
+    let _ = fs::remove_dir_all(&old);
+    rename_retrying(dest, &old).map_err(|e| format!("Failed to replace {}: {}", dest.display(), e))?;
+    if let Err(e) = rename_retrying(staging, dest) {
+        let _ = rename_retrying(&old, dest);
+        return Err(format!("Failed to move {} into place: {}", staging.display(), e));
+    }
+    let _ = fs::remove_dir_all(&old);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_rel_path() {
+        assert_eq!(normalize_rel_path("\\Assets\\Characters/Ahri.bin"), "Assets/Characters/Ahri.bin");
+        assert_eq!(normalize_rel_path("//data/a.bin"), "data/a.bin");
+        assert_eq!(normalize_rel_path("data/a.bin"), "data/a.bin");
+    }
+
+    #[test]
+    fn test_is_safe_relative_path() {
+        assert!(is_safe_relative_path("assets/characters/ahri/ahri.skn"));
+        assert!(is_safe_relative_path("data/..hidden/a.bin"));
+        assert!(is_safe_relative_path("./data/a.bin"));
+
+        assert!(!is_safe_relative_path(".."));
+        assert!(!is_safe_relative_path("../outside.txt"));
+        assert!(!is_safe_relative_path("data/../../outside.txt"));
+        assert!(!is_safe_relative_path("data\\..\\..\\outside.txt"));
+        assert!(!is_safe_relative_path("/etc/passwd"));
+        assert!(!is_safe_relative_path("\\Windows\\System32"));
+        assert!(!is_safe_relative_path("\\\\server\\share\\a.txt"));
+        assert!(!is_safe_relative_path("C:/Windows/System32/a.dll"));
+        assert!(!is_safe_relative_path("c:relative.txt"));
+        assert!(!is_safe_relative_path(&normalize_rel_path("C:\\Windows\\a.dll")));
+    }
+
+    #[test]
+    fn test_collect_files_sorted() {
+        let dir = std::env::temp_dir().join(format!("quartz_core_collect_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for rel in ["b.txt", "a/z.txt", "a/b/c.txt", "A.txt"] {
+            let path = dir.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, rel).unwrap();
+        }
+
+        let files = collect_files(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        let rels: Vec<&str> = files.iter().map(|(r, _)| r.as_str()).collect();
+        assert_eq!(rels, ["A.txt", "a/b/c.txt", "a/z.txt", "b.txt"]);
+        assert!(files.iter().all(|(r, p)| p.ends_with(r)));
+    }
+}
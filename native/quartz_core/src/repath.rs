@@ -0,0 +1,65 @@
+//! Rewriting asset paths inside bins: every string property (including lists,
+//! optionals, maps and nested structs) is offered to a mapping function, and
+//! the ones it returns a new path for are replaced.
+
+use ltk_meta::property::values::{self, Container, Optional};
+use ltk_meta::{Bin, PropertyValueEnum};
+
+/// `path` with the `from` prefix swapped for `to`, compared case-insensitively.
+/// Asset paths are case-insensitive in game, so the result is lowercased.
+pub fn replace_prefix(from: &str, to: &str, path: &str) -> Option<String> {
+    let lower = path.to_ascii_lowercase();
+    lower
+        .strip_prefix(&from.to_ascii_lowercase())
+        .map(|rest| format!("{}{}", to.to_ascii_lowercase(), rest))
+}
+
+fn rewrite_struct(s: &mut values::Struct, map: &dyn Fn(&str) -> Option<String>) -> u32 {
+    s.properties.values_mut().map(|p| rewrite_value(&mut p.value, map)).sum()
+}
+
+fn rewrite_string(v: &mut values::String, map: &dyn Fn(&str) -> Option<String>) -> u32 {
+    match map(&v.value) {
+        Some(p) => {
+            v.value = p;
+            1
+        }
+        None => 0,
+    }
+}
+
+/// Rewrite the strings under `value` with `map`; returns how many changed.
+pub fn rewrite_value(value: &mut PropertyValueEnum, map: &dyn Fn(&str) -> Option<String>) -> u32 {
+    use PropertyValueEnum as P;
+    match value {
+        P::String(v) => rewrite_string(v, map),
+        P::Optional(Optional::String(Some(v))) => rewrite_string(v, map),
+        P::Struct(s) => rewrite_struct(s, map),
+        P::Embedded(e) => rewrite_struct(&mut e.0, map),
+        P::Optional(Optional::Struct(Some(s))) => rewrite_struct(s, map),
+        P::Optional(Optional::Embedded(Some(e))) => rewrite_struct(&mut e.0, map),
+        P::Container(c) | P::UnorderedContainer(values::UnorderedContainer(c)) => match c {
+            Container::String { items, .. } => items.iter_mut().map(|v| rewrite_string(v, map)).sum(),
+            Container::Struct { items, .. } => items.iter_mut().map(|s| rewrite_struct(s, map)).sum(),
+            Container::Embedded { items, .. } => items.iter_mut().map(|e| rewrite_struct(&mut e.0, map)).sum(),
+            _ => 0,
+        },
+        P::Map(m) => {
+            let (key_kind, value_kind) = (m.key_kind(), m.value_kind());
+            let mut entries = std::mem::take(m).into_entries();
+            let n = entries.iter_mut().map(|(_, v)| rewrite_value(v, map)).sum();
+            // Kinds are unchanged, so rebuilding can't fail.
+            *m = values::Map::new(key_kind, value_kind, entries).unwrap_or_default();
+            n
+        }
+        _ => 0,
+    }
+}
+
+/// Rewrite the strings of every object in `bin`; returns how many changed.
+pub fn rewrite_bin(bin: &mut Bin, map: &dyn Fn(&str) -> Option<String>) -> u32 {
+    bin.objects
+        .values_mut()
+        .map(|o| o.properties.values_mut().map(|p| rewrite_value(&mut p.value, map)).sum::<u32>())
+        .sum()
+}
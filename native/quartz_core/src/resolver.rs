@@ -0,0 +1,313 @@
+//! Naming path hashes from several sources with a fixed precedence, so every
+//! tool resolves a hash to the same name and can tell where the name came from.
+
+use std::collections::HashMap;
+
+use heed::types::{Bytes, Str};
+
+/// User-maintained hash list in the hash dir; its names win over every other source.
+pub const CUSTOM_HASH_FILE: &str = "hashes.custom.txt";
+
+/// Which layer produced a name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashSource {
+    /// `hashes.custom.txt`.
+    Custom,
+    /// The LMDB built from the CDTB hash lists.
+    Lmdb,
+    /// An in-memory table, e.g. `hashes.extracted.txt`.
+    Table,
+    /// The `game` table of the LMDB (`hashes.game.txt`).
+    Game,
+    /// The `lcu` table of the LMDB, or `hashes.lcu.txt`.
+    Lcu,
+    /// `hashes.extracted.txt`, names found by earlier extractions.
+    Extracted,
+    /// Nothing knew the hash; the name is its hex form.
+    Hex,
+}
+
+impl HashSource {
+    /// A source a caller can ask for by name: "custom", "game", "lcu" or "extracted".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "custom" => Some(HashSource::Custom),
+            "game" => Some(HashSource::Game),
+            "lcu" => Some(HashSource::Lcu),
+            "extracted" => Some(HashSource::Extracted),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HashSource::Custom => "custom",
+            HashSource::Lmdb => "lmdb",
+            HashSource::Table => "table",
+            HashSource::Game => "game",
+            HashSource::Lcu => "lcu",
+            HashSource::Extracted => "extracted",
+            HashSource::Hex => "hex",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Resolved {
+    pub name: String,
+    pub source: HashSource,
+}
+
+pub trait HashResolver {
+    /// Names for `hashes`, in order; `None` where this resolver has no name.
+    fn lookup(&self, hashes: &[u64]) -> Vec<Option<String>>;
+}
+
+impl HashResolver for HashMap<u64, String> {
+    fn lookup(&self, hashes: &[u64]) -> Vec<Option<String>> {
+        hashes.iter().map(|h| self.get(h).cloned()).collect()
+    }
+}
+
+/// A path table of a hash LMDB (8-byte big-endian keys), read in one
+/// transaction per lookup batch: the combined main table, or one list's table.
+pub struct LmdbResolver<'e> {
+    env: &'e heed::Env,
+    table: Option<&'static str>,
+    snapshot: Option<&'e heed::RoTxn<'e>>,
+}
+
+impl<'e> LmdbResolver<'e> {
+    pub fn new(env: &'e heed::Env) -> Self {
+        LmdbResolver { env, table: None, snapshot: None }
+    }
+
+    /// The named table of one hash list (see `hash_db`); `None` when the DB
+    /// was built before the table existed.
+    pub fn table(env: &'e heed::Env, table: &'static str) -> Option<Self> {
+        let rtxn = env.read_txn().ok()?;
+        env.open_database::<Bytes, Str>(&rtxn, Some(table)).ok()??;
+        Some(LmdbResolver { env, table: Some(table), snapshot: None })
+    }
+
+    /// Read every batch from `rtxn` instead of a fresh transaction, so lookups
+    /// spread over several batches see one state of the DB even while it is
+    /// being updated.
+    pub fn in_snapshot(mut self, rtxn: &'e heed::RoTxn<'e>) -> Self {
+        self.snapshot = Some(rtxn);
+        self
+    }
+
+    fn lookup_in(&self, rtxn: &heed::RoTxn, hashes: &[u64]) -> Vec<Option<String>> {
+        let Ok(Some(db)) = self.env.open_database::<Bytes, Str>(rtxn, self.table) else {
+            return vec![None; hashes.len()];
+        };
+        hashes
+            .iter()
+            .map(|h| db.get(rtxn, &h.to_be_bytes()[..]).ok().flatten().map(str::to_string))
+            .collect()
+    }
+}
+
+impl HashResolver for LmdbResolver<'_> {
+    fn lookup(&self, hashes: &[u64]) -> Vec<Option<String>> {
+        if let Some(rtxn) = self.snapshot {
+            return self.lookup_in(rtxn, hashes);
+        }
+        match self.env.read_txn() {
+            Ok(rtxn) => self.lookup_in(&rtxn, hashes),
+            Err(_) => vec![None; hashes.len()],
+        }
+    }
+}
+
+/// Merge what each source answered for `hashes` (one `lookup` result per
+/// source, highest precedence first): the winning name of every hash, plus the
+/// names lower sources give it where they disagree, ignoring case. Unlike
+/// `LayeredResolver`, every source is expected to have been asked about every hash.
+pub fn resolve_each(hashes: &[u64], answers: &[(HashSource, Vec<Option<String>>)]) -> Vec<(Resolved, Vec<Resolved>)> {
+    (0..hashes.len())
+        .map(|i| {
+            let mut found = answers
+                .iter()
+                .filter_map(|(source, names)| names[i].clone().map(|name| Resolved { name, source: *source }));
+            let Some(winner) = found.next() else {
+                return (
+                    Resolved {
+                        name: format!("{:016x}", hashes[i]),
+                        source: HashSource::Hex,
+                    },
+                    Vec::new(),
+                );
+            };
+            let conflicts = found.filter(|r| !r.name.eq_ignore_ascii_case(&winner.name)).collect();
+            (winner, conflicts)
+        })
+        .collect()
+}
+
+/// Resolvers asked in the order they were added; each only sees the hashes
+/// the layers above it missed. Unknown hashes fall back to 16-digit hex.
+#[derive(Default)]
+pub struct LayeredResolver<'a> {
+    layers: Vec<(HashSource, &'a dyn HashResolver)>,
+}
+
+impl<'a> LayeredResolver<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn layer(mut self, source: HashSource, resolver: &'a dyn HashResolver) -> Self {
+        self.layers.push((source, resolver));
+        self
+    }
+
+    pub fn resolve(&self, hashes: &[u64]) -> Vec<Resolved> {
+        let mut out: Vec<Option<Resolved>> = vec![None; hashes.len()];
+        let mut pending: Vec<usize> = (0..hashes.len()).collect();
+        for (source, resolver) in &self.layers {
+            if pending.is_empty() {
+                break;
+            }
+            let batch: Vec<u64> = pending.iter().map(|&i| hashes[i]).collect();
+            let names = resolver.lookup(&batch);
+            let mut missed = Vec::new();
+            for (i, name) in pending.into_iter().zip(names) {
+                match name {
+                    Some(name) => out[i] = Some(Resolved { name, source: *source }),
+                    None => missed.push(i),
+                }
+            }
+            pending = missed;
+        }
+        out.into_iter()
+            .zip(hashes)
+            .map(|(r, h)| {
+                r.unwrap_or_else(|| Resolved {
+                    name: format!("{:016x}", h),
+                    source: HashSource::Hex,
+                })
+            })
+            .collect()
+    }
+
+    pub fn resolve_names(&self, hashes: &[u64]) -> Vec<String> {
+        self.resolve(hashes).into_iter().map(|r| r.name).collect()
+    }
+}
+
+impl HashResolver for LayeredResolver<'_> {
+    fn lookup(&self, hashes: &[u64]) -> Vec<Option<String>> {
+        self.resolve(hashes)
+            .into_iter()
+            .map(|r| (r.source != HashSource::Hex).then_some(r.name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Records the hashes it was asked about.
+    struct Recording {
+        names: HashMap<u64, String>,
+        asked: RefCell<Vec<u64>>,
+    }
+
+    impl HashResolver for Recording {
+        fn lookup(&self, hashes: &[u64]) -> Vec<Option<String>> {
+            self.asked.borrow_mut().extend_from_slice(hashes);
+            self.names.lookup(hashes)
+        }
+    }
+
+    fn table(entries: &[(u64, &str)]) -> HashMap<u64, String> {
+        entries.iter().map(|(h, n)| (*h, n.to_string())).collect()
+    }
+
+    #[test]
+    fn test_layer_precedence() {
+        let custom = table(&[(1, "custom/one")]);
+        let lmdb = table(&[(1, "lmdb/one"), (2, "lmdb/two")]);
+        let extracted = table(&[(2, "extracted/two"), (3, "extracted/three")]);
+        let resolver = LayeredResolver::new()
+            .layer(HashSource::Custom, &custom)
+            .layer(HashSource::Lmdb, &lmdb)
+            .layer(HashSource::Table, &extracted);
+
+        let resolved = resolver.resolve(&[1, 2, 3, 0xabc]);
+        let got: Vec<(&str, HashSource)> = resolved.iter().map(|r| (r.name.as_str(), r.source)).collect();
+        assert_eq!(
+            got,
+            [
+                ("custom/one", HashSource::Custom),
+                ("lmdb/two", HashSource::Lmdb),
+                ("extracted/three", HashSource::Table),
+                ("0000000000000abc", HashSource::Hex),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lower_layers_only_see_misses() {
+        let custom = table(&[(1, "custom/one")]);
+        let lower = Recording { names: table(&[(1, "lower/one"), (2, "lower/two")]), asked: RefCell::new(Vec::new()) };
+        let resolver = LayeredResolver::new()
+            .layer(HashSource::Custom, &custom)
+            .layer(HashSource::Table, &lower);
+
+        assert_eq!(resolver.resolve_names(&[1, 2, 3]), ["custom/one", "lower/two", "0000000000000003"]);
+        assert_eq!(*lower.asked.borrow(), [2, 3]);
+    }
+
+    #[test]
+    fn test_nested_resolver_leaves_hex_unresolved() {
+        let inner_table = table(&[(1, "inner/one")]);
+        let inner = LayeredResolver::new().layer(HashSource::Lmdb, &inner_table);
+        assert_eq!(inner.lookup(&[1, 2]), [Some("inner/one".to_string()), None]);
+
+        let fallback = table(&[(2, "fallback/two")]);
+        let outer = LayeredResolver::new()
+            .layer(HashSource::Lmdb, &inner)
+            .layer(HashSource::Table, &fallback);
+        assert_eq!(outer.resolve_names(&[1, 2]), ["inner/one", "fallback/two"]);
+    }
+
+    #[test]
+    fn test_source_names() {
+        for source in [HashSource::Custom, HashSource::Game, HashSource::Lcu, HashSource::Extracted] {
+            assert_eq!(HashSource::from_name(source.as_str()), Some(source));
+        }
+        assert_eq!(HashSource::from_name("lmdb"), None);
+        assert_eq!(HashSource::from_name("hex"), None);
+    }
+
+    #[test]
+    fn test_resolve_each_reports_disagreements() {
+        let answers = [
+            (HashSource::Custom, vec![None, Some("custom/two".to_string()), None]),
+            (HashSource::Game, vec![Some("game/one".to_string()), Some("game/two".to_string()), None]),
+            (HashSource::Lcu, vec![Some("GAME/ONE".to_string()), None, None]),
+        ];
+        let resolved = resolve_each(&[1, 2, 3], &answers);
+
+        assert_eq!((resolved[0].0.name.as_str(), resolved[0].0.source), ("game/one", HashSource::Game));
+        assert!(resolved[0].1.is_empty(), "a case-only difference is not a conflict");
+        assert_eq!((resolved[1].0.name.as_str(), resolved[1].0.source), ("custom/two", HashSource::Custom));
+        let conflicts: Vec<(&str, HashSource)> = resolved[1].1.iter().map(|r| (r.name.as_str(), r.source)).collect();
+        assert_eq!(conflicts, [("game/two", HashSource::Game)]);
+        assert_eq!((resolved[2].0.name.as_str(), resolved[2].0.source), ("0000000000000003", HashSource::Hex));
+    }
+
+    #[test]
+    fn test_resolve_each_only_uses_given_sources() {
+        let answers = [(HashSource::Lcu, vec![None, Some("lcu/two".to_string())])];
+        let resolved = resolve_each(&[1, 2], &answers);
+        assert_eq!(resolved[0].0.source, HashSource::Hex);
+        assert_eq!((resolved[1].0.name.as_str(), resolved[1].0.source), ("lcu/two", HashSource::Lcu));
+    }
+}
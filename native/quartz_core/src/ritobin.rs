@@ -0,0 +1,48 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Cursor};
+use std::path::Path;
+
+use ltk_meta::Bin;
+use ltk_ritobin::{parse, write_with_hashes, HashMapProvider};
+
+/// Bin hashes from a hash directory; missing directories give an empty provider.
+pub fn load_hash_provider(dir: Option<&Path>) -> HashMapProvider {
+    let mut hashes = HashMapProvider::new();
+    if let Some(dir) = dir.filter(|d| d.exists()) {
+        hashes.load_from_directory(dir);
+    }
+    hashes
+}
+
+pub fn bin_from_bytes(data: &[u8]) -> Result<Bin, String> {
+    Bin::from_reader(&mut Cursor::new(data)).map_err(|e| format!("Failed to parse bin: {}", e))
+}
+
+pub fn bin_to_bytes(bin: &Bin) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    bin.to_writer(&mut Cursor::new(&mut data))
+        .map_err(|e| format!("Failed to write bin: {}", e))?;
+    Ok(data)
+}
+
+pub fn read_bin(path: &Path) -> Result<Bin, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    Bin::from_reader(&mut BufReader::new(file))
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn write_bin(path: &Path, bin: &Bin) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    bin.to_writer(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Ritobin text of a bin, naming hashes known to `hashes`.
+pub fn bin_to_text(bin: &Bin, hashes: &HashMapProvider) -> Result<String, String> {
+    write_with_hashes(bin, hashes).map_err(|e| format!("Failed to write ritobin text: {}", e))
+}
+
+/// Bin tree of ritobin text.
+pub fn text_to_bin(text: &str) -> Result<Bin, String> {
+    Ok(parse(text).map_err(|e| format!("Failed to parse ritobin text: {}", e))?.to_bin_tree())
+}
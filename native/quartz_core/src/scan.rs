@@ -0,0 +1,111 @@
+use crate::hash::{fnv1a_lower, xxhash_path};
+
+/// Top-level folders of game asset paths.
+pub const PATH_PREFIXES: &[&[u8]] = &[
+    b"assets/",
+    b"data/",
+    b"maps/",
+    b"levels/",
+    b"clientstates/",
+    b"ux/",
+    b"uiautoatlas/",
+];
+
+/// True when `s` looks like a game asset path (`assets/...`, `data/...`, ...).
+pub fn is_asset_path(s: &str) -> bool {
+    let b = s.as_bytes();
+    s.contains('/')
+        && PATH_PREFIXES
+            .iter()
+            .any(|p| b.len() >= p.len() && b[..p.len()].eq_ignore_ascii_case(p))
+}
+
+/// Asset paths (lowercased) referenced by a bin, found by scanning for
+/// length-prefixed strings that look like game paths.
+pub fn scan_bin_asset_paths(data: &[u8]) -> Vec<String> {
+    if data.len() < 4 {
+        return vec![];
+    }
+    if &data[..4] != b"PROP" && &data[..4] != b"PTCH" {
+        return vec![];
+    }
+    let mut results = Vec::new();
+    let mut i = 0usize;
+    while i + 2 <= data.len() {
+        let len = u16::from_le_bytes([data[i], data[i + 1]]) as usize;
+        if (8..=300).contains(&len) {
+            if let Some(slice) = data.get(i + 2..i + 2 + len) {
+                if let Ok(s) = std::str::from_utf8(slice) {
+                    if s.is_ascii() && is_asset_path(s) {
+                        results.push(s.to_ascii_lowercase());
+                        i += 2 + len;
+                        continue;
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+    results
+}
+
+/// `.dds` paths the game may also load at 2x/4x resolution: `dir/2x_name.dds`, `dir/4x_name.dds`.
+pub fn dds_scaled_variants(lower: &str) -> [String; 2] {
+    let slash = lower.rfind('/').map(|i| i + 1).unwrap_or(0);
+    let (dir, fname) = lower.split_at(slash);
+    [format!("{}2x_{}", dir, fname), format!("{}4x_{}", dir, fname)]
+}
+
+/// Path hashes of the assets a bin references, plus the 2x/4x variants of
+/// textures and the `.py` twin of linked bins.
+pub fn scan_bin_game_hashes(data: &[u8]) -> Vec<(u64, String)> {
+    let mut results = Vec::new();
+    for lower in scan_bin_asset_paths(data) {
+        results.push((xxhash_path(&lower), lower.clone()));
+        if lower.ends_with(".dds") {
+            for v in dds_scaled_variants(&lower) {
+                results.push((xxhash_path(&v), v));
+            }
+        }
+        if lower.ends_with(".bin") {
+            let py = format!("{}.py", &lower[..lower.len() - 4]);
+            results.push((xxhash_path(&py), py));
+        }
+    }
+    results
+}
+
+/// Submesh (material) names of a .skn and their bin hashes.
+pub fn scan_skn_bin_hashes(data: &[u8]) -> Vec<(u32, String)> {
+    if data.len() < 12 {
+        return vec![];
+    }
+    let magic = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    if magic != 0x00112233 {
+        return vec![];
+    }
+    let major = u16::from_le_bytes([data[4], data[5]]);
+    if major == 0 {
+        return vec![];
+    }
+    let range_count = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    if range_count == 0 || range_count > 256 {
+        return vec![];
+    }
+    let mut results = Vec::with_capacity(range_count);
+    let mut pos = 12usize;
+    for _ in 0..range_count {
+        if pos + 80 > data.len() {
+            break;
+        }
+        let name_bytes = &data[pos..pos + 64];
+        let null_pos = name_bytes.iter().position(|&b| b == 0).unwrap_or(64);
+        if let Ok(name) = std::str::from_utf8(&name_bytes[..null_pos]) {
+            if !name.is_empty() {
+                results.push((fnv1a_lower(name), name.to_string()));
+            }
+        }
+        pos += 80;
+    }
+    results
+}
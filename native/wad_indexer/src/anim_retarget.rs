@@ -0,0 +1,284 @@
+// ── Animation bin retargeting ────────────────────────────────────────────────
+// Repathing leaves `animations/skinX.bin` alone: its entry is named after the
+// skin slot ("Characters/Ahri/Animations/Skin3"), clips are keyed and linked by
+// name hash, and blend tables key on clip-hash pairs, none of which a path rule
+// can reach. `retargetAnimationBin` moves the graph to another skin slot or
+// champion (entry name, links to it, `.anm` paths) and renames clips through a
+// mapping table, updating every clip hash: map keys, clip references and both
+// halves of u64 blend keys. The skin bin's link to the graph is not touched;
+// repoint it to the returned `entryPath`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_meta::property::values::{self, Container, Optional};
+use ltk_meta::{Bin, PropertyValueEnum};
+use napi_derive::napi;
+use quartz_core::repath::rewrite_value;
+
+use crate::fnv1a_lower;
+use crate::freshness::{apply_repath, RepathRule};
+use crate::paths::write_retrying;
+use crate::project::skin_asset_folder;
+use crate::scripting::name_hash;
+
+#[napi(object)]
+pub struct RetargetAnimationOptions {
+  /// Champion the bin belongs to; read from a `data/characters/{champion}/animations/skinN.bin` path when omitted.
+  #[napi(js_name = "fromChampion")]
+  pub from_champion: Option<String>,
+  /// Defaults to `fromChampion`.
+  #[napi(js_name = "toChampion")]
+  pub to_champion: Option<String>,
+  /// Skin slot the bin belongs to; read from the file name when omitted.
+  #[napi(js_name = "fromSkin")]
+  pub from_skin: Option<u32>,
+  /// Defaults to `fromSkin`.
+  #[napi(js_name = "toSkin")]
+  pub to_skin: Option<u32>,
+  /// Clip renames: old clip name (or 0x hash) -> new clip name (or 0x hash).
+  #[napi(js_name = "clipMap")]
+  pub clip_map: Option<HashMap<String, String>>,
+  /// Write here instead of over the input bin.
+  #[napi(js_name = "outputPath")]
+  pub output_path: Option<String>,
+  /// Count what would change without writing.
+  #[napi(js_name = "dryRun")]
+  pub dry_run: Option<bool>,
+}
+
+#[napi(object)]
+pub struct RetargetAnimationResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Graph entry name after retargeting, e.g. "Characters/Ahri/Animations/Skin5".
+  #[napi(js_name = "entryPath")]
+  pub entry_path: Option<String>,
+  /// Where the game loads the retargeted bin from, e.g. "data/characters/ahri/animations/skin5.bin".
+  #[napi(js_name = "targetPath")]
+  pub target_path: Option<String>,
+  /// Entries renamed plus object links repointed to them.
+  #[napi(js_name = "linksRewritten")]
+  pub links_rewritten: u32,
+  /// `.anm` and other asset paths moved to the target skin folder.
+  #[napi(js_name = "pathsRewritten")]
+  pub paths_rewritten: u32,
+  /// Clip hashes rewritten (keys and references).
+  #[napi(js_name = "clipsRemapped")]
+  pub clips_remapped: u32,
+  /// Existing clips dropped because a renamed clip took their name.
+  #[napi(js_name = "clipsReplaced")]
+  pub clips_replaced: u32,
+  pub written: bool,
+}
+
+/// (champion, skin) from ".../characters/{champion}/animations/skin{N}.bin".
+fn slot_from_path(path: &Path) -> (Option<String>, Option<u32>) {
+  let lower = path.to_string_lossy().replace('\\', "/").to_ascii_lowercase();
+  let mut parts = lower.rsplit('/');
+  let skin = parts.next()
+    .and_then(|f| f.strip_prefix("skin"))
+    .and_then(|f| f.strip_suffix(".bin"))
+    .and_then(|n| n.parse().ok());
+  let champion = match (parts.next(), parts.next()) {
+    (Some("animations"), Some(champ)) if !champ.is_empty() => Some(champ.to_string()),
+    _ => None,
+  };
+  (champion, skin)
+}
+
+fn entry_path(champion: &str, skin: u32) -> String {
+  let mut chars = champion.chars();
+  let title: String = chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect();
+  format!("Characters/{}/Animations/Skin{}", title, skin)
+}
+
+struct Retarget {
+  clips: HashMap<u32, u32>,
+  /// Old and new entry hash of the graph.
+  link: Option<(u32, u32)>,
+  links: u32,
+  remapped: u32,
+  replaced: u32,
+}
+
+impl Retarget {
+  fn hash(&mut self, h: &mut u32) -> bool {
+    match self.clips.get(h) {
+      Some(&to) => { *h = to; self.remapped += 1; true }
+      None => false,
+    }
+  }
+
+  fn link(&mut self, h: &mut u32) {
+    if let Some((from, to)) = self.link {
+      if *h == from {
+        *h = to;
+        self.links += 1;
+      }
+    }
+  }
+
+  /// Blend tables key on `from << 32 | to` clip hashes.
+  fn pair(&mut self, key: &mut u64) -> bool {
+    let (mut hi, mut lo) = ((*key >> 32) as u32, *key as u32);
+    let changed = self.hash(&mut hi) | self.hash(&mut lo);
+    *key = (hi as u64) << 32 | lo as u64;
+    changed
+  }
+
+  fn visit_struct(&mut self, s: &mut values::Struct) {
+    for prop in s.properties.values_mut() {
+      self.visit(&mut prop.value);
+    }
+  }
+
+  fn visit(&mut self, value: &mut PropertyValueEnum) {
+    use PropertyValueEnum as P;
+    match value {
+      P::Hash(v) => { self.hash(&mut v.value); }
+      P::ObjectLink(v) => self.link(&mut v.value),
+      P::Optional(Optional::Hash(Some(v))) => { self.hash(&mut v.value); }
+      P::Optional(Optional::ObjectLink(Some(v))) => self.link(&mut v.value),
+      P::Struct(s) => self.visit_struct(s),
+      P::Embedded(e) => self.visit_struct(&mut e.0),
+      P::Optional(Optional::Struct(Some(s))) => self.visit_struct(s),
+      P::Optional(Optional::Embedded(Some(e))) => self.visit_struct(&mut e.0),
+      P::Container(c) | P::UnorderedContainer(values::UnorderedContainer(c)) => match c {
+        Container::Hash { items, .. } => items.iter_mut().for_each(|v| { self.hash(&mut v.value); }),
+        Container::ObjectLink { items, .. } => items.iter_mut().for_each(|v| self.link(&mut v.value)),
+        Container::Struct { items, .. } => items.iter_mut().for_each(|s| self.visit_struct(s)),
+        Container::Embedded { items, .. } => items.iter_mut().for_each(|e| self.visit_struct(&mut e.0)),
+        _ => {}
+      },
+      P::Map(m) => {
+        let (key_kind, value_kind) = (m.key_kind(), m.value_kind());
+        let mut entries = std::mem::take(m).into_entries();
+        let mut renamed = Vec::with_capacity(entries.len());
+        for (k, v) in entries.iter_mut() {
+          renamed.push(match k {
+            P::Hash(h) => self.hash(&mut h.value),
+            P::U64(h) => self.pair(&mut h.value),
+            _ => false,
+          });
+          self.visit(v);
+        }
+        // A renamed clip replaces an existing one of the same name.
+        let taken: HashSet<u64> = entries.iter().zip(&renamed).filter(|(_, r)| **r).filter_map(|((k, _), _)| key_of(k)).collect();
+        let before = entries.len();
+        let mut flags = renamed.into_iter();
+        entries.retain(|(k, _)| flags.next().unwrap_or(false) || key_of(k).is_none_or(|k| !taken.contains(&k)));
+        self.replaced += (before - entries.len()) as u32;
+        // Kinds are unchanged, so rebuilding can't fail.
+        *m = values::Map::new(key_kind, value_kind, entries).unwrap_or_default();
+      }
+      _ => {}
+    }
+  }
+}
+
+fn key_of(key: &PropertyValueEnum) -> Option<u64> {
+  match key {
+    PropertyValueEnum::Hash(h) => Some(h.value as u64),
+    PropertyValueEnum::U64(h) => Some(h.value),
+    _ => None,
+  }
+}
+
+fn retarget(bin_path: &Path, options: &RetargetAnimationOptions) -> Result<RetargetAnimationResult, String> {
+  let (path_champion, path_skin) = slot_from_path(bin_path);
+  let from_champion = options.from_champion.clone().filter(|c| !c.is_empty()).or(path_champion);
+  let from_skin = options.from_skin.or(path_skin);
+  let to_champion = options.to_champion.clone().filter(|c| !c.is_empty()).or_else(|| from_champion.clone());
+  let to_skin = options.to_skin.or(from_skin);
+  let clips: HashMap<u32, u32> = options.clip_map.iter()
+    .flatten()
+    .map(|(from, to)| (name_hash(from), name_hash(to)))
+    .filter(|(from, to)| from != to)
+    .collect();
+
+  let data = fs::read(bin_path).map_err(|e| format!("Failed to read {}: {}", bin_path.display(), e))?;
+  let mut tree = Bin::from_reader(&mut Cursor::new(&data)).map_err(|e| format!("Failed to parse {}: {}", bin_path.display(), e))?;
+
+  let slots = match (&from_champion, from_skin, &to_champion, to_skin) {
+    (Some(fc), Some(fs), Some(tc), Some(ts)) => Some((fc.as_str(), fs, tc.as_str(), ts)),
+    _ => None,
+  };
+  if slots.is_none() && clips.is_empty() {
+    return Err("Nothing to retarget: give a champion and skin slot or a clip map".to_string());
+  }
+
+  let mut rules = Vec::new();
+  let mut link = None;
+  if let Some((fc, fs, tc, ts)) = slots {
+    let (from_entry, to_entry) = (entry_path(fc, fs), entry_path(tc, ts));
+    link = Some((fnv1a_lower(&from_entry), fnv1a_lower(&to_entry))).filter(|(a, b)| a != b);
+    rules.push(RepathRule {
+      from: format!("assets/characters/{}/skins/{}/", fc, skin_asset_folder(fs)),
+      to: format!("assets/characters/{}/skins/{}/", tc, skin_asset_folder(ts)),
+    });
+    if !fc.eq_ignore_ascii_case(tc) {
+      rules.push(RepathRule {
+        from: format!("assets/characters/{}/", fc),
+        to: format!("assets/characters/{}/", tc),
+      });
+    }
+  }
+
+  let mut walk = Retarget { clips, link, links: 0, remapped: 0, replaced: 0 };
+  let mut paths = 0;
+  let objects = std::mem::take(&mut tree.objects);
+  tree.objects = objects.into_iter()
+    .map(|(mut key, mut object)| {
+      walk.link(&mut key);
+      object.path_hash = key;
+      for prop in object.properties.values_mut() {
+        walk.visit(&mut prop.value);
+        paths += rewrite_value(&mut prop.value, &|p| apply_repath(&rules, p));
+      }
+      (key, object)
+    })
+    .collect();
+
+  let changed = walk.links + walk.remapped + paths > 0;
+  let written = !options.dry_run.unwrap_or(false) && changed;
+  if written {
+    let out = options.output_path.as_deref().filter(|p| !p.is_empty()).map(Path::new).unwrap_or(bin_path);
+    let mut buf = Cursor::new(Vec::new());
+    tree.to_writer(&mut buf).map_err(|e| format!("Failed to serialize bin: {}", e))?;
+    if let Some(parent) = out.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    write_retrying(out, buf.get_ref()).map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+  }
+  Ok(RetargetAnimationResult {
+    success: true,
+    error: None,
+    entry_path: slots.map(|(_, _, tc, ts)| entry_path(tc, ts)),
+    target_path: slots.map(|(_, _, tc, ts)| format!("data/characters/{}/animations/skin{}.bin", tc.to_ascii_lowercase(), ts)),
+    links_rewritten: walk.links,
+    paths_rewritten: paths,
+    clips_remapped: walk.remapped,
+    clips_replaced: walk.replaced,
+    written,
+  })
+}
+
+/// Move an animation bin to another skin slot and/or champion and rename its
+/// clips through `clipMap`.
+#[napi(js_name = "retargetAnimationBin")]
+pub fn retarget_animation_bin(bin_path: String, options: RetargetAnimationOptions) -> RetargetAnimationResult {
+  retarget(Path::new(&bin_path), &options).unwrap_or_else(|e| RetargetAnimationResult {
+    success: false,
+    error: Some(e),
+    entry_path: None,
+    target_path: None,
+    links_rewritten: 0,
+    paths_rewritten: 0,
+    clips_remapped: 0,
+    clips_replaced: 0,
+    written: false,
+  })
+}
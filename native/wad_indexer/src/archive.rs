@@ -0,0 +1,192 @@
+// ── Archive extraction ───────────────────────────────────────────────────────
+// Community mods are shipped as .zip/.fantome or .7z. These helpers read both
+// formats directly so users don't need an external unzipper before importing,
+// and give mod import / updates a single way to walk archive entries.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use napi_derive::napi;
+use sevenz_rust::{Password, SevenZReader};
+use zip::ZipArchive;
+
+use crate::{is_safe_relative_path, normalize_rel_path};
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_EMPTY_MAGIC: &[u8] = b"PK\x05\x06";
+const SEVEN_ZIP_MAGIC: &[u8] = b"7z\xBC\xAF\x27\x1C";
+const RAR_MAGIC: &[u8] = b"Rar!";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveFormat {
+  Zip,
+  SevenZip,
+}
+
+impl ArchiveFormat {
+  pub(crate) fn as_str(self) -> &'static str {
+    match self {
+      ArchiveFormat::Zip => "zip",
+      ArchiveFormat::SevenZip => "7z",
+    }
+  }
+}
+
+#[napi(object)]
+pub struct ExtractArchiveOptions {
+  /// Strip a single top-level folder shared by every entry ("MyMod/WAD/..." -> "WAD/...").
+  pub flatten: Option<bool>,
+}
+
+#[napi(object)]
+pub struct ExtractArchiveResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// "zip" or "7z".
+  pub format: Option<String>,
+  #[napi(js_name = "extractedCount")]
+  pub extracted_count: u32,
+  /// Entries skipped because their path would escape `dest`.
+  #[napi(js_name = "skippedCount")]
+  pub skipped_count: u32,
+}
+
+/// Sniff the archive format from its magic bytes; the extension is not trusted
+/// (.fantome files are zips, and renamed downloads are common).
+pub(crate) fn detect_archive_format(path: &Path) -> Result<ArchiveFormat, String> {
+  let mut head = [0u8; 6];
+  let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let n = file.read(&mut head).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  let head = &head[..n];
+  if head.starts_with(ZIP_MAGIC) || head.starts_with(ZIP_EMPTY_MAGIC) {
+    Ok(ArchiveFormat::Zip)
+  } else if head.starts_with(SEVEN_ZIP_MAGIC) {
+    Ok(ArchiveFormat::SevenZip)
+  } else if head.starts_with(RAR_MAGIC) {
+    Err("RAR archives are not supported, repack the mod as .zip or .7z".to_string())
+  } else {
+    Err(format!("{} is not a zip or 7z archive", path.display()))
+  }
+}
+
+/// Call `each(name, reader)` for every file entry (directories are skipped).
+/// Names are passed as stored in the archive; callers must validate them.
+pub(crate) fn for_each_archive_file<F>(path: &Path, mut each: F) -> Result<ArchiveFormat, String>
+where
+  F: FnMut(&str, &mut dyn Read) -> Result<(), String>,
+{
+  let format = detect_archive_format(path)?;
+  match format {
+    ArchiveFormat::Zip => {
+      let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+      let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+      for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        if entry.is_dir() { continue; }
+        let name = entry.name().to_string();
+        each(&name, &mut entry)?;
+      }
+    }
+    ArchiveFormat::SevenZip => {
+      let mut reader = SevenZReader::open(path, Password::empty())
+        .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+      let mut failed: Option<String> = None;
+      reader
+        .for_each_entries(|entry, data| {
+          if entry.is_directory() || !entry.has_stream() { return Ok(true); }
+          if let Err(e) = each(entry.name(), data) {
+            failed = Some(e);
+            return Ok(false);
+          }
+          // Solid blocks are decoded sequentially, so drain whatever the callback left.
+          io::copy(data, &mut io::sink()).map_err(sevenz_rust::Error::io)?;
+          Ok(true)
+        })
+        .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+      if let Some(e) = failed { return Err(e); }
+    }
+  }
+  Ok(format)
+}
+
+fn archive_file_names(path: &Path, format: ArchiveFormat) -> Result<Vec<String>, String> {
+  match format {
+    ArchiveFormat::Zip => {
+      let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+      let zip = ZipArchive::new(file).map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+      Ok(zip.file_names().filter(|n| !n.ends_with('/')).map(str::to_string).collect())
+    }
+    ArchiveFormat::SevenZip => {
+      let archive = sevenz_rust::Archive::open(path)
+        .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+      Ok(archive.files.iter().filter(|f| !f.is_directory()).map(|f| f.name().to_string()).collect())
+    }
+  }
+}
+
+/// The single top-level folder every entry lives under, if there is one.
+fn common_root(names: &[String]) -> Option<String> {
+  let mut root: Option<&str> = None;
+  for name in names {
+    let name = name.trim_start_matches(['/', '\\']);
+    let (first, rest) = name.split_once(['/', '\\'])?;
+    if rest.is_empty() { return None; }
+    match root {
+      None => root = Some(first),
+      Some(r) if r == first => {}
+      Some(_) => return None,
+    }
+  }
+  root.map(|r| format!("{}/", r))
+}
+
+/// Extract every safe entry of `path` into `dest`. Returns (format, extracted, skipped).
+pub(crate) fn extract_archive_to(path: &Path, dest: &Path, flatten: bool) -> Result<(ArchiveFormat, u32, u32), String> {
+  let prefix = if flatten {
+    let format = detect_archive_format(path)?;
+    common_root(&archive_file_names(path, format)?)
+  } else {
+    None
+  };
+  fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+
+  let (mut extracted, mut skipped) = (0u32, 0u32);
+  let format = for_each_archive_file(path, |name, reader| {
+    let mut rel = normalize_rel_path(name);
+    if let Some(prefix) = &prefix {
+      rel = rel.strip_prefix(prefix.as_str()).unwrap_or(&rel).to_string();
+    }
+    if rel.is_empty() || !is_safe_relative_path(&rel) {
+      skipped += 1;
+      return Ok(());
+    }
+    let out = dest.join(&rel);
+    if let Some(parent) = out.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = fs::File::create(&out).map_err(|e| format!("Failed to create {}: {}", out.display(), e))?;
+    io::copy(reader, &mut file).map_err(|e| format!("Failed to extract {}: {}", rel, e))?;
+    extracted += 1;
+    Ok(())
+  })?;
+  Ok((format, extracted, skipped))
+}
+
+/// Extract a .zip/.fantome/.7z archive into `dest`. Entries that would land
+/// outside `dest` are skipped.
+#[napi(js_name = "extractArchive")]
+pub fn extract_archive(path: String, dest: String, options: Option<ExtractArchiveOptions>) -> ExtractArchiveResult {
+  let flatten = options.and_then(|o| o.flatten).unwrap_or(false);
+  match extract_archive_to(Path::new(&path), Path::new(&dest), flatten) {
+    Ok((format, extracted, skipped)) => ExtractArchiveResult {
+      success: true,
+      error: None,
+      format: Some(format.as_str().to_string()),
+      extracted_count: extracted,
+      skipped_count: skipped,
+    },
+    Err(e) => ExtractArchiveResult { success: false, error: Some(e), format: None, extracted_count: 0, skipped_count: 0 },
+  }
+}
@@ -0,0 +1,389 @@
+// ── Asset dependency graph ───────────────────────────────────────────────────
+// Links each bin in a WAD (or extracted WAD folder) to the assets it names:
+// textures, meshes, animations, audio banks and other bins. Answers "what
+// breaks if I delete this?" (incoming edges) and, for `findOrphans`, "what
+// does nothing use?". References come from scanning bins for path
+// strings, the same way hash extraction discovers names.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::project::read_project;
+use crate::skins::skin_bin_path;
+use crate::threads::{run_cpu, run_io};
+use crate::wad_build::{
+  chunk_hash_for_rel_path, collect_files, plan_wad_dir, project_wad_dirs, read_hashed_files, HASHED_FILES_JSON,
+};
+use crate::{dds_scaled_variants, scan_bin_asset_paths, unique_chunks, xxhash_path, HashLayers};
+
+pub(crate) struct GraphNode {
+  pub(crate) hash: u64,
+  /// Lowercased asset path when known.
+  pub(crate) path: Option<String>,
+  /// False for assets referenced but not contained in the source (another WAD).
+  pub(crate) present: bool,
+  pub(crate) size: u64,
+}
+
+pub(crate) struct Graph {
+  pub(crate) nodes: Vec<GraphNode>,
+  /// (from, to) node indices.
+  pub(crate) edges: Vec<(u32, u32)>,
+  /// Node indices of the bins that were scanned.
+  pub(crate) bins: Vec<u32>,
+}
+
+/// One asset in the source: hash, known path, uncompressed size.
+type SourceAsset = (u64, Option<String>, u64);
+/// (assets in the source, (bin hash, paths it references))
+type Scan = (Vec<SourceAsset>, Vec<(u64, Vec<String>)>);
+
+fn is_bin_candidate(path: &Option<String>) -> bool {
+  // Unresolved chunks may be bins; the scanner checks the magic.
+  path.as_deref().is_none_or(|p| p.ends_with(".bin"))
+}
+
+fn scan_wad(wad_path: &Path, hash_dir: Option<&str>) -> Result<Scan, String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+  let names = HashLayers::open(hash_dir).resolve_names(&hashes);
+  let assets: Vec<SourceAsset> = chunks
+    .iter()
+    .zip(names)
+    .map(|(c, name)| {
+      let known = (name != format!("{:016x}", c.path_hash())).then(|| name.to_ascii_lowercase());
+      (c.path_hash(), known, c.uncompressed_size() as u64)
+    })
+    .collect();
+
+  let wad_data = &mmap[..];
+  let refs = run_cpu(|| {
+    chunks
+      .par_iter()
+      .zip(assets.par_iter())
+      .filter(|(_, (_, path, _))| is_bin_candidate(path))
+      .filter_map(|(chunk, (hash, _, _))| {
+        let data = decompress_chunk(wad_data, chunk).ok()?;
+        (data.starts_with(b"PROP") || data.starts_with(b"PTCH")).then(|| (*hash, scan_bin_asset_paths(&data)))
+      })
+      .collect()
+  });
+  Ok((assets, refs))
+}
+
+fn scan_dir(dir: &Path) -> Result<Scan, String> {
+  let renamed = read_hashed_files(dir);
+  let files: Vec<_> = collect_files(dir)?
+    .into_iter()
+    .filter(|(rel, _)| !rel.eq_ignore_ascii_case(HASHED_FILES_JSON))
+    .collect();
+  let assets: Vec<SourceAsset> = files
+    .iter()
+    .map(|(rel, p)| {
+      let original = renamed.get(rel).unwrap_or(rel);
+      let hash = chunk_hash_for_rel_path(original);
+      // Hex-named files are unresolved chunks; their name says nothing about the path.
+      let lower = original.to_ascii_lowercase();
+      let known = (hash == xxhash_path(&lower)).then_some(lower);
+      let size = fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+      (hash, known, size)
+    })
+    .collect();
+  let refs = run_io(|| {
+    files
+      .par_iter()
+      .zip(assets.par_iter())
+      .filter(|(_, (_, path, _))| is_bin_candidate(path))
+      .filter_map(|((_, p), (hash, _, _))| {
+        let data = fs::read(p).ok()?;
+        (data.starts_with(b"PROP") || data.starts_with(b"PTCH")).then(|| (*hash, scan_bin_asset_paths(&data)))
+      })
+      .collect()
+  });
+  Ok((assets, refs))
+}
+
+/// Build the graph for a `.wad.client` file or an extracted WAD folder.
+pub(crate) fn build_graph(source: &Path, hash_dir: Option<&str>) -> Result<Graph, String> {
+  let (assets, refs) = if source.is_dir() { scan_dir(source)? } else { scan_wad(source, hash_dir)? };
+
+  let mut nodes: Vec<GraphNode> = Vec::with_capacity(assets.len());
+  let mut index: HashMap<u64, u32> = HashMap::with_capacity(assets.len());
+  for (hash, path, size) in assets {
+    index.insert(hash, nodes.len() as u32);
+    nodes.push(GraphNode { hash, path, present: true, size });
+  }
+
+  let mut bins = Vec::with_capacity(refs.len());
+  let mut edges = Vec::new();
+  for (from_hash, paths) in refs {
+    let from = index[&from_hash];
+    bins.push(from);
+    let mut seen = HashSet::new();
+    for path in paths {
+      let hash = xxhash_path(&path);
+      let mut targets = vec![(hash, path.clone())];
+      // 2x/4x textures are loaded alongside the base .dds, so they're used too.
+      if path.ends_with(".dds") {
+        for v in dds_scaled_variants(&path) {
+          let h = xxhash_path(&v);
+          if index.contains_key(&h) { targets.push((h, v)); }
+        }
+      }
+      for (hash, path) in targets {
+        if hash == from_hash || !seen.insert(hash) { continue; }
+        let to = *index.entry(hash).or_insert_with(|| {
+          nodes.push(GraphNode { hash, path: Some(path.clone()), present: false, size: 0 });
+          nodes.len() as u32 - 1
+        });
+        // Name assets we only knew by hash.
+        if nodes[to as usize].path.is_none() { nodes[to as usize].path = Some(path); }
+        edges.push((from, to));
+      }
+    }
+  }
+  Ok(Graph { nodes, edges, bins })
+}
+
+/// "bin", "texture", "model", "animation", "audio", "other" or "unknown".
+pub(crate) fn asset_kind(path: Option<&str>) -> &'static str {
+  let Some(path) = path else { return "unknown" };
+  match path.rsplit('.').next().unwrap_or("") {
+    "bin" => "bin",
+    "dds" | "tex" | "png" | "jpg" | "tga" => "texture",
+    "skn" | "skl" | "scb" | "sco" | "mapgeo" => "model",
+    "anm" => "animation",
+    "bnk" | "wpk" => "audio",
+    _ => "other",
+  }
+}
+
+#[napi(object)]
+pub struct AssetNode {
+  /// Path hash, 16-digit hex.
+  pub hash: String,
+  pub path: Option<String>,
+  pub kind: String,
+  /// False when referenced but not contained in the source (lives in another WAD).
+  pub present: bool,
+  pub size: f64,
+  /// Edges into this node: how many bins use it.
+  #[napi(js_name = "referencedBy")]
+  pub referenced_by: u32,
+}
+
+#[napi(object)]
+pub struct AssetEdge {
+  /// Index into `nodes` of the referencing bin.
+  pub from: u32,
+  /// Index into `nodes` of the referenced asset.
+  pub to: u32,
+}
+
+#[napi(object)]
+pub struct AssetGraphResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub nodes: Vec<AssetNode>,
+  pub edges: Vec<AssetEdge>,
+  /// Bins scanned for references.
+  #[napi(js_name = "binCount")]
+  pub bin_count: u32,
+  /// Referenced assets not found in the source.
+  #[napi(js_name = "externalCount")]
+  pub external_count: u32,
+}
+
+fn to_result(graph: Graph) -> AssetGraphResult {
+  let mut referenced_by = vec![0u32; graph.nodes.len()];
+  for (_, to) in &graph.edges { referenced_by[*to as usize] += 1; }
+  let external_count = graph.nodes.iter().filter(|n| !n.present).count() as u32;
+  AssetGraphResult {
+    success: true,
+    error: None,
+    nodes: graph
+      .nodes
+      .into_iter()
+      .zip(referenced_by)
+      .map(|(n, referenced_by)| AssetNode {
+        hash: format!("{:016x}", n.hash),
+        kind: asset_kind(n.path.as_deref()).to_string(),
+        path: n.path,
+        present: n.present,
+        size: n.size as f64,
+        referenced_by,
+      })
+      .collect(),
+    edges: graph.edges.into_iter().map(|(from, to)| AssetEdge { from, to }).collect(),
+    bin_count: graph.bins.len() as u32,
+    external_count,
+  }
+}
+
+fn failed(e: String) -> AssetGraphResult {
+  AssetGraphResult { success: false, error: Some(e), nodes: Vec::new(), edges: Vec::new(), bin_count: 0, external_count: 0 }
+}
+
+/// Dependency graph of a `.wad.client` or extracted WAD folder: bins -> the
+/// assets they reference. `hashDir` resolves chunk names for WAD files.
+#[napi(js_name = "buildAssetGraph")]
+pub fn build_asset_graph(wad_or_dir: String, hash_dir: Option<String>) -> AssetGraphResult {
+  build_graph(Path::new(&wad_or_dir), hash_dir.as_deref()).map(to_result).unwrap_or_else(failed)
+}
+
+pub struct AssetGraphTask {
+  wad_or_dir: String,
+  hash_dir: Option<String>,
+}
+
+#[napi]
+impl Task for AssetGraphTask {
+  type Output = AssetGraphResult;
+  type JsValue = AssetGraphResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(build_asset_graph(self.wad_or_dir.clone(), self.hash_dir.clone()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+#[napi(js_name = "buildAssetGraphAsync")]
+pub fn build_asset_graph_async(wad_or_dir: String, hash_dir: Option<String>) -> AsyncTask<AssetGraphTask> {
+  AsyncTask::new(AssetGraphTask { wad_or_dir, hash_dir })
+}
+
+#[napi(object)]
+pub struct OrphanAsset {
+  /// WAD folder the file lives in, e.g. "Ahri.wad.client".
+  pub wad: String,
+  /// Relative to the WAD folder.
+  pub path: String,
+  pub kind: String,
+  pub size: f64,
+}
+
+#[napi(object)]
+pub struct FindOrphansResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Files no project bin references.
+  pub orphans: Vec<OrphanAsset>,
+  /// Bins not reachable from the main skin bin. Empty when the project has no
+  /// champion or doesn't contain its skin bin.
+  #[napi(js_name = "unreachableBins")]
+  pub unreachable_bins: Vec<OrphanAsset>,
+  /// Skin bin reachability was computed from, if any.
+  #[napi(js_name = "rootBin")]
+  pub root_bin: Option<String>,
+  /// Total size of `orphans` plus `unreachableBins`.
+  #[napi(js_name = "totalBytes")]
+  pub total_bytes: f64,
+}
+
+struct ProjectFile {
+  hash: u64,
+  asset: OrphanAsset,
+  is_bin: bool,
+}
+
+fn find(project: &Path) -> Result<FindOrphansResult, String> {
+  let (data, _) = read_project(project)?;
+  let root = data.champion.as_deref().map(|c| skin_bin_path(c, data.skin_id.unwrap_or(0)));
+
+  let mut files: Vec<ProjectFile> = Vec::new();
+  let mut adjacency: HashMap<u64, Vec<u64>> = HashMap::new();
+  let mut referenced: HashSet<u64> = HashSet::new();
+  // WAD folders are scanned separately but references cross them, so edges are
+  // merged by path hash.
+  for dir in project_wad_dirs(project) {
+    let graph = build_graph(&dir, None)?;
+    let (plan, _) = plan_wad_dir(&dir)?;
+    let wad = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    for (from, to) in &graph.edges {
+      let (from, to) = (graph.nodes[*from as usize].hash, graph.nodes[*to as usize].hash);
+      adjacency.entry(from).or_default().push(to);
+      referenced.insert(to);
+    }
+    let bins: HashSet<u32> = graph.bins.iter().copied().collect();
+    for (i, node) in graph.nodes.iter().enumerate().filter(|(_, n)| n.present) {
+      let Some(file) = plan.get(&node.hash) else { continue };
+      let rel = file.strip_prefix(&dir).unwrap_or(file).to_string_lossy().replace('\\', "/");
+      files.push(ProjectFile {
+        hash: node.hash,
+        is_bin: bins.contains(&(i as u32)),
+        asset: OrphanAsset { wad: wad.clone(), path: rel, kind: asset_kind(node.path.as_deref()).to_string(), size: node.size as f64 },
+      });
+    }
+  }
+
+  let present: HashSet<u64> = files.iter().map(|f| f.hash).collect();
+  let root_hash = root.as_deref().map(|r| xxhash_path(&r.to_ascii_lowercase())).filter(|h| present.contains(h));
+  let reachable: Option<HashSet<u64>> = root_hash.map(|start| {
+    let mut seen = HashSet::from([start]);
+    let mut stack = vec![start];
+    while let Some(h) = stack.pop() {
+      for next in adjacency.get(&h).into_iter().flatten() {
+        if seen.insert(*next) { stack.push(*next); }
+      }
+    }
+    seen
+  });
+  // A .py next to its .bin is that bin's editable text, not a separate asset.
+  let bin_files: HashSet<(String, String)> =
+    files.iter().filter(|f| f.is_bin).map(|f| (f.asset.wad.clone(), f.asset.path.to_ascii_lowercase())).collect();
+  let is_bin_text = |a: &OrphanAsset| {
+    let lower = a.path.to_ascii_lowercase();
+    lower.strip_suffix(".py").is_some_and(|stem| bin_files.contains(&(a.wad.clone(), format!("{}.bin", stem))))
+  };
+
+  let mut orphans = Vec::new();
+  let mut unreachable_bins = Vec::new();
+  for f in files {
+    if f.is_bin {
+      if reachable.as_ref().is_some_and(|r| !r.contains(&f.hash)) { unreachable_bins.push(f.asset); }
+      continue;
+    }
+    if !referenced.contains(&f.hash) && !is_bin_text(&f.asset) { orphans.push(f.asset); }
+  }
+  orphans.sort_by(|a, b| (&a.wad, &a.path).cmp(&(&b.wad, &b.path)));
+  unreachable_bins.sort_by(|a, b| (&a.wad, &a.path).cmp(&(&b.wad, &b.path)));
+  let total_bytes = orphans.iter().chain(&unreachable_bins).map(|a| a.size).sum();
+  Ok(FindOrphansResult {
+    success: true,
+    error: None,
+    orphans,
+    unreachable_bins,
+    root_bin: root.filter(|_| root_hash.is_some()),
+    total_bytes,
+  })
+}
+
+/// Files in a project that no project bin references, and bins the main skin
+/// bin never reaches. Read-only: nothing is deleted.
+#[napi(js_name = "findOrphans")]
+pub fn find_orphans(project_path: String) -> FindOrphansResult {
+  find(Path::new(&project_path)).unwrap_or_else(|e| FindOrphansResult {
+    success: false,
+    error: Some(e),
+    orphans: Vec::new(),
+    unreachable_bins: Vec::new(),
+    root_bin: None,
+    total_bytes: 0.0,
+  })
+}
@@ -0,0 +1,139 @@
+// ── Asset path suggestions ───────────────────────────────────────────────────
+// A file dropped into a project needs a game-style asset path before a bin can
+// reference it, and that path needs to be in a hash list or the built WAD shows
+// the chunk as an unknown hash. New assets go under
+// `ASSETS/{creator}/{project}/...` so they never collide with Riot's files or
+// another mod's; the suggestion is registered in `hashes.custom.txt` right away.
+
+use std::fs;
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::project::read_project;
+use crate::wad_patch::merge_custom_hashes;
+use crate::xxhash_path;
+
+#[napi(object)]
+pub struct SuggestAssetPathOptions {
+  /// Hash dir whose hashes.custom.txt receives the path. Nothing is registered without it.
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+  /// Folder inside `ASSETS/{creator}/{project}/`, e.g. "Textures".
+  pub subdir: Option<String>,
+  /// Project WAD folder (e.g. "Ahri.wad.client") to place the file in. Defaults
+  /// to the project's only WAD folder, or its champion's WAD.
+  pub wad: Option<String>,
+}
+
+#[napi(object)]
+pub struct AssetPathSuggestion {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Suggested asset path, e.g. "ASSETS/Jane/StarAhri/Textures/ahri_tx_cm.dds".
+  pub path: Option<String>,
+  /// xxh64 of the lowercased path, 16-digit hex.
+  pub hash: Option<String>,
+  /// Where to copy the file, relative to the project's `content/`; None when
+  /// the target WAD folder can't be determined.
+  #[napi(js_name = "contentPath")]
+  pub content_path: Option<String>,
+  /// True when the path was newly added to hashes.custom.txt.
+  pub registered: bool,
+}
+
+/// One path segment from a free-form name: spaces and characters that are
+/// awkward in asset paths become `_`.
+fn path_segment(name: &str) -> String {
+  let segment: String = name.trim()
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+    .collect();
+  segment.trim_matches(['_', '.']).to_string()
+}
+
+/// The project's WAD folder under `content/`: the requested one, its only one, or its champion's.
+fn target_wad(project: &Path, wanted: Option<&str>, champion: Option<&str>) -> Option<String> {
+  if let Some(w) = wanted.filter(|w| !w.is_empty()) {
+    return Some(w.to_string());
+  }
+  let wads: Vec<String> = fs::read_dir(project.join("content")).ok()?
+    .flatten()
+    .filter(|e| e.path().is_dir())
+    .map(|e| e.file_name().to_string_lossy().into_owned())
+    .collect();
+  match (wads.len(), champion) {
+    (1, _) => wads.into_iter().next(),
+    (_, Some(champ)) => {
+      let name = format!("{}.wad.client", champ);
+      wads.into_iter().find(|w| w.eq_ignore_ascii_case(&name)).or(Some(name))
+    }
+    _ => None,
+  }
+}
+
+fn suggest(file: &Path, project: &Path, options: &SuggestAssetPathOptions) -> Result<AssetPathSuggestion, String> {
+  let (data, _) = read_project(project)?;
+  let file_name = file.file_name()
+    .map(|n| n.to_string_lossy().into_owned())
+    .ok_or_else(|| format!("Not a file path: {}", file.display()))?;
+  let (stem, ext) = match file_name.rsplit_once('.') {
+    Some((s, e)) if !s.is_empty() => (path_segment(s), format!(".{}", e.to_ascii_lowercase())),
+    _ => (path_segment(&file_name), String::new()),
+  };
+  if stem.is_empty() {
+    return Err(format!("Can't build an asset name from {}", file_name));
+  }
+
+  let or_default = |segment: String, default: &str| if segment.is_empty() { default.to_string() } else { segment };
+  let mut base = format!(
+    "ASSETS/{}/{}",
+    or_default(path_segment(&data.creator), "Quartz"),
+    or_default(path_segment(&data.name), "Project"),
+  );
+  for part in options.subdir.as_deref().unwrap_or("").split(['/', '\\']).map(path_segment).filter(|p| !p.is_empty()) {
+    base.push('/');
+    base.push_str(&part);
+  }
+
+  let wad = target_wad(project, options.wad.as_deref(), data.champion.as_deref());
+  let content = project.join("content");
+  // Another file may already sit at the suggested spot; number the new one.
+  let taken = |path: &str| wad.as_ref().is_some_and(|w| content.join(w).join(path.to_ascii_lowercase()).exists());
+  let mut path = format!("{}/{}{}", base, stem, ext);
+  let mut n = 1;
+  while taken(&path) {
+    path = format!("{}/{}_{}{}", base, stem, n, ext);
+    n += 1;
+  }
+
+  let lower = path.to_ascii_lowercase();
+  let hash = xxhash_path(&lower);
+  let registered = match options.hash_dir.as_deref().filter(|d| !d.is_empty()) {
+    Some(dir) => merge_custom_hashes(Path::new(dir), &[(hash, lower.clone())])? > 0,
+    None => false,
+  };
+  Ok(AssetPathSuggestion {
+    success: true,
+    error: None,
+    content_path: wad.map(|w| format!("{}/{}", w, lower)),
+    path: Some(path),
+    hash: Some(format!("{:016x}", hash)),
+    registered,
+  })
+}
+
+/// Propose a unique `ASSETS/{creator}/{project}/...` path for a new file,
+/// with its path hash, and register it in `hashes.custom.txt` when `hashDir` is given.
+#[napi(js_name = "suggestAssetPath")]
+pub fn suggest_asset_path(file_path: String, project_path: String, options: Option<SuggestAssetPathOptions>) -> AssetPathSuggestion {
+  let options = options.unwrap_or(SuggestAssetPathOptions { hash_dir: None, subdir: None, wad: None });
+  suggest(Path::new(&file_path), Path::new(&project_path), &options).unwrap_or_else(|e| AssetPathSuggestion {
+    success: false,
+    error: Some(e),
+    path: None,
+    hash: None,
+    content_path: None,
+    registered: false,
+  })
+}
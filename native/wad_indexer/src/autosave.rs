@@ -0,0 +1,165 @@
+// ── Autosave ─────────────────────────────────────────────────────────────────
+// Dirty editor buffers written to `{userData}/recovery/` every few seconds by
+// the editor, so a webview crash or power loss costs seconds of edits rather
+// than a session's worth. Each buffer is one JSON file keyed by the edited
+// path, replaced atomically; saving the file for real discards its buffer.
+// On the next launch `recoverBuffers` lists what is left to offer restoring.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::paths::rename_retrying;
+
+const RECOVERY_DIR: &str = "recovery";
+
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredBuffer {
+  path: String,
+  saved_at: i64,
+  /// Modification time (unix seconds) of the file when editing started from it.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  file_modified: Option<i64>,
+  text_hash: String,
+  text: String,
+}
+
+#[napi(object)]
+pub struct RecoveredBuffer {
+  pub path: String,
+  pub text: String,
+  /// Unix seconds.
+  #[napi(js_name = "savedAt")]
+  pub saved_at: i64,
+  /// True when the file on disk changed after the buffer was started, so
+  /// restoring it would overwrite those changes.
+  #[napi(js_name = "fileChanged")]
+  pub file_changed: bool,
+  /// False when the file has since been deleted or moved.
+  #[napi(js_name = "fileExists")]
+  pub file_exists: bool,
+}
+
+#[napi(object)]
+pub struct AutosaveResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// False when the buffer was unchanged since the last autosave.
+  pub written: bool,
+}
+
+#[napi(object)]
+pub struct RecoverBuffersResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Most recently saved first.
+  pub buffers: Vec<RecoveredBuffer>,
+}
+
+fn now_secs() -> i64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+fn modified_secs(path: &Path) -> Option<i64> {
+  let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+  modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs() as i64)
+}
+
+fn buffer_path(user_data_dir: &Path, path: &str) -> PathBuf {
+  let key = path.replace('\\', "/");
+  let key = if cfg!(windows) { key.to_lowercase() } else { key };
+  user_data_dir.join(RECOVERY_DIR).join(format!("buffer-{:016x}.json", xxh64(key.as_bytes(), 0)))
+}
+
+fn read_buffer(file: &Path) -> Option<StoredBuffer> {
+  serde_json::from_str(&fs::read_to_string(file).ok()?).ok()
+}
+
+fn autosave(user_data_dir: &Path, path: &str, text: &str) -> Result<bool, String> {
+  let file = buffer_path(user_data_dir, path);
+  let text_hash = format!("{:016x}", xxh64(text.as_bytes(), 0));
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let previous = read_buffer(&file);
+  if previous.as_ref().is_some_and(|b| b.text_hash == text_hash) { return Ok(false); }
+  // Keep the modification time from when the first autosave was made, so a
+  // later external change to the file is still detected.
+  let file_modified = match previous {
+    Some(b) => b.file_modified,
+    None => modified_secs(Path::new(path)),
+  };
+  let stored = StoredBuffer { path: path.to_string(), saved_at: now_secs(), file_modified, text_hash, text: text.to_string() };
+  let json = serde_json::to_string(&stored).map_err(|e| format!("Failed to serialize buffer: {}", e))?;
+  if let Some(parent) = file.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let tmp = file.with_extension("json.tmp");
+  fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  rename_retrying(&tmp, &file).map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+  Ok(true)
+}
+
+/// Persist the unsaved editor content of `path`. Cheap to call on a timer:
+/// identical content isn't rewritten.
+#[napi(js_name = "autosaveBuffer")]
+pub fn autosave_buffer(user_data_dir: String, path: String, text: String) -> AutosaveResult {
+  match autosave(Path::new(&user_data_dir), &path, &text) {
+    Ok(written) => AutosaveResult { success: true, error: None, written },
+    Err(e) => AutosaveResult { success: false, error: Some(e), written: false },
+  }
+}
+
+/// Drop the autosaved buffer of `path`, after the file was saved or the
+/// user declined to restore it.
+#[napi(js_name = "discardBuffer")]
+pub fn discard_buffer(user_data_dir: String, path: String) -> AutosaveResult {
+  let file = buffer_path(Path::new(&user_data_dir), &path);
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  match fs::remove_file(&file) {
+    Ok(()) => AutosaveResult { success: true, error: None, written: true },
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => AutosaveResult { success: true, error: None, written: false },
+    Err(e) => AutosaveResult { success: false, error: Some(format!("Failed to remove {}: {}", file.display(), e)), written: false },
+  }
+}
+
+fn recover(user_data_dir: &Path) -> Result<Vec<RecoveredBuffer>, String> {
+  let dir = user_data_dir.join(RECOVERY_DIR);
+  let entries = match fs::read_dir(&dir) {
+    Ok(e) => e,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+  };
+  let mut buffers = Vec::new();
+  for file in entries.flatten().map(|e| e.path()) {
+    if file.extension().is_none_or(|e| e != "json") { continue; }
+    let Some(stored) = read_buffer(&file) else { continue };
+    let target = Path::new(&stored.path);
+    // Content already matching the file on disk has nothing to recover.
+    if fs::read(target).is_ok_and(|d| d == stored.text.as_bytes()) {
+      let _ = fs::remove_file(&file);
+      continue;
+    }
+    let file_exists = target.exists();
+    let file_changed = file_exists && modified_secs(target) != stored.file_modified;
+    buffers.push(RecoveredBuffer { path: stored.path, text: stored.text, saved_at: stored.saved_at, file_changed, file_exists });
+  }
+  buffers.sort_by_key(|b| std::cmp::Reverse(b.saved_at));
+  Ok(buffers)
+}
+
+/// Buffers autosaved by a previous run that never got saved, for a restore
+/// prompt on launch.
+#[napi(js_name = "recoverBuffers")]
+pub fn recover_buffers(user_data_dir: String) -> RecoverBuffersResult {
+  match recover(Path::new(&user_data_dir)) {
+    Ok(buffers) => RecoverBuffersResult { success: true, error: None, buffers },
+    Err(e) => RecoverBuffersResult { success: false, error: Some(e), buffers: Vec::new() },
+  }
+}
@@ -0,0 +1,220 @@
+// ── Game file backups ────────────────────────────────────────────────────────
+// Raw-overwrite workflows replace WADs inside the install. Before that happens
+// the originals are copied to a backup folder and recorded in a manifest with
+// their size and xxh64, so they can be verified and put back later.
+//
+// Layout: {backupDir}/backup-manifest.json and {backupDir}/files/{rel path}.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use memmap2::Mmap;
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::game::game_dir;
+use crate::version::detect_game_version;
+use crate::{is_safe_relative_path, normalize_rel_path};
+
+const BACKUP_MANIFEST_JSON: &str = "backup-manifest.json";
+const BACKUP_FILES_DIR: &str = "files";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupEntry {
+  size: u64,
+  xxh64: String,
+  backed_up_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupManifest {
+  /// Game version the originals belong to; restoring them onto another patch would break the install.
+  #[serde(default)]
+  game_version: Option<String>,
+  /// Path relative to the Game folder ("DATA/FINAL/Champions/Ahri.wad.client") -> entry.
+  #[serde(default)]
+  files: BTreeMap<String, BackupEntry>,
+}
+
+#[napi(object)]
+pub struct GameBackupResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Files copied into the backup by this call (relative to the Game folder).
+  #[napi(js_name = "backedUp")]
+  pub backed_up: Vec<String>,
+  /// Files that already had a backup; the first (original) copy is kept.
+  #[napi(js_name = "alreadyBackedUp")]
+  pub already_backed_up: Vec<String>,
+}
+
+#[napi(object)]
+pub struct GameRestoreResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub restored: Vec<String>,
+  /// Backups whose content no longer matches the manifest; left in place, game file untouched.
+  pub corrupted: Vec<String>,
+}
+
+fn now_secs() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn hash_file(path: &Path) -> Result<(u64, String), String> {
+  let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+  if size == 0 { return Ok((0, format!("{:016x}", xxh64(&[], 0)))); }
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to map {}: {}", path.display(), e))?;
+  Ok((size, format!("{:016x}", xxh64(&mmap, 0))))
+}
+
+fn read_manifest(backup_dir: &Path) -> Result<BackupManifest, String> {
+  let path = backup_dir.join(BACKUP_MANIFEST_JSON);
+  if !path.exists() { return Ok(BackupManifest::default()); }
+  let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", BACKUP_MANIFEST_JSON, e))
+}
+
+fn write_manifest(backup_dir: &Path, manifest: &BackupManifest) -> Result<(), String> {
+  let path = backup_dir.join(BACKUP_MANIFEST_JSON);
+  let tmp = backup_dir.join(format!("{}.tmp", BACKUP_MANIFEST_JSON));
+  let json = serde_json::to_string_pretty(manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+  fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Game-relative path for `path`, which may be absolute or already relative to the Game folder.
+fn game_rel_path(game: &Path, path: &str) -> Result<String, String> {
+  let p = Path::new(path);
+  let rel = if p.is_absolute() {
+    let stripped = p.strip_prefix(game).map_err(|_| format!("{} is not inside {}", path, game.display()))?;
+    stripped.to_string_lossy().into_owned()
+  } else {
+    path.to_string()
+  };
+  let rel = normalize_rel_path(&rel);
+  if rel.is_empty() || !is_safe_relative_path(&rel) {
+    return Err(format!("Invalid game file path: {}", path));
+  }
+  Ok(rel)
+}
+
+fn copy_verified(src: &Path, dst: &Path, expected: &str) -> Result<(), String> {
+  if let Some(parent) = dst.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  fs::copy(src, dst).map_err(|e| format!("Failed to copy {} to {}: {}", src.display(), dst.display(), e))?;
+  let (_, hash) = hash_file(dst)?;
+  if hash != expected {
+    return Err(format!("Copy of {} failed verification", src.display()));
+  }
+  Ok(())
+}
+
+fn backup_files(league: &Path, paths: &[String], backup_dir: &Path) -> Result<GameBackupResult, String> {
+  let game = game_dir(league);
+  let mut manifest = read_manifest(backup_dir)?;
+  let version = detect_game_version(league).version;
+  if !manifest.files.is_empty() && manifest.game_version.is_some() && manifest.game_version != version {
+    return Err(format!(
+      "Existing backups are for game version {}; restore or clear them before backing up {}",
+      manifest.game_version.as_deref().unwrap_or("unknown"),
+      version.as_deref().unwrap_or("unknown"),
+    ));
+  }
+  manifest.game_version = version;
+  fs::create_dir_all(backup_dir).map_err(|e| format!("Failed to create {}: {}", backup_dir.display(), e))?;
+
+  let mut backed_up = Vec::new();
+  let mut already_backed_up = Vec::new();
+  for path in paths {
+    let rel = game_rel_path(&game, path)?;
+    if manifest.files.contains_key(&rel) {
+      already_backed_up.push(rel);
+      continue;
+    }
+    let src = game.join(&rel);
+    if !src.is_file() {
+      // Nothing to protect: the deploy will add a new file, not overwrite one.
+      continue;
+    }
+    let (size, hash) = hash_file(&src)?;
+    copy_verified(&src, &backup_dir.join(BACKUP_FILES_DIR).join(&rel), &hash)?;
+    manifest.files.insert(rel.clone(), BackupEntry { size, xxh64: hash, backed_up_at: now_secs() });
+    // Persist after every file so an interrupted run still knows what it saved.
+    write_manifest(backup_dir, &manifest)?;
+    backed_up.push(rel);
+  }
+  write_manifest(backup_dir, &manifest)?;
+  Ok(GameBackupResult { success: true, error: None, backed_up, already_backed_up })
+}
+
+fn restore_backups(league: &Path, backup_dir: &Path, force: bool) -> Result<GameRestoreResult, String> {
+  let game = game_dir(league);
+  let mut manifest = read_manifest(backup_dir)?;
+  if manifest.files.is_empty() {
+    return Ok(GameRestoreResult { success: true, error: None, restored: Vec::new(), corrupted: Vec::new() });
+  }
+  let version = detect_game_version(league).version;
+  if !force && manifest.game_version.is_some() && version.is_some() && manifest.game_version != version {
+    return Err(format!(
+      "Backups were taken on game version {} but the game is now {}; the patcher has already replaced these files",
+      manifest.game_version.as_deref().unwrap_or("unknown"),
+      version.as_deref().unwrap_or("unknown"),
+    ));
+  }
+
+  let files_dir = backup_dir.join(BACKUP_FILES_DIR);
+  let mut restored = Vec::new();
+  let mut corrupted = Vec::new();
+  let entries: Vec<(String, BackupEntry)> = manifest.files.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+  for (rel, entry) in entries {
+    let backup: PathBuf = files_dir.join(&rel);
+    let ok = backup.is_file() && matches!(hash_file(&backup), Ok((size, ref hash)) if size == entry.size && *hash == entry.xxh64);
+    if !ok {
+      corrupted.push(rel);
+      continue;
+    }
+    let target = game.join(&rel);
+    let unchanged = target.is_file() && hash_file(&target).map(|(_, h)| h == entry.xxh64).unwrap_or(false);
+    if !unchanged {
+      copy_verified(&backup, &target, &entry.xxh64)?;
+    }
+    let _ = fs::remove_file(&backup);
+    manifest.files.remove(&rel);
+    write_manifest(backup_dir, &manifest)?;
+    restored.push(rel);
+  }
+  if manifest.files.is_empty() {
+    let _ = fs::remove_dir_all(&files_dir);
+  }
+  Ok(GameRestoreResult { success: corrupted.is_empty(), error: None, restored, corrupted })
+}
+
+/// Snapshot game files that are about to be overwritten. `paths` may be absolute
+/// or relative to the Game folder. Files that already have a backup keep their
+/// first copy so the original is never replaced by a modded one.
+#[napi(js_name = "backupGameFiles")]
+pub fn backup_game_files(league_path: String, paths: Vec<String>, backup_dir: String) -> GameBackupResult {
+  backup_files(Path::new(&league_path), &paths, Path::new(&backup_dir)).unwrap_or_else(|e| GameBackupResult {
+    success: false,
+    error: Some(e),
+    backed_up: Vec::new(),
+    already_backed_up: Vec::new(),
+  })
+}
+
+/// Verify every backup against the manifest and copy it back into the game.
+/// Refuses when the game was patched since the backup unless `force` is set.
+#[napi(js_name = "restoreGameBackups")]
+pub fn restore_game_backups(league_path: String, backup_dir: String, force: Option<bool>) -> GameRestoreResult {
+  restore_backups(Path::new(&league_path), Path::new(&backup_dir), force.unwrap_or(false)).unwrap_or_else(|e| {
+    GameRestoreResult { success: false, error: Some(e), restored: Vec::new(), corrupted: Vec::new() }
+  })
+}
@@ -0,0 +1,213 @@
+// ── Benchmark / self-test ────────────────────────────────────────────────────
+// Times each stage of an extraction against one of the user's WADs: TOC parse,
+// hash resolution, decompression and disk writes. The report says which stage
+// would dominate a full extraction, so "extraction is slow" reports can be told
+// apart as disk-, CPU- or hash-DB-bound.
+
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::threads::{run_cpu, run_io};
+use crate::HashLayers;
+
+/// Decompression sample cap; enough for a stable rate without taking minutes on map WADs.
+const DECOMPRESS_SAMPLE_BYTES: u64 = 512 * 1024 * 1024;
+/// Data written for the disk test, split into files like a real extraction.
+const WRITE_SAMPLE_BYTES: usize = 128 * 1024 * 1024;
+const WRITE_FILE_BYTES: usize = 256 * 1024;
+
+#[napi(object)]
+pub struct BenchmarkReport {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  #[napi(js_name = "tocMs")]
+  pub toc_ms: f64,
+  #[napi(js_name = "resolveMs")]
+  pub resolve_ms: f64,
+  #[napi(js_name = "resolvedCount")]
+  pub resolved_count: u32,
+  /// False when no hash dir / LMDB was available, so resolution was not really measured.
+  #[napi(js_name = "hashDbAvailable")]
+  pub hash_db_available: bool,
+  #[napi(js_name = "decompressMbPerSec")]
+  pub decompress_mb_per_sec: f64,
+  #[napi(js_name = "decompressThreads")]
+  pub decompress_threads: u32,
+  #[napi(js_name = "writeMbPerSec")]
+  pub write_mb_per_sec: f64,
+  /// Projected time of a full extraction of this WAD, per stage.
+  #[napi(js_name = "estimatedResolveMs")]
+  pub estimated_resolve_ms: f64,
+  #[napi(js_name = "estimatedDecompressMs")]
+  pub estimated_decompress_ms: f64,
+  #[napi(js_name = "estimatedWriteMs")]
+  pub estimated_write_ms: f64,
+  /// "disk", "cpu" or "hashDb".
+  pub bottleneck: String,
+}
+
+fn ms_since(start: Instant) -> f64 {
+  start.elapsed().as_secs_f64() * 1000.0
+}
+
+fn mb_per_sec(bytes: u64, ms: f64) -> f64 {
+  if ms <= 0.0 { return 0.0; }
+  (bytes as f64 / (1024.0 * 1024.0)) / (ms / 1000.0)
+}
+
+fn benchmark_write(dir: &Path) -> Result<f64, String> {
+  let bench_dir = dir.join(format!(".quartz-bench-{}", std::process::id()));
+  fs::create_dir_all(&bench_dir).map_err(|e| format!("Failed to create {}: {}", bench_dir.display(), e))?;
+  // Incompressible-ish data so filesystem compression doesn't flatter the result.
+  let mut block = vec![0u8; WRITE_FILE_BYTES];
+  let mut x: u64 = 0x9E37_79B9_7F4A_7C15;
+  for b in block.iter_mut() {
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *b = x as u8;
+  }
+  let files = WRITE_SAMPLE_BYTES / WRITE_FILE_BYTES;
+  let start = Instant::now();
+  let result = run_io(|| {
+    (0..files).into_par_iter().try_for_each(|i| -> Result<(), String> {
+      let path = bench_dir.join(format!("{}.bin", i));
+      let mut f = fs::File::create(&path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+      f.write_all(&block).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+      f.sync_all().map_err(|e| format!("Failed to sync {}: {}", path.display(), e))
+    })
+  });
+  let elapsed = ms_since(start);
+  let _ = fs::remove_dir_all(&bench_dir);
+  result?;
+  Ok(mb_per_sec((files * WRITE_FILE_BYTES) as u64, elapsed))
+}
+
+fn run(wad_path: &str, hash_dir: Option<&str>, scratch_dir: Option<&str>) -> Result<BenchmarkReport, String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path, e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path, e))?;
+
+  let start = Instant::now();
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path, e))?;
+  let chunks: Vec<_> = wad.chunks().iter().copied().collect();
+  let toc_ms = ms_since(start);
+
+  let start = Instant::now();
+  let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+  let layers = HashLayers::open(hash_dir);
+  let resolved = layers.resolve_names(&hashes);
+  let resolve_ms = ms_since(start);
+  let resolved_count = resolved.iter().zip(&hashes).filter(|(p, h)| **p != format!("{:016x}", h)).count() as u32;
+
+  // Decompress a prefix of the TOC up to the sample cap, in parallel like extraction does.
+  let mut sample = Vec::new();
+  let mut sample_bytes = 0u64;
+  for c in &chunks {
+    if sample_bytes >= DECOMPRESS_SAMPLE_BYTES { break; }
+    sample_bytes += c.uncompressed_size() as u64;
+    sample.push(*c);
+  }
+  let wad_data = &mmap[..];
+  let (decompress_ms, threads) = run_cpu(|| {
+    let start = Instant::now();
+    sample.par_iter().for_each(|c| { let _ = decompress_chunk(wad_data, c); });
+    (ms_since(start), rayon::current_num_threads() as u32)
+  });
+  let decompress_mb_per_sec = mb_per_sec(sample_bytes, decompress_ms);
+
+  let scratch: PathBuf = scratch_dir.map(PathBuf::from).unwrap_or_else(std::env::temp_dir);
+  let write_mb_per_sec = benchmark_write(&scratch)?;
+
+  let total_mb = chunks.iter().map(|c| c.uncompressed_size() as f64).sum::<f64>() / (1024.0 * 1024.0);
+  let estimate = |rate: f64| if rate > 0.0 { total_mb / rate * 1000.0 } else { 0.0 };
+  let estimated_decompress_ms = estimate(decompress_mb_per_sec);
+  let estimated_write_ms = estimate(write_mb_per_sec);
+  let estimated_resolve_ms = toc_ms + resolve_ms;
+  let bottleneck = if estimated_write_ms >= estimated_decompress_ms && estimated_write_ms >= estimated_resolve_ms {
+    "disk"
+  } else if estimated_decompress_ms >= estimated_resolve_ms {
+    "cpu"
+  } else {
+    "hashDb"
+  };
+
+  Ok(BenchmarkReport {
+    success: true,
+    error: None,
+    chunk_count: chunks.len() as u32,
+    toc_ms,
+    resolve_ms,
+    resolved_count,
+    hash_db_available: layers.has_lmdb(),
+    decompress_mb_per_sec,
+    decompress_threads: threads,
+    write_mb_per_sec,
+    estimated_resolve_ms,
+    estimated_decompress_ms,
+    estimated_write_ms,
+    bottleneck: bottleneck.to_string(),
+  })
+}
+
+fn failed(e: String) -> BenchmarkReport {
+  BenchmarkReport {
+    success: false,
+    error: Some(e),
+    chunk_count: 0,
+    toc_ms: 0.0,
+    resolve_ms: 0.0,
+    resolved_count: 0,
+    hash_db_available: false,
+    decompress_mb_per_sec: 0.0,
+    decompress_threads: 0,
+    write_mb_per_sec: 0.0,
+    estimated_resolve_ms: 0.0,
+    estimated_decompress_ms: 0.0,
+    estimated_write_ms: 0.0,
+    bottleneck: String::new(),
+  }
+}
+
+/// Measure extraction stages on this machine using `wadPath`. The disk test
+/// writes ~128 MB to `scratchDir` (default: the system temp dir) and removes it.
+#[napi(js_name = "runBenchmark")]
+pub fn run_benchmark(wad_path: String, hash_dir: Option<String>, scratch_dir: Option<String>) -> BenchmarkReport {
+  run(&wad_path, hash_dir.as_deref(), scratch_dir.as_deref()).unwrap_or_else(failed)
+}
+
+pub struct BenchmarkTask {
+  wad_path: String,
+  hash_dir: Option<String>,
+  scratch_dir: Option<String>,
+}
+
+#[napi]
+impl Task for BenchmarkTask {
+  type Output = BenchmarkReport;
+  type JsValue = BenchmarkReport;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(run_benchmark(self.wad_path.clone(), self.hash_dir.clone(), self.scratch_dir.clone()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+#[napi(js_name = "runBenchmarkAsync")]
+pub fn run_benchmark_async(wad_path: String, hash_dir: Option<String>, scratch_dir: Option<String>) -> AsyncTask<BenchmarkTask> {
+  AsyncTask::new(BenchmarkTask { wad_path, hash_dir, scratch_dir })
+}
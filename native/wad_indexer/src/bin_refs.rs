@@ -0,0 +1,185 @@
+// ── Bin asset references ─────────────────────────────────────────────────────
+// References a bin makes, read from the parsed tree rather than its ritobin
+// text: asset path strings, WAD chunk links (`file` values, which text scans
+// only see as hex) and links to other bin objects, plus the bin's linked
+// dependencies. Each reference names the object and property it sits in, so
+// the caller can show "VfxEmitterDefinitionData.texture" rather than a line.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_meta::property::values::{self, Container, Optional};
+use ltk_meta::{Bin, PropertyValueEnum};
+use ltk_ritobin::{HashMapProvider, HashProvider};
+use napi_derive::napi;
+use quartz_core::resolver::HashSource;
+use quartz_core::scan::is_asset_path;
+
+use crate::project_search::hash_provider;
+use crate::HashLayers;
+
+#[napi(object)]
+pub struct AssetReference {
+  /// "path" (string value), "wadChunk" (file link), "object" (link to a bin
+  /// object) or "dependency" (linked bin).
+  pub kind: String,
+  /// The path, or the hex hash for links.
+  pub value: String,
+  /// Name of a hashed link when known.
+  pub resolved: Option<String>,
+  /// Object the reference is in (entry name or hex path hash); empty for dependencies.
+  pub object: String,
+  /// Class of that object.
+  #[napi(js_name = "objectClass")]
+  pub object_class: String,
+  /// Property path inside the object, e.g. "complexEmitterDefinitionData[2].texture".
+  pub property: String,
+}
+
+#[napi(object)]
+pub struct AssetReferencesResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub references: Vec<AssetReference>,
+}
+
+struct Walker<'a> {
+  hashes: &'a HashMapProvider,
+  object: String,
+  object_class: String,
+  refs: Vec<AssetReference>,
+  /// WAD chunk links to resolve once the walk is done, by index into `refs`.
+  chunk_links: Vec<(usize, u64)>,
+}
+
+impl Walker<'_> {
+  fn field_name(&self, hash: u32) -> String {
+    self.hashes.lookup_field(hash).map(str::to_string).unwrap_or_else(|| format!("{:#010x}", hash))
+  }
+
+  fn push(&mut self, kind: &str, value: String, resolved: Option<String>, property: &str) {
+    self.refs.push(AssetReference {
+      kind: kind.to_string(),
+      value,
+      resolved,
+      object: self.object.clone(),
+      object_class: self.object_class.clone(),
+      property: property.to_string(),
+    });
+  }
+
+  fn visit_struct(&mut self, s: &values::Struct, path: &str) {
+    for (h, prop) in &s.properties {
+      let child = format!("{}.{}", path, self.field_name(*h));
+      self.visit_value(&prop.value, &child);
+    }
+  }
+
+  fn visit_string(&mut self, s: &values::String, path: &str) {
+    if is_asset_path(&s.value) {
+      self.push("path", s.value.clone(), None, path);
+    }
+  }
+
+  fn visit_chunk_link(&mut self, hash: u64, path: &str) {
+    if hash == 0 { return; }
+    self.chunk_links.push((self.refs.len(), hash));
+    self.push("wadChunk", format!("{:016x}", hash), None, path);
+  }
+
+  fn visit_object_link(&mut self, hash: u32, path: &str) {
+    if hash == 0 { return; }
+    let resolved = self.hashes.lookup_entry(hash).map(str::to_string);
+    self.push("object", format!("{:08x}", hash), resolved, path);
+  }
+
+  fn visit_value(&mut self, value: &PropertyValueEnum, path: &str) {
+    use PropertyValueEnum as P;
+    match value {
+      P::String(s) => self.visit_string(s, path),
+      P::WadChunkLink(l) => self.visit_chunk_link(l.value, path),
+      P::ObjectLink(l) => self.visit_object_link(l.value, path),
+      P::Struct(s) => self.visit_struct(s, path),
+      P::Embedded(e) => self.visit_struct(&e.0, path),
+      P::Optional(o) => match o {
+        Optional::String(Some(s)) => self.visit_string(s, path),
+        Optional::WadChunkLink(Some(l)) => self.visit_chunk_link(l.value, path),
+        Optional::ObjectLink(Some(l)) => self.visit_object_link(l.value, path),
+        Optional::Struct(Some(s)) => self.visit_struct(s, path),
+        Optional::Embedded(Some(e)) => self.visit_struct(&e.0, path),
+        _ => {}
+      },
+      P::Container(c) | P::UnorderedContainer(values::UnorderedContainer(c)) => match c {
+        Container::String { items, .. } => {
+          for (i, s) in items.iter().enumerate() { self.visit_string(s, &format!("{}[{}]", path, i)); }
+        }
+        Container::WadChunkLink { items, .. } => {
+          for (i, l) in items.iter().enumerate() { self.visit_chunk_link(l.value, &format!("{}[{}]", path, i)); }
+        }
+        Container::ObjectLink { items, .. } => {
+          for (i, l) in items.iter().enumerate() { self.visit_object_link(l.value, &format!("{}[{}]", path, i)); }
+        }
+        Container::Struct { items, .. } => {
+          for (i, s) in items.iter().enumerate() { self.visit_struct(s, &format!("{}[{}]", path, i)); }
+        }
+        Container::Embedded { items, .. } => {
+          for (i, e) in items.iter().enumerate() { self.visit_struct(&e.0, &format!("{}[{}]", path, i)); }
+        }
+        _ => {}
+      },
+      P::Map(m) => {
+        for (i, (k, v)) in m.entries().iter().enumerate() {
+          let key = match k {
+            P::String(s) => format!("{:?}", s.value),
+            P::Hash(h) => self.hashes.lookup_hash(h.value).map(str::to_string).unwrap_or_else(|| format!("{:#010x}", h.value)),
+            _ => i.to_string(),
+          };
+          let child = format!("{}{{{}}}", path, key);
+          self.visit_value(k, &child);
+          self.visit_value(v, &child);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+fn extract(bin_path: &Path, hash_dir: Option<&str>) -> Result<Vec<AssetReference>, String> {
+  let data = fs::read(bin_path).map_err(|e| format!("Failed to read {}: {}", bin_path.display(), e))?;
+  let tree = Bin::from_reader(&mut Cursor::new(&data)).map_err(|e| format!("Failed to parse {}: {:?}", bin_path.display(), e))?;
+  let hashes = hash_provider(hash_dir);
+  let mut walker = Walker { hashes: &hashes, object: String::new(), object_class: String::new(), refs: Vec::new(), chunk_links: Vec::new() };
+
+  for dep in &tree.dependencies {
+    walker.push("dependency", dep.clone(), None, "");
+  }
+  for (path_hash, object) in &tree.objects {
+    walker.object = hashes.lookup_entry(*path_hash).map(str::to_string).unwrap_or_else(|| format!("{:#010x}", path_hash));
+    walker.object_class = hashes.lookup_type(object.class_hash).map(str::to_string).unwrap_or_else(|| format!("{:#010x}", object.class_hash));
+    for (h, prop) in &object.properties {
+      let name = walker.field_name(*h);
+      walker.visit_value(&prop.value, &name);
+    }
+  }
+
+  if let Some(dir) = hash_dir.filter(|_| !walker.chunk_links.is_empty()) {
+    let link_hashes: Vec<u64> = walker.chunk_links.iter().map(|(_, h)| *h).collect();
+    let names = HashLayers::open(Some(dir)).resolve(&link_hashes);
+    for ((i, _), name) in walker.chunk_links.iter().zip(names) {
+      if name.source != HashSource::Hex { walker.refs[*i].resolved = Some(name.name); }
+    }
+  }
+  Ok(walker.refs)
+}
+
+/// Asset references of a .bin, read structurally: path strings, WAD chunk
+/// links, object links and dependencies, each with its object and property.
+/// `hashDir` names fields, objects and hashed links.
+#[napi(js_name = "extractAssetReferences")]
+pub fn extract_asset_references(bin_path: String, hash_dir: Option<String>) -> AssetReferencesResult {
+  match extract(Path::new(&bin_path), hash_dir.as_deref()) {
+    Ok(references) => AssetReferencesResult { success: true, error: None, references },
+    Err(e) => AssetReferencesResult { success: false, error: Some(e), references: Vec::new() },
+  }
+}
@@ -0,0 +1,258 @@
+// ── Bin full-text search ─────────────────────────────────────────────────────
+// Tantivy index over the ritobin text of every bin (and .py/.ritobin text)
+// under an extracted WAD or project, for "which bins mention
+// leblanc_base_q_mis" over tens of thousands of files in well under a second.
+// `project_search` scans in memory and suits a single project; this persists
+// to disk and scales to a whole extracted game. Re-indexing only converts
+// files whose mtime changed (tracked in `files.json` next to the index).
+// Built with the `bin-search` cargo feature.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, UNIX_EPOCH};
+
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+use tantivy::collector::{Count, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, Schema, Value, STORED, STRING, TEXT};
+use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+
+use crate::project_search::{extract_text, hash_provider};
+use crate::threads::run_io;
+use crate::wad_build::collect_files;
+
+const FILES_JSON: &str = "files.json";
+const WRITER_HEAP_BYTES: usize = 256 * 1024 * 1024;
+const DEFAULT_LIMIT: u32 = 200;
+
+struct Fields {
+  path: Field,
+  body: Field,
+}
+
+fn schema() -> (Schema, Fields) {
+  let mut builder = Schema::builder();
+  let path = builder.add_text_field("path", STRING | STORED);
+  let body = builder.add_text_field("body", TEXT);
+  (builder.build(), Fields { path, body })
+}
+
+fn open_index(index_dir: &Path) -> Result<(Index, Fields), String> {
+  fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create {}: {}", index_dir.display(), e))?;
+  let dir = MmapDirectory::open(index_dir).map_err(|e| format!("Failed to open {}: {}", index_dir.display(), e))?;
+  let (schema, fields) = schema();
+  let index = Index::open_or_create(dir, schema).map_err(|e| format!("Failed to open index {}: {}", index_dir.display(), e))?;
+  Ok((index, fields))
+}
+
+/// Readers are kept per index dir so repeated queries don't reopen segments.
+fn readers() -> &'static Mutex<HashMap<PathBuf, (Index, IndexReader)>> {
+  static READERS: OnceLock<Mutex<HashMap<PathBuf, (Index, IndexReader)>>> = OnceLock::new();
+  READERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached_reader(index_dir: &Path) -> Result<(Index, IndexReader), String> {
+  let mut readers = readers().lock().unwrap_or_else(|e| e.into_inner());
+  if let Some(entry) = readers.get(index_dir) { return Ok(entry.clone()); }
+  if !index_dir.join("meta.json").exists() {
+    return Err(format!("No bin index at {}; run indexBins first", index_dir.display()));
+  }
+  let (index, _) = open_index(index_dir)?;
+  let reader = index
+    .reader_builder()
+    .reload_policy(ReloadPolicy::Manual)
+    .try_into()
+    .map_err(|e| format!("Failed to open index reader: {}", e))?;
+  readers.insert(index_dir.to_path_buf(), (index.clone(), reader));
+  Ok(readers[index_dir].clone())
+}
+
+fn is_indexable(rel: &str) -> bool {
+  let lower = rel.to_ascii_lowercase();
+  lower.ends_with(".bin") || lower.ends_with(".py") || lower.ends_with(".ritobin")
+}
+
+fn mtime_ms(path: &Path) -> f64 {
+  fs::metadata(path)
+    .and_then(|m| m.modified())
+    .ok()
+    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+    .map(|d| d.as_millis() as f64)
+    .unwrap_or(0.0)
+}
+
+#[napi(object)]
+pub struct BinIndexResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "fileCount")]
+  pub file_count: u32,
+  /// Files converted and (re)added this run.
+  #[napi(js_name = "indexedCount")]
+  pub indexed_count: u32,
+  #[napi(js_name = "removedCount")]
+  pub removed_count: u32,
+  /// Files that couldn't be read or converted.
+  #[napi(js_name = "failedCount")]
+  pub failed_count: u32,
+  #[napi(js_name = "elapsedMs")]
+  pub elapsed_ms: f64,
+}
+
+fn index(source_dir: &Path, index_dir: &Path, hash_dir: Option<&str>) -> Result<BinIndexResult, String> {
+  let start = Instant::now();
+  if !source_dir.is_dir() {
+    return Err(format!("Folder not found: {}", source_dir.display()));
+  }
+  let (index, fields) = open_index(index_dir)?;
+  let files_json = index_dir.join(FILES_JSON);
+  let previous: HashMap<String, f64> = fs::read_to_string(&files_json)
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default();
+  let current: Vec<(String, PathBuf, f64)> = collect_files(source_dir)?
+    .into_iter()
+    .filter(|(rel, _)| is_indexable(rel))
+    .map(|(rel, p)| { let m = mtime_ms(&p); (rel, p, m) })
+    .collect();
+
+  let mut writer: IndexWriter = index.writer(WRITER_HEAP_BYTES).map_err(|e| format!("Failed to open index writer: {}", e))?;
+  let current_names: HashMap<&str, f64> = current.iter().map(|(rel, _, m)| (rel.as_str(), *m)).collect();
+  let mut removed = 0u32;
+  for rel in previous.keys() {
+    if !current_names.contains_key(rel.as_str()) {
+      writer.delete_term(Term::from_field_text(fields.path, rel));
+      removed += 1;
+    }
+  }
+
+  let changed: Vec<&(String, PathBuf, f64)> = current.iter().filter(|(rel, _, m)| previous.get(rel) != Some(m)).collect();
+  let hashes = hash_provider(hash_dir);
+  let (path_field, body_field) = (fields.path, fields.body);
+  let outcomes: Vec<(&str, f64, bool)> = run_io(|| {
+    changed
+      .par_iter()
+      .map(|(rel, path, m)| {
+        writer.delete_term(Term::from_field_text(path_field, rel));
+        let Some(text) = extract_text(path, &rel.to_ascii_lowercase(), &hashes) else { return (rel.as_str(), *m, false) };
+        let added = writer.add_document(doc!(path_field => rel.as_str(), body_field => text)).is_ok();
+        (rel.as_str(), *m, added)
+      })
+      .collect()
+  });
+  writer.commit().map_err(|e| format!("Failed to commit index: {}", e))?;
+
+  let indexed = outcomes.iter().filter(|(_, _, ok)| *ok).count() as u32;
+  let failed = outcomes.len() as u32 - indexed;
+  // Failed files are recorded too, so they're retried only once they change.
+  let mut recorded: HashMap<String, f64> = previous.into_iter().filter(|(rel, _)| current_names.contains_key(rel.as_str())).collect();
+  for (rel, m, _) in &outcomes { recorded.insert(rel.to_string(), *m); }
+  let text = serde_json::to_string(&recorded).map_err(|e| format!("Failed to serialize {}: {}", FILES_JSON, e))?;
+  fs::write(&files_json, text).map_err(|e| format!("Failed to write {}: {}", files_json.display(), e))?;
+
+  if let Some((_, reader)) = readers().lock().unwrap_or_else(|e| e.into_inner()).get(index_dir) {
+    let _ = reader.reload();
+  }
+  Ok(BinIndexResult {
+    success: true,
+    error: None,
+    file_count: current.len() as u32,
+    indexed_count: indexed,
+    removed_count: removed,
+    failed_count: failed,
+    elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+  })
+}
+
+pub struct IndexBinsTask {
+  source_dir: String,
+  index_dir: String,
+  hash_dir: Option<String>,
+}
+
+#[napi]
+impl Task for IndexBinsTask {
+  type Output = BinIndexResult;
+  type JsValue = BinIndexResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(index(Path::new(&self.source_dir), Path::new(&self.index_dir), self.hash_dir.as_deref()).unwrap_or_else(|e| BinIndexResult {
+      success: false,
+      error: Some(e),
+      file_count: 0,
+      indexed_count: 0,
+      removed_count: 0,
+      failed_count: 0,
+      elapsed_ms: 0.0,
+    }))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// Index the bins under `sourceDir` (an extracted WAD or a project) into
+/// `indexDir`. `hashDir` names hashes in the converted text so field and
+/// class names are searchable.
+#[napi(js_name = "indexBins")]
+pub fn index_bins(source_dir: String, index_dir: String, hash_dir: Option<String>) -> AsyncTask<IndexBinsTask> {
+  AsyncTask::new(IndexBinsTask { source_dir, index_dir, hash_dir })
+}
+
+#[napi(object)]
+pub struct BinSearchHit {
+  /// Relative to the indexed folder.
+  pub path: String,
+  pub score: f64,
+}
+
+#[napi(object)]
+pub struct BinSearchResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// All matching files, including ones beyond `limit`.
+  #[napi(js_name = "totalHits")]
+  pub total_hits: u32,
+  pub hits: Vec<BinSearchHit>,
+}
+
+fn query(index_dir: &Path, q: &str, limit: u32) -> Result<BinSearchResult, String> {
+  let (index, reader) = cached_reader(index_dir)?;
+  let (_, fields) = schema();
+  let searcher = reader.searcher();
+  // A term like "leblanc_base_q_mis" tokenizes into a phrase, so it matches
+  // the name as written rather than any bin containing "base".
+  let parser = QueryParser::for_index(&index, vec![fields.body, fields.path]);
+  let (parsed, _) = parser.parse_query_lenient(q);
+  let (top, total) = searcher
+    .search(&parsed, &(TopDocs::with_limit(limit.max(1) as usize), Count))
+    .map_err(|e| format!("Search failed: {}", e))?;
+  let hits = top
+    .into_iter()
+    .filter_map(|(score, addr)| {
+      let doc: TantivyDocument = searcher.doc(addr).ok()?;
+      let path = doc.get_first(fields.path)?.as_str()?.to_string();
+      Some(BinSearchHit { path, score: score as f64 })
+    })
+    .collect();
+  Ok(BinSearchResult { success: true, error: None, total_hits: total as u32, hits })
+}
+
+/// Query an index built by `indexBins`. Supports tantivy query syntax
+/// (`"quoted phrases"`, `AND`/`OR`, `-excluded`).
+#[napi(js_name = "queryBins")]
+pub fn query_bins(index_dir: String, query_text: String, limit: Option<u32>) -> BinSearchResult {
+  query(Path::new(&index_dir), &query_text, limit.unwrap_or(DEFAULT_LIMIT)).unwrap_or_else(|e| BinSearchResult {
+    success: false,
+    error: Some(e),
+    total_hits: 0,
+    hits: Vec::new(),
+  })
+}
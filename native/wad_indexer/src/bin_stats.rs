@@ -0,0 +1,265 @@
+// ── Bin hash usage ───────────────────────────────────────────────────────────
+// Counts how often each class (type) and field hash occurs across every .bin
+// under an extracted game folder. Sorting by frequency shows which unknown
+// hashes are worth cracking first: a field on every VFX emitter matters more
+// than one used by a single map prop.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_meta::property::values::{self, Container, Optional};
+use ltk_meta::{Bin, PropertyValueEnum};
+use ltk_ritobin::HashProvider;
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::project_search::hash_provider;
+use crate::threads::run_io;
+use crate::wad_build::collect_files;
+
+const DEFAULT_LIMIT: u32 = 500;
+
+#[derive(Default)]
+struct Counts {
+  /// hash -> (occurrences, bins containing it)
+  classes: HashMap<u32, (u64, u32)>,
+  fields: HashMap<u32, (u64, u32)>,
+  bins: u32,
+  failed: u32,
+}
+
+impl Counts {
+  fn merge(mut self, other: Counts) -> Counts {
+    for (h, (n, f)) in other.classes {
+      let e = self.classes.entry(h).or_default();
+      e.0 += n;
+      e.1 += f;
+    }
+    for (h, (n, f)) in other.fields {
+      let e = self.fields.entry(h).or_default();
+      e.0 += n;
+      e.1 += f;
+    }
+    self.bins += other.bins;
+    self.failed += other.failed;
+    self
+  }
+}
+
+/// Per-bin tallies, folded into `Counts` so file counts stay per bin.
+#[derive(Default)]
+struct BinTally {
+  classes: HashMap<u32, u64>,
+  fields: HashMap<u32, u64>,
+}
+
+impl BinTally {
+  fn visit_struct(&mut self, s: &values::Struct) {
+    *self.classes.entry(s.class_hash).or_default() += 1;
+    for (h, prop) in &s.properties {
+      *self.fields.entry(*h).or_default() += 1;
+      self.visit_value(&prop.value);
+    }
+  }
+
+  fn visit_value(&mut self, value: &PropertyValueEnum) {
+    use PropertyValueEnum as P;
+    match value {
+      P::Struct(s) => self.visit_struct(s),
+      P::Embedded(e) => self.visit_struct(&e.0),
+      P::Optional(Optional::Struct(Some(s))) => self.visit_struct(s),
+      P::Optional(Optional::Embedded(Some(e))) => self.visit_struct(&e.0),
+      P::Container(c) | P::UnorderedContainer(values::UnorderedContainer(c)) => match c {
+        Container::Struct { items, .. } => items.iter().for_each(|s| self.visit_struct(s)),
+        Container::Embedded { items, .. } => items.iter().for_each(|e| self.visit_struct(&e.0)),
+        _ => {}
+      },
+      P::Map(m) => {
+        for (k, v) in m.entries() {
+          self.visit_value(k);
+          self.visit_value(v);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn add_to(self, counts: &mut Counts) {
+    for (h, n) in self.classes {
+      let e = counts.classes.entry(h).or_default();
+      e.0 += n;
+      e.1 += 1;
+    }
+    for (h, n) in self.fields {
+      let e = counts.fields.entry(h).or_default();
+      e.0 += n;
+      e.1 += 1;
+    }
+    counts.bins += 1;
+  }
+}
+
+fn tally_bin(path: &Path) -> Option<BinTally> {
+  let data = fs::read(path).ok()?;
+  let tree = Bin::from_reader(&mut Cursor::new(&data)).ok()?;
+  let mut tally = BinTally::default();
+  for object in tree.objects.values() {
+    *tally.classes.entry(object.class_hash).or_default() += 1;
+    for (h, prop) in &object.properties {
+      *tally.fields.entry(*h).or_default() += 1;
+      tally.visit_value(&prop.value);
+    }
+  }
+  Some(tally)
+}
+
+#[napi(object)]
+pub struct BinHashUsageOptions {
+  /// Names known hashes (hashes.bintypes.txt / hashes.binfields.txt).
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+  /// Rows per table, most frequent first. Default 500; 0 for all.
+  pub limit: Option<u32>,
+  /// Only list hashes without a known name.
+  #[napi(js_name = "unknownOnly")]
+  pub unknown_only: Option<bool>,
+}
+
+#[napi(object)]
+pub struct BinHashUsage {
+  /// "0x"-prefixed 8-digit hex.
+  pub hash: String,
+  pub name: Option<String>,
+  pub count: f64,
+  /// Bins the hash appears in at least once.
+  #[napi(js_name = "binCount")]
+  pub bin_count: u32,
+}
+
+#[napi(object)]
+pub struct BinHashUsageResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "binCount")]
+  pub bin_count: u32,
+  /// Bins that failed to parse (skipped).
+  #[napi(js_name = "failedCount")]
+  pub failed_count: u32,
+  pub classes: Vec<BinHashUsage>,
+  pub fields: Vec<BinHashUsage>,
+  #[napi(js_name = "unknownClassCount")]
+  pub unknown_class_count: u32,
+  #[napi(js_name = "unknownFieldCount")]
+  pub unknown_field_count: u32,
+}
+
+fn table(
+  counts: HashMap<u32, (u64, u32)>,
+  lookup: impl Fn(u32) -> Option<String>,
+  unknown_only: bool,
+  limit: u32,
+) -> (Vec<BinHashUsage>, u32) {
+  let mut rows: Vec<BinHashUsage> = counts
+    .into_iter()
+    .map(|(h, (count, bins))| BinHashUsage { hash: format!("0x{:08x}", h), name: lookup(h), count: count as f64, bin_count: bins })
+    .collect();
+  let unknown = rows.iter().filter(|r| r.name.is_none()).count() as u32;
+  if unknown_only { rows.retain(|r| r.name.is_none()); }
+  rows.sort_by(|a, b| b.count.total_cmp(&a.count).then_with(|| a.hash.cmp(&b.hash)));
+  if limit > 0 { rows.truncate(limit as usize); }
+  (rows, unknown)
+}
+
+fn analyze(extracted_dir: &str, options: BinHashUsageOptions) -> Result<BinHashUsageResult, String> {
+  let root = Path::new(extracted_dir);
+  if !root.is_dir() {
+    return Err(format!("Folder not found: {}", extracted_dir));
+  }
+  let bins: Vec<_> = collect_files(root)?
+    .into_iter()
+    .filter(|(rel, _)| rel.to_ascii_lowercase().ends_with(".bin"))
+    .map(|(_, p)| p)
+    .collect();
+  let counts = run_io(|| {
+    bins
+      .par_iter()
+      .fold(Counts::default, |mut acc, path| {
+        match tally_bin(path) {
+          Some(tally) => tally.add_to(&mut acc),
+          None => acc.failed += 1,
+        }
+        acc
+      })
+      .reduce(Counts::default, Counts::merge)
+  });
+
+  let hashes = hash_provider(options.hash_dir.as_deref());
+  let unknown_only = options.unknown_only.unwrap_or(false);
+  let limit = options.limit.unwrap_or(DEFAULT_LIMIT);
+  let (classes, unknown_class_count) =
+    table(counts.classes, |h| hashes.lookup_type(h).map(str::to_string), unknown_only, limit);
+  let (fields, unknown_field_count) =
+    table(counts.fields, |h| hashes.lookup_field(h).map(str::to_string), unknown_only, limit);
+  Ok(BinHashUsageResult {
+    success: true,
+    error: None,
+    bin_count: counts.bins,
+    failed_count: counts.failed,
+    classes,
+    fields,
+    unknown_class_count,
+    unknown_field_count,
+  })
+}
+
+fn failed(e: String) -> BinHashUsageResult {
+  BinHashUsageResult {
+    success: false,
+    error: Some(e),
+    bin_count: 0,
+    failed_count: 0,
+    classes: Vec::new(),
+    fields: Vec::new(),
+    unknown_class_count: 0,
+    unknown_field_count: 0,
+  }
+}
+
+fn default_options() -> BinHashUsageOptions {
+  BinHashUsageOptions { hash_dir: None, limit: None, unknown_only: None }
+}
+
+/// Class and field hash frequencies across all .bin files under `extractedDir`.
+#[napi(js_name = "analyzeBinHashUsage")]
+pub fn analyze_bin_hash_usage(extracted_dir: String, options: Option<BinHashUsageOptions>) -> BinHashUsageResult {
+  analyze(&extracted_dir, options.unwrap_or_else(default_options)).unwrap_or_else(failed)
+}
+
+pub struct BinHashUsageTask {
+  extracted_dir: String,
+  options: Option<BinHashUsageOptions>,
+}
+
+#[napi]
+impl Task for BinHashUsageTask {
+  type Output = BinHashUsageResult;
+  type JsValue = BinHashUsageResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let options = self.options.take().unwrap_or_else(default_options);
+    Ok(analyze(&self.extracted_dir, options).unwrap_or_else(failed))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+#[napi(js_name = "analyzeBinHashUsageAsync")]
+pub fn analyze_bin_hash_usage_async(extracted_dir: String, options: Option<BinHashUsageOptions>) -> AsyncTask<BinHashUsageTask> {
+  AsyncTask::new(BinHashUsageTask { extracted_dir, options })
+}
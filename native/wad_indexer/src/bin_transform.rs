@@ -0,0 +1,348 @@
+// ── Bulk numeric transforms ──────────────────────────────────────────────────
+// Scale, offset, clamp or set every numeric value under the fields matching a
+// selector across a whole bin, e.g. `lifetime` ×1.5 on every emitter, instead of
+// hand-editing dozens of them. A selector is a field name or 0x hash, optionally
+// prefixed with the class holding it: `VfxEmitterDefinitionData.rate`. A matched
+// field that is itself a struct (ValueFloat and friends) has all numbers inside
+// it transformed, except dynamics keyframe `times`. Dry runs list what would
+// change, with before/after statistics, without writing the bin.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_meta::property::values::{self, Container, Optional};
+use ltk_meta::{Bin, BinProperty, PropertyValueEnum};
+use ltk_ritobin::{HashMapProvider, HashProvider};
+use napi_derive::napi;
+
+use crate::fnv1a_lower;
+use crate::paths::write_retrying;
+use crate::project_search::hash_provider;
+use crate::scripting::name_hash;
+
+/// Changes listed in the result; statistics still cover every value.
+const MAX_LISTED: usize = 5000;
+
+#[napi(object)]
+pub struct BinValueTransform {
+  /// "scale", "offset", "clamp" or "set".
+  pub op: String,
+  /// Factor for scale, amount for offset, new value for set.
+  pub value: Option<f64>,
+  /// Bounds for clamp; either may be omitted.
+  pub min: Option<f64>,
+  pub max: Option<f64>,
+}
+
+#[napi(object)]
+pub struct TransformBinValuesOptions {
+  /// List matches without writing the bin.
+  #[napi(js_name = "dryRun")]
+  pub dry_run: Option<bool>,
+  /// Names objects and fields in the listing.
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+  /// Write the result here instead of over the input bin.
+  #[napi(js_name = "outputPath")]
+  pub output_path: Option<String>,
+}
+
+#[napi(object)]
+pub struct BinValueChange {
+  /// Entry path of the bin object, or its 0x hash.
+  pub object: String,
+  /// Field path inside the object, e.g. "complexEmitterDefinitionData.3.rate.constantValue".
+  pub path: String,
+  pub before: f64,
+  pub after: f64,
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct BinValueStats {
+  pub count: u32,
+  pub min: Option<f64>,
+  pub max: Option<f64>,
+  pub mean: Option<f64>,
+}
+
+#[napi(object)]
+pub struct TransformBinValuesResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Numeric values under matching fields.
+  pub matched: u32,
+  /// Matched values whose value actually changed.
+  pub changed: u32,
+  /// Up to 5000 matched values in bin order.
+  pub changes: Vec<BinValueChange>,
+  pub truncated: bool,
+  pub before: BinValueStats,
+  pub after: BinValueStats,
+  /// True when the bin was written (not a dry run, and something changed).
+  pub written: bool,
+}
+
+enum Op {
+  Scale(f64),
+  Offset(f64),
+  Clamp(Option<f64>, Option<f64>),
+  Set(f64),
+}
+
+impl Op {
+  fn parse(t: &BinValueTransform) -> Result<Op, String> {
+    let value = || t.value.ok_or_else(|| format!("\"{}\" needs a value", t.op));
+    match t.op.as_str() {
+      "scale" => Ok(Op::Scale(value()?)),
+      "offset" => Ok(Op::Offset(value()?)),
+      "set" => Ok(Op::Set(value()?)),
+      "clamp" if t.min.is_none() && t.max.is_none() => Err("\"clamp\" needs min or max".to_string()),
+      "clamp" => Ok(Op::Clamp(t.min, t.max)),
+      other => Err(format!("Unknown operation: {}", other)),
+    }
+  }
+
+  fn apply(&self, x: f64) -> f64 {
+    match *self {
+      Op::Scale(f) => x * f,
+      Op::Offset(d) => x + d,
+      Op::Clamp(min, max) => x.max(min.unwrap_or(f64::MIN)).min(max.unwrap_or(f64::MAX)),
+      Op::Set(v) => v,
+    }
+  }
+}
+
+fn stats(values: impl Iterator<Item = f64>) -> BinValueStats {
+  let mut s = BinValueStats::default();
+  let mut sum = 0.0;
+  for v in values {
+    s.count += 1;
+    sum += v;
+    s.min = Some(s.min.map_or(v, |m| m.min(v)));
+    s.max = Some(s.max.map_or(v, |m| m.max(v)));
+  }
+  s.mean = (s.count > 0).then(|| sum / s.count as f64);
+  s
+}
+
+/// Transform an integer in place, rounding and saturating to its type.
+macro_rules! int {
+  ($w:ident, $path:expr, $v:expr, $t:ty) => {
+    $v = $w.number($path, $v as f64, |x| x.round().clamp(<$t>::MIN as f64, <$t>::MAX as f64)) as $t
+  };
+}
+
+struct Walker<'a> {
+  op: Op,
+  field: u32,
+  class: Option<u32>,
+  times: u32,
+  hashes: &'a HashMapProvider,
+  object: String,
+  /// (object, path, before, after) of every matched value.
+  values: Vec<(String, String, f64, f64)>,
+}
+
+impl Walker<'_> {
+  fn number(&mut self, path: &str, before: f64, store: impl Fn(f64) -> f64) -> f64 {
+    let after = store(self.op.apply(before));
+    self.values.push((self.object.clone(), path.to_string(), before, after));
+    after
+  }
+
+  fn f32(&mut self, path: &str, v: &mut f32) {
+    *v = self.number(path, *v as f64, |x| x as f32 as f64) as f32;
+  }
+
+  fn floats(&mut self, path: &mut String, items: &mut [f32], names: &[&str]) {
+    for (c, name) in items.iter_mut().zip(names) {
+      let len = path.len();
+      path.push('.');
+      path.push_str(name);
+      self.f32(path, c);
+      path.truncate(len);
+    }
+  }
+
+  fn props<'p>(&mut self, class: u32, props: impl Iterator<Item = (&'p u32, &'p mut BinProperty)>, path: &mut String, inside: bool) {
+    let class_matches = self.class.is_none_or(|c| c == class);
+    for (h, prop) in props {
+      if inside && *h == self.times {
+        continue;
+      }
+      let len = path.len();
+      if !path.is_empty() {
+        path.push('.');
+      }
+      match self.hashes.lookup_field(*h) {
+        Some(name) => path.push_str(name),
+        None => path.push_str(&format!("0x{:08x}", h)),
+      }
+      let matched = inside || (*h == self.field && class_matches);
+      self.value(&mut prop.value, path, matched);
+      path.truncate(len);
+    }
+  }
+
+  fn indexed<T>(&mut self, path: &mut String, items: &mut [T], mut f: impl FnMut(&mut Self, &mut String, &mut T)) {
+    for (i, item) in items.iter_mut().enumerate() {
+      let len = path.len();
+      path.push_str(&format!(".{}", i));
+      f(self, path, item);
+      path.truncate(len);
+    }
+  }
+
+  fn value(&mut self, value: &mut PropertyValueEnum, path: &mut String, matched: bool) {
+    use PropertyValueEnum as P;
+    match value {
+      P::Struct(s) => self.props(s.class_hash, s.properties.iter_mut(), path, matched),
+      P::Embedded(e) => self.props(e.0.class_hash, e.0.properties.iter_mut(), path, matched),
+      P::Optional(Optional::Struct(Some(s))) => self.props(s.class_hash, s.properties.iter_mut(), path, matched),
+      P::Optional(Optional::Embedded(Some(e))) => self.props(e.0.class_hash, e.0.properties.iter_mut(), path, matched),
+      P::Container(c) | P::UnorderedContainer(values::UnorderedContainer(c)) => match c {
+        Container::Struct { items, .. } => {
+          self.indexed(path, items, |w, p, s| w.props(s.class_hash, s.properties.iter_mut(), p, matched))
+        }
+        Container::Embedded { items, .. } => {
+          self.indexed(path, items, |w, p, e| w.props(e.0.class_hash, e.0.properties.iter_mut(), p, matched))
+        }
+        Container::F32 { items, .. } if matched => self.indexed(path, items, |w, p, v| w.f32(p, &mut v.value)),
+        Container::I32 { items, .. } if matched => self.indexed(path, items, |w, p, v| int!(w, p, v.value, i32)),
+        Container::U32 { items, .. } if matched => self.indexed(path, items, |w, p, v| int!(w, p, v.value, u32)),
+        Container::Vector2 { items, .. } if matched => self.indexed(path, items, |w, p, v| {
+          let mut a = v.value.to_array();
+          w.floats(p, &mut a, &["x", "y"]);
+          v.value = a.into();
+        }),
+        Container::Vector3 { items, .. } if matched => self.indexed(path, items, |w, p, v| {
+          let mut a = v.value.to_array();
+          w.floats(p, &mut a, &["x", "y", "z"]);
+          v.value = a.into();
+        }),
+        Container::Vector4 { items, .. } if matched => self.indexed(path, items, |w, p, v| {
+          let mut a = v.value.to_array();
+          w.floats(p, &mut a, &["x", "y", "z", "w"]);
+          v.value = a.into();
+        }),
+        _ => {}
+      },
+      P::Map(m) => {
+        let (key_kind, value_kind) = (m.key_kind(), m.value_kind());
+        let mut entries = std::mem::take(m).into_entries();
+        self.indexed(path, &mut entries, |w, p, (_, v)| w.value(v, p, matched));
+        // Kinds are unchanged, so rebuilding can't fail.
+        *m = values::Map::new(key_kind, value_kind, entries).unwrap_or_default();
+      }
+      _ if !matched => {}
+      P::F32(v) => self.f32(path, &mut v.value),
+      P::Optional(Optional::F32(Some(v))) => self.f32(path, &mut v.value),
+      P::I8(v) => int!(self, path, v.value, i8),
+      P::U8(v) => int!(self, path, v.value, u8),
+      P::I16(v) => int!(self, path, v.value, i16),
+      P::U16(v) => int!(self, path, v.value, u16),
+      P::I32(v) => int!(self, path, v.value, i32),
+      P::U32(v) => int!(self, path, v.value, u32),
+      P::I64(v) => int!(self, path, v.value, i64),
+      P::U64(v) => int!(self, path, v.value, u64),
+      P::Vector2(v) => {
+        let mut a = v.value.to_array();
+        self.floats(path, &mut a, &["x", "y"]);
+        v.value = a.into();
+      }
+      P::Vector3(v) => {
+        let mut a = v.value.to_array();
+        self.floats(path, &mut a, &["x", "y", "z"]);
+        v.value = a.into();
+      }
+      P::Vector4(v) => {
+        let mut a = v.value.to_array();
+        self.floats(path, &mut a, &["x", "y", "z", "w"]);
+        v.value = a.into();
+      }
+      _ => {}
+    }
+  }
+}
+
+fn run(bin_path: &Path, selector: &str, op: Op, options: &TransformBinValuesOptions) -> Result<TransformBinValuesResult, String> {
+  let (class, field) = match selector.trim().rsplit_once('.') {
+    Some((class, field)) => (Some(name_hash(class)), field),
+    None => (None, selector.trim()),
+  };
+  if field.is_empty() {
+    return Err("Selector needs a field name".to_string());
+  }
+  let data = fs::read(bin_path).map_err(|e| format!("Failed to read {}: {}", bin_path.display(), e))?;
+  let mut tree = Bin::from_reader(&mut Cursor::new(&data)).map_err(|e| format!("Failed to parse {}: {}", bin_path.display(), e))?;
+
+  let hashes = hash_provider(options.hash_dir.as_deref());
+  let mut walker = Walker {
+    op,
+    field: name_hash(field),
+    class,
+    times: fnv1a_lower("times"),
+    hashes: &hashes,
+    object: String::new(),
+    values: Vec::new(),
+  };
+  let mut path = String::new();
+  for object in tree.objects.values_mut() {
+    walker.object = match hashes.lookup_entry(object.path_hash) {
+      Some(name) => name.to_string(),
+      None => format!("0x{:08x}", object.path_hash),
+    };
+    walker.props(object.class_hash, object.properties.iter_mut(), &mut path, false);
+  }
+
+  let values = walker.values;
+  let changed = values.iter().filter(|v| v.2 != v.3).count() as u32;
+  let written = !options.dry_run.unwrap_or(false) && changed > 0;
+  if written {
+    let out = options.output_path.as_deref().filter(|p| !p.is_empty()).map(Path::new).unwrap_or(bin_path);
+    let mut buf = Cursor::new(Vec::new());
+    tree.to_writer(&mut buf).map_err(|e| format!("Failed to serialize bin: {}", e))?;
+    write_retrying(out, buf.get_ref()).map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+  }
+  Ok(TransformBinValuesResult {
+    success: true,
+    error: None,
+    matched: values.len() as u32,
+    changed,
+    truncated: values.len() > MAX_LISTED,
+    before: stats(values.iter().map(|v| v.2)),
+    after: stats(values.iter().map(|v| v.3)),
+    changes: values.into_iter()
+      .take(MAX_LISTED)
+      .map(|(object, path, before, after)| BinValueChange { object, path, before, after })
+      .collect(),
+    written,
+  })
+}
+
+/// Apply `transform` to every numeric value under the fields matching `selector`
+/// (`field` or `Class.field`), listing each value with before/after statistics.
+#[napi(js_name = "transformBinValues")]
+pub fn transform_bin_values(
+  bin_path: String,
+  selector: String,
+  transform: BinValueTransform,
+  options: Option<TransformBinValuesOptions>,
+) -> TransformBinValuesResult {
+  let options = options.unwrap_or(TransformBinValuesOptions { dry_run: None, hash_dir: None, output_path: None });
+  Op::parse(&transform)
+    .and_then(|op| run(Path::new(&bin_path), &selector, op, &options))
+    .unwrap_or_else(|e| TransformBinValuesResult {
+      success: false,
+      error: Some(e),
+      matched: 0,
+      changed: 0,
+      changes: Vec::new(),
+      truncated: false,
+      before: BinValueStats::default(),
+      after: BinValueStats::default(),
+      written: false,
+    })
+}
@@ -0,0 +1,124 @@
+// ── Project checkpoints ──────────────────────────────────────────────────────
+// Snapshots of a project's `content/` folder, kept in a content-addressed
+// object store under `.quartz/` (see `quartz_core::checkpoints`, which the CLI
+// shares). These are the napi wrappers.
+
+use std::path::Path;
+
+use napi_derive::napi;
+use quartz_core::checkpoints;
+
+#[napi(object)]
+pub struct CheckpointInfo {
+  pub id: String,
+  pub label: Option<String>,
+  /// Unix milliseconds.
+  #[napi(js_name = "createdAt")]
+  pub created_at: i64,
+  #[napi(js_name = "fileCount")]
+  pub file_count: u32,
+  #[napi(js_name = "totalSize")]
+  pub total_size: f64,
+}
+
+#[napi(object)]
+pub struct CheckpointResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub checkpoint: Option<CheckpointInfo>,
+  /// Objects written to the store; files already stored by an earlier checkpoint aren't counted.
+  #[napi(js_name = "newObjects")]
+  pub new_objects: u32,
+}
+
+#[napi(object)]
+pub struct CheckpointListResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Newest first.
+  pub checkpoints: Vec<CheckpointInfo>,
+}
+
+#[napi(object)]
+pub struct CheckpointRestoreResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "restoredCount")]
+  pub restored_count: u32,
+  /// Restored files that were cloned (copy-on-write) rather than copied.
+  #[napi(js_name = "linkedCount")]
+  pub linked_count: u32,
+  /// Why a `link` restore copied files instead of cloning them (always the
+  /// case on Windows, whose NTFS volumes can't clone).
+  #[napi(js_name = "linkFallback")]
+  pub link_fallback: Option<String>,
+  /// Files not in the checkpoint that were removed from `content/`.
+  #[napi(js_name = "removedCount")]
+  pub removed_count: u32,
+}
+
+#[napi(object)]
+pub struct CheckpointVerifyResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Files whose stored object is missing or damaged.
+  pub damaged: Vec<String>,
+}
+
+impl From<checkpoints::CheckpointInfo> for CheckpointInfo {
+  fn from(c: checkpoints::CheckpointInfo) -> Self {
+    CheckpointInfo { id: c.id, label: c.label, created_at: c.created_at, file_count: c.file_count, total_size: c.total_size as f64 }
+  }
+}
+
+/// Snapshot the project's `content/` folder into a new checkpoint.
+#[napi(js_name = "createCheckpoint")]
+pub fn create_checkpoint(project_path: String, label: Option<String>) -> CheckpointResult {
+  match checkpoints::create(Path::new(&project_path), label) {
+    Ok(c) => CheckpointResult { success: true, error: None, checkpoint: Some(c.checkpoint.into()), new_objects: c.new_objects },
+    Err(e) => CheckpointResult { success: false, error: Some(e), checkpoint: None, new_objects: 0 },
+  }
+}
+
+#[napi(js_name = "listCheckpoints")]
+pub fn list_checkpoints(project_path: String) -> CheckpointListResult {
+  match checkpoints::list(Path::new(&project_path)) {
+    Ok(list) => CheckpointListResult { success: true, error: None, checkpoints: list.into_iter().map(Into::into).collect() },
+    Err(e) => CheckpointListResult { success: false, error: Some(e), checkpoints: Vec::new() },
+  }
+}
+
+/// Hash every stored object of checkpoint `id`. Restores only check sizes, so
+/// this is the call that finds bit rot in the store.
+#[napi(js_name = "verifyCheckpoint")]
+pub fn verify_checkpoint(project_path: String, id: String) -> CheckpointVerifyResult {
+  match checkpoints::verify(Path::new(&project_path), &id) {
+    Ok(damaged) => CheckpointVerifyResult { success: true, error: None, damaged },
+    Err(e) => CheckpointVerifyResult { success: false, error: Some(e), damaged: Vec::new() },
+  }
+}
+
+/// Make `content/` match checkpoint `id`. With `link`, files are cloned from
+/// the object store instead of copied (copy fallback per file, explained in
+/// `linkFallback`).
+#[napi(js_name = "restoreCheckpoint")]
+pub fn restore_checkpoint(project_path: String, id: String, link: Option<bool>) -> CheckpointRestoreResult {
+  match checkpoints::restore(Path::new(&project_path), &id, link.unwrap_or(false)) {
+    Ok(r) => CheckpointRestoreResult {
+      success: true,
+      error: None,
+      restored_count: r.restored_count,
+      linked_count: r.linked_count,
+      link_fallback: r.link_fallback,
+      removed_count: r.removed_count,
+    },
+    Err(e) => CheckpointRestoreResult {
+      success: false,
+      error: Some(e),
+      restored_count: 0,
+      linked_count: 0,
+      link_fallback: None,
+      removed_count: 0,
+    },
+  }
+}
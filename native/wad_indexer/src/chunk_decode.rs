@@ -0,0 +1,169 @@
+// ── Chunk decoding over a shared mmap ────────────────────────────────────────
+// `Wad::load_chunk_decompressed` copies the raw chunk out of the source and
+// builds a fresh zstd decoder for every call, and needs `&mut Wad`, so parallel
+// extraction used to remount the WAD per rayon slice. Here the TOC is parsed
+// once, workers slice raw bytes straight out of the mmap and each thread keeps
+// one reusable zstd decompression context.
+
+use std::cell::RefCell;
+use std::io::Read;
+use std::path::Path;
+
+use ltk_wad::{decompress_raw, WadChunk, WadChunkCompression};
+use sha2::{Digest, Sha256};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::CorruptedChunk;
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+thread_local! {
+  static ZSTD_CONTEXT: RefCell<Option<zstd::bulk::Decompressor<'static>>> = const { RefCell::new(None) };
+}
+
+/// Raw (still compressed) bytes of `chunk` inside the mapped WAD.
+pub(crate) fn raw_chunk_slice<'a>(wad_data: &'a [u8], chunk: &WadChunk) -> Option<&'a [u8]> {
+  wad_data.get(chunk.data_offset()..chunk.data_offset().checked_add(chunk.compressed_size())?)
+}
+
+fn zstd_into(src: &[u8], dst: &mut [u8]) -> Result<usize, String> {
+  ZSTD_CONTEXT.with(|cell| {
+    let mut slot = cell.borrow_mut();
+    if slot.is_none() {
+      *slot = Some(zstd::bulk::Decompressor::new().map_err(|e| format!("Failed to create zstd context: {}", e))?);
+    }
+    let ctx = slot.as_mut().expect("zstd context initialized above");
+    ctx.decompress_to_buffer(src, dst).map_err(|e| format!("zstd: {}", e))
+  })
+}
+
+/// Decompress one chunk from the mapped WAD bytes using this thread's zstd context.
+/// Compression types without a fast path fall back to ltk's decoder.
+pub(crate) fn decompress_chunk(wad_data: &[u8], chunk: &WadChunk) -> Result<Vec<u8>, String> {
+  let raw = raw_chunk_slice(wad_data, chunk)
+    .ok_or_else(|| format!("Chunk {:016x} is out of bounds", chunk.path_hash()))?;
+  decompress_chunk_bytes(raw, chunk)
+}
+
+/// Decompress the raw bytes of `chunk` fetched some other way (e.g. over HTTP).
+pub(crate) fn decompress_chunk_bytes(raw: &[u8], chunk: &WadChunk) -> Result<Vec<u8>, String> {
+  let size = chunk.uncompressed_size();
+  match chunk.compression_type() {
+    WadChunkCompression::None => Ok(raw.to_vec()),
+    WadChunkCompression::Zstd => {
+      let mut out = vec![0u8; size];
+      let n = zstd_into(raw, &mut out)?;
+      if n != size {
+        return Err(format!("Chunk {:016x}: expected {} bytes, got {}", chunk.path_hash(), size, n));
+      }
+      Ok(out)
+    }
+    WadChunkCompression::ZstdMulti => {
+      // Uncompressed bytes may precede the first zstd frame.
+      let start = raw
+        .windows(ZSTD_MAGIC.len())
+        .position(|w| w == ZSTD_MAGIC)
+        .ok_or_else(|| format!("Chunk {:016x}: no zstd frame", chunk.path_hash()))?;
+      if start > size {
+        return Err(format!("Chunk {:016x}: corrupt zstd-multi header", chunk.path_hash()));
+      }
+      let mut out = vec![0u8; size];
+      out[..start].copy_from_slice(&raw[..start]);
+      let n = zstd_into(&raw[start..], &mut out[start..])?;
+      if n != size - start {
+        return Err(format!("Chunk {:016x}: expected {} bytes, got {}", chunk.path_hash(), size, start + n));
+      }
+      Ok(out)
+    }
+    other => decompress_raw(raw, other, size)
+      .map(|d| d.into_vec())
+      .map_err(|e| format!("Chunk {:016x}: {}", chunk.path_hash(), e)),
+  }
+}
+
+/// The first `len` decompressed bytes of `chunk` (all of it if smaller),
+/// without inflating the rest. Enough to sniff a file type.
+pub(crate) fn decompress_chunk_prefix(wad_data: &[u8], chunk: &WadChunk, len: usize) -> Result<Vec<u8>, String> {
+  let raw = raw_chunk_slice(wad_data, chunk)
+    .ok_or_else(|| format!("Chunk {:016x} is out of bounds", chunk.path_hash()))?;
+  let len = len.min(chunk.uncompressed_size());
+  match chunk.compression_type() {
+    WadChunkCompression::None => Ok(raw[..len.min(raw.len())].to_vec()),
+    WadChunkCompression::Zstd => {
+      let mut decoder = zstd::stream::read::Decoder::with_buffer(raw)
+        .map_err(|e| format!("Chunk {:016x}: zstd: {}", chunk.path_hash(), e))?;
+      let mut out = vec![0u8; len];
+      decoder.read_exact(&mut out).map_err(|e| format!("Chunk {:016x}: zstd: {}", chunk.path_hash(), e))?;
+      Ok(out)
+    }
+    _ => decompress_chunk_bytes(raw, chunk).map(|mut data| {
+      data.truncate(len);
+      data
+    }),
+  }
+}
+
+/// Checksum stored in the TOC: xxh3 of the raw chunk (3.1+) or the first eight
+/// bytes of its SHA-256 (3.0). Zero means the writer left it unset.
+fn checksum_matches(raw: &[u8], expected: u64) -> bool {
+  if expected == 0 || xxh3_64(raw) == expected { return true; }
+  let digest = Sha256::digest(raw);
+  u64::from_le_bytes(digest[..8].try_into().expect("SHA-256 digest is 32 bytes")) == expected
+}
+
+/// Classify a failed `decompress_chunk` so the frontend can tell a truncated
+/// download from bit rot or an unsupported encoding.
+pub(crate) fn diagnose_chunk_failure(wad_path: &Path, wad_data: &[u8], chunk: &WadChunk, error: String) -> CorruptedChunk {
+  let (kind, repair_suggestion) = match raw_chunk_slice(wad_data, chunk) {
+    None => (
+      "shortRead",
+      "The WAD is truncated. Repair the game installation (or re-download the WAD) and extract again.",
+    ),
+    Some(raw) if !checksum_matches(raw, chunk.checksum()) => (
+      "checksumMismatch",
+      "The chunk data is damaged on disk. Repair the game installation (or re-download the WAD) and extract again.",
+    ),
+    Some(_) => (
+      "decompression",
+      "The chunk passed its checksum but could not be decoded. Re-download the WAD; if it persists, report it with this WAD and hash.",
+    ),
+  };
+  CorruptedChunk {
+    wad_path: wad_path.to_string_lossy().into_owned(),
+    path_hash: format!("{:016x}", chunk.path_hash()),
+    kind: kind.to_string(),
+    message: error,
+    data_offset: chunk.data_offset() as f64,
+    compressed_size: chunk.compressed_size() as u32,
+    uncompressed_size: chunk.uncompressed_size() as u32,
+    wad_size: wad_data.len() as f64,
+    repair_suggestion: repair_suggestion.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_short_zstd_multi_frame_is_an_error() {
+    let mut raw = b"head".to_vec();
+    raw.extend(zstd::encode_all(&b"body"[..], 3).unwrap());
+    let chunk = WadChunk {
+      path_hash: 0x0123456789abcdef,
+      data_offset: 0,
+      compressed_size: raw.len(),
+      uncompressed_size: 18,
+      compression_type: WadChunkCompression::ZstdMulti,
+      is_duplicated: false,
+      frame_count: 1,
+      start_frame: 0,
+      checksum: 0,
+    };
+    let err = decompress_chunk_bytes(&raw, &chunk).unwrap_err();
+    assert!(err.contains("expected 18 bytes, got 8"), "{}", err);
+
+    let whole = WadChunk { uncompressed_size: 8, ..chunk };
+    assert_eq!(decompress_chunk_bytes(&raw, &whole).unwrap(), b"headbody");
+  }
+}
@@ -0,0 +1,128 @@
+// ── In-memory chunk reads ────────────────────────────────────────────────────
+// Returns decompressed chunks to JS without going through the filesystem.
+//
+// Buffers are zero-copy: the `Vec<u8>` holding the decompressed chunk is handed
+// to V8 as an external buffer and freed by its finalizer when the JS Buffer is
+// garbage collected. Lifetime rules for callers:
+// - the Buffer owns its memory; it stays valid for as long as JS references it,
+//   and the WAD file may be closed, replaced or deleted in the meantime;
+// - the memory is only released on GC, so drop references to large chunks
+//   (100 MB mapgeo) as soon as they are processed instead of caching them;
+// - runtimes that forbid external buffers (Electron with the V8 memory cage)
+//   make napi fall back to a single copy. The Rust allocation is freed right
+//   after, so memory is still not held twice.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi::bindgen_prelude::{AsyncTask, Buffer};
+use napi::{Env, Task};
+use napi_derive::napi;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::parse_hash_hex;
+use crate::remote_wad::mount_remote;
+use crate::rman::is_url;
+
+#[napi(object)]
+pub struct WadChunkReadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Decompressed chunk bytes; None when the chunk does not exist.
+  pub data: Option<Buffer>,
+}
+
+/// One chunk of a batch read: its data, `None` when the WAD has no such chunk,
+/// or why it couldn't be decompressed.
+pub(crate) type ChunkRead = Result<Option<Vec<u8>>, String>;
+
+/// Decompress the chunks with the given hashes, one result per hash in order.
+/// Only a WAD that can't be opened fails the whole call. `wad_path` may also be
+/// an http(s) URL of a remote WAD.
+pub(crate) fn read_chunks(wad_path: &Path, hashes: &[u64]) -> Result<Vec<ChunkRead>, String> {
+  let raw = wad_path.to_string_lossy();
+  if is_url(&raw) { return Ok(mount_remote(&raw)?.read(hashes)); }
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  Ok(hashes
+    .iter()
+    .map(|h| match wad.chunks().get(*h) {
+      Some(chunk) => decompress_chunk(&mmap[..], chunk).map(Some),
+      None => Ok(None),
+    })
+    .collect())
+}
+
+fn read_chunk(wad_path: &str, path_hash: &str) -> ChunkRead {
+  let hash = parse_hash_hex(path_hash).ok_or_else(|| format!("Invalid path hash: {}", path_hash))?;
+  Ok(read_chunks(Path::new(wad_path), &[hash])?.pop().transpose()?.flatten())
+}
+
+fn chunk_result(read: ChunkRead) -> WadChunkReadResult {
+  match read {
+    Ok(data) => WadChunkReadResult { success: true, error: None, data: data.map(Buffer::from) },
+    Err(e) => WadChunkReadResult { success: false, error: Some(e), data: None },
+  }
+}
+
+/// Read one decompressed chunk into a zero-copy Buffer.
+#[napi(js_name = "readWadChunk")]
+pub fn read_wad_chunk(wad_path: String, path_hash: String) -> WadChunkReadResult {
+  chunk_result(read_chunk(&wad_path, &path_hash))
+}
+
+/// Read several chunks of one WAD with a single mount. There is one result per
+/// entry of `pathHashes`, in order: an invalid hash or a chunk that fails to
+/// decompress fails only its own entry, and a WAD that can't be opened fails
+/// every entry.
+#[napi(js_name = "readWadChunks")]
+pub fn read_wad_chunks(wad_path: String, path_hashes: Vec<String>) -> Vec<WadChunkReadResult> {
+  read_each(Path::new(&wad_path), &path_hashes).into_iter().map(chunk_result).collect()
+}
+
+fn read_each(wad_path: &Path, path_hashes: &[String]) -> Vec<ChunkRead> {
+  let parsed: Vec<Option<u64>> = path_hashes.iter().map(|h| parse_hash_hex(h)).collect();
+  let hashes: Vec<u64> = parsed.iter().flatten().copied().collect();
+  let mut chunks = match read_chunks(wad_path, &hashes) {
+    Ok(chunks) => chunks.into_iter(),
+    Err(e) => return path_hashes.iter().map(|_| Err(e.clone())).collect(),
+  };
+  path_hashes
+    .iter()
+    .zip(parsed)
+    .map(|(h, hash)| match hash {
+      Some(_) => chunks.next().unwrap_or(Ok(None)),
+      None => Err(format!("Invalid path hash: {}", h)),
+    })
+    .collect()
+}
+
+pub struct ReadWadChunkTask {
+  wad_path: String,
+  path_hash: String,
+}
+
+#[napi]
+impl Task for ReadWadChunkTask {
+  type Output = ChunkRead;
+  type JsValue = WadChunkReadResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(read_chunk(&self.wad_path, &self.path_hash))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    // The Vec moves into the Buffer here; no copy on the way to JS.
+    Ok(chunk_result(output))
+  }
+}
+
+/// `readWadChunk` off the main thread, for large chunks.
+#[napi(js_name = "readWadChunkAsync")]
+pub fn read_wad_chunk_async(wad_path: String, path_hash: String) -> AsyncTask<ReadWadChunkTask> {
+  AsyncTask::new(ReadWadChunkTask { wad_path, path_hash })
+}
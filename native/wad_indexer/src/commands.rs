@@ -0,0 +1,265 @@
+// ── Command registry ─────────────────────────────────────────────────────────
+// Describes the exported operations (id, title, arguments) so the frontend can
+// build a searchable command palette and scripts can discover what exists.
+// `id` is the JS export name; keep this table in step with the `#[napi]`
+// exports when adding or changing one.
+
+use napi_derive::napi;
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct CommandArg {
+  pub name: String,
+  /// TypeScript-style type: "string", "string[]", "boolean", "number", "object", "function".
+  #[napi(js_name = "type")]
+  pub kind: String,
+  pub optional: bool,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct CommandInfo {
+  pub id: String,
+  pub title: String,
+  pub category: String,
+  pub args: Vec<CommandArg>,
+  /// Export name of the promise-returning variant, when there is one.
+  #[napi(js_name = "asyncId")]
+  pub async_id: Option<String>,
+}
+
+struct Spec {
+  id: &'static str,
+  title: &'static str,
+  category: &'static str,
+  /// (name, type, optional)
+  args: &'static [(&'static str, &'static str, bool)],
+  async_id: Option<&'static str>,
+}
+
+const fn cmd(
+  category: &'static str,
+  id: &'static str,
+  title: &'static str,
+  args: &'static [(&'static str, &'static str, bool)],
+) -> Spec {
+  Spec { id, title, category, args, async_id: None }
+}
+
+const fn cmd_async(
+  category: &'static str,
+  id: &'static str,
+  async_id: &'static str,
+  title: &'static str,
+  args: &'static [(&'static str, &'static str, bool)],
+) -> Spec {
+  Spec { id, title, category, args, async_id: Some(async_id) }
+}
+
+const S: &str = "string";
+const SS: &str = "string[]";
+const B: &str = "boolean";
+const N: &str = "number";
+const O: &str = "object";
+const F: &str = "function";
+
+const COMMANDS: &[Spec] = &[
+  // Hashes
+  cmd("hashes", "buildHashDb", "Build hash database", &[("hashDir", S, false)]),
+  cmd("hashes", "primeHashTables", "Preload hash tables", &[("hashPath", S, false)]),
+  cmd("hashes", "clearHashTables", "Clear loaded hash tables", &[]),
+  cmd("hashes", "getHashGeneration", "Get hash generation", &[("hashDir", S, false)]),
+  cmd_async("hashes", "compactHashDb", "compactHashDbAsync", "Compact hash database", &[("hashDir", S, false)]),
+  cmd("hashes", "resolveHashes", "Resolve path hashes", &[("hexHashes", SS, false), ("hashDir", S, false), ("options", O, true)]),
+  cmd("hashes", "resolveHashSources", "Resolve path hashes with their source", &[("hexHashes", SS, false), ("hashDir", S, false), ("options", O, true)]),
+  cmd("hashes", "extractHashesFromWad", "Extract hashes from WAD", &[("wadPath", S, false), ("hashDir", S, true)]),
+  cmd("hashes", "resolveWwiseHashes", "Resolve Wwise event and bank IDs", &[("ids", SS, false), ("hashDir", S, false)]),
+  // WAD
+  cmd("wad", "loadAllIndexes", "Index WADs", &[("wadPaths", SS, false), ("hashPath", S, true), ("concurrency", N, true)]),
+  cmd_async("wad", "extractWad", "extractWadAsync", "Extract WAD", &[
+    ("wadPath", S, false), ("outputDir", S, false), ("hashPath", S, true),
+    ("replaceExisting", B, true), ("options", O, true),
+  ]),
+  cmd_async("wad", "extractSelected", "extractSelectedAsync", "Extract selected WAD files", &[
+    ("items", "object[]", false), ("outputDir", S, false), ("replaceExisting", B, true),
+    ("preservePaths", B, true), ("options", O, true),
+  ]),
+  cmd("wad", "extractWadStreaming", "Extract WAD with per-file events", &[
+    ("wadPath", S, false), ("outputDir", S, false), ("hashPath", S, true), ("ifExists", S, true),
+    ("outputTemplate", S, true), ("callback", F, false),
+  ]),
+  cmd("wad", "extractSelectedStreaming", "Extract selected WAD files with per-file events", &[
+    ("items", "object[]", false), ("outputDir", S, false), ("preservePaths", B, true), ("ifExists", S, true),
+    ("callback", F, false),
+  ]),
+  cmd_async("wad", "extractByPrefix", "extractByPrefixAsync", "Extract WAD folders by path prefix", &[
+    ("wadPath", S, false), ("prefixes", SS, false), ("outputDir", S, false), ("hashDir", S, true), ("options", O, true),
+  ]),
+  cmd_async("wad", "readWadChunk", "readWadChunkAsync", "Read WAD chunk", &[("wadPath", S, false), ("pathHash", S, false)]),
+  cmd("wad", "readWadChunks", "Read WAD chunks", &[("wadPath", S, false), ("pathHashes", SS, false)]),
+  cmd("wad", "analyzeWadCompression", "Analyze WAD compression", &[("wadPath", S, false)]),
+  cmd("wad", "wadTypeStats", "WAD type statistics", &[("wadPath", S, false)]),
+  cmd_async("wad", "repackWad", "repackWadAsync", "Repack WAD with a compression policy", &[
+    ("input", S, false), ("output", S, false), ("policy", S, true),
+  ]),
+  cmd("wad", "listRemoteWad", "List chunks of a remote WAD", &[("url", S, false), ("hashDir", S, true)]),
+  cmd("wad", "packWadDir", "Pack folder into WAD", &[("inputDir", S, false), ("outputWad", S, false), ("delta", B, true)]),
+  cmd("wad", "verifyReproducible", "Verify WAD matches a fresh build of its folder", &[("inputDir", S, false), ("wadPath", S, false)]),
+  cmd("wad", "renameWadChunks", "Rename chunks in WAD", &[("wadPath", S, false), ("renames", "object[]", false), ("options", O, true)]),
+  cmd("wad", "patchWad", "Patch chunks into WAD", &[("wadPath", S, false), ("patches", "object[]", false)]),
+  cmd_async("wad", "mergeWads", "mergeWadsAsync", "Merge mod WADs", &[("inputs", SS, false), ("output", S, false), ("options", O, true)]),
+  cmd("wad", "saveChunkToWad", "Save chunk to WAD", &[("wadPath", S, false), ("target", S, false), ("data", "Buffer", false), ("options", O, true)]),
+  cmd("wad", "openBinFromWad", "Open bin from WAD", &[("wadPath", S, false), ("chunkHash", S, false), ("hashDir", S, true)]),
+  cmd("wad", "saveWadBin", "Save bin opened from WAD", &[("tempPath", S, false), ("text", S, false), ("writeBack", B, true)]),
+  cmd("wad", "closeWadBin", "Close bin opened from WAD", &[("tempPath", S, false)]),
+  cmd_async("wad", "buildAssetGraph", "buildAssetGraphAsync", "Build asset dependency graph", &[("wadOrDir", S, false), ("hashDir", S, true)]),
+  cmd("wad", "listLanguageWads", "List language WADs", &[("gamePath", S, false)]),
+  cmd("wad", "getWadLocale", "Get WAD locale", &[("wadPath", S, false)]),
+  cmd("wad", "listGameWads", "List game WADs", &[("leaguePath", S, false), ("includeLcu", B, true)]),
+  cmd("wad", "readRmanManifest", "Read patcher manifest", &[("source", S, false), ("filter", S, true)]),
+  cmd_async("wad", "downloadRmanFiles", "downloadRmanFiles", "Download files from patcher manifest", &[
+    ("source", S, false), ("files", SS, false), ("outDir", S, false), ("options", O, true),
+  ]),
+  cmd("wad", "listWadTree", "Browse WAD folders", &[("wadPath", S, false), ("dirPath", S, false), ("hashDir", S, true)]),
+  cmd("wad", "clearWadTree", "Clear WAD tree cache", &[("wadPath", S, true)]),
+  cmd_async("wad", "runBenchmark", "runBenchmarkAsync", "Benchmark extraction", &[
+    ("wadPath", S, false), ("hashDir", S, true), ("scratchDir", S, true),
+  ]),
+  // Game
+  cmd("game", "getGameVersion", "Get game version", &[("leaguePath", S, false)]),
+  cmd("game", "getChampionSkins", "List champion skins", &[("leaguePath", S, false), ("champion", S, false), ("locale", S, true), ("hashDir", S, true)]),
+  cmd("game", "getSkinFamilies", "Group champion skins by skin line", &[("leaguePath", S, false), ("champion", S, false), ("locale", S, true), ("hashDir", S, true)]),
+  cmd("game", "locateSkinBin", "Locate skin bin", &[("dir", S, false), ("champion", S, false), ("skinId", N, false)]),
+  cmd("game", "getChampionIcon", "Get champion icon", &[("leaguePath", S, false), ("champion", S, false)]),
+  cmd("game", "clearChampionIconCache", "Clear champion icon cache", &[]),
+  cmd("game", "extractChampion", "Extract champion", &[
+    ("leaguePath", S, false), ("champion", S, false), ("outDir", S, false), ("options", O, true),
+  ]),
+  cmd("game", "buildGameIndex", "Build game file index", &[("leaguePath", S, false), ("indexDir", S, false)]),
+  cmd("game", "lookupGameFile", "Look up game file", &[("indexDir", S, false), ("pathOrHash", S, false)]),
+  cmd("game", "lookupGameFiles", "Look up game files", &[("indexDir", S, false), ("pathsOrHashes", SS, false)]),
+  cmd("game", "validateBinAssets", "Validate bin references against the game", &[("binPath", S, false), ("indexDir", S, false), ("localDirs", SS, true)]),
+  cmd("game", "backupGameFiles", "Back up game files", &[("leaguePath", S, false), ("paths", SS, false), ("backupDir", S, false)]),
+  cmd("game", "restoreGameBackups", "Restore game backups", &[("leaguePath", S, false), ("backupDir", S, false), ("force", B, true)]),
+  cmd("game", "testInGame", "Test in game", &[
+    ("projectPath", S, false), ("leaguePath", S, false), ("options", O, false), ("callback", F, false),
+  ]),
+  cmd("game", "stopInGameTest", "Stop in-game test", &[("id", N, false)]),
+  cmd("game", "diffStringtables", "Diff localized strings between installs", &[
+    ("oldGame", S, false), ("newGame", S, false), ("locale", S, false), ("hashDir", S, true),
+  ]),
+  cmd("game", "exportVoIndex", "List champion VO lines with text", &[
+    ("champion", S, false), ("locale", S, false), ("leaguePath", S, false), ("hashDir", S, true),
+  ]),
+  cmd("game", "exportAudioEvents", "Export champion audio events to CSV", &[
+    ("champion", S, false), ("leaguePath", S, false), ("outCsv", S, false), ("hashDir", S, true),
+  ]),
+  // Bin / files
+  cmd("bin", "binToPy", "Convert bin to text", &[("binPath", S, false), ("pyPath", S, false), ("hashDir", S, true)]),
+  cmd("bin", "pyToBin", "Convert text to bin", &[("pyPath", S, false), ("binPath", S, false)]),
+  cmd("bin", "outlineRitobin", "Outline ritobin text", &[("textOrPath", S, false), ("hashDir", S, true)]),
+  cmd("bin", "recordEdit", "Record bin edit", &[("userDataDir", S, false), ("path", S, false), ("operation", S, false), ("before", S, false), ("after", S, false), ("offset", N, true)]),
+  cmd("bin", "getEditHistory", "Edit history", &[("userDataDir", S, false), ("path", S, false)]),
+  cmd("bin", "revertTo", "Revert to edit", &[("userDataDir", S, false), ("path", S, false), ("entryId", N, false), ("hashDir", S, true)]),
+  cmd("bin", "autosaveBuffer", "Autosave editor buffer", &[("userDataDir", S, false), ("path", S, false), ("text", S, false)]),
+  cmd("bin", "discardBuffer", "Discard autosaved buffer", &[("userDataDir", S, false), ("path", S, false)]),
+  cmd("bin", "recoverBuffers", "Recover autosaved buffers", &[("userDataDir", S, false)]),
+  cmd("bin", "extractAssetReferences", "Extract asset references", &[("binPath", S, false), ("hashDir", S, true)]),
+  cmd("bin", "decodeTextureToPng", "Decode texture to PNG", &[("filePath", S, false)]),
+  cmd_async("bin", "analyzeBinHashUsage", "analyzeBinHashUsageAsync", "Analyze bin hash usage", &[("extractedDir", S, false), ("options", O, true)]),
+  #[cfg(feature = "bin-search")]
+  cmd_async("bin", "indexBins", "indexBins", "Index bins for full-text search", &[("sourceDir", S, false), ("indexDir", S, false), ("hashDir", S, true)]),
+  #[cfg(feature = "bin-search")]
+  cmd("bin", "queryBins", "Search indexed bins", &[("indexDir", S, false), ("queryText", S, false), ("limit", N, true)]),
+  cmd("bin", "transformBinValues", "Scale, offset or clamp bin values", &[("binPath", S, false), ("selector", S, false), ("transform", O, false), ("options", O, true)]),
+  cmd("bin", "retargetAnimationBin", "Retarget animation bin to another skin or champion", &[("binPath", S, false), ("options", O, false)]),
+  cmd_async("bin", "runScript", "runScriptAsync", "Run script", &[("scriptPath", S, false), ("args", SS, true)]),
+  // Projects
+  cmd("project", "loadProject", "Load project", &[("projectPath", S, false)]),
+  cmd("project", "saveProject", "Save project", &[("projectPath", S, false), ("project", O, false)]),
+  cmd("project", "createProject", "Create project", &[("projectPath", S, false), ("template", S, false), ("options", O, false)]),
+  cmd("project", "suggestAssetPath", "Suggest asset path for a new file", &[("filePath", S, false), ("projectPath", S, false), ("options", O, true)]),
+  cmd("project", "buildOverlay", "Build overlay", &[("projectPath", S, false), ("outDir", S, false), ("leaguePath", S, false)]),
+  cmd("project", "mountOverlay", "Mount overlay for previews", &[("projectPath", S, false), ("leaguePath", S, false)]),
+  cmd_async("project", "readOverlayChunk", "readOverlayChunkAsync", "Read overlay chunk", &[("mountId", N, false), ("gameWad", S, false), ("pathHash", S, false)]),
+  cmd("project", "unmountOverlay", "Unmount overlay", &[("mountId", N, false)]),
+  cmd("project", "createCheckpoint", "Create checkpoint", &[("projectPath", S, false), ("label", S, true)]),
+  cmd("project", "listCheckpoints", "List checkpoints", &[("projectPath", S, false)]),
+  cmd("project", "restoreCheckpoint", "Restore checkpoint", &[("projectPath", S, false), ("id", S, false), ("link", B, true)]),
+  cmd("project", "verifyCheckpoint", "Verify checkpoint", &[("projectPath", S, false), ("id", S, false)]),
+  cmd("project", "importLeagueModProject", "Import league-mod project", &[("projectPath", S, false)]),
+  cmd("project", "exportLeagueModProject", "Export league-mod project", &[("projectPath", S, false), ("outDir", S, true)]),
+  cmd_async("project", "indexProject", "indexProject", "Index project for search", &[("projectPath", S, false), ("hashDir", S, true)]),
+  cmd("project", "searchProject", "Search project", &[("projectPath", S, false), ("query", S, false), ("limit", N, true)]),
+  cmd("project", "clearProjectIndex", "Clear project search index", &[("projectPath", S, false)]),
+  cmd("project", "lintProject", "Check project for problems", &[("projectPath", S, false), ("options", O, true)]),
+  cmd("project", "findOrphans", "Find unused project files", &[("projectPath", S, false)]),
+  cmd("project", "textureUsageReport", "Texture usage report", &[("projectPath", S, false), ("options", O, true)]),
+  cmd("project", "checkProjectFreshness", "Check project against game updates", &[("projectPath", S, false), ("leaguePath", S, false)]),
+  cmd("project", "recordProjectOrigins", "Record project file origins", &[("projectPath", S, false), ("leaguePath", S, false), ("hashDir", S, true)]),
+  cmd("project", "portProject", "Port project to current patch", &[("projectPath", S, false), ("leaguePath", S, false)]),
+  cmd("project", "setProjectRepathRules", "Set project repath rules", &[("projectPath", S, false), ("rules", "object[]", false)]),
+  cmd("project", "listRecentProjects", "List recent projects", &[("userDataDir", S, false)]),
+  cmd("project", "recordRecentProject", "Record recent project", &[("userDataDir", S, false), ("projectPath", S, false)]),
+  cmd("project", "pinRecentProject", "Pin recent project", &[("userDataDir", S, false), ("projectPath", S, false), ("pinned", B, false)]),
+  cmd("project", "removeRecentProject", "Remove recent project", &[("userDataDir", S, false), ("projectPath", S, false)]),
+  cmd("project", "saveSession", "Save session", &[("userDataDir", S, false), ("stateJson", S, false), ("projectPath", S, true)]),
+  cmd("project", "loadSession", "Load session", &[("userDataDir", S, false), ("projectPath", S, true)]),
+  cmd("project", "clearSession", "Clear session", &[("userDataDir", S, false), ("projectPath", S, true)]),
+  // Mods / packages
+  cmd("mod", "exportFantome", "Export .fantome", &[("projectPath", S, false), ("outFile", S, false), ("meta", O, false)]),
+  cmd("mod", "exportCslolMod", "Export cslol mod", &[
+    ("projectPath", S, false), ("outDir", S, false), ("meta", O, false), ("leaguePath", S, true),
+  ]),
+  cmd("mod", "getPackagePreview", "Preview package", &[("archivePath", S, false)]),
+  cmd("mod", "importModArchive", "Import mod archive", &[("archivePath", S, false), ("projectPath", S, false), ("hashDir", S, true)]),
+  cmd("mod", "extractArchive", "Extract archive", &[("path", S, false), ("dest", S, false), ("options", O, true)]),
+  cmd("mod", "detectConflicts", "Detect mod conflicts", &[("modPaths", SS, false), ("hashDir", S, true)]),
+  cmd("mod", "generateSigningKey", "Generate signing key", &[]),
+  cmd("mod", "signPackage", "Sign package", &[("packagePath", S, false), ("privateKey", S, false)]),
+  cmd("mod", "verifyPackage", "Verify package signature", &[("packagePath", S, false), ("trustedKeys", SS, true)]),
+  // App
+  cmd("app", "getPreferences", "Get preferences", &[("userDataDir", S, false)]),
+  cmd("app", "updatePreferences", "Update preferences", &[("userDataDir", S, false), ("patch", O, false)]),
+  cmd("app", "onPreferencesChanged", "Listen for preference changes", &[("callback", F, true)]),
+  cmd("app", "setLogListener", "Set log listener", &[("callback", F, true)]),
+  cmd("app", "enableFileLogging", "Enable file logging", &[("logDir", S, false), ("options", O, true)]),
+  cmd("app", "disableFileLogging", "Disable file logging", &[]),
+  cmd("app", "exportLogs", "Export logs", &[("logDir", S, false), ("outZip", S, false), ("appInfo", O, true)]),
+  cmd("app", "configureThreads", "Configure thread pools", &[("options", O, false)]),
+  cmd("app", "getThreadConfig", "Get thread configuration", &[]),
+  cmd("app", "closeAll", "Close hash and index databases", &[]),
+  cmd("app", "watchPaths", "Watch paths", &[("options", O, false), ("callback", F, false)]),
+  cmd("app", "unwatchPaths", "Stop watching paths", &[("id", N, false)]),
+  cmd("app", "watchFile", "Watch file", &[("path", S, false), ("callback", F, false), ("debounceMs", N, true)]),
+  cmd("app", "unwatchFile", "Stop watching file", &[("id", N, false)]),
+  cmd("app", "createTempFile", "Create managed temp file", &[("category", S, false), ("name", S, true), ("extension", S, true)]),
+  cmd("app", "releaseTempFile", "Release managed temp file", &[("path", S, false)]),
+  cmd("app", "cleanupTempFiles", "Clean up session temp files", &[]),
+  cmd("app", "cleanupOrphanTempFiles", "Clean up orphaned temp files", &[]),
+  cmd_async("app", "downloadFile", "downloadFileAsync", "Download file", &[("url", S, false), ("destPath", S, false), ("options", O, true)]),
+  cmd("app", "listCommands", "List commands", &[]),
+];
+
+pub(crate) fn commands() -> Vec<CommandInfo> {
+  COMMANDS
+    .iter()
+    .map(|c| CommandInfo {
+      id: c.id.to_string(),
+      title: c.title.to_string(),
+      category: c.category.to_string(),
+      args: c
+        .args
+        .iter()
+        .map(|(name, kind, optional)| CommandArg { name: name.to_string(), kind: kind.to_string(), optional: *optional })
+        .collect(),
+      async_id: c.async_id.map(str::to_string),
+    })
+    .collect()
+}
+
+/// Every exported operation with its argument schema, in palette order.
+#[napi(js_name = "listCommands")]
+pub fn list_commands() -> Vec<CommandInfo> {
+  commands()
+}
@@ -0,0 +1,168 @@
+// ── Mod conflict detection ───────────────────────────────────────────────────
+// Compares the chunk sets of several mods (packed WADs, .fantome/.zip/.7z archives,
+// installed mod folders or projects) and reports files touched by more than one.
+// Mods are given in load order; like cslol-manager, the first mod to provide a
+// file wins and later ones are shadowed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_wad::Wad;
+use napi_derive::napi;
+
+use crate::archive::for_each_archive_file;
+use crate::mod_import::split_wad_entry;
+use crate::wad_build::{chunk_hash_for_rel_path, plan_wad_dir, project_wad_dirs, wad_file_name_for_dir};
+use crate::HashLayers;
+
+/// Lowercased WAD file name -> path hashes the mod provides in it.
+type ModChunks = BTreeMap<String, Vec<u64>>;
+
+#[napi(object)]
+pub struct ModChunkSummary {
+  pub path: String,
+  pub error: Option<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+}
+
+#[napi(object)]
+pub struct ModConflict {
+  /// WAD file name the conflicting chunk lives in, e.g. "ahri.wad.client".
+  pub wad: String,
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// Resolved path when a hash dir was given, otherwise the hex hash.
+  pub path: String,
+  /// Every mod providing this chunk, in load order.
+  pub mods: Vec<String>,
+  /// The mod whose copy is used (first in load order).
+  pub winner: String,
+}
+
+#[napi(object)]
+pub struct ModConflictsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub mods: Vec<ModChunkSummary>,
+  pub conflicts: Vec<ModConflict>,
+}
+
+fn wad_toc_hashes(data: Vec<u8>) -> Result<Vec<u64>, String> {
+  let wad = Wad::mount(Cursor::new(data)).map_err(|e| format!("Failed to mount WAD: {}", e))?;
+  Ok(wad.chunks().iter().map(|c| c.path_hash()).collect())
+}
+
+fn add_chunks(out: &mut ModChunks, wad_name: &str, hashes: impl IntoIterator<Item = u64>) {
+  out.entry(wad_file_name_for_dir(wad_name).to_ascii_lowercase()).or_default().extend(hashes);
+}
+
+fn archive_chunks(path: &Path) -> Result<ModChunks, String> {
+  let mut out = ModChunks::new();
+  for_each_archive_file(path, |name, entry| {
+    let Some((wad_name, inner)) = split_wad_entry(name) else { return Ok(()) };
+    if inner.is_empty() {
+      let mut data = Vec::new();
+      entry.read_to_end(&mut data).map_err(|e| format!("Failed to read {}: {}", wad_name, e))?;
+      add_chunks(&mut out, &wad_name, wad_toc_hashes(data).map_err(|e| format!("{}: {}", wad_name, e))?);
+    } else {
+      add_chunks(&mut out, &wad_name, [chunk_hash_for_rel_path(&inner)]);
+    }
+    Ok(())
+  })?;
+  Ok(out)
+}
+
+fn folder_chunks(path: &Path) -> Result<ModChunks, String> {
+  let mut out = ModChunks::new();
+  // Installed cslol-manager mod: WAD/ holds packed WADs and/or WAD folders.
+  let wad_dir = path.join("WAD");
+  if wad_dir.is_dir() {
+    let entries = fs::read_dir(&wad_dir).map_err(|e| format!("Failed to read {}: {}", wad_dir.display(), e))?;
+    for entry in entries.flatten() {
+      let p = entry.path();
+      let name = entry.file_name().to_string_lossy().into_owned();
+      if p.is_dir() {
+        add_chunks(&mut out, &name, plan_wad_dir(&p)?.0.into_keys());
+      } else if split_wad_entry(&name).is_some() {
+        let data = fs::read(&p).map_err(|e| format!("Failed to read {}: {}", p.display(), e))?;
+        add_chunks(&mut out, &name, wad_toc_hashes(data).map_err(|e| format!("{}: {}", name, e))?);
+      }
+    }
+    return Ok(out);
+  }
+  // Otherwise treat it as a Quartz project.
+  for dir in project_wad_dirs(path) {
+    let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    add_chunks(&mut out, &name, plan_wad_dir(&dir)?.0.into_keys());
+  }
+  Ok(out)
+}
+
+fn mod_chunks(path: &Path) -> Result<ModChunks, String> {
+  if path.is_dir() { return folder_chunks(path); }
+  let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+  if split_wad_entry(&name).is_some() {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut out = ModChunks::new();
+    add_chunks(&mut out, &name, wad_toc_hashes(data)?);
+    return Ok(out);
+  }
+  archive_chunks(path)
+}
+
+/// Report chunks provided by more than one mod. `modPaths` is the load order:
+/// earlier mods take priority. Pass `hashDir` to resolve conflicting paths.
+#[napi(js_name = "detectConflicts")]
+pub fn detect_conflicts(mod_paths: Vec<String>, hash_dir: Option<String>) -> ModConflictsResult {
+  if mod_paths.len() < 2 {
+    return ModConflictsResult {
+      success: false,
+      error: Some("At least two mods are required".to_string()),
+      mods: Vec::new(),
+      conflicts: Vec::new(),
+    };
+  }
+
+  let mut summaries = Vec::with_capacity(mod_paths.len());
+  // (wad, hash) -> indices of mods providing it, in load order.
+  let mut owners: BTreeMap<(String, u64), Vec<usize>> = BTreeMap::new();
+  for (idx, path) in mod_paths.iter().enumerate() {
+    match mod_chunks(Path::new(path)) {
+      Ok(chunks) => {
+        let mut count = 0u32;
+        for (wad, mut hashes) in chunks {
+          hashes.sort_unstable();
+          hashes.dedup();
+          count += hashes.len() as u32;
+          for h in hashes {
+            owners.entry((wad.clone(), h)).or_default().push(idx);
+          }
+        }
+        summaries.push(ModChunkSummary { path: path.clone(), error: None, chunk_count: count });
+      }
+      Err(e) => summaries.push(ModChunkSummary { path: path.clone(), error: Some(e), chunk_count: 0 }),
+    }
+  }
+
+  let overlapping: Vec<((String, u64), Vec<usize>)> = owners.into_iter().filter(|(_, m)| m.len() > 1).collect();
+  let hashes: Vec<u64> = overlapping.iter().map(|((_, h), _)| *h).collect();
+  let layers = HashLayers::open(hash_dir.as_deref());
+  let resolved = layers.resolve_names(&hashes);
+
+  let conflicts = overlapping
+    .into_iter()
+    .zip(resolved)
+    .map(|(((wad, hash), mods), path)| ModConflict {
+      wad,
+      path_hash: format!("{:016x}", hash),
+      path,
+      winner: mod_paths[mods[0]].clone(),
+      mods: mods.into_iter().map(|i| mod_paths[i].clone()).collect(),
+    })
+    .collect();
+
+  ModConflictsResult { success: true, error: None, mods: summaries, conflicts }
+}
@@ -0,0 +1,42 @@
+// ── Disk space preflight ─────────────────────────────────────────────────────
+// Extracting a full champion or map WAD can need several GB. Checking the sum
+// of the planned chunks' uncompressed sizes against the free space up front
+// turns a disk-full halfway through (thousands of partial writes) into one
+// clear error before anything is written.
+
+use std::path::Path;
+
+use ltk_wad::WadChunk;
+use napi_derive::napi;
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct InsufficientSpace {
+  /// Bytes the extraction would write.
+  pub required: f64,
+  /// Bytes free on the destination volume.
+  pub available: f64,
+}
+
+impl InsufficientSpace {
+  pub(crate) fn message(&self) -> String {
+    format!(
+      "Not enough disk space: {:.1} MB required, {:.1} MB available",
+      self.required / 1_048_576.0,
+      self.available / 1_048_576.0,
+    )
+  }
+}
+
+/// Check that the volume holding `dest` can take the uncompressed `chunks`.
+/// Passes when the free space can't be queried, so an odd mount never blocks extraction.
+pub(crate) fn check_space<'a>(dest: &Path, chunks: impl IntoIterator<Item = &'a WadChunk>) -> Result<(), InsufficientSpace> {
+  let required: u64 = chunks.into_iter().map(|c| c.uncompressed_size() as u64).sum();
+  // `dest` may not exist yet; ask about the nearest ancestor that does.
+  let Some(existing) = dest.ancestors().find(|p| p.exists()) else { return Ok(()) };
+  let Ok(available) = fs4::available_space(existing) else { return Ok(()) };
+  if required > available {
+    return Err(InsufficientSpace { required: required as f64, available: available as f64 });
+  }
+  Ok(())
+}
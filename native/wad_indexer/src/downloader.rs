@@ -0,0 +1,239 @@
+// ── Downloads ────────────────────────────────────────────────────────────────
+// The one HTTP path for everything Quartz fetches (patcher manifests and
+// bundles, remote WADs, files for the frontend). Requests share an agent that
+// honours the proxy environment variables (ALL_PROXY/HTTPS_PROXY/HTTP_PROXY,
+// NO_PROXY) unless an explicit proxy is given, and transient failures
+// (connection errors, timeouts, 408/429/5xx) are retried with exponential
+// backoff. File downloads stream into `{dest}.part`, continue from it with a
+// Range request after an interruption, and are verified against an optional
+// SHA-256 before being moved into place.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use sha2::{Digest, Sha256};
+
+use crate::paths::rename_retrying;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_ATTEMPTS: u32 = 4;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const USER_AGENT: &str = "Quartz";
+
+fn build_agent(proxy: Option<&str>) -> Result<ureq::Agent, String> {
+  let mut config = ureq::Agent::config_builder().timeout_connect(Some(Duration::from_secs(15)));
+  if let Some(p) = proxy.filter(|p| !p.is_empty()) {
+    let proxy = ureq::Proxy::new(p).map_err(|e| format!("Invalid proxy {}: {}", p, e))?;
+    config = config.proxy(Some(proxy));
+  }
+  Ok(config.build().into())
+}
+
+/// Agent with the environment's proxy settings, shared so connections are reused.
+fn shared_agent() -> &'static ureq::Agent {
+  static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+  AGENT.get_or_init(|| build_agent(None).expect("agent without proxy always builds"))
+}
+
+fn is_transient(e: &ureq::Error) -> bool {
+  match e {
+    ureq::Error::StatusCode(code) => matches!(code, 408 | 429) || *code >= 500,
+    ureq::Error::Io(_) | ureq::Error::Timeout(_) | ureq::Error::ConnectionFailed | ureq::Error::HostNotFound => true,
+    _ => false,
+  }
+}
+
+/// Run `f` until it succeeds, fails permanently or `attempts` are used up,
+/// sleeping 0.5s, 1s, 2s, ... in between. `f` reports whether its error is transient.
+fn with_retries<T>(attempts: u32, mut f: impl FnMut() -> Result<T, (bool, String)>) -> Result<(T, u32), String> {
+  let attempts = attempts.max(1);
+  let mut attempt = 1;
+  loop {
+    match f() {
+      Ok(v) => return Ok((v, attempt)),
+      Err((transient, e)) if !transient || attempt >= attempts => return Err(e),
+      Err(_) => {
+        thread::sleep(BACKOFF_BASE * 2u32.pow(attempt - 1));
+        attempt += 1;
+      }
+    }
+  }
+}
+
+/// GET `url` into memory, optionally only bytes `start..end` (exclusive).
+pub(crate) fn http_get(url: &str, range: Option<(u64, u64)>) -> Result<Vec<u8>, String> {
+  let fetch = || -> Result<Vec<u8>, (bool, String)> {
+    let mut request = shared_agent().get(url).header("User-Agent", USER_AGENT);
+    if let Some((start, end)) = range {
+      request = request.header("Range", format!("bytes={}-{}", start, end.saturating_sub(1)));
+    }
+    let fail = |e: ureq::Error| (is_transient(&e), format!("Failed to download {}: {}", url, e));
+    let mut response = request.config().timeout_global(Some(HTTP_TIMEOUT)).build().call().map_err(fail)?;
+    let body = response.body_mut().with_config().limit(u64::MAX).read_to_vec().map_err(fail)?;
+    if let Some((start, end)) = range {
+      if body.len() as u64 != end - start {
+        return Err((false, format!("Server ignored range request for {} ({} bytes instead of {})", url, body.len(), end - start)));
+      }
+    }
+    Ok(body)
+  };
+  with_retries(DEFAULT_ATTEMPTS, fetch).map(|(body, _)| body)
+}
+
+pub(crate) struct Downloaded {
+  pub(crate) bytes: u64,
+  /// Bytes that were already in the `.part` file when the download finished.
+  pub(crate) resumed_from: u64,
+  pub(crate) attempts: u32,
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct DownloadOptions {
+  /// Expected SHA-256 (hex) of the complete file.
+  pub sha256: Option<String>,
+  /// Attempts before giving up. Defaults to 4.
+  pub attempts: Option<u32>,
+  /// Proxy URL, e.g. "http://host:8080" or "socks5://host:1080". Defaults to
+  /// the proxy environment variables.
+  pub proxy: Option<String>,
+  /// Continue from a leftover `.part` file. Defaults to true.
+  pub resume: Option<bool>,
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+  let mut name = dest.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+  name.push(".part");
+  dest.with_file_name(name)
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+  let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let mut hasher = Sha256::new();
+  io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// One attempt: continue `part` from its current length where the server allows it.
+/// Returns the offset the transfer started at.
+fn fetch_into(agent: &ureq::Agent, url: &str, part: &Path) -> Result<u64, (bool, String)> {
+  let have = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+  let mut request = agent.get(url).header("User-Agent", USER_AGENT);
+  if have > 0 {
+    request = request.header("Range", format!("bytes={}-", have));
+  }
+  let fail = |e: ureq::Error| (is_transient(&e), format!("Failed to download {}: {}", url, e));
+  let mut response = match request.call() {
+    // The part file already holds the whole resource.
+    Err(ureq::Error::StatusCode(416)) if have > 0 => return Ok(have),
+    r => r.map_err(fail)?,
+  };
+  // 206 continues the part file; a plain 200 means the server sent everything again.
+  let offset = if response.status() == 206 { have } else { 0 };
+  let file = fs::OpenOptions::new()
+    .create(true)
+    .write(true)
+    .append(offset > 0)
+    .truncate(offset == 0)
+    .open(part)
+    .map_err(|e| (false, format!("Failed to open {}: {}", part.display(), e)))?;
+  let mut writer = io::BufWriter::new(file);
+  let mut reader = response.body_mut().as_reader();
+  let mut buf = vec![0u8; 256 * 1024];
+  loop {
+    // Read errors are network errors (retry, keeping what arrived); write errors are local.
+    let n = reader.read(&mut buf).map_err(|e| (true, format!("Failed to download {}: {}", url, e)))?;
+    if n == 0 { break; }
+    writer.write_all(&buf[..n]).map_err(|e| (false, format!("Failed to write {}: {}", part.display(), e)))?;
+  }
+  writer.flush().map_err(|e| (false, format!("Failed to write {}: {}", part.display(), e)))?;
+  Ok(offset)
+}
+
+/// Download `url` to `dest` (see the module comment for retry/resume/verify).
+pub(crate) fn download_to(url: &str, dest: &Path, options: &DownloadOptions) -> Result<Downloaded, String> {
+  let custom_agent = options.proxy.as_deref().map(|p| build_agent(Some(p))).transpose()?;
+  let agent = custom_agent.as_ref().unwrap_or_else(|| shared_agent());
+  if let Some(parent) = dest.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let part = part_path(dest);
+  if !options.resume.unwrap_or(true) {
+    let _ = fs::remove_file(&part);
+  }
+
+  let (resumed_from, attempts) = with_retries(options.attempts.unwrap_or(DEFAULT_ATTEMPTS), || fetch_into(agent, url, &part))?;
+  if let Some(expected) = options.sha256.as_deref() {
+    let actual = sha256_file(&part)?;
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+      // A bad part file must not be resumed from next time.
+      let _ = fs::remove_file(&part);
+      return Err(format!("Checksum mismatch for {}: expected {}, got {}", url, expected, actual));
+    }
+  }
+  let bytes = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+  rename_retrying(&part, dest).map_err(|e| format!("Failed to move download to {}: {}", dest.display(), e))?;
+  Ok(Downloaded { bytes, resumed_from, attempts })
+}
+
+#[napi(object)]
+pub struct DownloadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub bytes: f64,
+  #[napi(js_name = "resumedFrom")]
+  pub resumed_from: f64,
+  pub attempts: u32,
+}
+
+fn download_result(r: Result<Downloaded, String>) -> DownloadResult {
+  match r {
+    Ok(d) => DownloadResult {
+      success: true,
+      error: None,
+      bytes: d.bytes as f64,
+      resumed_from: d.resumed_from as f64,
+      attempts: d.attempts,
+    },
+    Err(e) => DownloadResult { success: false, error: Some(e), bytes: 0.0, resumed_from: 0.0, attempts: 0 },
+  }
+}
+
+/// Download a file with retries, resume and optional checksum verification.
+#[napi(js_name = "downloadFile")]
+pub fn download_file(url: String, dest_path: String, options: Option<DownloadOptions>) -> DownloadResult {
+  download_result(download_to(&url, Path::new(&dest_path), &options.unwrap_or_default()))
+}
+
+pub struct DownloadFileTask {
+  url: String,
+  dest_path: String,
+  options: DownloadOptions,
+}
+
+#[napi]
+impl Task for DownloadFileTask {
+  type Output = DownloadResult;
+  type JsValue = DownloadResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(download_result(download_to(&self.url, Path::new(&self.dest_path), &self.options)))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// `downloadFile` off the main thread.
+#[napi(js_name = "downloadFileAsync")]
+pub fn download_file_async(url: String, dest_path: String, options: Option<DownloadOptions>) -> AsyncTask<DownloadFileTask> {
+  AsyncTask::new(DownloadFileTask { url, dest_path, options: options.unwrap_or_default() })
+}
@@ -0,0 +1,223 @@
+// ── Edit journal ─────────────────────────────────────────────────────────────
+// Append-only history of the edits made to an open bin, one JSON line per edit
+// in `{userData}/edit-journal/bin-{hash}.jsonl`. Lines are flushed to disk as
+// they are recorded, so the history survives a crash or a webview reload that
+// loses the in-memory undo stack. Edits are text snippets of the ritobin view
+// (`before` replaced by `after`); reverting applies the inverse of every later
+// edit, newest first, and is itself journaled so it can be reverted too.
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ltk_meta::Bin;
+use ltk_ritobin::{parse, write_with_hashes, HashMapProvider};
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::paths::rename_retrying;
+
+const JOURNAL_DIR: &str = "edit-journal";
+
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditEntry {
+  /// Sequence number within the file's journal, starting at 1.
+  pub id: u32,
+  /// What the edit was, for display ("Set birthColor", "Delete emitter"...).
+  pub operation: String,
+  /// Unix milliseconds.
+  pub timestamp: i64,
+  pub before: String,
+  pub after: String,
+  /// Byte offset of `before` in the text the edit was made on, when known.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub offset: Option<u32>,
+}
+
+#[napi(object)]
+pub struct EditHistoryResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Oldest first.
+  pub entries: Vec<EditEntry>,
+}
+
+fn now_millis() -> i64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+/// One journal per file, keyed like session scopes so separators and (on
+/// Windows) case don't split a file's history.
+fn journal_path(user_data_dir: &Path, path: &str) -> PathBuf {
+  let key = path.replace('\\', "/");
+  let key = if cfg!(windows) { key.to_lowercase() } else { key };
+  user_data_dir.join(JOURNAL_DIR).join(format!("bin-{:016x}.jsonl", xxh64(key.as_bytes(), 0)))
+}
+
+/// Journal entries, skipping a torn last line left by a crash mid-write.
+fn read_journal(journal: &Path) -> Result<Vec<EditEntry>, String> {
+  let text = match fs::read_to_string(journal) {
+    Ok(t) => t,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(format!("Failed to read {}: {}", journal.display(), e)),
+  };
+  Ok(text.lines().filter_map(|l| serde_json::from_str(l).ok()).collect())
+}
+
+fn append(journal: &Path, entries: &[EditEntry]) -> Result<(), String> {
+  if let Some(parent) = journal.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let mut text = String::new();
+  for entry in entries {
+    let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize edit: {}", e))?;
+    text.push_str(&line);
+    text.push('\n');
+  }
+  let mut file = OpenOptions::new().create(true).append(true).open(journal)
+    .map_err(|e| format!("Failed to open {}: {}", journal.display(), e))?;
+  file.write_all(text.as_bytes())
+    .and_then(|_| file.sync_data())
+    .map_err(|e| format!("Failed to write {}: {}", journal.display(), e))
+}
+
+fn record(user_data_dir: &Path, path: &str, operation: &str, before: &str, after: &str, offset: Option<u32>) -> Result<EditEntry, String> {
+  let journal = journal_path(user_data_dir, path);
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let id = read_journal(&journal)?.last().map(|e| e.id + 1).unwrap_or(1);
+  let entry = EditEntry {
+    id,
+    operation: operation.to_string(),
+    timestamp: now_millis(),
+    before: before.to_string(),
+    after: after.to_string(),
+    offset,
+  };
+  append(&journal, std::slice::from_ref(&entry))?;
+  Ok(entry)
+}
+
+/// Append an edit to the journal of `path` and return it with its ID.
+#[napi(js_name = "recordEdit")]
+pub fn record_edit(
+  user_data_dir: String,
+  path: String,
+  operation: String,
+  before: String,
+  after: String,
+  offset: Option<u32>,
+) -> EditHistoryResult {
+  match record(Path::new(&user_data_dir), &path, &operation, &before, &after, offset) {
+    Ok(entry) => EditHistoryResult { success: true, error: None, entries: vec![entry] },
+    Err(e) => EditHistoryResult { success: false, error: Some(e), entries: Vec::new() },
+  }
+}
+
+/// Every journaled edit of `path`. A file without a journal has an empty history.
+#[napi(js_name = "getEditHistory")]
+pub fn get_edit_history(user_data_dir: String, path: String) -> EditHistoryResult {
+  match read_journal(&journal_path(Path::new(&user_data_dir), &path)) {
+    Ok(entries) => EditHistoryResult { success: true, error: None, entries },
+    Err(e) => EditHistoryResult { success: false, error: Some(e), entries: Vec::new() },
+  }
+}
+
+/// Replace `from` with `to` in `text`, at `offset` when it still matches there,
+/// otherwise at the only occurrence of `from`.
+fn replace_snippet(text: &mut String, from: &str, to: &str, offset: Option<u32>) -> Result<(), String> {
+  let at = offset
+    .map(|o| o as usize)
+    .filter(|&o| text.get(o..o + from.len()) == Some(from))
+    .or_else(|| {
+      let first = text.find(from)?;
+      text[first + from.len()..].find(from).is_none().then_some(first)
+    })
+    .ok_or_else(|| "the file was changed outside the editor since this edit".to_string())?;
+  text.replace_range(at..at + from.len(), to);
+  Ok(())
+}
+
+fn read_text(path: &Path, hash_dir: Option<&str>) -> Result<String, String> {
+  let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  if !data.starts_with(b"PROP") && !data.starts_with(b"PTCH") {
+    return String::from_utf8(data).map_err(|_| format!("{} is neither a bin nor text", path.display()));
+  }
+  let bin = Bin::from_reader(&mut Cursor::new(&data))
+    .map_err(|e| format!("Failed to parse {}: {:?}", path.display(), e))?;
+  let mut hashes = HashMapProvider::new();
+  if let Some(dir) = hash_dir.map(Path::new).filter(|d| d.exists()) {
+    hashes.load_from_directory(dir);
+  }
+  write_with_hashes(&bin, &hashes).map_err(|e| format!("Failed to convert {}: {:?}", path.display(), e))
+}
+
+fn write_text(path: &Path, text: &str, as_bin: bool) -> Result<(), String> {
+  let tmp = path.with_extension("journal.tmp");
+  let result = if as_bin {
+    let tree = parse(text).map_err(|e| format!("Reverted text no longer parses: {:?}", e))?.to_bin_tree();
+    let file = fs::File::create(&tmp).map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    tree.to_writer(&mut writer)
+      .map_err(|e| e.to_string())
+      .and_then(|_| writer.flush().map_err(|e| e.to_string()))
+      .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))
+  } else {
+    fs::write(&tmp, text).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))
+  };
+  if let Err(e) = result {
+    let _ = fs::remove_file(&tmp);
+    return Err(e);
+  }
+  rename_retrying(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+fn revert(user_data_dir: &Path, path: &str, entry_id: u32, hash_dir: Option<&str>) -> Result<Vec<EditEntry>, String> {
+  let journal = journal_path(user_data_dir, path);
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let entries = read_journal(&journal)?;
+  if entry_id != 0 && !entries.iter().any(|e| e.id == entry_id) {
+    return Err(format!("No edit #{} in the history of {}", entry_id, path));
+  }
+  let later: Vec<&EditEntry> = entries.iter().filter(|e| e.id > entry_id).collect();
+  if later.is_empty() { return Ok(Vec::new()); }
+
+  let file = Path::new(path);
+  let as_bin = fs::read(file).map(|d| d.starts_with(b"PROP") || d.starts_with(b"PTCH")).unwrap_or(false);
+  let mut text = read_text(file, hash_dir)?;
+  let first_id = entries.last().map(|e| e.id + 1).unwrap_or(1);
+  let mut reverts = Vec::with_capacity(later.len());
+  for (id, edit) in (first_id..).zip(later.iter().rev()) {
+    replace_snippet(&mut text, &edit.after, &edit.before, edit.offset)
+      .map_err(|e| format!("Can't undo edit #{} ({}): {}", edit.id, edit.operation, e))?;
+    reverts.push(EditEntry {
+      id,
+      operation: format!("Revert #{}: {}", edit.id, edit.operation),
+      timestamp: now_millis(),
+      before: edit.after.clone(),
+      after: edit.before.clone(),
+      offset: edit.offset,
+    });
+  }
+  write_text(file, &text, as_bin)?;
+  append(&journal, &reverts)?;
+  Ok(reverts)
+}
+
+/// Restore `path` to its state right after edit `entryId` (0 = before the first
+/// edit) by undoing every later edit. Returns the revert entries appended to
+/// the journal. `.bin` files are edited through their ritobin text, so
+/// `hashDir` should be the one the editor used to show them.
+#[napi(js_name = "revertTo")]
+pub fn revert_to(user_data_dir: String, path: String, entry_id: u32, hash_dir: Option<String>) -> EditHistoryResult {
+  match revert(Path::new(&user_data_dir), &path, entry_id, hash_dir.as_deref()) {
+    Ok(entries) => EditHistoryResult { success: true, error: None, entries },
+    Err(e) => EditHistoryResult { success: false, error: Some(e), entries: Vec::new() },
+  }
+}
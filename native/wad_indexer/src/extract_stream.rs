@@ -0,0 +1,161 @@
+// ── Streaming extraction ─────────────────────────────────────────────────────
+// `extractWadStreaming` / `extractSelectedStreaming` run the regular extraction
+// but report each chunk to `callback` as `{hash, path, ok}` as soon as it is
+// written, so the WAD browser can fill in its file tree while the extraction
+// is still running. Events go through a bounded queue: when JS falls behind,
+// the extraction workers wait rather than buffering a whole WAD's worth of
+// events. The callback gets `null` once every event has been delivered, just
+// before the returned promise resolves with the usual summary.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use napi::bindgen_prelude::AsyncTask;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction, JsUnknown, Task};
+use napi_derive::napi;
+
+use crate::paths::IfExists;
+use crate::{
+  extract_atomically, extract_selected_to, extract_wad_to, selected_chunks, ExtractOptions, SelectedChunk, WadExtractItem,
+  WadExtractResult,
+};
+
+/// Events that may be waiting for JS before extraction workers block.
+const EVENT_QUEUE_SIZE: usize = 256;
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct ExtractedChunkEvent {
+  /// 16-digit hex path hash.
+  pub hash: String,
+  /// Output path relative to the output dir, with forward slashes.
+  pub path: String,
+  /// False when the chunk could not be decoded or written.
+  pub ok: bool,
+}
+
+type EventSink = ThreadsafeFunction<Option<ExtractedChunkEvent>, ErrorStrategy::Fatal>;
+
+enum StreamJob {
+  Wad { wad_path: PathBuf, hash_path: Option<String> },
+  Selected { items: Vec<SelectedChunk>, invalid: u32, preserve_paths: Option<bool> },
+}
+
+pub struct ExtractStreamTask {
+  job: StreamJob,
+  output_dir: PathBuf,
+  if_exists: IfExists,
+  resume: Option<bool>,
+  atomic: bool,
+  output_template: Option<String>,
+  events: EventSink,
+}
+
+impl ExtractStreamTask {
+  /// Wait until JS has seen every queued event (the queue is FIFO), sending the closing `null`.
+  fn finish_events(&self) {
+    let (tx, rx) = mpsc::channel();
+    self.events.call_with_return_value(None, ThreadsafeFunctionCallMode::Blocking, move |_: JsUnknown| {
+      let _ = tx.send(());
+      Ok(())
+    });
+    // If the environment is shutting down the closure is dropped and this returns at once.
+    let _ = rx.recv();
+  }
+}
+
+#[napi]
+impl Task for ExtractStreamTask {
+  type Output = WadExtractResult;
+  type JsValue = WadExtractResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let events = &self.events;
+    let report = |hash: u64, path: &str, ok: bool| {
+      let event = ExtractedChunkEvent { hash: format!("{:016x}", hash), path: path.to_string(), ok };
+      events.call(Some(event), ThreadsafeFunctionCallMode::Blocking);
+    };
+    let (if_exists, resume, template) = (self.if_exists, self.resume, self.output_template.as_deref());
+    let job = &mut self.job;
+    let invalid = match job { StreamJob::Selected { invalid, .. } => *invalid, StreamJob::Wad { .. } => 0 };
+    let mut run = |output: &Path| match job {
+      StreamJob::Wad { wad_path, hash_path } => {
+        extract_wad_to(wad_path, output, hash_path.as_deref(), if_exists, resume, template, Some(&report))
+      }
+      StreamJob::Selected { items, preserve_paths, .. } => {
+        extract_selected_to(std::mem::take(items), output, if_exists, *preserve_paths, resume, template, Some(&report))
+      }
+    };
+    let mut result = if self.atomic {
+      extract_atomically(&self.output_dir, resume.unwrap_or(false), if_exists, run)
+    } else {
+      run(&self.output_dir)
+    };
+    result.skipped_count += invalid;
+    self.finish_events();
+    Ok(result)
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+fn event_sink(env: &Env, callback: JsFunction) -> napi::Result<EventSink> {
+  let mut events: EventSink = callback.create_threadsafe_function(EVENT_QUEUE_SIZE, |ctx| Ok(vec![ctx.value]))?;
+  events.unref(env)?;
+  Ok(events)
+}
+
+fn stream_task(
+  env: &Env,
+  job: StreamJob,
+  output_dir: String,
+  options: Option<ExtractOptions>,
+  callback: JsFunction,
+) -> napi::Result<ExtractStreamTask> {
+  if output_dir.is_empty() {
+    return Err(napi::Error::from_reason("Output directory is required"));
+  }
+  let ExtractOptions { resume, atomic, output_template, if_exists } = options.unwrap_or_default();
+  Ok(ExtractStreamTask {
+    job,
+    output_dir: PathBuf::from(output_dir),
+    if_exists: IfExists::resolve(if_exists.as_deref(), None).map_err(napi::Error::from_reason)?,
+    resume,
+    atomic: atomic.unwrap_or(false),
+    output_template,
+    events: event_sink(env, callback)?,
+  })
+}
+
+/// Extract a whole WAD, calling `callback` with an `ExtractedChunkEvent` per
+/// chunk and `null` at the end. `options` are the same as `extractWad`'s.
+#[napi(js_name = "extractWadStreaming")]
+pub fn extract_wad_streaming(
+  env: Env,
+  wad_path: String,
+  output_dir: String,
+  hash_path: Option<String>,
+  options: Option<ExtractOptions>,
+  callback: JsFunction,
+) -> napi::Result<AsyncTask<ExtractStreamTask>> {
+  let job = StreamJob::Wad { wad_path: PathBuf::from(wad_path), hash_path };
+  Ok(AsyncTask::new(stream_task(&env, job, output_dir, options, callback)?))
+}
+
+/// `extractSelected` with per-chunk events (see `extractWadStreaming`).
+#[napi(js_name = "extractSelectedStreaming")]
+pub fn extract_selected_streaming(
+  env: Env,
+  items: Vec<WadExtractItem>,
+  output_dir: String,
+  preserve_paths: Option<bool>,
+  options: Option<ExtractOptions>,
+  callback: JsFunction,
+) -> napi::Result<AsyncTask<ExtractStreamTask>> {
+  let (items, invalid) = selected_chunks(items);
+  let job = StreamJob::Selected { items, invalid, preserve_paths };
+  Ok(AsyncTask::new(stream_task(&env, job, output_dir, options, callback)?))
+}
@@ -0,0 +1,329 @@
+// ── .fantome export ──────────────────────────────────────────────────────────
+// Packs a project's WAD folders into the community .fantome layout understood by
+// mod managers: a zip with `META/info.json` (+ optional `META/image.png` preview)
+// and one `WAD/{Name}.wad.client` per WAD.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::archive::for_each_archive_file;
+use crate::game::{find_file_ci, walk_final_wads};
+use crate::icons::{encode_png, png_dimensions};
+use crate::normalize_rel_path;
+use crate::paths::{rename_retrying, staging_dir, swap_dir_in};
+use crate::wad_build::{build_wad_bytes, collect_files, project_content_dir, project_wad_dirs, wad_file_name_for_dir};
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct ModMeta {
+  pub name: String,
+  pub author: Option<String>,
+  pub version: Option<String>,
+  pub description: Option<String>,
+  /// PNG/JPG shown by mod managers. Downscaled and embedded as `META/image.png` on export.
+  pub preview: Option<String>,
+}
+
+#[napi(object)]
+pub struct FantomeExportResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "outFile")]
+  pub out_file: String,
+  #[napi(js_name = "wadCount")]
+  pub wad_count: u32,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+}
+
+/// `META/info.json` contents in the casing mod managers expect.
+pub(crate) fn fantome_info_json(meta: &ModMeta) -> String {
+  let info = serde_json::json!({
+    "Name": meta.name,
+    "Author": meta.author.clone().unwrap_or_else(|| "Unknown".to_string()),
+    "Version": meta.version.clone().unwrap_or_else(|| "1.0.0".to_string()),
+    "Description": meta.description.clone().unwrap_or_default(),
+  });
+  serde_json::to_string_pretty(&info).unwrap_or_default()
+}
+
+/// Previews are shown as small cards in mod managers; anything larger only bloats the package.
+const PREVIEW_MAX_SIZE: u32 = 512;
+const PREVIEW_ENTRY: &str = "META/image.png";
+
+/// Downscale `img` to fit `PREVIEW_MAX_SIZE` (keeping its aspect ratio) and encode as PNG.
+fn fit_preview_png(img: image::DynamicImage) -> Result<Vec<u8>, String> {
+  let img = if img.width() > PREVIEW_MAX_SIZE || img.height() > PREVIEW_MAX_SIZE {
+    img.resize(PREVIEW_MAX_SIZE, PREVIEW_MAX_SIZE, image::imageops::FilterType::Lanczos3)
+  } else {
+    img
+  };
+  encode_png(img.to_rgba8())
+}
+
+/// Load a PNG/JPG preview, downscale it to fit `PREVIEW_MAX_SIZE` and re-encode as PNG.
+pub(crate) fn preview_png(path: &Path) -> Result<Vec<u8>, String> {
+  let img = image::open(path).map_err(|e| format!("Failed to read preview {}: {}", path.display(), e))?;
+  fit_preview_png(img)
+}
+
+fn meta_preview(meta: &ModMeta) -> Result<Option<Vec<u8>>, String> {
+  match meta.preview.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+    Some(p) => preview_png(Path::new(p)).map(Some),
+    None => Ok(None),
+  }
+}
+
+fn write_fantome(project: &Path, out_file: &Path, meta: &ModMeta) -> Result<(u32, u32), String> {
+  let wad_dirs = project_wad_dirs(project);
+  if wad_dirs.is_empty() {
+    return Err(format!("No WAD folders (*.wad.client) found in {}", project.display()));
+  }
+  if let Some(parent) = out_file.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  let file = fs::File::create(out_file)
+    .map_err(|e| format!("Failed to create {}: {}", out_file.display(), e))?;
+  let mut zip = ZipWriter::new(file);
+  let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+  // WAD chunks are already compressed; deflating them again only costs time.
+  let stored = SimpleFileOptions::default()
+    .compression_method(CompressionMethod::Stored)
+    .large_file(true);
+
+  zip.start_file("META/info.json", deflated).map_err(|e| format!("Failed to write info.json: {}", e))?;
+  zip.write_all(fantome_info_json(meta).as_bytes()).map_err(|e| format!("Failed to write info.json: {}", e))?;
+  if let Some(png) = meta_preview(meta)? {
+    // PNG data is already compressed.
+    zip.start_file(PREVIEW_ENTRY, stored).map_err(|e| format!("Failed to write {}: {}", PREVIEW_ENTRY, e))?;
+    zip.write_all(&png).map_err(|e| format!("Failed to write {}: {}", PREVIEW_ENTRY, e))?;
+  }
+
+  let mut chunk_count = 0u32;
+  for dir in &wad_dirs {
+    let dir_name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let (bytes, chunks) = build_wad_bytes(dir)?;
+    let entry = format!("WAD/{}", wad_file_name_for_dir(&dir_name));
+    zip.start_file(entry.as_str(), stored).map_err(|e| format!("Failed to write {}: {}", entry, e))?;
+    zip.write_all(&bytes).map_err(|e| format!("Failed to write {}: {}", entry, e))?;
+    chunk_count += chunks;
+  }
+
+  // Loose files that bypass WADs (`content/RAW/...`) are shipped as-is.
+  let raw_dir = project_content_dir(project).join("RAW");
+  if raw_dir.is_dir() {
+    for (rel, path) in collect_files(&raw_dir)? {
+      let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+      let entry = format!("RAW/{}", rel);
+      zip.start_file(entry.as_str(), deflated).map_err(|e| format!("Failed to write {}: {}", entry, e))?;
+      zip.write_all(&data).map_err(|e| format!("Failed to write {}: {}", entry, e))?;
+    }
+  }
+
+  zip.finish().map_err(|e| format!("Failed to finalize {}: {}", out_file.display(), e))?;
+  Ok((wad_dirs.len() as u32, chunk_count))
+}
+
+/// Write the package to `<out>.partial` and rename it over `out` once complete,
+/// so a failed export never touches an existing package.
+fn export_to(project: &Path, out: &Path, meta: &ModMeta) -> Result<(u32, u32), String> {
+  let tmp = staging_dir(out).ok_or_else(|| format!("Invalid output file: {}", out.display()))?;
+  let counts = write_fantome(project, &tmp, meta).inspect_err(|_| {
+    let _ = fs::remove_file(&tmp);
+  })?;
+  rename_retrying(&tmp, out).map_err(|e| {
+    let _ = fs::remove_file(&tmp);
+    format!("Failed to write {}: {}", out.display(), e)
+  })?;
+  Ok(counts)
+}
+
+/// Build every WAD folder in a project and package them with `meta` as a .fantome.
+#[napi(js_name = "exportFantome")]
+pub fn export_fantome(project_path: String, out_file: String, meta: ModMeta) -> FantomeExportResult {
+  if meta.name.trim().is_empty() {
+    return FantomeExportResult {
+      success: false,
+      error: Some("Mod name is required".to_string()),
+      out_file,
+      wad_count: 0,
+      chunk_count: 0,
+    };
+  }
+  match export_to(Path::new(&project_path), Path::new(&out_file), &meta) {
+    Ok((wad_count, chunk_count)) => FantomeExportResult { success: true, error: None, out_file, wad_count, chunk_count },
+    Err(e) => FantomeExportResult { success: false, error: Some(e), out_file, wad_count: 0, chunk_count: 0 },
+  }
+}
+
+// ── cslol-manager layout ─────────────────────────────────────────────────────
+// cslol-manager keeps installed mods as plain folders: `META/info.json`,
+// `WAD/{Game}.wad.client` and optional `RAW/` loose files. WAD names must match
+// the game's file names, so project folders are renamed to the installed casing.
+
+/// Lowercased game WAD file name -> actual file name, for every WAD under DATA/FINAL.
+fn game_wad_names(league_path: Option<&Path>) -> HashMap<String, String> {
+  let Some(league) = league_path else { return HashMap::new() };
+  walk_final_wads(league)
+    .into_iter()
+    .filter_map(|(p, _)| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+    .map(|n| (n.to_ascii_lowercase(), n))
+    .collect()
+}
+
+/// Game-matching WAD file name for a project WAD folder ("ahri.wad.client" -> "Ahri.wad.client").
+fn game_wad_file_name(dir_name: &str, game_names: &HashMap<String, String>) -> String {
+  let name = wad_file_name_for_dir(dir_name);
+  game_names.get(&name.to_ascii_lowercase()).cloned().unwrap_or(name)
+}
+
+/// Folder-safe version of a mod name.
+fn sanitize_mod_folder_name(name: &str) -> String {
+  let cleaned: String = name.trim()
+    .chars()
+    .map(|c| if c.is_control() || "<>:\"/\\|?*".contains(c) { '_' } else { c })
+    .collect();
+  let cleaned = cleaned.trim_end_matches(['.', ' ']).to_string();
+  if cleaned.is_empty() { "mod".to_string() } else { cleaned }
+}
+
+fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<u32, String> {
+  let mut copied = 0u32;
+  for (rel, path) in collect_files(src)? {
+    let out = dst.join(&rel);
+    if let Some(parent) = out.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::copy(&path, &out).map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+    copied += 1;
+  }
+  Ok(copied)
+}
+
+fn write_cslol_mod(project: &Path, mod_dir: &Path, meta: &ModMeta, league_path: Option<&Path>) -> Result<(u32, u32), String> {
+  let wad_dirs = project_wad_dirs(project);
+  let raw_dir = project_content_dir(project).join("RAW");
+  if wad_dirs.is_empty() && !raw_dir.is_dir() {
+    return Err(format!("No WAD folders (*.wad.client) found in {}", project.display()));
+  }
+  let meta_dir = mod_dir.join("META");
+  let wad_out = mod_dir.join("WAD");
+  for d in [&meta_dir, &wad_out] {
+    fs::create_dir_all(d).map_err(|e| format!("Failed to create {}: {}", d.display(), e))?;
+  }
+  fs::write(meta_dir.join("info.json"), fantome_info_json(meta))
+    .map_err(|e| format!("Failed to write info.json: {}", e))?;
+  if let Some(png) = meta_preview(meta)? {
+    fs::write(meta_dir.join("image.png"), png).map_err(|e| format!("Failed to write image.png: {}", e))?;
+  }
+
+  let game_names = game_wad_names(league_path);
+  let mut chunk_count = 0u32;
+  for dir in &wad_dirs {
+    let dir_name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let (bytes, chunks) = build_wad_bytes(dir)?;
+    let out = wad_out.join(game_wad_file_name(&dir_name, &game_names));
+    fs::write(&out, bytes).map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+    chunk_count += chunks;
+  }
+  if raw_dir.is_dir() {
+    copy_dir_recursive(&raw_dir, &mod_dir.join("RAW"))?;
+  }
+  Ok((wad_dirs.len() as u32, chunk_count))
+}
+
+/// Build the mod in `<mod>.partial` and swap it in only once it is complete,
+/// so a failed export leaves an existing install of the mod untouched.
+fn export_cslol(project: &Path, mod_dir: &Path, meta: &ModMeta, league_path: Option<&Path>) -> Result<(u32, u32), String> {
+  let staging = staging_dir(mod_dir).ok_or_else(|| format!("Invalid mod folder: {}", mod_dir.display()))?;
+  let _ = fs::remove_dir_all(&staging);
+  let counts = write_cslol_mod(project, &staging, meta, league_path).inspect_err(|_| {
+    let _ = fs::remove_dir_all(&staging);
+  })?;
+  swap_dir_in(&staging, mod_dir)?;
+  Ok(counts)
+}
+
+/// Write the project as an installed cslol-manager mod: `{outDir}/{Name}/META`, `WAD`, `RAW`.
+/// Pass `leaguePath` to match WAD names to the installed game files. An existing
+/// `{Name}` folder is replaced only after the new one has been fully written.
+#[napi(js_name = "exportCslolMod")]
+pub fn export_cslol_mod(
+  project_path: String,
+  out_dir: String,
+  meta: ModMeta,
+  league_path: Option<String>,
+) -> FantomeExportResult {
+  let mod_dir = Path::new(&out_dir).join(sanitize_mod_folder_name(&meta.name));
+  let out_file = mod_dir.to_string_lossy().into_owned();
+  if meta.name.trim().is_empty() {
+    return FantomeExportResult { success: false, error: Some("Mod name is required".to_string()), out_file, wad_count: 0, chunk_count: 0 };
+  }
+  match export_cslol(Path::new(&project_path), &mod_dir, &meta, league_path.as_deref().map(Path::new)) {
+    Ok((wad_count, chunk_count)) => FantomeExportResult { success: true, error: None, out_file, wad_count, chunk_count },
+    Err(e) => FantomeExportResult { success: false, error: Some(e), out_file, wad_count: 0, chunk_count: 0 },
+  }
+}
+
+// ── Package previews ─────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct PackagePreviewResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// None when the package has no preview image.
+  pub png: Option<Buffer>,
+  pub width: u32,
+  pub height: u32,
+}
+
+fn is_preview_entry(name: &str) -> bool {
+  let name = normalize_rel_path(name).to_ascii_lowercase();
+  matches!(name.as_str(), "meta/image.png" | "meta/image.jpg" | "meta/image.jpeg")
+}
+
+fn read_package_preview(path: &Path) -> Result<Option<Vec<u8>>, String> {
+  let mut found: Option<Vec<u8>> = None;
+  if path.is_dir() {
+    // Installed cslol-manager mod folder.
+    for name in ["image.png", "image.jpg", "image.jpeg"] {
+      if let Some(p) = find_file_ci(&path.join("META"), name) {
+        found = Some(fs::read(&p).map_err(|e| format!("Failed to read {}: {}", p.display(), e))?);
+        break;
+      }
+    }
+  } else {
+    for_each_archive_file(path, |name, entry| {
+      if found.is_none() && is_preview_entry(name) {
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        found = Some(data);
+      }
+      Ok(())
+    })?;
+  }
+  let Some(data) = found else { return Ok(None) };
+  // Normalize JPGs (and oversized images from other tools) to a small PNG for the UI.
+  let img = image::load_from_memory(&data).map_err(|e| format!("Failed to decode preview: {}", e))?;
+  fit_preview_png(img).map(Some)
+}
+
+/// Preview image of a .fantome/.zip/.7z package or installed mod folder, as PNG.
+#[napi(js_name = "getPackagePreview")]
+pub fn get_package_preview(archive_path: String) -> PackagePreviewResult {
+  match read_package_preview(Path::new(&archive_path)) {
+    Ok(Some(png)) => {
+      let (width, height) = png_dimensions(&png);
+      PackagePreviewResult { success: true, error: None, png: Some(png.into()), width, height }
+    }
+    Ok(None) => PackagePreviewResult { success: true, error: None, png: None, width: 0, height: 0 },
+    Err(e) => PackagePreviewResult { success: false, error: Some(e), png: None, width: 0, height: 0 },
+  }
+}
@@ -0,0 +1,356 @@
+// ── Project freshness ────────────────────────────────────────────────────────
+// Records where a project's files came from (game WAD, path hash, checksum of
+// the original data, patch) in `.quartz/origins.json`, and later compares
+// those records with the installed game to list the assets Riot changed or
+// removed since. That list is what has to be re-ported after a patch.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi_derive::napi;
+use quartz_core::repath::replace_prefix;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::game::{game_dir, walk_final_wads};
+use crate::threads::run_io;
+use crate::version::detect_game_version;
+use crate::wad_build::{plan_wad_dir, project_wad_dirs, wad_file_name_for_dir};
+use crate::{parse_hash_hex, unique_chunks, xxhash_path, HashLayers};
+
+pub(crate) const ORIGINS_JSON: &str = "origins.json";
+const ORIGINS_VERSION: u32 = 1;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChunkOrigin {
+  /// Asset path (or hex hash when it wasn't resolved).
+  pub(crate) path: String,
+  /// xxh3 of the decompressed game data, 16-digit hex.
+  pub(crate) checksum: String,
+  pub(crate) size: u64,
+  /// Game patch the data was taken from.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) patch: Option<String>,
+  /// Checksum of the project's copy when Quartz last wrote or repathed it; a
+  /// file matching this is still unmodified even though it differs from the game.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) synced: Option<String>,
+}
+
+/// Asset path prefix moved by repathing ("assets/characters/ahri/" ->
+/// "assets/bum/characters/ahri/"). Lowercase.
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepathRule {
+  pub from: String,
+  pub to: String,
+}
+
+/// `path` with the first matching rule applied, if any.
+pub(crate) fn apply_repath(rules: &[RepathRule], path: &str) -> Option<String> {
+  rules.iter().find_map(|r| replace_prefix(&r.from, &r.to, path))
+}
+
+/// The project file holding `hash`'s asset: at its original path or where the repath rules moved it.
+pub(crate) fn project_file_for<'a>(
+  files: &'a HashMap<u64, PathBuf>,
+  hash: u64,
+  origin: &ChunkOrigin,
+  rules: &[RepathRule],
+) -> Option<&'a PathBuf> {
+  files.get(&hash).or_else(|| files.get(&xxhash_path(&apply_repath(rules, &origin.path)?)))
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WadOrigin {
+  /// Game WAD relative to DATA/FINAL, e.g. "Champions/Ahri.wad.client".
+  pub(crate) game_wad: String,
+  /// Hex path hash -> origin.
+  pub(crate) chunks: BTreeMap<String, ChunkOrigin>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Origins {
+  pub(crate) version: u32,
+  /// Project WAD folder name -> its origins.
+  pub(crate) wads: BTreeMap<String, WadOrigin>,
+  /// Repathing applied to the project, re-applied when porting.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) repath: Vec<RepathRule>,
+}
+
+impl Default for Origins {
+  fn default() -> Self {
+    Origins { version: ORIGINS_VERSION, wads: BTreeMap::new(), repath: Vec::new() }
+  }
+}
+
+pub(crate) fn data_checksum(data: &[u8]) -> String {
+  format!("{:016x}", xxh3_64(data))
+}
+
+fn origins_path(project: &Path) -> PathBuf {
+  project.join(".quartz").join(ORIGINS_JSON)
+}
+
+pub(crate) fn read_origins(project: &Path) -> Result<Origins, String> {
+  let path = origins_path(project);
+  match fs::read_to_string(&path) {
+    Ok(text) => serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e)),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Origins::default()),
+    Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+  }
+}
+
+pub(crate) fn write_origins(project: &Path, origins: &Origins) -> Result<(), String> {
+  let path = origins_path(project);
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let text = serde_json::to_string_pretty(origins).map_err(|e| format!("Failed to serialize origins: {}", e))?;
+  let tmp = path.with_extension("json.tmp");
+  fs::write(&tmp, text).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Add origins for chunks extracted from `game_wad_rel` into the project WAD folder `wad_dir_name`.
+pub(crate) fn record_origins(
+  project: &Path,
+  wad_dir_name: &str,
+  game_wad_rel: &str,
+  entries: impl IntoIterator<Item = (u64, ChunkOrigin)>,
+) -> Result<(), String> {
+  let mut origins = read_origins(project)?;
+  let wad = origins.wads.entry(wad_dir_name.to_string()).or_default();
+  wad.game_wad = game_wad_rel.to_string();
+  for (hash, origin) in entries {
+    wad.chunks.insert(format!("{:016x}", hash), origin);
+  }
+  write_origins(project, &origins)
+}
+
+/// Game WAD path relative to DATA/FINAL.
+pub(crate) fn final_rel_path(league: &Path, game_wad: &Path) -> String {
+  let final_dir = game_dir(league).join("DATA").join("FINAL");
+  game_wad.strip_prefix(&final_dir).unwrap_or(game_wad).to_string_lossy().replace('\\', "/")
+}
+
+pub(crate) struct MountedWad {
+  mmap: Mmap,
+  chunks: HashMap<u64, ltk_wad::WadChunk>,
+}
+
+impl MountedWad {
+  pub(crate) fn read(&self, hash: u64) -> Option<Vec<u8>> {
+    decompress_chunk(&self.mmap[..], self.chunks.get(&hash)?).ok()
+  }
+}
+
+pub(crate) fn mount(path: &Path) -> Result<MountedWad, String> {
+  let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let chunks = chunks.into_iter().map(|c| (c.path_hash(), c)).collect();
+  Ok(MountedWad { mmap, chunks })
+}
+
+/// Current (checksum, size) of `hash` in the game WAD, or None if it's gone.
+fn game_checksum(wad: &MountedWad, hash: u64) -> Option<(String, u64)> {
+  let data = wad.read(hash)?;
+  Some((data_checksum(&data), data.len() as u64))
+}
+
+// ── checkProjectFreshness ────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct StaleAsset {
+  /// Project WAD folder, e.g. "Ahri.wad.client".
+  pub wad: String,
+  pub path: String,
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// "changed" or "removed" in the game.
+  pub change: String,
+  /// The project's copy: "unmodified" (still the recorded original), "modified"
+  /// or "deleted".
+  #[napi(js_name = "projectState")]
+  pub project_state: String,
+  #[napi(js_name = "recordedPatch")]
+  pub recorded_patch: Option<String>,
+  #[napi(js_name = "recordedSize")]
+  pub recorded_size: f64,
+  /// 0 when removed.
+  #[napi(js_name = "currentSize")]
+  pub current_size: f64,
+}
+
+#[napi(object)]
+pub struct ProjectFreshnessResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "currentPatch")]
+  pub current_patch: Option<String>,
+  /// Chunks with a recorded origin.
+  #[napi(js_name = "trackedCount")]
+  pub tracked_count: u32,
+  pub stale: Vec<StaleAsset>,
+  /// Recorded game WADs that no longer exist in the install.
+  #[napi(js_name = "missingWads")]
+  pub missing_wads: Vec<String>,
+}
+
+pub(crate) fn project_state(file: Option<&PathBuf>, origin: &ChunkOrigin) -> &'static str {
+  let Some(Ok(data)) = file.map(fs::read) else { return "deleted" };
+  let sum = data_checksum(&data);
+  if sum == origin.checksum || origin.synced.as_ref() == Some(&sum) { "unmodified" } else { "modified" }
+}
+
+pub(crate) fn check_freshness(project: &Path, league: &Path) -> Result<ProjectFreshnessResult, String> {
+  let origins = read_origins(project)?;
+  let final_dir = game_dir(league).join("DATA").join("FINAL");
+  let content_dirs: HashMap<String, PathBuf> = project_wad_dirs(project)
+    .into_iter()
+    .filter_map(|d| Some((d.file_name()?.to_string_lossy().into_owned(), d)))
+    .collect();
+
+  let mut stale = Vec::new();
+  let mut missing_wads = Vec::new();
+  let mut tracked = 0u32;
+  for (wad_name, origin) in &origins.wads {
+    tracked += origin.chunks.len() as u32;
+    let game_wad = final_dir.join(&origin.game_wad);
+    if !game_wad.is_file() {
+      missing_wads.push(origin.game_wad.clone());
+      continue;
+    }
+    let mounted = mount(&game_wad)?;
+    let project_files = match content_dirs.get(wad_name) {
+      Some(dir) => plan_wad_dir(dir)?.0,
+      None => HashMap::new(),
+    };
+    let entries: Vec<(&String, &ChunkOrigin)> = origin.chunks.iter().collect();
+    let found: Vec<StaleAsset> = run_io(|| {
+      entries
+        .par_iter()
+        .filter_map(|(hex, o)| {
+          let hash = parse_hash_hex(hex)?;
+          let (change, current_size) = match game_checksum(&mounted, hash) {
+            Some((sum, _)) if sum == o.checksum => return None,
+            Some((_, size)) => ("changed", size),
+            None => ("removed", 0),
+          };
+          Some(StaleAsset {
+            wad: wad_name.clone(),
+            path: o.path.clone(),
+            path_hash: (*hex).clone(),
+            change: change.to_string(),
+            project_state: project_state(project_file_for(&project_files, hash, o, &origins.repath), o).to_string(),
+            recorded_patch: o.patch.clone(),
+            recorded_size: o.size as f64,
+            current_size: current_size as f64,
+          })
+        })
+        .collect()
+    });
+    stale.extend(found);
+  }
+  stale.sort_by(|a, b| (&a.wad, &a.path).cmp(&(&b.wad, &b.path)));
+  Ok(ProjectFreshnessResult {
+    success: true,
+    error: None,
+    current_patch: detect_game_version(league).patch,
+    tracked_count: tracked,
+    stale,
+    missing_wads,
+  })
+}
+
+/// Compare the project's recorded origins with the installed game and list the
+/// assets changed or removed since they were extracted.
+#[napi(js_name = "checkProjectFreshness")]
+pub fn check_project_freshness(project_path: String, league_path: String) -> ProjectFreshnessResult {
+  check_freshness(Path::new(&project_path), Path::new(&league_path)).unwrap_or_else(|e| ProjectFreshnessResult {
+    success: false,
+    error: Some(e),
+    current_patch: None,
+    tracked_count: 0,
+    stale: Vec::new(),
+    missing_wads: Vec::new(),
+  })
+}
+
+// ── recordProjectOrigins ─────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct RecordOriginsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "recordedCount")]
+  pub recorded_count: u32,
+  /// Project files the game doesn't have (new assets); nothing to track.
+  #[napi(js_name = "newAssetCount")]
+  pub new_asset_count: u32,
+  /// Project WAD folders with no matching game WAD.
+  #[napi(js_name = "unmappedWads")]
+  pub unmapped_wads: Vec<String>,
+}
+
+fn record_baseline(project: &Path, league: &Path, hash_dir: Option<&str>) -> Result<RecordOriginsResult, String> {
+  let game_wads = walk_final_wads(league);
+  let by_name: HashMap<String, &PathBuf> = game_wads
+    .iter()
+    .filter_map(|(abs, _)| Some((abs.file_name()?.to_string_lossy().to_ascii_lowercase(), abs)))
+    .collect();
+  let patch = detect_game_version(league).patch;
+  let mut origins = read_origins(project)?;
+  let mut recorded = 0u32;
+  let mut new_assets = 0u32;
+  let mut unmapped = Vec::new();
+  let layers = HashLayers::open(hash_dir);
+
+  for dir in project_wad_dirs(project) {
+    let dir_name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let Some(game_wad) = by_name.get(&wad_file_name_for_dir(&dir_name).to_ascii_lowercase()).copied() else {
+      unmapped.push(dir_name);
+      continue;
+    };
+    let mounted = mount(game_wad)?;
+    let wad_origin = origins.wads.entry(dir_name).or_default();
+    wad_origin.game_wad = final_rel_path(league, game_wad);
+    let (files, _) = plan_wad_dir(&dir)?;
+    let untracked: Vec<u64> = files.keys().copied().filter(|h| !wad_origin.chunks.contains_key(&format!("{:016x}", h))).collect();
+    let names = layers.resolve_names(&untracked);
+    let sums: Vec<Option<(String, u64)>> = run_io(|| untracked.par_iter().map(|h| game_checksum(&mounted, *h)).collect());
+    for ((hash, name), sum) in untracked.iter().zip(names).zip(sums) {
+      let Some((checksum, size)) = sum else { new_assets += 1; continue };
+      let origin = ChunkOrigin { path: name, checksum, size, patch: patch.clone(), synced: None };
+      wad_origin.chunks.insert(format!("{:016x}", hash), origin);
+      recorded += 1;
+    }
+  }
+  write_origins(project, &origins)?;
+  Ok(RecordOriginsResult { success: true, error: None, recorded_count: recorded, new_asset_count: new_assets, unmapped_wads: unmapped })
+}
+
+/// Record the installed game's version of every project file that has no
+/// origin yet, so later patches can be checked against it. Files extracted by
+/// `createProject` are recorded automatically.
+#[napi(js_name = "recordProjectOrigins")]
+pub fn record_project_origins(project_path: String, league_path: String, hash_dir: Option<String>) -> RecordOriginsResult {
+  record_baseline(Path::new(&project_path), Path::new(&league_path), hash_dir.as_deref()).unwrap_or_else(|e| RecordOriginsResult {
+    success: false,
+    error: Some(e),
+    recorded_count: 0,
+    new_asset_count: 0,
+    unmapped_wads: Vec::new(),
+  })
+}
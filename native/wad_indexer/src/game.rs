@@ -0,0 +1,183 @@
+// ── Game install helpers ─────────────────────────────────────────────────────
+// Shared lookups for paths inside a League install. Callers may pass either the
+// install root ("League of Legends") or its "Game" folder.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use ltk_wad::Wad;
+use xxhash_rust::xxh64::xxh64;
+
+/// Resolve the "Game" folder from either the install root or the folder itself.
+pub(crate) fn game_dir(league_path: &Path) -> PathBuf {
+  let nested = league_path.join("Game");
+  if nested.join("DATA").is_dir() { nested } else { league_path.to_path_buf() }
+}
+
+/// Resolve the install root (parent of "Game") from either form of path.
+pub(crate) fn install_root(league_path: &Path) -> PathBuf {
+  let is_game_dir = league_path
+    .file_name()
+    .map(|n| n.to_string_lossy().eq_ignore_ascii_case("game"))
+    .unwrap_or(false);
+  match league_path.parent() {
+    Some(parent) if is_game_dir && league_path.join("DATA").is_dir() => parent.to_path_buf(),
+    _ => league_path.to_path_buf(),
+  }
+}
+
+pub(crate) fn champions_dir(league_path: &Path) -> PathBuf {
+  game_dir(league_path).join("DATA").join("FINAL").join("Champions")
+}
+
+/// Case-insensitive lookup of a file name inside `dir`.
+pub(crate) fn find_file_ci(dir: &Path, file_name: &str) -> Option<PathBuf> {
+  let exact = dir.join(file_name);
+  if exact.is_file() { return Some(exact); }
+  fs::read_dir(dir).ok()?
+    .filter_map(|e| e.ok())
+    .map(|e| e.path())
+    .find(|p| {
+      p.is_file() && p.file_name()
+        .map(|n| n.to_string_lossy().eq_ignore_ascii_case(file_name))
+        .unwrap_or(false)
+    })
+}
+
+/// Locate `Champions/{champion}.wad.client` for a champion alias (e.g. "Ahri", "MonkeyKing").
+pub(crate) fn find_champion_wad(league_path: &Path, champion: &str) -> Option<PathBuf> {
+  find_file_ci(&champions_dir(league_path), &format!("{}.wad.client", champion))
+}
+
+/// Locate the LCU game-data WAD (`default-assets.wad`, or `{locale}-assets.wad`).
+pub(crate) fn lcu_game_data_wad(league_path: &Path, locale: Option<&str>) -> Option<PathBuf> {
+  let file_name = match locale {
+    Some(l) if !l.is_empty() && !l.eq_ignore_ascii_case("default") => format!("{}-assets.wad", l),
+    _ => "default-assets.wad".to_string(),
+  };
+  let root = install_root(league_path);
+  [
+    root.join("Plugins").join("rcp-be-lol-game-data"),
+    root.join("LeagueClient").join("Plugins").join("rcp-be-lol-game-data"),
+  ]
+  .iter()
+  .find_map(|dir| find_file_ci(dir, &file_name))
+}
+
+/// LCU game-data asset path for `global/{locale}/v1/{file}`.
+pub(crate) fn lcu_game_data_path(locale: Option<&str>, file: &str) -> String {
+  let locale = locale.filter(|l| !l.is_empty()).unwrap_or("default").to_ascii_lowercase();
+  format!("plugins/rcp-be-lol-game-data/global/{}/v1/{}", locale, file)
+}
+
+pub(crate) fn wad_path_hash(path: &str) -> u64 {
+  xxh64(path.to_ascii_lowercase().as_bytes(), 0)
+}
+
+/// Read and decompress the chunks with the given path hashes from one WAD.
+/// Missing chunks (or chunks that fail to decompress) come back as `None`.
+pub(crate) fn read_wad_chunks(wad_path: &Path, hashes: &[u64]) -> Result<Vec<Option<Vec<u8>>>, String> {
+  let file = fs::File::open(wad_path)
+    .map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mut wad = Wad::mount(file)
+    .map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  Ok(hashes.iter().map(|h| {
+    let chunk = wad.chunks().get(*h).copied()?;
+    wad.load_chunk_decompressed(&chunk).ok().map(|d| d.into_vec())
+  }).collect())
+}
+
+/// Read a single chunk by its (unhashed) path.
+pub(crate) fn read_wad_chunk_by_path(wad_path: &Path, path: &str) -> Result<Option<Vec<u8>>, String> {
+  Ok(read_wad_chunks(wad_path, &[wad_path_hash(path)])?.pop().flatten())
+}
+
+/// Locale suffix of a WAD file name: "Ahri.en_US.wad.client" / "Map11_en_us.wad.client" -> "en_us".
+pub(crate) fn wad_locale(file_name: &str) -> Option<String> {
+  let lower = file_name.to_ascii_lowercase();
+  let stem = lower.strip_suffix(".wad.client").or_else(|| lower.strip_suffix(".wad"))?;
+  let bytes = stem.as_bytes();
+  if bytes.len() < 6 { return None; }
+  let (sep, b) = (bytes[bytes.len() - 6], &bytes[bytes.len() - 5..]);
+  let is_locale = b[2] == b'_'
+    && b[..2].iter().chain(&b[3..]).all(|c| c.is_ascii_lowercase())
+    && (sep == b'.' || sep == b'_');
+  is_locale.then(|| String::from_utf8_lossy(b).into_owned())
+}
+
+/// Which hash list names a WAD's chunks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum WadKind {
+  /// Game WADs (`DATA/FINAL/**.wad.client`): hashes.game.txt.
+  Game,
+  /// League client plugin WADs (`Plugins/rcp-*/**.wad`): hashes.lcu.txt.
+  Lcu,
+}
+
+impl WadKind {
+  pub(crate) fn as_str(self) -> &'static str {
+    match self {
+      WadKind::Game => "game",
+      WadKind::Lcu => "lcu",
+    }
+  }
+}
+
+/// WAD file names recognized without reading the file.
+pub(crate) fn is_wad_file_name(name: &str) -> bool {
+  let lower = name.to_ascii_lowercase();
+  lower.ends_with(".wad.client") || lower.ends_with(".wad.mobile") || lower.ends_with(".wad")
+}
+
+/// "RW" followed by a known major version (1-3).
+fn has_wad_magic(path: &Path) -> bool {
+  let mut header = [0u8; 3];
+  fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)).is_ok()
+    && &header[..2] == b"RW"
+    && (1..=3).contains(&header[2])
+}
+
+/// Classify a WAD by name and location; files with other names (legacy or
+/// renamed archives) are recognized by their header. `None` if not a WAD.
+pub(crate) fn wad_kind(path: &Path) -> Option<WadKind> {
+  let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+  if !is_wad_file_name(&name) && !has_wad_magic(path) { return None; }
+  if name.ends_with(".wad.client") || name.ends_with(".wad.mobile") { return Some(WadKind::Game); }
+  let in_plugins = path.components().any(|c| c.as_os_str().to_string_lossy().eq_ignore_ascii_case("plugins"));
+  Some(if in_plugins || name.ends_with("-assets.wad") { WadKind::Lcu } else { WadKind::Game })
+}
+
+/// WAD files below `root` (any depth), as (absolute path, "/"-separated path relative to `root`).
+fn walk_wads(root: &Path) -> Vec<(PathBuf, String)> {
+  let mut out = Vec::new();
+  let mut stack = vec![root.to_path_buf()];
+  while let Some(dir) = stack.pop() {
+    let Ok(entries) = fs::read_dir(&dir) else { continue };
+    for entry in entries.flatten() {
+      let p = entry.path();
+      if p.is_dir() { stack.push(p); continue; }
+      if wad_kind(&p).is_none() { continue; }
+      let rel = p.strip_prefix(root).map(|r| r.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+      out.push((p, rel));
+    }
+  }
+  out.sort_by(|a, b| a.1.cmp(&b.1));
+  out
+}
+
+/// All WAD files under Game/DATA/FINAL, as (absolute path, path relative to FINAL).
+pub(crate) fn walk_final_wads(league_path: &Path) -> Vec<(PathBuf, String)> {
+  walk_wads(&game_dir(league_path).join("DATA").join("FINAL"))
+}
+
+/// League client plugin WADs, as (absolute path, path relative to the install root).
+pub(crate) fn walk_lcu_wads(league_path: &Path) -> Vec<(PathBuf, String)> {
+  let root = install_root(league_path);
+  let mut out = Vec::new();
+  for plugins in [root.join("Plugins"), root.join("LeagueClient").join("Plugins")] {
+    let prefix = plugins.strip_prefix(&root).map(|r| r.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+    out.extend(walk_wads(&plugins).into_iter().map(|(p, rel)| (p, format!("{}/{}", prefix, rel))));
+  }
+  out
+}
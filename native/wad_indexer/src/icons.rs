@@ -0,0 +1,148 @@
+// ── Champion icons ───────────────────────────────────────────────────────────
+// Square portraits for the champion grid. The LCU game-data WAD already ships
+// them as PNG (`champion-icons/{id}.png`); installs without the client plugin
+// fall back to decoding `{champ}_square` from the champion WAD's HUD folder.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use ltk_texture::Texture;
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+
+use crate::game::{find_champion_wad, lcu_game_data_path, lcu_game_data_wad, read_wad_chunks, wad_path_hash};
+use crate::get_file_mtime_ms;
+use crate::skins::lcu_champion_id;
+
+struct CachedIcon {
+  source_mtime: u128,
+  png: Arc<Vec<u8>>,
+  width: u32,
+  height: u32,
+  source: String,
+}
+
+static ICON_CACHE: OnceLock<Mutex<HashMap<String, CachedIcon>>> = OnceLock::new();
+
+fn icon_cache() -> &'static Mutex<HashMap<String, CachedIcon>> {
+  ICON_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[napi(object)]
+pub struct ChampionIcon {
+  pub width: u32,
+  pub height: u32,
+  pub png: Buffer,
+  /// "lcu" when taken from the client game-data WAD, "game" when decoded from the champion WAD.
+  pub source: String,
+}
+
+pub(crate) fn png_dimensions(png: &[u8]) -> (u32, u32) {
+  // IHDR is always the first chunk: 8-byte signature, 4-byte length, "IHDR", width, height.
+  if png.len() < 24 || &png[12..16] != b"IHDR" { return (0, 0); }
+  let w = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+  let h = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+  (w, h)
+}
+
+pub(crate) fn encode_png(rgba: image::RgbaImage) -> Result<Vec<u8>, String> {
+  let mut out = Cursor::new(Vec::new());
+  image::DynamicImage::ImageRgba8(rgba)
+    .write_to(&mut out, image::ImageFormat::Png)
+    .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+  Ok(out.into_inner())
+}
+
+/// Decode TEX or DDS bytes (mip 0) into an RGBA image.
+pub(crate) fn decode_texture_bytes(data: &[u8]) -> Result<image::RgbaImage, String> {
+  let texture = Texture::from_reader(&mut Cursor::new(data))
+    .map_err(|e| format!("Failed to parse texture: {}", e))?;
+  texture
+    .decode_mipmap(0)
+    .map_err(|e| format!("Failed to decode mip0: {}", e))?
+    .into_rgba_image()
+    .map_err(|e| format!("Failed to convert texture: {}", e))
+}
+
+fn icon_from_lcu(league_path: &Path, champion: &str) -> Option<(Vec<u8>, u128)> {
+  let wad = lcu_game_data_wad(league_path, None)?;
+  let id = lcu_champion_id(league_path, champion)?;
+  let path = lcu_game_data_path(None, &format!("champion-icons/{}.png", id));
+  let png = read_wad_chunks(&wad, &[wad_path_hash(&path)]).ok()?.pop().flatten()?;
+  Some((png, get_file_mtime_ms(&wad)))
+}
+
+fn icon_from_game(league_path: &Path, champion: &str) -> Result<(Vec<u8>, u128), String> {
+  let wad = find_champion_wad(league_path, champion)
+    .ok_or_else(|| format!("Champion WAD not found for {}", champion))?;
+  let champ = champion.to_ascii_lowercase();
+  let candidates: Vec<u64> = ["tex", "dds"].iter()
+    .map(|ext| wad_path_hash(&format!("assets/characters/{0}/hud/{0}_square.{1}", champ, ext)))
+    .collect();
+  let data = read_wad_chunks(&wad, &candidates)?
+    .into_iter()
+    .flatten()
+    .next()
+    .ok_or_else(|| format!("No square icon found in {}", wad.display()))?;
+  let png = encode_png(decode_texture_bytes(&data)?)?;
+  Ok((png, get_file_mtime_ms(&wad)))
+}
+
+/// Square portrait of a champion as PNG bytes. Results are cached per install
+/// and invalidated when the source WAD changes on disk.
+#[napi(js_name = "getChampionIcon")]
+pub fn get_champion_icon(league_path: String, champion: String) -> napi::Result<ChampionIcon> {
+  let key = format!("{}|{}", league_path, champion).to_ascii_lowercase();
+  let root = Path::new(&league_path);
+
+  {
+    let cache = icon_cache().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(hit) = cache.get(&key) {
+      let current_mtime = match hit.source.as_str() {
+        "lcu" => lcu_game_data_wad(root, None).map(|p| get_file_mtime_ms(&p)),
+        _ => find_champion_wad(root, &champion).map(|p| get_file_mtime_ms(&p)),
+      };
+      if current_mtime == Some(hit.source_mtime) {
+        return Ok(ChampionIcon {
+          width: hit.width,
+          height: hit.height,
+          png: hit.png.as_ref().clone().into(),
+          source: hit.source.clone(),
+        });
+      }
+    }
+  }
+
+  let (png, source_mtime, source) = match icon_from_lcu(root, &champion) {
+    Some((png, mtime)) => (png, mtime, "lcu"),
+    None => {
+      let (png, mtime) = icon_from_game(root, &champion).map_err(napi::Error::from_reason)?;
+      (png, mtime, "game")
+    }
+  };
+  let (width, height) = png_dimensions(&png);
+  let png = Arc::new(png);
+
+  icon_cache().lock().unwrap_or_else(|e| e.into_inner()).insert(key, CachedIcon {
+    source_mtime,
+    png: Arc::clone(&png),
+    width,
+    height,
+    source: source.to_string(),
+  });
+
+  Ok(ChampionIcon {
+    width,
+    height,
+    png: png.as_ref().clone().into(),
+    source: source.to_string(),
+  })
+}
+
+/// Drop all cached champion icons.
+#[napi(js_name = "clearChampionIconCache")]
+pub fn clear_champion_icon_cache() {
+  icon_cache().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
@@ -0,0 +1,182 @@
+// ── In-game testing ──────────────────────────────────────────────────────────
+// Builds the project overlay and hands it to cslol's `mod-tools runoverlay`,
+// which patches the game on launch. Runtime output is streamed to JS line by
+// line so the app can show it while the user tests in game. The build runs on
+// the session thread too, so a large project never blocks the JS thread.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+
+use crate::game::game_dir;
+use crate::overlay::build_overlay_dir;
+
+/// A test session's mod-tools process; `None` while the overlay is still building.
+type Session = Arc<Mutex<Option<Child>>>;
+
+static NEXT_SESSION_ID: AtomicU32 = AtomicU32::new(1);
+static SESSIONS: OnceLock<Mutex<HashMap<u32, Session>>> = OnceLock::new();
+
+fn sessions() -> &'static Mutex<HashMap<u32, Session>> {
+  SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[napi(object)]
+pub struct TestInGameOptions {
+  /// Path to cslol's `mod-tools(.exe)`.
+  #[napi(js_name = "modToolsPath")]
+  pub mod_tools_path: String,
+  /// Where the overlay is built. Defaults to `{project}/.quartz/overlay`.
+  #[napi(js_name = "overlayDir")]
+  pub overlay_dir: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct TestLogEvent {
+  /// "build", "error", "stdout", "stderr" or "exit".
+  pub kind: String,
+  pub line: String,
+  #[napi(js_name = "exitCode")]
+  pub exit_code: Option<i32>,
+}
+
+fn log_event(kind: &str, line: String) -> TestLogEvent {
+  TestLogEvent { kind: kind.to_string(), line, exit_code: None }
+}
+
+fn pipe_lines<R: Read + Send + 'static>(
+  reader: R,
+  kind: &'static str,
+  tsfn: ThreadsafeFunction<TestLogEvent, ErrorStrategy::Fatal>,
+) -> thread::JoinHandle<()> {
+  thread::spawn(move || {
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+      tsfn.call(log_event(kind, line), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+  })
+}
+
+/// Build the overlay, start mod-tools on it and report its output until it
+/// exits. Returns early, with no exit code, if the session is stopped or fails.
+fn run_session(
+  id: u32,
+  project: &Path,
+  league: &Path,
+  overlay_dir: &Path,
+  mod_tools: &Path,
+  tsfn: &ThreadsafeFunction<TestLogEvent, ErrorStrategy::Fatal>,
+) -> Result<Option<i32>, String> {
+  tsfn.call(log_event("build", "Building overlay".to_string()), ThreadsafeFunctionCallMode::NonBlocking);
+  let built = build_overlay_dir(project, overlay_dir, league)?;
+  for wad in &built.wads {
+    tsfn.call(
+      log_event("build", format!("{} ({} replaced, {} added)", wad.game_wad, wad.overridden_count, wad.added_count)),
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  }
+  for path in &built.unmapped {
+    tsfn.call(log_event("build", format!("Skipped unmapped file: {}", path)), ThreadsafeFunctionCallMode::NonBlocking);
+  }
+
+  // Hold the session table while starting so a concurrent stop can't miss the process.
+  let (session, stdout, stderr) = {
+    let sessions = sessions().lock().unwrap_or_else(|e| e.into_inner());
+    let Some(session) = sessions.get(&id).cloned() else { return Ok(None) };
+    let mut child = Command::new(mod_tools)
+      .arg("runoverlay")
+      .arg(overlay_dir)
+      .arg(overlay_dir.join("cslol-config.txt"))
+      .arg(format!("--game:{}", game_dir(league).display()))
+      .arg("--opts:none")
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .map_err(|e| format!("Failed to start mod-tools: {}", e))?;
+    let stdout = child.stdout.take().map(|r| pipe_lines(r, "stdout", tsfn.clone()));
+    let stderr = child.stderr.take().map(|r| pipe_lines(r, "stderr", tsfn.clone()));
+    *session.lock().unwrap_or_else(|e| e.into_inner()) = Some(child);
+    (session, stdout, stderr)
+  };
+
+  // Reap the process once its output closes.
+  for handle in [stdout, stderr].into_iter().flatten() {
+    let _ = handle.join();
+  }
+  // Poll instead of wait() so stopInGameTest can take the lock to kill it.
+  loop {
+    let status = match session.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+      Some(child) => child.try_wait(),
+      None => return Ok(None),
+    };
+    match status {
+      Ok(Some(status)) => return Ok(status.code()),
+      Ok(None) => {}
+      Err(_) => return Ok(None),
+    }
+    thread::sleep(Duration::from_millis(100));
+  }
+}
+
+/// Build the project overlay and run it through mod-tools against the game.
+/// The build and the run happen in the background: `callback` receives
+/// `TestLogEvent`s for build progress, failures, output and the final exit.
+/// Returns a session id for `stopInGameTest`.
+#[napi(js_name = "testInGame")]
+pub fn test_in_game(
+  env: Env,
+  project_path: String,
+  league_path: String,
+  options: TestInGameOptions,
+  callback: JsFunction,
+) -> napi::Result<u32> {
+  let mod_tools = PathBuf::from(&options.mod_tools_path);
+  if !mod_tools.is_file() {
+    return Err(napi::Error::from_reason(format!("mod-tools not found: {}", options.mod_tools_path)));
+  }
+  let project = PathBuf::from(&project_path);
+  let league = PathBuf::from(&league_path);
+  let overlay_dir = options.overlay_dir
+    .map(PathBuf::from)
+    .unwrap_or_else(|| project.join(".quartz").join("overlay"));
+
+  let mut tsfn: ThreadsafeFunction<TestLogEvent, ErrorStrategy::Fatal> = callback
+    .create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+  tsfn.unref(&env)?;
+
+  let id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+  sessions().lock().unwrap_or_else(|e| e.into_inner()).insert(id, Arc::new(Mutex::new(None)));
+
+  thread::spawn(move || {
+    let code = run_session(id, &project, &league, &overlay_dir, &mod_tools, &tsfn).unwrap_or_else(|e| {
+      tsfn.call(log_event("error", e), ThreadsafeFunctionCallMode::NonBlocking);
+      None
+    });
+    sessions().lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+    tsfn.call(
+      TestLogEvent { kind: "exit".to_string(), line: String::new(), exit_code: code },
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  });
+
+  Ok(id)
+}
+
+/// Stop a running `testInGame` session. Returns false for unknown ids.
+#[napi(js_name = "stopInGameTest")]
+pub fn stop_in_game_test(id: u32) -> bool {
+  let Some(session) = sessions().lock().unwrap_or_else(|e| e.into_inner()).remove(&id) else { return false };
+  let mut session = session.lock().unwrap_or_else(|e| e.into_inner());
+  // Still building: removing the session keeps mod-tools from being started.
+  session.as_mut().is_none_or(|child| child.kill().is_ok())
+}
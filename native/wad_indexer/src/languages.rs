@@ -0,0 +1,52 @@
+// ── Language WADs ────────────────────────────────────────────────────────────
+// Voice-over and localized assets live in `*.{locale}.wad.client` archives.
+// VO mods need to target them explicitly, and other mods should warn when they
+// touch them (the edit only applies to users of that locale).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::game::{walk_final_wads, wad_locale};
+
+#[napi(object)]
+pub struct LanguageWad {
+  pub name: String,
+  pub path: String,
+  #[napi(js_name = "relPath")]
+  pub rel_path: String,
+  pub size: f64,
+}
+
+#[napi(object)]
+pub struct LocaleWadGroup {
+  pub locale: String,
+  pub wads: Vec<LanguageWad>,
+}
+
+/// Enumerate language-specific WADs under Game/DATA/FINAL grouped by locale.
+#[napi(js_name = "listLanguageWads")]
+pub fn list_language_wads(game_path: String) -> Vec<LocaleWadGroup> {
+  let mut groups: BTreeMap<String, Vec<LanguageWad>> = BTreeMap::new();
+  for (path, rel_path) in walk_final_wads(Path::new(&game_path)) {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let Some(locale) = wad_locale(&name) else { continue };
+    let size = fs::metadata(&path).map(|m| m.len() as f64).unwrap_or(0.0);
+    groups.entry(locale).or_default().push(LanguageWad {
+      name,
+      path: path.to_string_lossy().into_owned(),
+      rel_path,
+      size,
+    });
+  }
+  groups.into_iter().map(|(locale, wads)| LocaleWadGroup { locale, wads }).collect()
+}
+
+/// Locale of a WAD file name or path, or null when it is not language-specific.
+#[napi(js_name = "getWadLocale")]
+pub fn get_wad_locale(wad_path: String) -> Option<String> {
+  let name = Path::new(&wad_path).file_name()?.to_string_lossy().into_owned();
+  wad_locale(&name)
+}
@@ -0,0 +1,221 @@
+// ── league-mod interop ───────────────────────────────────────────────────────
+// LeagueToolkit's `league-mod` CLI describes a project with `mod.config.toml`
+// (or `.json`) and keeps WAD folders in layers: `content/{layer}/{Name}.wad.client`.
+// Quartz keeps a single layer directly under `content/`. These commands convert
+// between the two so a folder can be used by either tool.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::{rename_retrying, retry_on_lock};
+use crate::project::{read_project, write_project, Project, PROJECT_JSON, PROJECT_SCHEMA_VERSION};
+use crate::wad_build::{collect_files, project_wad_dirs};
+
+const LEAGUE_MOD_TOML: &str = "mod.config.toml";
+const LEAGUE_MOD_JSON: &str = "mod.config.json";
+const BASE_LAYER: &str = "base";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LeagueModAuthor {
+  Name(String),
+  Role { name: String, #[serde(default, skip_serializing_if = "Option::is_none")] role: Option<String> },
+}
+
+impl LeagueModAuthor {
+  fn name(&self) -> &str {
+    match self {
+      LeagueModAuthor::Name(n) => n,
+      LeagueModAuthor::Role { name, .. } => name,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LeagueModLayer {
+  name: String,
+  priority: i32,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LeagueModConfig {
+  name: String,
+  #[serde(default)]
+  display_name: String,
+  version: String,
+  #[serde(default)]
+  description: String,
+  #[serde(default)]
+  authors: Vec<LeagueModAuthor>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  license: Option<String>,
+  #[serde(default)]
+  layers: Vec<LeagueModLayer>,
+}
+
+#[napi(object)]
+pub struct LeagueModInteropResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// WAD folders moved or copied between layouts.
+  #[napi(js_name = "wadCount")]
+  pub wad_count: u32,
+  /// Non-base layers that were left untouched (Quartz projects only have one layer).
+  #[napi(js_name = "skippedLayers")]
+  pub skipped_layers: Vec<String>,
+}
+
+/// league-mod names are lowercase slugs: "My Cool Mod!" -> "my-cool-mod".
+fn slugify(name: &str) -> String {
+  let mut out = String::new();
+  for c in name.trim().chars() {
+    if c.is_ascii_alphanumeric() {
+      out.push(c.to_ascii_lowercase());
+    } else if !out.ends_with('-') && !out.is_empty() {
+      out.push('-');
+    }
+  }
+  let out = out.trim_end_matches('-').to_string();
+  if out.is_empty() { "mod".to_string() } else { out }
+}
+
+fn read_league_mod_config(dir: &Path) -> Result<LeagueModConfig, String> {
+  let toml_path = dir.join(LEAGUE_MOD_TOML);
+  if toml_path.is_file() {
+    let text = fs::read_to_string(&toml_path).map_err(|e| format!("Failed to read {}: {}", toml_path.display(), e))?;
+    return toml::from_str(&text).map_err(|e| format!("Invalid {}: {}", LEAGUE_MOD_TOML, e));
+  }
+  let json_path = dir.join(LEAGUE_MOD_JSON);
+  let text = fs::read_to_string(&json_path)
+    .map_err(|_| format!("No {} or {} found in {}", LEAGUE_MOD_TOML, LEAGUE_MOD_JSON, dir.display()))?;
+  serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", LEAGUE_MOD_JSON, e))
+}
+
+fn move_or_copy_dir(src: &Path, dst: &Path, copy: bool) -> Result<(), String> {
+  if dst.exists() {
+    return Err(format!("{} already exists", dst.display()));
+  }
+  if let Some(parent) = dst.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  if !copy && rename_retrying(src, dst).is_ok() { return Ok(()); }
+  for (_, path) in collect_files(src)? {
+    let Ok(rel) = path.strip_prefix(src) else { continue };
+    let out = dst.join(rel);
+    if let Some(parent) = out.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    retry_on_lock(|| fs::copy(&path, &out)).map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+  }
+  if !copy { let _ = fs::remove_dir_all(src); }
+  Ok(())
+}
+
+fn import_league_mod(dir: &Path) -> Result<LeagueModInteropResult, String> {
+  let config = read_league_mod_config(dir)?;
+  let content = dir.join("content");
+  let base = content.join(BASE_LAYER);
+
+  let mut wad_count = 0u32;
+  if base.is_dir() {
+    let entries = fs::read_dir(&base).map_err(|e| format!("Failed to read {}: {}", base.display(), e))?;
+    for entry in entries.flatten() {
+      let p = entry.path();
+      if !p.is_dir() { continue; }
+      move_or_copy_dir(&p, &content.join(entry.file_name()), false)?;
+      wad_count += 1;
+    }
+    let _ = fs::remove_dir(&base);
+  }
+  let skipped_layers = config.layers.iter()
+    .map(|l| l.name.clone())
+    .filter(|n| !n.eq_ignore_ascii_case(BASE_LAYER) && content.join(n).is_dir())
+    .collect();
+
+  let display = if config.display_name.trim().is_empty() { config.name.clone() } else { config.display_name.clone() };
+  let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+  let mut project = read_project(dir).map(|(p, _)| p).unwrap_or(Project {
+    schema_version: PROJECT_SCHEMA_VERSION,
+    name: String::new(),
+    creator: String::new(),
+    version: String::new(),
+    description: None,
+    template: None,
+    champion: None,
+    skin_id: None,
+    target_patch: None,
+    created,
+  });
+  project.name = display;
+  project.version = config.version.clone();
+  project.creator = config.authors.iter().map(|a| a.name()).collect::<Vec<_>>().join(", ");
+  project.description = Some(config.description.clone()).filter(|d| !d.is_empty());
+  write_project(dir, &project)?;
+
+  Ok(LeagueModInteropResult { success: true, error: None, wad_count, skipped_layers })
+}
+
+fn export_league_mod(project_dir: &Path, out_dir: Option<&Path>) -> Result<LeagueModInteropResult, String> {
+  let (project, _) = read_project(project_dir)?;
+  let target = out_dir.map(Path::to_path_buf).unwrap_or_else(|| project_dir.to_path_buf());
+  let in_place = target == project_dir;
+  let base = target.join("content").join(BASE_LAYER);
+
+  let wad_dirs: Vec<PathBuf> = project_wad_dirs(project_dir);
+  for dir in &wad_dirs {
+    let Some(name) = dir.file_name() else { continue };
+    move_or_copy_dir(dir, &base.join(name), !in_place)?;
+  }
+
+  let authors = project.creator
+    .split(',')
+    .map(str::trim)
+    .filter(|a| !a.is_empty())
+    .map(|a| LeagueModAuthor::Name(a.to_string()))
+    .collect();
+  let config = LeagueModConfig {
+    name: slugify(&project.name),
+    display_name: project.name.clone(),
+    version: project.version.clone(),
+    description: project.description.clone().unwrap_or_default(),
+    authors,
+    license: None,
+    layers: vec![LeagueModLayer {
+      name: BASE_LAYER.to_string(),
+      priority: 0,
+      description: Some("Base layer of the mod".to_string()),
+    }],
+  };
+  let text = toml::to_string_pretty(&config).map_err(|e| format!("Failed to serialize {}: {}", LEAGUE_MOD_TOML, e))?;
+  fs::create_dir_all(&target).map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+  fs::write(target.join(LEAGUE_MOD_TOML), text).map_err(|e| format!("Failed to write {}: {}", LEAGUE_MOD_TOML, e))?;
+  if !in_place {
+    // Keep Quartz metadata alongside so the export can be opened again without loss.
+    let _ = fs::copy(project_dir.join(PROJECT_JSON), target.join(PROJECT_JSON));
+  }
+  Ok(LeagueModInteropResult { success: true, error: None, wad_count: wad_dirs.len() as u32, skipped_layers: Vec::new() })
+}
+
+fn interop_error(e: String) -> LeagueModInteropResult {
+  LeagueModInteropResult { success: false, error: Some(e), wad_count: 0, skipped_layers: Vec::new() }
+}
+
+/// Convert a league-mod project (mod.config.toml/json, `content/base/...`) into a
+/// Quartz project in place: WAD folders move up to `content/` and project.json is written.
+#[napi(js_name = "importLeagueModProject")]
+pub fn import_league_mod_project(project_path: String) -> LeagueModInteropResult {
+  import_league_mod(Path::new(&project_path)).unwrap_or_else(interop_error)
+}
+
+/// Write a league-mod project from a Quartz project. With `outDir` the content is
+/// copied there; without it the project is converted in place.
+#[napi(js_name = "exportLeagueModProject")]
+pub fn export_league_mod_project(project_path: String, out_dir: Option<String>) -> LeagueModInteropResult {
+  export_league_mod(Path::new(&project_path), out_dir.as_deref().map(Path::new)).unwrap_or_else(interop_error)
+}
@@ -1,53 +1,136 @@
+pub mod anim_retarget;
+pub mod archive;
+pub mod asset_graph;
+pub mod asset_suggest;
+pub mod autosave;
+pub mod backup;
+pub mod benchmark;
+pub mod bin_refs;
+#[cfg(feature = "bin-search")]
+pub mod bin_search;
+pub mod bin_stats;
+pub mod bin_transform;
+mod chunk_decode;
+pub mod checkpoints;
+pub mod chunk_read;
+pub mod commands;
+pub mod conflicts;
+pub mod downloader;
+mod disk_space;
+pub mod edit_journal;
+pub mod extract_stream;
+pub mod fantome;
+pub mod freshness;
+mod game;
+pub mod icons;
+pub mod ingame;
+pub mod languages;
+pub mod league_mod;
+pub mod lint;
+pub mod log_file;
+pub mod logging;
+pub mod mod_import;
+mod output_template;
+pub mod overlay;
+pub mod overlay_mount;
+pub mod path_index;
+mod paths;
+pub mod port;
+pub mod preferences;
+pub mod presets;
+pub mod project;
+pub mod project_search;
+pub mod recent_projects;
+pub mod remote_wad;
+pub mod ritobin_outline;
+mod resume;
+pub mod rman;
+pub mod scripting;
+pub mod session;
+pub mod signing;
+pub mod skins;
+pub mod stringtable;
+pub mod temp_files;
+pub mod texture_report;
+pub mod threads;
+pub mod version;
+pub mod vo_index;
+pub mod wad_bin_edit;
+pub mod wad_build;
+pub mod wad_compression;
+mod wad_delta;
+pub mod wad_merge;
+pub mod wad_patch;
+pub mod wad_reproducible;
+pub mod wad_stats;
+pub mod wad_tree;
+pub mod watcher;
+pub mod wwise;
+
 use napi_derive::napi;
 use rayon::prelude::*;
+use std::borrow::Cow;
 use std::fs;
-use std::io::{Write, Cursor, Read};
+use std::io::Cursor;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::UNIX_EPOCH;
-use ltk_wad::Wad;
+use ltk_wad::{Wad, WadChunk, WadChunkCompression, WadChunks, WadError};
 use ltk_file::LeagueFileKind;
-use xxhash_rust::xxh64::xxh64;
 use napi::{Env, Task, bindgen_prelude::{AsyncTask, Buffer}};
-use heed::{Database, EnvOpenOptions};
-use heed::types::{Bytes, Str};
 use memmap2::Mmap;
+use chunk_decode::{decompress_chunk, diagnose_chunk_failure};
+use disk_space::{check_space, InsufficientSpace};
+use output_template::OutputTemplate;
+use game::{wad_kind, WadKind};
+use threads::{reserve_memory, run_cpu, run_io};
+use resume::ResumeTracker;
+use paths::{commit_staging, long_path, merge_hashed_files_sidecar, same_contents, sanitize_rel_path, staging_dir, write_retrying, IfExists};
+use tracing::{info, info_span, warn};
+use quartz_core::hash::{fnv1a_lower, parse_hash_hex, parse_hash_text_file, parse_hash_value, xxhash_path};
+use quartz_core::paths::{is_safe_relative_path, normalize_rel_path};
+use quartz_core::hash_db;
+use quartz_core::resolver::{resolve_each, HashResolver, HashSource, LayeredResolver, LmdbResolver, Resolved, CUSTOM_HASH_FILE};
+use quartz_core::scan::{dds_scaled_variants, scan_bin_asset_paths, scan_bin_game_hashes, scan_skn_bin_hashes};
 
 // ── Global LMDB env cache ───────────────────────────────────────────────────
 // Opened once per hash dir, reused for all reads.
 // OS memory-maps the file — only physically pages in what's actually touched.
-static LMDB_CACHE: OnceLock<Mutex<Option<(String, Arc<heed::Env>)>>> = OnceLock::new();
-static EXTRACTED_HASH_CACHE: OnceLock<Mutex<Option<(String, u128, Arc<HashMap<u64, String>>)>>> = OnceLock::new();
+// While cached, the DB holds its shared lock, so other processes wait for it
+// (or `closeAll`) before rebuilding or compacting; see `hash_db`.
+type LmdbCacheEntry = Option<(String, Arc<hash_db::HashDb>)>;
+type ExtractedHashCacheEntry = Option<(String, u128, Arc<HashMap<u64, String>>)>;
+
+static LMDB_CACHE: OnceLock<Mutex<LmdbCacheEntry>> = OnceLock::new();
+static EXTRACTED_HASH_CACHE: OnceLock<Mutex<ExtractedHashCacheEntry>> = OnceLock::new();
+static LCU_HASH_CACHE: OnceLock<Mutex<ExtractedHashCacheEntry>> = OnceLock::new();
+static CUSTOM_HASH_CACHE: OnceLock<Mutex<ExtractedHashCacheEntry>> = OnceLock::new();
 
-fn lmdb_mutex() -> &'static Mutex<Option<(String, Arc<heed::Env>)>> {
+fn lmdb_mutex() -> &'static Mutex<LmdbCacheEntry> {
   LMDB_CACHE.get_or_init(|| Mutex::new(None))
 }
 
-fn extracted_hash_mutex() -> &'static Mutex<Option<(String, u128, Arc<HashMap<u64, String>>)>> {
+fn extracted_hash_mutex() -> &'static Mutex<ExtractedHashCacheEntry> {
   EXTRACTED_HASH_CACHE.get_or_init(|| Mutex::new(None))
 }
 
-fn get_or_open_env(hash_dir: &str) -> Option<Arc<heed::Env>> {
-  let lmdb_dir = Path::new(hash_dir).join("hashes.lmdb");
-  if !lmdb_dir.exists() { return None; }
-  let key = lmdb_dir.to_string_lossy().into_owned();
+fn get_or_open_env(hash_dir: &str) -> Option<Arc<hash_db::HashDb>> {
+  let key = hash_db::lmdb_dir(Path::new(hash_dir)).to_string_lossy().into_owned();
 
   let mut g = lmdb_mutex().lock().unwrap_or_else(|e| e.into_inner());
   if let Some((ref k, ref env)) = *g {
     if *k == key { return Some(Arc::clone(env)); }
   }
 
-  let env = match unsafe {
-    EnvOpenOptions::new()
-      .map_size(512 * 1024 * 1024) // 512MB virtual — OS pages in only accessed data
-      .max_dbs(1)
-      .open(&lmdb_dir)
-  } {
-    Ok(e) => e,
-    Err(_) => return None,
+  let db = match hash_db::open(Path::new(hash_dir)) {
+    Ok(db) => db?,
+    Err(e) => {
+      warn!(hash_dir = %hash_dir, error = %e, "Failed to open hash DB");
+      return None;
+    }
   };
-  let arc = Arc::new(env);
+  let arc = Arc::new(db);
   *g = Some((key, Arc::clone(&arc)));
   Some(arc)
 }
@@ -66,57 +149,190 @@ fn get_file_mtime_ms(path: &Path) -> u128 {
     .unwrap_or(0)
 }
 
-fn parse_hash_text_file(path: &Path, hash_len: usize) -> HashMap<u64, String> {
-  let mut out = HashMap::new();
-  let Ok(content) = fs::read_to_string(path) else {
-    return out;
-  };
-  for line in content.lines() {
-    let l = line.trim();
-    if l.is_empty() || l.starts_with('#') || l.len() <= hash_len + 1 {
-      continue;
-    }
-    let h = &l[..hash_len];
-    let p = l[hash_len + 1..].trim();
-    if let Ok(v) = u64::from_str_radix(h, 16) {
-      out.entry(v).or_insert_with(|| p.to_string());
-    }
-  }
-  out
-}
+/// Parse a hash text file once and reuse it until its mtime changes.
+fn get_or_load_hash_file(cache: &Mutex<ExtractedHashCacheEntry>, path: &Path) -> Arc<HashMap<u64, String>> {
+  let mtime_ms = get_file_mtime_ms(path);
+  let key = path.to_string_lossy().into_owned();
 
-fn get_or_load_extracted_hashes(hash_dir: &str) -> Arc<HashMap<u64, String>> {
-  let extracted_path = Path::new(hash_dir).join("hashes.extracted.txt");
-  let mtime_ms = get_file_mtime_ms(&extracted_path);
-  let key = extracted_path.to_string_lossy().into_owned();
-
-  let mut g = extracted_hash_mutex().lock().unwrap_or_else(|e| e.into_inner());
+  let mut g = cache.lock().unwrap_or_else(|e| e.into_inner());
   if let Some((ref cached_key, cached_mtime, ref cached_map)) = *g {
     if *cached_key == key && cached_mtime == mtime_ms {
       return Arc::clone(cached_map);
     }
   }
 
-  let map = Arc::new(parse_hash_text_file(&extracted_path, 16));
+  let map = Arc::new(parse_hash_text_file(path, 16));
   *g = Some((key, mtime_ms, Arc::clone(&map)));
   map
 }
 
-fn resolve_hashes_with_overlay(
-  hashes: &[u64],
-  env_opt: Option<&heed::Env>,
-  extracted: &HashMap<u64, String>,
-) -> Vec<String> {
-  let mut base = match env_opt {
-    Some(env) => resolve_hashes_lmdb(hashes, env),
-    None => hashes.iter().map(|h| format!("{:016x}", h)).collect(),
-  };
-  for (idx, h) in hashes.iter().enumerate() {
-    if let Some(v) = extracted.get(h) {
-      base[idx] = v.clone();
+fn get_or_load_extracted_hashes(hash_dir: &str) -> Arc<HashMap<u64, String>> {
+  get_or_load_hash_file(extracted_hash_mutex(), &Path::new(hash_dir).join(hash_db::EXTRACTED_HASH_FILE))
+}
+
+/// hashes.lcu.txt on its own, for when the LMDB has no `lcu` table yet. The
+/// combined view prefers game names, so LCU WADs must not resolve from it.
+fn get_or_load_lcu_hashes(hash_dir: &str) -> Arc<HashMap<u64, String>> {
+  let cache = LCU_HASH_CACHE.get_or_init(|| Mutex::new(None));
+  get_or_load_hash_file(cache, &Path::new(hash_dir).join(hash_db::LCU_HASH_FILE))
+}
+
+/// hashes.custom.txt: names the user added by hand, above every other source.
+fn get_or_load_custom_hashes(hash_dir: &str) -> Arc<HashMap<u64, String>> {
+  let cache = CUSTOM_HASH_CACHE.get_or_init(|| Mutex::new(None));
+  get_or_load_hash_file(cache, &Path::new(hash_dir).join(CUSTOM_HASH_FILE))
+}
+
+// ── Hash generations ─────────────────────────────────────────────────────────
+// Names for a hash dir change when a list is rebuilt into the LMDB or a text
+// layer is rewritten (extractHashesFromWad). Each change a resolve notices
+// bumps the dir's generation, so callers holding resolved names can tell they
+// are stale by comparing generations.
+
+/// mtimes of the LMDB fingerprint and the text layers of a hash dir.
+type SourceStamp = [u128; 4];
+
+static HASH_GENERATIONS: OnceLock<Mutex<HashMap<String, (SourceStamp, u32)>>> = OnceLock::new();
+
+fn source_stamp(hash_dir: &Path) -> SourceStamp {
+  [
+    get_file_mtime_ms(&hash_db::lmdb_dir(hash_dir).join(hash_db::FINGERPRINT_FILE)),
+    get_file_mtime_ms(&hash_dir.join(CUSTOM_HASH_FILE)),
+    get_file_mtime_ms(&hash_dir.join(hash_db::EXTRACTED_HASH_FILE)),
+    get_file_mtime_ms(&hash_dir.join(hash_db::LCU_HASH_FILE)),
+  ]
+}
+
+/// Current generation of a hash dir's names, starting at 1. Read before the
+/// sources are, so a change racing a resolve bumps it again on the next call
+/// rather than being missed.
+fn hash_generation(hash_dir: &str) -> u32 {
+  let stamp = source_stamp(Path::new(hash_dir));
+  let mut g = HASH_GENERATIONS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap_or_else(|e| e.into_inner());
+  let entry = g.entry(hash_dir.to_string()).or_insert((stamp, 1));
+  if entry.0 != stamp {
+    *entry = (stamp, entry.1 + 1);
+  }
+  entry.1
+}
+
+/// Generation of the names `hashDir` resolves to; it goes up whenever a hash
+/// list changed since the last resolve. Compare with the `generation` of
+/// earlier results to know when to re-resolve.
+#[napi(js_name = "getHashGeneration")]
+pub fn get_hash_generation(hash_dir: String) -> u32 {
+  hash_generation(&hash_dir)
+}
+
+/// The path-hash sources of one hash dir, resolved in the shared order:
+/// hashes.custom.txt, the LMDB, hashes.extracted.txt, then hex. Every source
+/// is pinned when opened (the text layers are immutable maps, the LMDB is read
+/// through one transaction), so all lookups through one `HashLayers` agree even
+/// while the hash lists are being updated.
+pub(crate) struct HashLayers {
+  hash_dir: Option<String>,
+  generation: u32,
+  custom: Arc<HashMap<u64, String>>,
+  snapshot: Option<heed::RoTxn<'static>>,
+  env: Option<Arc<hash_db::HashDb>>,
+  extracted: Arc<HashMap<u64, String>>,
+}
+
+impl HashLayers {
+  pub(crate) fn open(hash_dir: Option<&str>) -> Self {
+    let generation = hash_dir.map(hash_generation).unwrap_or(0);
+    let env = hash_dir.and_then(get_or_open_env);
+    HashLayers {
+      hash_dir: hash_dir.map(str::to_string),
+      generation,
+      custom: hash_dir.map(get_or_load_custom_hashes).unwrap_or_default(),
+      snapshot: env.as_deref().and_then(|db| db.snapshot().ok()),
+      env,
+      extracted: hash_dir.map(get_or_load_extracted_hashes).unwrap_or_default(),
+    }
+  }
+
+  /// One table of the pinned LMDB, or the combined view for `None`.
+  fn lmdb(&self, table: Option<&'static str>) -> Option<LmdbResolver<'_>> {
+    let env = self.env.as_deref()?;
+    let resolver = match table {
+      Some(name) => LmdbResolver::table(env, name)?,
+      None => LmdbResolver::new(env),
+    };
+    Some(match &self.snapshot {
+      Some(rtxn) => resolver.in_snapshot(rtxn),
+      None => resolver,
+    })
+  }
+
+  pub(crate) fn generation(&self) -> u32 {
+    self.generation
+  }
+
+  /// Names for `hashes` from one source on its own; `None` where it has none.
+  /// Game and LCU names come from their LMDB tables, LCU falling back to
+  /// hashes.lcu.txt when the DB predates the table; extracted names come from
+  /// hashes.extracted.txt, which is never behind its table.
+  fn lookup_source(&self, source: HashSource, hashes: &[u64]) -> Vec<Option<String>> {
+    let table = |name| self.lmdb(Some(name));
+    match source {
+      HashSource::Custom => self.custom.lookup(hashes),
+      HashSource::Extracted => self.extracted.lookup(hashes),
+      HashSource::Game => table(hash_db::GAME_TABLE).map(|t| t.lookup(hashes)).unwrap_or_else(|| vec![None; hashes.len()]),
+      HashSource::Lcu => match table(hash_db::LCU_TABLE) {
+        Some(t) => t.lookup(hashes),
+        None => match &self.hash_dir {
+          Some(dir) => get_or_load_lcu_hashes(dir).lookup(hashes),
+          None => vec![None; hashes.len()],
+        },
+      },
+      _ => vec![None; hashes.len()],
     }
   }
-  base
+
+  /// Resolve from `sources` only, highest precedence first. Every source is
+  /// asked about every hash, so names the others disagree on come back too.
+  pub(crate) fn resolve_from(&self, sources: &[HashSource], hashes: &[u64]) -> Vec<(Resolved, Vec<Resolved>)> {
+    let answers: Vec<(HashSource, Vec<Option<String>>)> = sources.iter().map(|s| (*s, self.lookup_source(*s, hashes))).collect();
+    resolve_each(hashes, &answers)
+  }
+
+  pub(crate) fn has_lmdb(&self) -> bool {
+    self.env.is_some()
+  }
+
+  pub(crate) fn resolve(&self, hashes: &[u64]) -> Vec<Resolved> {
+    let lmdb = self.lmdb(None);
+    let mut layers = LayeredResolver::new().layer(HashSource::Custom, &*self.custom);
+    if let Some(lmdb) = &lmdb { layers = layers.layer(HashSource::Lmdb, lmdb); }
+    layers.layer(HashSource::Table, &*self.extracted).resolve(hashes)
+  }
+
+  pub(crate) fn resolve_names(&self, hashes: &[u64]) -> Vec<String> {
+    self.resolve(hashes).into_iter().map(|r| r.name).collect()
+  }
+}
+
+/// Resolve a WAD's chunk hashes from the hash list matching its kind. LCU
+/// WADs use the LMDB `lcu` table (or hashes.lcu.txt) in place of the combined
+/// view, falling back to the combined view when neither exists.
+fn resolve_wad_hashes(kind: WadKind, hashes: &[u64], layers: &HashLayers, hash_dir: Option<&str>) -> Vec<String> {
+  if kind != WadKind::Lcu { return layers.resolve_names(hashes); }
+  let table = layers.lmdb(Some(hash_db::LCU_TABLE));
+  let text = match (&table, hash_dir) {
+    (None, Some(dir)) => Some(get_or_load_lcu_hashes(dir)).filter(|m| !m.is_empty()),
+    _ => None,
+  };
+  let lcu: &dyn HashResolver = match (&table, &text) {
+    (Some(t), _) => t,
+    (None, Some(m)) => &**m,
+    (None, None) => return layers.resolve_names(hashes),
+  };
+  LayeredResolver::new()
+    .layer(HashSource::Custom, &*layers.custom)
+    .layer(HashSource::Lcu, lcu)
+    .layer(HashSource::Table, &*layers.extracted)
+    .resolve_names(hashes)
 }
 
 // ── napi structs ────────────────────────────────────────────────────────────
@@ -125,9 +341,22 @@ fn resolve_hashes_with_overlay(
 pub struct WadIndexBatch {
   pub path: String,
   pub error: Option<String>,
+  /// What `error` is about, for picking the guidance to show.
+  #[napi(js_name = "errorDetail")]
+  pub error_detail: Option<WadLoadError>,
+  /// "game" or "lcu": which hash list names this WAD's chunks.
+  pub kind: String,
   pub paths: Vec<String>,
+  /// Distinct chunks; duplicate TOC entries are counted once.
   #[napi(js_name = "chunkCount")]
   pub chunk_count: u32,
+  /// Path hashes listed more than once in the TOC (hex). Only the last entry
+  /// for each is used.
+  #[napi(js_name = "duplicateHashes")]
+  pub duplicate_hashes: Vec<String>,
+  /// Hash generation (see `getHashGeneration`) every batch of the call was
+  /// resolved at; 0 without a hash dir.
+  pub generation: u32,
 }
 
 #[napi(object)]
@@ -138,6 +367,34 @@ pub struct WadExtractResult {
   pub extracted_count: u32,
   #[napi(js_name = "skippedCount")]
   pub skipped_count: u32,
+  /// Chunks that failed to decode (also counted in `skippedCount`).
+  #[napi(js_name = "corruptedChunks")]
+  pub corrupted_chunks: Vec<CorruptedChunk>,
+  /// Set when the preflight found too little free space; nothing was written.
+  #[napi(js_name = "insufficientSpace")]
+  pub insufficient_space: Option<InsufficientSpace>,
+}
+
+#[napi(object)]
+pub struct CorruptedChunk {
+  #[napi(js_name = "wadPath")]
+  pub wad_path: String,
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// "shortRead" (chunk runs past the end of the file), "checksumMismatch"
+  /// or "decompression".
+  pub kind: String,
+  pub message: String,
+  #[napi(js_name = "dataOffset")]
+  pub data_offset: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: u32,
+  #[napi(js_name = "uncompressedSize")]
+  pub uncompressed_size: u32,
+  #[napi(js_name = "wadSize")]
+  pub wad_size: f64,
+  #[napi(js_name = "repairSuggestion")]
+  pub repair_suggestion: String,
 }
 
 #[napi(object)]
@@ -151,24 +408,24 @@ pub struct WadExtractItem {
   pub rel_path: String,
 }
 
-// ── Helpers ─────────────────────────────────────────────────────────────────
-
-fn is_safe_relative_path(path: &str) -> bool {
-  let p = Path::new(path);
-  if p.is_absolute() { return false; }
-  for comp in p.components() {
-    use std::path::Component;
-    match comp {
-      Component::ParentDir | Component::RootDir | Component::Prefix(_) => return false,
-      _ => {}
-    }
-  }
-  true
+/// Options shared by `extractWad`, `extractSelected`, `extractByPrefix` and
+/// their streaming variants.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ExtractOptions {
+  /// Skip chunks an interrupted earlier run already wrote.
+  pub resume: Option<bool>,
+  /// Extract into `<outputDir>.partial` and move it into place only on success.
+  pub atomic: Option<bool>,
+  /// Output layout; see `OutputTemplate::parse`. Game paths are mirrored by default.
+  #[napi(js_name = "outputTemplate")]
+  pub output_template: Option<String>,
+  /// "overwrite", "skip" or "overwriteIfDifferent"; takes precedence over `replaceExisting`.
+  #[napi(js_name = "ifExists")]
+  pub if_exists: Option<String>,
 }
 
-fn normalize_rel_path(v: &str) -> String {
-  v.replace('\\', "/").trim_start_matches('/').to_string()
-}
+// ── Helpers ─────────────────────────────────────────────────────────────────
 
 fn flat_output_name(
   rel_path: &str,
@@ -181,6 +438,13 @@ fn flat_output_name(
     .map(|n| n.to_string_lossy().into_owned())
     .filter(|n| !n.is_empty())
     .unwrap_or_else(|| format!("{:016x}", path_hash));
+  let base_name = match sanitize_rel_path(&base_name) {
+    Cow::Owned(safe) => {
+      hashed_files.insert(safe.clone(), rel_path.to_string());
+      safe
+    }
+    Cow::Borrowed(_) => base_name,
+  };
 
   let ext = Path::new(&base_name)
     .extension()
@@ -208,170 +472,188 @@ fn flat_output_name(
   candidate
 }
 
-fn parse_hash_hex(s: &str) -> Option<u64> {
-  let raw = s.trim().trim_start_matches("0x").trim_start_matches("0X");
-  if raw.len() != 16 || !raw.bytes().all(|b| b.is_ascii_hexdigit()) { return None; }
-  u64::from_str_radix(raw, 16).ok()
+/// TOC entries with one chunk per path hash. Malformed or tampered WADs can
+/// list a hash more than once; the winner is the entry `WadChunks::get`
+/// returns (the last one in TOC order), and the duplicated hashes are returned
+/// so callers can report them instead of racing two writes to one file.
+pub(crate) fn unique_chunks(chunks: &WadChunks) -> (Vec<WadChunk>, Vec<u64>) {
+  let mut unique: Vec<WadChunk> = Vec::with_capacity(chunks.len());
+  let mut duplicates = Vec::new();
+  // Chunks are sorted by hash (stable), so duplicates are adjacent and in TOC order.
+  for chunk in chunks.iter() {
+    match unique.last_mut() {
+      Some(last) if last.path_hash() == chunk.path_hash() => {
+        if duplicates.last() != Some(&chunk.path_hash()) { duplicates.push(chunk.path_hash()); }
+        *last = *chunk;
+      }
+      _ => unique.push(*chunk),
+    }
+  }
+  (unique, duplicates)
 }
 
-/// Resolve u64 hashes to paths using a single LMDB read txn.
-/// Opens one txn per call — fast (microseconds per lookup after OS page warmup).
-fn resolve_hashes_lmdb(hashes: &[u64], env: &heed::Env) -> Vec<String> {
-  let rtxn = match env.read_txn() {
-    Ok(t) => t,
-    Err(_) => return hashes.iter().map(|h| format!("{:016x}", h)).collect(),
-  };
-  let db = match env.open_database::<Bytes, Str>(&rtxn, None) {
-    Ok(Some(d)) => d,
-    _ => return hashes.iter().map(|h| format!("{:016x}", h)).collect(),
-  };
-  hashes.iter().map(|h| {
-    let key = h.to_be_bytes();
-    db.get(&rtxn, &key[..])
-      .ok().flatten()
-      .map(|s| s.to_string())
-      .unwrap_or_else(|| format!("{:016x}", h))
-  }).collect()
+pub(crate) struct WadToc {
+  pub hashes: Vec<u64>,
+  pub duplicates: Vec<u64>,
+  /// Some chunk is split into frames described by the WAD's subchunk TOC.
+  pub multi_frame: bool,
 }
 
-/// Parse WAD TOC only — returns chunk hashes and count. No I/O beyond the TOC.
-fn parse_wad_toc(wad_path: &str) -> Result<(Vec<u64>, u32), String> {
-  let file = fs::File::open(wad_path)
-    .map_err(|e| format!("Failed to open {}: {}", wad_path, e))?;
-  let wad = Wad::mount(file)
-    .map_err(|e| format!("Failed to mount {}: {}", wad_path, e))?;
-  let chunk_count = wad.chunks().len() as u32;
-  let hashes = wad.chunks().iter().map(|c| c.path_hash()).collect();
-  Ok((hashes, chunk_count))
+/// Why a WAD couldn't be read, for the UI to suggest a fix.
+#[napi(object)]
+pub struct WadLoadError {
+  /// "notFound", "badMagic" (not a WAD), "unsupportedVersion",
+  /// "subchunkTocMissing" (multi-frame chunks without their frame table) or
+  /// "io" (unreadable or truncated).
+  pub kind: String,
+  pub message: String,
+  /// WAD version, for "unsupportedVersion".
+  pub major: Option<u32>,
+  pub minor: Option<u32>,
 }
 
-fn fingerprint_file_path(hash_dir: &Path) -> std::path::PathBuf {
-  hash_dir.join("hashes.lmdb").join("sources.fingerprint")
+impl WadLoadError {
+  fn new(kind: &str, message: String) -> Self {
+    WadLoadError { kind: kind.to_string(), message, major: None, minor: None }
+  }
 }
 
-fn compute_file_xxh64(path: &Path) -> Option<(u64, u64)> {
-  let mut file = fs::File::open(path).ok()?;
-  let mut buf = Vec::new();
-  if file.read_to_end(&mut buf).is_err() {
-    return None;
-  }
-  let size = buf.len() as u64;
-  let digest = xxh64(&buf, 0);
-  Some((size, digest))
-}
-
-fn build_sources_fingerprint(dir: &Path, sources: &[(&str, usize)]) -> String {
-  let mut out = String::new();
-  for (name, _sep) in sources {
-    let p = dir.join(name);
-    match compute_file_xxh64(&p) {
-      Some((size, digest)) => {
-        out.push_str(&format!("{}|{}|{:016x}\n", name, size, digest));
-      }
-      None => {
-        out.push_str(&format!("{}|missing\n", name));
-      }
+fn wad_load_error(wad_path: &Path, e: WadError) -> WadLoadError {
+  let message = format!("Failed to mount {}: {}", wad_path.display(), e);
+  match e {
+    WadError::InvalidHeader { .. } => WadLoadError::new("badMagic", message),
+    WadError::InvalidVersion { major, minor } => {
+      WadLoadError { major: Some(major as u32), minor: Some(minor as u32), ..WadLoadError::new("unsupportedVersion", message) }
+    }
+    WadError::IoError(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+      WadLoadError::new("io", format!("Failed to mount {}: file is truncated", wad_path.display()))
     }
+    WadError::IoError(e) => WadLoadError::new("io", format!("Failed to mount {}: {}", wad_path.display(), e)),
+    _ => WadLoadError::new("io", message),
   }
-  out
 }
 
-fn read_sources_fingerprint(dir: &Path) -> Option<String> {
-  fs::read_to_string(fingerprint_file_path(dir)).ok()
+/// Path hash of the subchunk TOC of a game WAD: its DATA/FINAL path with
+/// `.client` swapped for `.subchunktoc`. None outside a DATA/FINAL tree.
+fn subchunk_toc_hash(wad_path: &Path) -> Option<u64> {
+  let path = wad_path.to_string_lossy().replace('\\', "/").to_ascii_lowercase();
+  let rel = &path[path.find("data/final/")?..];
+  Some(xxhash_path(&format!("{}.subchunktoc", rel.strip_suffix(".client").unwrap_or(rel))))
+}
+
+/// Parse WAD TOC only — returns distinct chunk hashes. No I/O beyond the TOC.
+fn parse_wad_toc(wad_path: &Path) -> Result<WadToc, WadLoadError> {
+  let file = fs::File::open(wad_path).map_err(|e| {
+    let kind = if e.kind() == std::io::ErrorKind::NotFound { "notFound" } else { "io" };
+    WadLoadError::new(kind, format!("Failed to open {}: {}", wad_path.display(), e))
+  })?;
+  let wad = Wad::mount(file).map_err(|e| wad_load_error(wad_path, e))?;
+  let (chunks, duplicates) = unique_chunks(wad.chunks());
+  let multi_frame = chunks.iter().any(|c| c.compression_type() == WadChunkCompression::ZstdMulti);
+  let hashes = chunks.iter().map(|c| c.path_hash()).collect();
+  Ok(WadToc { hashes, duplicates, multi_frame })
 }
 
-fn write_sources_fingerprint(dir: &Path, content: &str) {
-  let p = fingerprint_file_path(dir);
-  let _ = fs::write(p, content.as_bytes());
+/// `parse_wad_toc` for indexing: a game WAD whose multi-frame chunks lost
+/// their subchunk TOC fails to load in game, so it is reported instead.
+fn load_wad_toc(wad_path: &Path) -> Result<WadToc, WadLoadError> {
+  let toc = parse_wad_toc(wad_path)?;
+  if toc.multi_frame {
+    if let Some(hash) = subchunk_toc_hash(wad_path) {
+      if !toc.hashes.contains(&hash) {
+        return Err(WadLoadError::new(
+          "subchunkTocMissing",
+          format!("{} has multi-frame chunks but no subchunk TOC", wad_path.display()),
+        ));
+      }
+    }
+  }
+  Ok(toc)
 }
 
 // ── buildHashDb ──────────────────────────────────────────────────────────────
 
-/// Build (or update) hashes.lmdb from the text hash files.
-/// Only rebuilds when a source file is newer than the existing LMDB.
-/// Keys are u64 xxhash stored as 8-byte big-endian; values are path strings.
+/// Build (or update) hashes.lmdb from the text hash lists. Tables whose list
+/// didn't change since the last build are left alone; see `hash_db`.
 #[napi(js_name = "buildHashDb")]
 pub fn build_hash_db(hash_dir: String) -> bool {
-  let dir = Path::new(&hash_dir);
-  let lmdb_dir = dir.join("hashes.lmdb");
-
-  let sources: &[(&str, usize)] = &[
-    ("hashes.game.txt", 16),
-    ("hashes.lcu.txt",  16),
-  ];
-
-  let current_fp = build_sources_fingerprint(dir, sources);
-  let stored_fp = read_sources_fingerprint(dir);
-  let data_exists = lmdb_dir.join("data.mdb").exists();
-  if lmdb_dir.exists() && data_exists && stored_fp.as_deref() == Some(current_fp.as_str()) {
-    return true;
-  }
-
-  // Close cached env before deleting the directory (Windows won't delete open files)
+  // Close the cached env first: a full rebuild deletes the directory (Windows
+  // won't delete open files).
   drop_lmdb_cache();
+  let built = hash_db::build(Path::new(&hash_dir));
+  if let Err(e) = &built { warn!(hash_dir = %hash_dir, error = %e, "Failed to build hash DB"); }
+  built.is_ok()
+}
 
-  if lmdb_dir.exists() && fs::remove_dir_all(&lmdb_dir).is_err() { return false; }
-  if fs::create_dir_all(&lmdb_dir).is_err() { return false; }
-
-  let env = match unsafe {
-    EnvOpenOptions::new()
-      .map_size(512 * 1024 * 1024)
-      .max_dbs(1)
-      .open(&lmdb_dir)
-  } {
-    Ok(e) => e,
-    Err(_) => return false,
-  };
+#[napi(js_name = "primeHashTables")]
+pub fn prime_hash_tables(hash_path: String) -> bool {
+  build_hash_db(hash_path)
+}
 
-  let mut wtxn = match env.write_txn() {
-    Ok(t) => t,
-    Err(_) => return false,
-  };
-  let db: Database<Bytes, Str> = match env.create_database(&mut wtxn, None) {
-    Ok(d) => d,
-    Err(_) => return false,
-  };
+/// Clear the cached LMDB env — drops it from memory. Frees any mmap'd pages.
+#[napi(js_name = "clearHashTables")]
+pub fn clear_hash_tables() {
+  drop_lmdb_cache();
+}
 
-  // Collect all entries across all sources, sort by key for fast MDB_APPEND-style insert
-  let mut entries: Vec<([u8; 8], String)> = Vec::with_capacity(2_000_000);
-  for (filename, sep) in sources {
-    let file_path = dir.join(filename);
-    let Ok(content) = fs::read_to_string(&file_path) else { continue };
-    for line in content.lines() {
-      if line.len() <= sep + 1 || line.starts_with('#') { continue; }
-      let hash_hex = &line[..*sep];
-      let path = line[*sep + 1..].trim_end_matches('\r');
-      let Ok(hash_u64) = u64::from_str_radix(hash_hex, 16) else { continue };
-      entries.push((hash_u64.to_be_bytes(), path.to_string()));
-    }
+/// Release everything this process holds open in hash and index dirs: the
+/// hash DB (and its lock), the game index envs and the cached hash lists. Call
+/// on shutdown so other tools sharing the dirs can rebuild them right away.
+#[napi(js_name = "closeAll")]
+pub fn close_all() {
+  drop_lmdb_cache();
+  path_index::close_index_envs();
+  for cache in [&EXTRACTED_HASH_CACHE, &LCU_HASH_CACHE, &CUSTOM_HASH_CACHE] {
+    if let Some(m) = cache.get() { *m.lock().unwrap_or_else(|e| e.into_inner()) = None; }
   }
+}
 
-  // Sort by key — LMDB B-tree is ordered so sorted inserts are ~2x faster
-  entries.sort_unstable_by_key(|(k, _)| *k);
-  entries.dedup_by_key(|(k, _)| *k);
+// ── compactHashDb ────────────────────────────────────────────────────────────
 
-  for (key, path) in &entries {
-    if db.put(&mut wtxn, key.as_slice(), path.as_str()).is_err() {
-      return false;
-    }
-  }
+#[napi(object)]
+pub struct CompactHashDbResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "sizeBefore")]
+  pub size_before: f64,
+  #[napi(js_name = "sizeAfter")]
+  pub size_after: f64,
+}
 
-  let committed = wtxn.commit().is_ok();
-  if committed {
-    write_sources_fingerprint(dir, &current_fp);
+/// Shrink hashes.lmdb to its live data: LMDB keeps the pages freed by table
+/// rewrites, so the file only ever grows. The copy is swapped in atomically;
+/// lookups made meanwhile simply reopen the DB afterwards.
+#[napi(js_name = "compactHashDb")]
+pub fn compact_hash_db(hash_dir: String) -> CompactHashDbResult {
+  // The env has to close for the swap; the cache would keep it open.
+  drop_lmdb_cache();
+  match hash_db::compact(Path::new(&hash_dir)) {
+    Ok(c) => CompactHashDbResult { success: true, error: None, size_before: c.size_before as f64, size_after: c.size_after as f64 },
+    Err(e) => CompactHashDbResult { success: false, error: Some(e), size_before: 0.0, size_after: 0.0 },
   }
-  committed
 }
 
-#[napi(js_name = "primeHashTables")]
-pub fn prime_hash_tables(hash_path: String) -> bool {
-  build_hash_db(hash_path)
+pub struct CompactHashDbTask {
+  hash_dir: String,
 }
 
-/// Clear the cached LMDB env — drops it from memory. Frees any mmap'd pages.
-#[napi(js_name = "clearHashTables")]
-pub fn clear_hash_tables() {
-  drop_lmdb_cache();
+#[napi]
+impl Task for CompactHashDbTask {
+  type Output = CompactHashDbResult;
+  type JsValue = CompactHashDbResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(compact_hash_db(self.hash_dir.clone()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+#[napi(js_name = "compactHashDbAsync")]
+pub fn compact_hash_db_async(hash_dir: String) -> AsyncTask<CompactHashDbTask> {
+  AsyncTask::new(CompactHashDbTask { hash_dir })
 }
 
 // ── loadAllIndexes ───────────────────────────────────────────────────────────
@@ -383,15 +665,18 @@ pub fn load_all_indexes(
   concurrency: Option<u32>,
 ) -> Vec<WadIndexBatch> {
   if wad_paths.is_empty() { return Vec::new(); }
+  let _span = info_span!("load_all_indexes", wads = wad_paths.len()).entered();
 
   // Phase 1: parallel WAD TOC parsing — I/O bound, benefits from Rayon
   let make_tocs = || {
     wad_paths.par_iter()
-      .map(|p| (p.as_str(), parse_wad_toc(p)))
+      .map(|p| (p.as_str(), load_wad_toc(Path::new(p))))
       .collect::<Vec<_>>()
   };
 
-  let toc_results: Vec<(&str, Result<(Vec<u64>, u32), String>)> = {
+  type TocResult<'a> = (&'a str, Result<WadToc, WadLoadError>);
+  let toc_span = info_span!("toc").entered();
+  let toc_results: Vec<TocResult> = {
     if let Some(c) = concurrency {
       let threads = (c as usize).clamp(1, 32);
       if let Ok(pool) = rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
@@ -400,180 +685,323 @@ pub fn load_all_indexes(
         make_tocs()
       }
     } else {
-      make_tocs()
+      run_io(make_tocs)
     }
   };
 
-  // Phase 2: LMDB lookups — single open env, per-WAD read txns (cheap)
+  drop(toc_span);
+
+  // Phase 2: LMDB lookups — single open env and one read txn for all WADs, so
+  // every batch sees the same names even if the hash lists change meanwhile.
   // RAM stays near zero — OS only pages in what's touched (~5-20MB for typical use)
-  let env_opt = hash_path.as_deref().and_then(get_or_open_env);
-  let extracted_map = hash_path
-    .as_deref()
-    .map(get_or_load_extracted_hashes)
-    .unwrap_or_else(|| Arc::new(HashMap::new()));
+  let layers = HashLayers::open(hash_path.as_deref());
 
+  let _resolve_span = info_span!("resolve").entered();
   toc_results.into_iter().map(|(path, result)| {
     match result {
       Err(e) => WadIndexBatch {
         path: path.to_string(),
-        error: Some(e),
+        error: Some(e.message.clone()),
+        error_detail: Some(e),
+        kind: String::new(),
         paths: Vec::new(),
         chunk_count: 0,
+        duplicate_hashes: Vec::new(),
+        generation: layers.generation(),
       },
-      Ok((hashes, chunk_count)) => {
-        let paths = resolve_hashes_with_overlay(&hashes, env_opt.as_deref(), &extracted_map);
+      Ok(toc) => {
+        if !toc.duplicates.is_empty() {
+          warn!(wad = %path, count = toc.duplicates.len(), "WAD lists duplicate path hashes");
+        }
+        let kind = wad_kind(Path::new(path)).unwrap_or(WadKind::Game);
+        let paths = resolve_wad_hashes(kind, &toc.hashes, &layers, hash_path.as_deref());
         WadIndexBatch {
           path: path.to_string(),
           error: None,
+          error_detail: None,
+          kind: kind.as_str().to_string(),
+          chunk_count: paths.len() as u32,
           paths,
-          chunk_count,
+          duplicate_hashes: toc.duplicates.iter().map(|h| format!("{:016x}", h)).collect(),
+          generation: layers.generation(),
         }
       }
     }
   }).collect()
 }
 
+// ── listGameWads ─────────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct GameWadEntry {
+  pub path: String,
+  /// Relative to DATA/FINAL for game WADs, to the install root for LCU WADs.
+  #[napi(js_name = "relPath")]
+  pub rel_path: String,
+  /// "game" or "lcu".
+  pub kind: String,
+}
+
+/// WAD files of an install: DATA/FINAL, plus League client plugin WADs when
+/// `includeLcu` is set. Archives without a `.wad`/`.wad.client` name are
+/// recognized by their header.
+#[napi(js_name = "listGameWads")]
+pub fn list_game_wads(league_path: String, include_lcu: Option<bool>) -> Vec<GameWadEntry> {
+  let league = Path::new(&league_path);
+  let mut wads = game::walk_final_wads(league);
+  if include_lcu.unwrap_or(false) { wads.extend(game::walk_lcu_wads(league)); }
+  wads
+    .into_iter()
+    .map(|(p, rel_path)| GameWadEntry {
+      kind: wad_kind(&p).unwrap_or(WadKind::Game).as_str().to_string(),
+      path: p.to_string_lossy().into_owned(),
+      rel_path,
+    })
+    .collect()
+}
+
 // ── resolveHashes ────────────────────────────────────────────────────────────
 
+#[napi(object)]
+pub struct ResolveHashOptions {
+  /// Sources to name hashes from, highest precedence first: any of "custom",
+  /// "game", "lcu", "extracted"; unknown names are ignored. Default: the usual
+  /// layers (custom, the combined LMDB view, extracted).
+  pub sources: Option<Vec<String>>,
+}
+
+#[napi(object)]
+pub struct HashNameConflict {
+  pub source: String,
+  pub name: String,
+}
+
+#[napi(object)]
+pub struct ResolvedHash {
+  pub hash: String,
+  pub name: String,
+  /// "custom" (hashes.custom.txt), "lmdb", "table" (hashes.extracted.txt) or
+  /// "hex" when nothing knew the hash. With `sources`, the source's own name.
+  pub source: String,
+  /// Other names the requested sources give the hash; only filled with `sources`.
+  pub conflicts: Vec<HashNameConflict>,
+}
+
 /// Resolve hex hash strings to paths using LMDB point lookups.
 /// ~1-5ms for a typical WAD (~4000 hashes) vs 80-155ms with the old SQLite approach.
+/// Strings that aren't hex are returned unchanged.
 #[napi(js_name = "resolveHashes")]
-pub fn resolve_hashes(hex_hashes: Vec<String>, hash_dir: String) -> Vec<String> {
-  let env_opt = get_or_open_env(&hash_dir);
-  let extracted_map = get_or_load_extracted_hashes(&hash_dir);
-  let db_ctx = env_opt.as_deref().and_then(|env| {
-    let rtxn = env.read_txn().ok()?;
-    let db = env.open_database::<Bytes, Str>(&rtxn, None).ok()??;
-    Some((rtxn, db))
-  });
-
-  hex_hashes.iter().map(|h| {
-    let Ok(hash_u64) = u64::from_str_radix(h.trim(), 16) else { return h.clone(); };
-    if let Some(v) = extracted_map.get(&hash_u64) {
-      return v.clone();
-    }
-    match db_ctx.as_ref() {
-      Some((rtxn, db)) => {
-        let key = hash_u64.to_be_bytes();
-        db.get(rtxn, &key[..])
-          .ok().flatten()
-          .map(|s| s.to_string())
-          .unwrap_or_else(|| h.clone())
-      }
-      None => h.clone(),
+pub fn resolve_hashes(hex_hashes: Vec<String>, hash_dir: String, options: Option<ResolveHashOptions>) -> Vec<String> {
+  resolve_hash_sources(hex_hashes, hash_dir, options).into_iter().map(|r| r.name).collect()
+}
+
+/// `resolveHashes` with the layer each name came from, for telling a
+/// hand-added name from a CDTB one or an unknown hash. With `sources`, every
+/// requested source is consulted and disagreeing names are listed.
+#[napi(js_name = "resolveHashSources")]
+pub fn resolve_hash_sources(hex_hashes: Vec<String>, hash_dir: String, options: Option<ResolveHashOptions>) -> Vec<ResolvedHash> {
+  let parsed: Vec<Option<u64>> = hex_hashes.iter().map(|h| u64::from_str_radix(h.trim(), 16).ok()).collect();
+  let valid: Vec<u64> = parsed.iter().flatten().copied().collect();
+  let layers = HashLayers::open(Some(&hash_dir));
+  let resolved: Vec<(Resolved, Vec<Resolved>)> = match options.and_then(|o| o.sources) {
+    Some(names) => {
+      let sources: Vec<HashSource> = names.iter().filter_map(|n| HashSource::from_name(n)).collect();
+      layers.resolve_from(&sources, &valid)
     }
-  }).collect()
+    None => layers.resolve(&valid).into_iter().map(|r| (r, Vec::new())).collect(),
+  };
+  let mut resolved = resolved.into_iter();
+  hex_hashes
+    .into_iter()
+    .zip(parsed)
+    .map(|(hash, h)| match h.and_then(|_| resolved.next()) {
+      // Unknown hashes keep the caller's spelling.
+      Some((r, conflicts)) if r.source != HashSource::Hex => ResolvedHash {
+        hash,
+        name: r.name,
+        source: r.source.as_str().to_string(),
+        conflicts: conflicts.into_iter().map(|c| HashNameConflict { source: c.source.as_str().to_string(), name: c.name }).collect(),
+      },
+      _ => ResolvedHash { name: hash.clone(), hash, source: HashSource::Hex.as_str().to_string(), conflicts: Vec::new() },
+    })
+    .collect()
 }
 
 // ── extractWad ───────────────────────────────────────────────────────────────
 
+/// An extraction that failed before anything was written.
+fn failed(error: String) -> WadExtractResult {
+  WadExtractResult {
+    success: false,
+    error: Some(error),
+    extracted_count: 0,
+    skipped_count: 0,
+    corrupted_chunks: Vec::new(),
+    insufficient_space: None,
+  }
+}
+
+fn insufficient_space_result(space: InsufficientSpace) -> WadExtractResult {
+  let error = space.message();
+  WadExtractResult { insufficient_space: Some(space), ..failed(error) }
+}
+
+/// With `atomic`, extraction writes into `<output>.partial` and is moved into
+/// place only when it succeeded, so a crash never leaves a half-populated
+/// output dir behind. `resume` continues a leftover staging dir instead of
+/// starting over.
+pub(crate) fn extract_atomically(
+  output: &Path,
+  resume: bool,
+  if_exists: IfExists,
+  extract: impl FnOnce(&Path) -> WadExtractResult,
+) -> WadExtractResult {
+  let Some(staging) = staging_dir(output) else {
+    return failed(format!("Invalid output directory: {}", output.display()));
+  };
+  if !resume { let _ = fs::remove_dir_all(&staging); }
+  let mut result = extract(&staging);
+  if !result.success { return result; }
+  match commit_staging(&staging, output, if_exists) {
+    Ok(kept) => {
+      result.extracted_count = result.extracted_count.saturating_sub(kept);
+      result.skipped_count += kept;
+      result
+    }
+    Err(e) => WadExtractResult { success: false, error: Some(e), ..result },
+  }
+}
+
 #[napi(js_name = "extractWad")]
 pub fn extract_wad(
   wad_path: String,
   output_dir: String,
   hash_path: Option<String>,
   replace_existing: Option<bool>,
+  options: Option<ExtractOptions>,
 ) -> WadExtractResult {
-  if wad_path.is_empty() || !Path::new(&wad_path).exists() {
-    return WadExtractResult {
-      success: false,
-      error: Some(format!("WAD file not found: {}", wad_path)),
-      extracted_count: 0,
-      skipped_count: 0,
-    };
-  }
+  let ExtractOptions { resume, atomic, output_template, if_exists } = options.unwrap_or_default();
   if output_dir.is_empty() {
-    return WadExtractResult {
-      success: false,
-      error: Some("Output directory is required".to_string()),
-      extracted_count: 0,
-      skipped_count: 0,
-    };
+    return failed("Output directory is required".to_string());
   }
-  if let Err(e) = fs::create_dir_all(&output_dir) {
-    return WadExtractResult {
-      success: false,
-      error: Some(format!("Failed to create output directory: {}", e)),
-      extracted_count: 0,
-      skipped_count: 0,
-    };
+  let if_exists = match IfExists::resolve(if_exists.as_deref(), replace_existing) {
+    Ok(p) => p,
+    Err(e) => return failed(e),
+  };
+  let wad_path = Path::new(&wad_path);
+  let hash_path = hash_path.as_deref();
+  let template = output_template.as_deref();
+  if atomic.unwrap_or(false) {
+    return extract_atomically(Path::new(&output_dir), resume.unwrap_or(false), if_exists, |staging| {
+      extract_wad_to(wad_path, staging, hash_path, if_exists, resume, template, None)
+    });
   }
+  extract_wad_to(wad_path, Path::new(&output_dir), hash_path, if_exists, resume, template, None)
+}
 
-  let replace = replace_existing.unwrap_or(true);
-  let env_opt = hash_path.as_deref().and_then(get_or_open_env);
+/// `extractWad` on native paths, so non-UTF-8 install or output dirs are never
+/// round-tripped through a lossy string. `output_template` lays out the output
+/// (see `OutputTemplate::parse`); by default game paths are mirrored.
+pub(crate) fn extract_wad_to(
+  wad_path: &Path,
+  output_root: &Path,
+  hash_path: Option<&str>,
+  if_exists: IfExists,
+  resume: Option<bool>,
+  output_template: Option<&str>,
+  on_chunk: Option<ChunkObserver>,
+) -> WadExtractResult {
+  let mut template = match output_template.map(OutputTemplate::parse).transpose() {
+    Ok(t) => t,
+    Err(e) => return failed(e),
+  };
+  if wad_path.as_os_str().is_empty() || !wad_path.exists() {
+    return failed(format!("WAD file not found: {}", wad_path.display()));
+  }
+  if let Err(e) = fs::create_dir_all(output_root) {
+    return failed(format!("Failed to create output directory: {}", e));
+  }
 
-  let file = match fs::File::open(&wad_path) {
+  let _span = info_span!("extract_wad", wad = %wad_path.display()).entered();
+  let file = match fs::File::open(wad_path) {
     Ok(f) => f,
-    Err(e) => return WadExtractResult {
-      success: false,
-      error: Some(format!("Failed to open WAD: {}", e)),
-      extracted_count: 0,
-      skipped_count: 0,
-    },
+    Err(e) => return failed(format!("Failed to open WAD: {}", e)),
   };
   let mmap = match unsafe { Mmap::map(&file) } {
     Ok(m) => m,
-    Err(e) => return WadExtractResult {
-      success: false,
-      error: Some(format!("Failed to mmap WAD: {}", e)),
-      extracted_count: 0,
-      skipped_count: 0,
-    },
+    Err(e) => return failed(format!("Failed to mmap WAD: {}", e)),
   };
 
   let wad = match Wad::mount(Cursor::new(&mmap[..])) {
     Ok(w) => w,
-    Err(e) => return WadExtractResult {
-      success: false,
-      error: Some(format!("Failed to mount WAD: {}", e)),
-      extracted_count: 0,
-      skipped_count: 0,
-    },
+    Err(e) => return failed(format!("Failed to mount WAD: {}", e)),
   };
 
-  let chunks: Vec<_> = wad.chunks().iter().map(|c| *c).collect();
+  let (chunks, duplicates) = unique_chunks(wad.chunks());
+  if !duplicates.is_empty() {
+    warn!(wad = %wad_path.display(), count = duplicates.len(), "WAD lists duplicate path hashes; using the last entry for each");
+  }
+  let resolve_span = info_span!("resolve", chunks = chunks.len()).entered();
   let hash_u64s: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
-  let extracted_map = hash_path
-    .as_deref()
-    .map(get_or_load_extracted_hashes)
-    .unwrap_or_else(|| Arc::new(HashMap::new()));
-  let resolved_paths: Vec<String> = resolve_hashes_with_overlay(&hash_u64s, env_opt.as_deref(), &extracted_map);
+  let kind = wad_kind(wad_path).unwrap_or(WadKind::Game);
+  let resolved_paths: Vec<String> = resolve_wad_hashes(kind, &hash_u64s, &HashLayers::open(hash_path), hash_path);
+  drop(resolve_span);
 
   let mut extracted_count: u32 = 0;
   let mut skipped_count: u32 = 0;
-  let output_root = Path::new(&output_dir);
   let mut hashed_files: HashMap<String, String> = HashMap::new();
+  // Chunks finished by an interrupted earlier run count as skipped.
+  let tracker = ResumeTracker::open(output_root);
+  let already_done = tracker.begin(wad_path, resume.unwrap_or(false));
 
   // 1. Pre-process metadata and directories SEQUENTIALLY to avoid thread fighting
+  let plan_span = info_span!("plan").entered();
   let mut extraction_plan = Vec::new();
   let mut parents_to_create = HashSet::new();
 
-  for (chunk, resolved) in chunks.into_iter().zip(resolved_paths.into_iter()) {
+  for (chunk, resolved) in chunks.into_iter().zip(resolved_paths) {
+    if already_done.contains(&chunk.path_hash()) { skipped_count += 1; continue; }
     let mut rel = normalize_rel_path(&resolved);
+    if let Some(t) = template.as_mut() {
+      rel = t.render(wad_path, &rel, chunk.path_hash(), &mut hashed_files);
+    }
     if !is_safe_relative_path(&rel) { skipped_count += 1; continue; }
+    if let Cow::Owned(safe) = sanitize_rel_path(&rel) {
+      hashed_files.insert(safe.clone(), resolved.to_string());
+      rel = safe;
+    }
 
-    let mut out_path = output_root.join(&rel);
+    let mut out_path = long_path(&output_root.join(&rel));
     let file_name = out_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
     
     // Minimal disk hits: only check if we need to hash the path
     let should_be_hashed = file_name.len() > 255 || (out_path.exists() && out_path.is_dir());
     
     if should_be_hashed {
-      let ext = if rel.contains('.') { format!(".{}", rel.split('.').last().unwrap_or("")) } else { "".to_string() };
-      let hex_hash = format!("{:016x}", chunk.path_hash() as u64);
+      let ext = if rel.contains('.') { format!(".{}", rel.split('.').next_back().unwrap_or("")) } else { "".to_string() };
+      let hex_hash = format!("{:016x}", chunk.path_hash());
       let basename = format!("{}{}", hex_hash, ext);
       hashed_files.insert(basename.clone(), resolved.to_string());
       rel = basename;
-      out_path = output_root.join(&rel);
+      out_path = long_path(&output_root.join(&rel));
     }
 
-    if out_path.exists() && !replace { skipped_count += 1; continue; }
+    // Names without an extension get a sniffed one at write time, so the
+    // worker makes the Skip check for those once the final path is known.
+    if if_exists == IfExists::Skip && out_path.extension().is_some() && out_path.exists() {
+      report_chunk(on_chunk, chunk.path_hash(), &rel, true);
+      skipped_count += 1;
+      continue;
+    }
 
     if let Some(parent) = out_path.parent() {
       parents_to_create.insert(parent.to_path_buf());
     }
 
-    extraction_plan.push((chunk, out_path));
+    extraction_plan.push((chunk, out_path, rel));
+  }
+  if let Err(space) = check_space(output_root, extraction_plan.iter().map(|(c, _, _)| c)) {
+    return insufficient_space_result(space);
   }
 
   // Batch create directories
@@ -581,64 +1009,79 @@ pub fn extract_wad(
     let _ = fs::create_dir_all(parent);
   }
 
+  drop(plan_span);
+
   // 2. Parallel Extraction: No more filesystem fighting!
-  let mmap_ref = &mmap;
-  let thread_results: Vec<(u32, u32)> = extraction_plan
+  let write_span = info_span!("write").entered();
+  // Workers share the parsed TOC and read straight from the mmap.
+  let wad_data = &mmap[..];
+  let thread_results: Vec<(u32, u32, Vec<CorruptedChunk>)> = run_io(|| extraction_plan
     .par_chunks((extraction_plan.len() / rayon::current_num_threads().max(1)).max(1))
     .map(|slice| {
       let mut e = 0;
       let mut s = 0;
-      let mut local_wad = match Wad::mount(Cursor::new(&mmap_ref[..])) {
-        Ok(w) => w,
-        Err(_) => return (0, slice.len() as u32),
-      };
+      let mut corrupted = Vec::new();
 
-      for (chunk, out_path) in slice {
-        let data = match local_wad.load_chunk_decompressed(chunk) {
+      for (chunk, out_path, rel) in slice {
+        let _reserved = reserve_memory(chunk.uncompressed_size() as u64);
+        let data = match decompress_chunk(wad_data, chunk) {
           Ok(d) => d,
-          Err(_) => { s += 1; continue; }
+          Err(err) => {
+            corrupted.push(diagnose_chunk_failure(wad_path, wad_data, chunk, err));
+            report_chunk(on_chunk, chunk.path_hash(), rel, false);
+            s += 1;
+            continue;
+          }
         };
         let mut final_path = out_path.clone();
+        let mut out_rel = Cow::Borrowed(rel.as_str());
         if final_path.extension().is_none() {
           if let Some(ext) = LeagueFileKind::identify_from_bytes_with_offset(&data, 64).extension() {
             final_path.set_extension(ext);
+            out_rel = Cow::Owned(format!("{}.{}", rel, ext));
           }
         }
+        if if_exists == IfExists::Skip && final_path.exists() {
+          report_chunk(on_chunk, chunk.path_hash(), &out_rel, true);
+          s += 1;
+          continue;
+        }
+        if if_exists == IfExists::OverwriteIfDifferent && same_contents(&final_path, &data) {
+          tracker.mark(wad_path, chunk.path_hash());
+          report_chunk(on_chunk, chunk.path_hash(), &out_rel, true);
+          s += 1;
+          continue;
+        }
         // Simple write_all - binary writing is fast, directory is already there.
-        if fs::write(final_path, &data).is_ok() {
+        let ok = write_retrying(&final_path, &data).is_ok();
+        report_chunk(on_chunk, chunk.path_hash(), &out_rel, ok);
+        if ok {
+          tracker.mark(wad_path, chunk.path_hash());
           e += 1;
         } else {
           s += 1;
         }
       }
-      (e, s)
+      (e, s, corrupted)
     })
-    .collect();
+    .collect());
 
-  for (e, s) in thread_results {
+  let mut corrupted_chunks = Vec::new();
+  for (e, s, corrupted) in thread_results {
     extracted_count += e;
     skipped_count += s;
+    corrupted_chunks.extend(corrupted);
   }
-
-  if !hashed_files.is_empty() {
-    let json_path = output_root.join("hashed_files.json");
-    let mut existing: HashMap<String, String> = HashMap::new();
-    if let Ok(content) = fs::read_to_string(&json_path) {
-        if let Ok(map) = serde_json::from_str(&content) {
-            existing = map;
-        }
-    }
-    existing.extend(hashed_files);
-    if let Ok(json) = serde_json::to_string_pretty(&existing) {
-        let mut opts = fs::OpenOptions::new();
-        opts.write(true).create(true).truncate(true);
-        if let Ok(mut f) = opts.open(&json_path) {
-            let _ = f.write_all(json.as_bytes());
-        }
-    }
+  if !corrupted_chunks.is_empty() {
+    warn!(wad = %wad_path.display(), count = corrupted_chunks.len(), "Corrupted chunks skipped during extraction");
   }
+  drop(write_span);
+  tracker.finish(wad_path);
+  tracker.flush();
 
-  WadExtractResult { success: true, error: None, extracted_count, skipped_count }
+  merge_hashed_files_sidecar(output_root, hashed_files);
+
+  WadExtractResult { success: true, error: None, extracted_count, skipped_count, corrupted_chunks, insufficient_space: None }
 }
 
 pub struct ExtractWadTask {
@@ -646,6 +1089,7 @@ pub struct ExtractWadTask {
   output_dir: String,
   hash_path: Option<String>,
   replace_existing: Option<bool>,
+  options: Option<ExtractOptions>,
 }
 
 #[napi]
@@ -659,6 +1103,7 @@ impl Task for ExtractWadTask {
       self.output_dir.clone(),
       self.hash_path.clone(),
       self.replace_existing,
+      self.options.take(),
     ))
   }
 
@@ -673,12 +1118,14 @@ pub fn extract_wad_async(
   output_dir: String,
   hash_path: Option<String>,
   replace_existing: Option<bool>,
+  options: Option<ExtractOptions>,
 ) -> AsyncTask<ExtractWadTask> {
   AsyncTask::new(ExtractWadTask {
     wad_path,
     output_dir,
     hash_path,
     replace_existing,
+    options,
   })
 }
 
@@ -689,6 +1136,7 @@ pub struct ExtractSelectedTask {
   output_dir: String,
   replace_existing: Option<bool>,
   preserve_paths: Option<bool>,
+  options: Option<ExtractOptions>,
 }
 
 #[napi]
@@ -702,6 +1150,7 @@ impl Task for ExtractSelectedTask {
       self.output_dir.clone(),
       self.replace_existing,
       self.preserve_paths,
+      self.options.take(),
     ))
   }
 
@@ -716,12 +1165,14 @@ pub fn extract_selected_async(
   output_dir: String,
   replace_existing: Option<bool>,
   preserve_paths: Option<bool>,
+  options: Option<ExtractOptions>,
 ) -> AsyncTask<ExtractSelectedTask> {
   AsyncTask::new(ExtractSelectedTask {
     items,
     output_dir,
     replace_existing,
     preserve_paths,
+    options,
   })
 }
 
@@ -731,46 +1182,111 @@ pub fn extract_selected(
   output_dir: String,
   replace_existing: Option<bool>,
   preserve_paths: Option<bool>,
+  options: Option<ExtractOptions>,
 ) -> WadExtractResult {
+  let ExtractOptions { resume, atomic, output_template, if_exists } = options.unwrap_or_default();
   if output_dir.is_empty() {
-    return WadExtractResult {
-      success: false,
-      error: Some("Output directory is required".to_string()),
-      extracted_count: 0,
-      skipped_count: 0,
-    };
+    return failed("Output directory is required".to_string());
+  }
+  let if_exists = match IfExists::resolve(if_exists.as_deref(), replace_existing) {
+    Ok(p) => p,
+    Err(e) => return failed(e),
+  };
+  let template = output_template.as_deref();
+  let (selected, invalid) = selected_chunks(items);
+  let mut result = if atomic.unwrap_or(false) {
+    extract_atomically(Path::new(&output_dir), resume.unwrap_or(false), if_exists, |staging| {
+      extract_selected_to(selected, staging, if_exists, preserve_paths, resume, template, None)
+    })
+  } else {
+    extract_selected_to(selected, Path::new(&output_dir), if_exists, preserve_paths, resume, template, None)
+  };
+  result.skipped_count += invalid;
+  result
+}
+
+/// One chunk to extract: source WAD, path hash and the relative output path.
+pub(crate) type SelectedChunk = (PathBuf, u64, String);
+
+/// Parse `extractSelected` items; returns the chunks and how many items were invalid.
+pub(crate) fn selected_chunks(items: Vec<WadExtractItem>) -> (Vec<SelectedChunk>, u32) {
+  let mut invalid = 0u32;
+  let mut selected: Vec<SelectedChunk> = Vec::with_capacity(items.len());
+  for item in items {
+    if item.wad_path.is_empty() || item.rel_path.is_empty() { invalid += 1; continue; }
+    let Some(hash) = parse_hash_hex(&item.path_hash) else { invalid += 1; continue; };
+    selected.push((PathBuf::from(item.wad_path), hash, item.rel_path));
   }
-  if let Err(e) = fs::create_dir_all(&output_dir) {
+  (selected, invalid)
+}
+
+/// A chunk, the file it is extracted to and that file relative to the output root.
+type PlannedChunk = (WadChunk, PathBuf, String);
+
+/// Called as chunks finish extracting: path hash, output path relative to the
+/// output root (forward slashes) and whether the file is now in place.
+pub(crate) type ChunkObserver<'a> = &'a (dyn Fn(u64, &str, bool) + Sync);
+
+fn report_chunk(on_chunk: Option<ChunkObserver>, hash: u64, rel: &str, ok: bool) {
+  if let Some(report) = on_chunk {
+    report(hash, rel, ok);
+  }
+}
+
+/// `extractSelected` on native paths (see `extract_wad_to`). A template is
+/// applied before `preserve_paths` flattening.
+pub(crate) fn extract_selected_to(
+  items: Vec<SelectedChunk>,
+  output_root: &Path,
+  if_exists: IfExists,
+  preserve_paths: Option<bool>,
+  resume: Option<bool>,
+  output_template: Option<&str>,
+  on_chunk: Option<ChunkObserver>,
+) -> WadExtractResult {
+  let mut template = match output_template.map(OutputTemplate::parse).transpose() {
+    Ok(t) => t,
+    Err(e) => return failed(e),
+  };
+  if let Err(e) = fs::create_dir_all(output_root) {
+    return failed(format!("Failed to create output directory: {}", e));
+  }
+  if items.is_empty() {
     return WadExtractResult {
-      success: false,
-      error: Some(format!("Failed to create output directory: {}", e)),
+      success: true,
+      error: None,
       extracted_count: 0,
       skipped_count: 0,
+      corrupted_chunks: Vec::new(),
+      insufficient_space: None,
     };
   }
-  if items.is_empty() {
-    return WadExtractResult { success: true, error: None, extracted_count: 0, skipped_count: 0 };
-  }
 
-  let replace = replace_existing.unwrap_or(true);
+  let _span = info_span!("extract_selected", items = items.len()).entered();
   let preserve = preserve_paths.unwrap_or(true);
-  let output_root = Path::new(&output_dir);
   let mut extracted_count: u32 = 0;
   let mut skipped_count: u32 = 0;
   let mut hashed_files: HashMap<String, String> = HashMap::new();
   let mut used_flat_names: HashSet<String> = HashSet::new();
-
-  let mut grouped: HashMap<String, Vec<(u64, String)>> = HashMap::new();
-  for item in items {
-    if item.wad_path.is_empty() || item.rel_path.is_empty() { skipped_count += 1; continue; }
-    let Some(hash) = parse_hash_hex(&item.path_hash) else { skipped_count += 1; continue; };
-    let rel = normalize_rel_path(&item.rel_path);
+  let mut corrupted_chunks: Vec<CorruptedChunk> = Vec::new();
+  let tracker = ResumeTracker::open(output_root);
+
+  let mut grouped: HashMap<PathBuf, Vec<(u64, String)>> = HashMap::new();
+  for (wad_path, hash, rel_path) in items {
+    let mut rel = normalize_rel_path(&rel_path);
+    if let Some(t) = template.as_mut() {
+      rel = t.render(&wad_path, &rel, hash, &mut hashed_files);
+    }
     if !is_safe_relative_path(&rel) { skipped_count += 1; continue; }
-    grouped.entry(item.wad_path).or_default().push((hash, rel));
+    grouped.entry(wad_path).or_default().push((hash, rel));
   }
 
+  // Planning is sequential (flat names and the sidecar are shared); writing
+  // then runs across WADs as well as within each one.
+  let mut groups: Vec<(PathBuf, Mmap, Vec<PlannedChunk>)> = Vec::with_capacity(grouped.len());
+  let mut parents_to_create = HashSet::new();
   for (wad_path, entries) in grouped {
-    if !Path::new(&wad_path).exists() { skipped_count += entries.len() as u32; continue; }
+    if !wad_path.exists() { skipped_count += entries.len() as u32; continue; }
     let file = match fs::File::open(&wad_path) {
       Ok(f) => f,
       Err(_) => { skipped_count += entries.len() as u32; continue; }
@@ -783,197 +1299,261 @@ pub fn extract_selected(
       Ok(w) => w,
       Err(_) => { skipped_count += entries.len() as u32; continue; }
     };
+    let already_done = tracker.begin(&wad_path, resume.unwrap_or(false));
 
     let mut extraction_plan = Vec::new();
-    let mut parents_to_create = HashSet::new();
 
     for (path_hash, rel_path) in entries {
       let Some(chunk) = wad.chunks().get(path_hash).copied() else { skipped_count += 1; continue; };
+      if already_done.contains(&path_hash) { skipped_count += 1; continue; }
       let mut rel = if preserve {
-        rel_path.clone()
+        match sanitize_rel_path(&rel_path) {
+          Cow::Owned(safe) => {
+            hashed_files.insert(safe.clone(), rel_path.clone());
+            safe
+          }
+          Cow::Borrowed(_) => rel_path.clone(),
+        }
       } else {
-        flat_output_name(&rel_path, chunk.path_hash() as u64, &mut used_flat_names, &mut hashed_files)
+        flat_output_name(&rel_path, chunk.path_hash(), &mut used_flat_names, &mut hashed_files)
       };
-      let mut out_path = output_root.join(&rel);
+      let mut out_path = long_path(&output_root.join(&rel));
 
       let file_name = out_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
       let should_be_hashed = file_name.len() > 255 || (out_path.exists() && out_path.is_dir());
 
       if should_be_hashed {
-        let ext = if rel.contains('.') { format!(".{}", rel.split('.').last().unwrap_or("")) } else { "".to_string() };
-        let hex_hash = format!("{:016x}", chunk.path_hash() as u64);
+        let ext = if rel.contains('.') { format!(".{}", rel.split('.').next_back().unwrap_or("")) } else { "".to_string() };
+        let hex_hash = format!("{:016x}", chunk.path_hash());
         let basename = format!("{}{}", hex_hash, ext);
         hashed_files.insert(basename.clone(), rel_path.clone());
         rel = basename;
-        out_path = output_root.join(&rel);
+        out_path = long_path(&output_root.join(&rel));
         if !preserve {
           used_flat_names.insert(rel.to_ascii_lowercase());
         }
       }
 
-      if out_path.exists() && !replace { skipped_count += 1; continue; }
+      if if_exists == IfExists::Skip && out_path.extension().is_some() && out_path.exists() {
+        report_chunk(on_chunk, chunk.path_hash(), &rel, true);
+        skipped_count += 1;
+        continue;
+      }
       
       if let Some(parent) = out_path.parent() {
         parents_to_create.insert(parent.to_path_buf());
       }
-      extraction_plan.push((chunk, out_path));
+      extraction_plan.push((chunk, out_path, rel));
     }
 
-    for p in parents_to_create { let _ = fs::create_dir_all(p); }
-
-    let mmap_ref = &mmap;
-    let results: Vec<(u32, u32)> = extraction_plan
-      .par_chunks((extraction_plan.len() / rayon::current_num_threads().max(1)).max(1))
-      .map(|slice| {
-        let mut e = 0;
-        let mut s = 0;
-        let mut local_wad = match Wad::mount(Cursor::new(&mmap_ref[..])) {
-          Ok(w) => w,
-          Err(_) => return (0, slice.len() as u32),
-        };
-        for (chunk, out_path) in slice {
-          let data = match local_wad.load_chunk_decompressed(chunk) {
-            Ok(d) => d,
-            Err(_) => { s += 1; continue; }
-          };
-          let mut final_path = out_path.clone();
-          if final_path.extension().is_none() {
-            if let Some(ext) = LeagueFileKind::identify_from_bytes_with_offset(&data, 64).extension() {
-              final_path.set_extension(ext);
+    drop(wad);
+    groups.push((wad_path, mmap, extraction_plan));
+  }
+  let planned = groups.iter().flat_map(|(_, _, plan)| plan.iter().map(|(c, _, _)| c));
+  if let Err(space) = check_space(output_root, planned) {
+    return insufficient_space_result(space);
+  }
+  for p in parents_to_create { let _ = fs::create_dir_all(p); }
+
+  let results: Vec<(u32, u32, Vec<CorruptedChunk>)> = run_io(|| groups
+    .par_iter()
+    .flat_map_iter(|(wad_path, mmap, extraction_plan)| {
+      let _write_span = info_span!("write", wad = %wad_path.display(), chunks = extraction_plan.len()).entered();
+      let wad_data = &mmap[..];
+      extraction_plan
+        .par_chunks((extraction_plan.len() / rayon::current_num_threads().max(1)).max(1))
+        .map(|slice| {
+          let mut e = 0;
+          let mut s = 0;
+          let mut corrupted = Vec::new();
+          for (chunk, out_path, rel) in slice {
+            let _reserved = reserve_memory(chunk.uncompressed_size() as u64);
+            let data = match decompress_chunk(wad_data, chunk) {
+              Ok(d) => d,
+              Err(err) => {
+                corrupted.push(diagnose_chunk_failure(wad_path, wad_data, chunk, err));
+                report_chunk(on_chunk, chunk.path_hash(), rel, false);
+                s += 1;
+                continue;
+              }
+            };
+            let mut final_path = out_path.clone();
+            let mut out_rel = Cow::Borrowed(rel.as_str());
+            if final_path.extension().is_none() {
+              if let Some(ext) = LeagueFileKind::identify_from_bytes_with_offset(&data, 64).extension() {
+                final_path.set_extension(ext);
+                out_rel = Cow::Owned(format!("{}.{}", rel, ext));
+              }
+            }
+            if if_exists == IfExists::Skip && final_path.exists() {
+              report_chunk(on_chunk, chunk.path_hash(), &out_rel, true);
+              s += 1;
+              continue;
+            }
+            if if_exists == IfExists::OverwriteIfDifferent && same_contents(&final_path, &data) {
+              tracker.mark(wad_path, chunk.path_hash());
+              report_chunk(on_chunk, chunk.path_hash(), &out_rel, true);
+              s += 1;
+              continue;
+            }
+            let ok = write_retrying(&final_path, &data).is_ok();
+            report_chunk(on_chunk, chunk.path_hash(), &out_rel, ok);
+            if ok {
+              tracker.mark(wad_path, chunk.path_hash());
+              e += 1;
+            } else {
+              s += 1;
             }
           }
-          if fs::write(final_path, &data).is_ok() { e += 1; } else { s += 1; }
-        }
-        (e, s)
-      })
-      .collect();
+          (e, s, corrupted)
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect());
 
-    for (e, s) in results {
-      extracted_count += e;
-      skipped_count += s;
-    }
+  for (e, s, corrupted) in results {
+    extracted_count += e;
+    skipped_count += s;
+    corrupted_chunks.extend(corrupted);
   }
+  tracker.flush();
 
-  if !hashed_files.is_empty() {
-    let json_path = output_root.join("hashed_files.json");
-    let mut existing: HashMap<String, String> = HashMap::new();
-    if let Ok(content) = fs::read_to_string(&json_path) {
-        if let Ok(map) = serde_json::from_str(&content) {
-            existing = map;
-        }
-    }
-    existing.extend(hashed_files);
-    if let Ok(json) = serde_json::to_string_pretty(&existing) {
-        let mut opts = fs::OpenOptions::new();
-        opts.write(true).create(true).truncate(true);
-        if let Ok(mut f) = opts.open(&json_path) {
-            let _ = f.write_all(json.as_bytes());
-        }
-    }
-  }
+  merge_hashed_files_sidecar(output_root, hashed_files);
 
-  WadExtractResult { success: true, error: None, extracted_count, skipped_count }
+  WadExtractResult { success: true, error: None, extracted_count, skipped_count, corrupted_chunks, insufficient_space: None }
 }
 
-// ── Hash extraction ──────────────────────────────────────────────────────────
+// ── extractByPrefix ──────────────────────────────────────────────────────────
+// "Extract this folder" from the tree view: the folder's chunks are found here
+// from the resolved names, so the renderer sends a prefix instead of one item
+// per file.
 
-#[napi(object)]
-pub struct ExtractHashesResult {
-  pub success: bool,
-  pub error: Option<String>,
-  #[napi(js_name = "newHashCount")]
-  pub new_hash_count: u32,
+/// Lowercased, forward-slash form of a prefix; None for an empty one.
+fn normalize_prefix(prefix: &str) -> Option<String> {
+  let p = prefix.trim().replace('\\', "/").to_ascii_lowercase();
+  let p = p.trim_start_matches('/');
+  (!p.is_empty()).then(|| p.to_string())
 }
 
-const PATH_PREFIXES: &[&[u8]] = &[
-  b"assets/", b"data/", b"maps/", b"levels/",
-  b"clientstates/", b"ux/", b"uiautoatlas/",
-];
+/// `path` is under `prefix`: a prefix ending in '/' is a folder, any other
+/// names a file or folder, so `skin6` does not match `skin61/...`.
+fn under_prefix(path: &str, prefix: &str) -> bool {
+  match path.get(..prefix.len()) {
+    Some(head) if head.eq_ignore_ascii_case(prefix) => {
+      let rest = &path[prefix.len()..];
+      prefix.ends_with('/') || rest.is_empty() || rest.starts_with('/')
+    }
+    _ => false,
+  }
+}
 
-fn xxhash_path(s: &str) -> u64 { xxh64(s.as_bytes(), 0) }
+/// The chunks of `wad_path` whose resolved path is under one of `prefixes`,
+/// to be extracted under that path.
+fn select_by_prefix(wad_path: &Path, prefixes: &[String], hash_dir: Option<&str>) -> Result<Vec<SelectedChunk>, String> {
+  let prefixes: Vec<String> = prefixes.iter().filter_map(|p| normalize_prefix(p)).collect();
+  if prefixes.is_empty() { return Err("At least one path prefix is required".to_string()); }
+  let hashes: Vec<u64> = {
+    let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open WAD: {}", e))?;
+    let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap WAD: {}", e))?;
+    let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount WAD: {}", e))?;
+    unique_chunks(wad.chunks()).0.iter().map(|c| c.path_hash()).collect()
+  };
 
-fn fnv1a_lower(s: &str) -> u32 {
-  let mut h: u32 = 0x811c9dc5;
-  for b in s.bytes().map(|b| b.to_ascii_lowercase()) {
-    h ^= b as u32;
-    h = h.wrapping_mul(0x01000193);
-  }
-  h
-}
-
-fn scan_bin_game_hashes(data: &[u8]) -> Vec<(u64, String)> {
-  if data.len() < 4 { return vec![]; }
-  if &data[..4] != b"PROP" && &data[..4] != b"PTCH" { return vec![]; }
-  let mut results = Vec::new();
-  let mut i = 0usize;
-  while i + 2 <= data.len() {
-    let len = u16::from_le_bytes([data[i], data[i + 1]]) as usize;
-    if len >= 8 && len <= 300 {
-      if let Some(slice) = data.get(i + 2..i + 2 + len) {
-        if let Ok(s) = std::str::from_utf8(slice) {
-          let lb = s.as_bytes();
-          let is_path = s.contains('/') && s.is_ascii()
-            && PATH_PREFIXES.iter().any(|p| lb.len() >= p.len() && lb[..p.len()].eq_ignore_ascii_case(p));
-          if is_path {
-            let lower = s.to_ascii_lowercase();
-            results.push((xxhash_path(&lower), lower.clone()));
-            if lower.ends_with(".dds") {
-              let slash = lower.rfind('/').map(|i| i + 1).unwrap_or(0);
-              let dir = &lower[..slash];
-              let fname = &lower[slash..];
-              let v2x = format!("{}2x_{}", dir, fname);
-              let v4x = format!("{}4x_{}", dir, fname);
-              results.push((xxhash_path(&v2x), v2x));
-              results.push((xxhash_path(&v4x), v4x));
-            }
-            if lower.ends_with(".bin") {
-              let py = format!("{}.py", &lower[..lower.len() - 4]);
-              results.push((xxhash_path(&py), py));
-            }
-            i += 2 + len;
-            continue;
-          }
-        }
-      }
-    }
-    i += 1;
+  let kind = wad_kind(wad_path).unwrap_or(WadKind::Game);
+  let names = resolve_wad_hashes(kind, &hashes, &HashLayers::open(hash_dir), hash_dir);
+  Ok(hashes
+    .into_iter()
+    .zip(names)
+    .filter(|(_, name)| prefixes.iter().any(|p| under_prefix(name, p)))
+    .map(|(hash, name)| (wad_path.to_path_buf(), hash, name))
+    .collect())
+}
+
+/// Extract every chunk of `wadPath` whose resolved path is under one of
+/// `prefixes` (e.g. `assets/characters/ahri/skins/skin61/`), keeping game
+/// paths. Matching ignores case; unresolved chunks never match.
+#[napi(js_name = "extractByPrefix")]
+pub fn extract_by_prefix(
+  wad_path: String,
+  prefixes: Vec<String>,
+  output_dir: String,
+  hash_dir: Option<String>,
+  options: Option<ExtractOptions>,
+) -> WadExtractResult {
+  let ExtractOptions { resume, atomic, output_template, if_exists } = options.unwrap_or_default();
+  if output_dir.is_empty() { return failed("Output directory is required".to_string()); }
+  if wad_path.is_empty() || !Path::new(&wad_path).exists() {
+    return failed(format!("WAD file not found: {}", wad_path));
   }
-  results
-}
-
-fn scan_skn_bin_hashes(data: &[u8]) -> Vec<(u32, String)> {
-  if data.len() < 12 { return vec![]; }
-  let magic = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-  if magic != 0x00112233 { return vec![]; }
-  let major = u16::from_le_bytes([data[4], data[5]]);
-  if major == 0 { return vec![]; }
-  let range_count = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
-  if range_count == 0 || range_count > 256 { return vec![]; }
-  let mut results = Vec::with_capacity(range_count);
-  let mut pos = 12usize;
-  for _ in 0..range_count {
-    if pos + 80 > data.len() { break; }
-    let name_bytes = &data[pos..pos + 64];
-    let null_pos = name_bytes.iter().position(|&b| b == 0).unwrap_or(64);
-    if let Ok(name) = std::str::from_utf8(&name_bytes[..null_pos]) {
-      if !name.is_empty() { results.push((fnv1a_lower(name), name.to_string())); }
-    }
-    pos += 80;
+  let if_exists = match IfExists::resolve(if_exists.as_deref(), None) {
+    Ok(p) => p,
+    Err(e) => return failed(e),
+  };
+  let selected = match select_by_prefix(Path::new(&wad_path), &prefixes, hash_dir.as_deref()) {
+    Ok(s) => s,
+    Err(e) => return failed(e),
+  };
+  let template = output_template.as_deref();
+  if atomic.unwrap_or(false) {
+    return extract_atomically(Path::new(&output_dir), resume.unwrap_or(false), if_exists, |staging| {
+      extract_selected_to(selected, staging, if_exists, Some(true), resume, template, None)
+    });
   }
-  results
+  extract_selected_to(selected, Path::new(&output_dir), if_exists, Some(true), resume, template, None)
+}
+
+pub struct ExtractByPrefixTask {
+  wad_path: String,
+  prefixes: Vec<String>,
+  output_dir: String,
+  hash_dir: Option<String>,
+  options: Option<ExtractOptions>,
 }
 
-fn parse_hash_value(s: &str) -> Option<u64> {
-  if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
-    return u64::from_str_radix(hex, 16).ok();
+#[napi]
+impl Task for ExtractByPrefixTask {
+  type Output = WadExtractResult;
+  type JsValue = WadExtractResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(extract_by_prefix(
+      self.wad_path.clone(),
+      std::mem::take(&mut self.prefixes),
+      self.output_dir.clone(),
+      self.hash_dir.clone(),
+      self.options.take(),
+    ))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
   }
-  if s.bytes().all(|b| b.is_ascii_digit()) { return s.parse::<u64>().ok(); }
-  if s.bytes().all(|b| b.is_ascii_hexdigit()) { return u64::from_str_radix(s, 16).ok(); }
-  None
 }
 
-/// Extract hashes from all BIN/SKN chunks inside a WAD file.
-/// Writes discovered hashes to `hash_dir/hashes.extracted.txt` only.
+#[napi(js_name = "extractByPrefixAsync")]
+pub fn extract_by_prefix_async(
+  wad_path: String,
+  prefixes: Vec<String>,
+  output_dir: String,
+  hash_dir: Option<String>,
+  options: Option<ExtractOptions>,
+) -> AsyncTask<ExtractByPrefixTask> {
+  AsyncTask::new(ExtractByPrefixTask { wad_path, prefixes, output_dir, hash_dir, options })
+}
+
+// ── Hash extraction ──────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct ExtractHashesResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "newHashCount")]
+  pub new_hash_count: u32,
+}
+
+/// Extract hashes from all BIN/SKN/BNK chunks inside a WAD file.
+/// Writes discovered hashes to `hash_dir/hashes.extracted.txt`, skin bin
+/// hashes to `hashes.binhashes.extracted.txt` and Wwise names to `hashes.wwise.txt`.
 #[napi(js_name = "extractHashesFromWad")]
 pub fn extract_hashes_from_wad(wad_path: String, hash_dir: Option<String>) -> ExtractHashesResult {
   if wad_path.is_empty() || !Path::new(&wad_path).exists() {
@@ -988,32 +1568,53 @@ pub fn extract_hashes_from_wad(wad_path: String, hash_dir: Option<String>) -> Ex
     Ok(f) => f,
     Err(e) => return ExtractHashesResult { success: false, error: Some(e.to_string()), new_hash_count: 0 },
   };
-  let mut wad = match Wad::mount(file) {
+  let mmap = match unsafe { Mmap::map(&file) } {
+    Ok(m) => m,
+    Err(e) => return ExtractHashesResult { success: false, error: Some(e.to_string()), new_hash_count: 0 },
+  };
+  let wad = match Wad::mount(Cursor::new(&mmap[..])) {
     Ok(w) => w,
     Err(e) => return ExtractHashesResult { success: false, error: Some(e.to_string()), new_hash_count: 0 },
   };
 
-  let chunks: Vec<_> = wad.chunks().iter().copied().collect();
-  let mut chunk_data: Vec<Vec<u8>> = Vec::with_capacity(chunks.len());
-  for chunk in &chunks {
-    chunk_data.push(wad.load_chunk_decompressed(chunk).map(|b| b.to_vec()).unwrap_or_default());
-  }
-
-  let game_hashes: HashMap<u64, String> = chunk_data
-    .par_iter()
-    .flat_map(|data| scan_bin_game_hashes(data))
-    .fold(HashMap::new, |mut m, (k, v)| { m.entry(k).or_insert(v); m })
-    .reduce(HashMap::new, |mut a, b| { for (k, v) in b { a.entry(k).or_insert(v); } a });
-
-  let bin_hashes: HashMap<u32, String> = chunk_data
+  let _span = info_span!("extract_hashes", wad = %wad_path).entered();
+  let scan_span = info_span!("scan").entered();
+  // Stream chunks through the scanners: each worker decompresses one chunk,
+  // scans it and drops it, so peak memory is a handful of chunks rather than
+  // the whole decompressed WAD (several GB for map WADs).
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let wad_data = &mmap[..];
+  type Found = (HashMap<u64, String>, HashMap<u32, String>, wwise::WwiseScan);
+  let (game_hashes, bin_hashes, audio): Found = run_cpu(|| chunks
     .par_iter()
-    .flat_map(|data| scan_skn_bin_hashes(data))
-    .fold(HashMap::new, |mut m, (k, v)| { m.entry(k).or_insert(v); m })
-    .reduce(HashMap::new, |mut a, b| { for (k, v) in b { a.entry(k).or_insert(v); } a });
+    .filter(|c| c.uncompressed_size() >= 4)
+    .fold(
+      || (HashMap::new(), HashMap::new(), wwise::WwiseScan::default()),
+      |(mut game, mut bin, mut audio): Found, chunk| {
+        let _reserved = reserve_memory(chunk.uncompressed_size() as u64);
+        let Ok(data) = decompress_chunk(wad_data, chunk) else { return (game, bin, audio) };
+        for (k, v) in scan_bin_game_hashes(&data) { game.entry(k).or_insert(v); }
+        for (k, v) in scan_skn_bin_hashes(&data) { bin.entry(k).or_insert(v); }
+        audio.scan(&data);
+        (game, bin, audio)
+      },
+    )
+    .reduce(
+      || (HashMap::new(), HashMap::new(), wwise::WwiseScan::default()),
+      |(mut ga, mut ba, aa): Found, (gb, bb, ab)| {
+        for (k, v) in gb { ga.entry(k).or_insert(v); }
+        for (k, v) in bb { ba.entry(k).or_insert(v); }
+        (ga, ba, aa.merge(ab))
+      },
+    ));
 
-  let new_count = (game_hashes.len() + bin_hashes.len()) as u32;
+  drop(scan_span);
+  let wwise_hashes = audio.resolved();
+  let new_count = (game_hashes.len() + bin_hashes.len() + wwise_hashes.len()) as u32;
+  info!(game = game_hashes.len(), bin = bin_hashes.len(), wwise = wwise_hashes.len(), "hashes found");
 
   if let Some(ref dir) = hash_dir {
+    let _save_span = info_span!("save").entered();
     let dir_path = Path::new(dir);
     let _ = fs::create_dir_all(dir_path);
 
@@ -1063,6 +1664,11 @@ pub fn extract_hashes_from_wad(wad_path: String, hash_dir: Option<String>) -> Ex
       let _ = fs::write(&bin_path, bin_out.as_bytes());
     }
 
+    // --- hashes.wwise.txt ---
+    if !wwise_hashes.is_empty() {
+      if let Err(e) = wwise::save_wwise_hashes(dir_path, &wwise_hashes) { warn!("{}", e); }
+    }
+
     // Invalidate extracted-hash overlay cache so subsequent resolve calls pick up the new file.
     {
       let extracted_path = dir_path.join("hashes.extracted.txt");
@@ -1081,9 +1687,8 @@ pub fn extract_hashes_from_wad(wad_path: String, hash_dir: Option<String>) -> Ex
 
 // ── Ritobin Conversion ───────────────────────────────────────────────────────
 
-use ltk_meta::Bin;
-use ltk_ritobin::{parse, write_with_hashes, HashMapProvider};
-use std::io::{BufReader, BufWriter};
+use quartz_core::ritobin;
+use std::io::BufReader;
 use ltk_texture::Texture;
 
 fn decode_dds_layer0_mip0_rgba(path: &str) -> Result<image::RgbaImage, String> {
@@ -1105,34 +1710,11 @@ fn decode_dds_layer0_mip0_rgba(path: &str) -> Result<image::RgbaImage, String> {
 
 #[napi(js_name = "binToPy")]
 pub fn bin_to_py(bin_path: String, py_path: String, hash_dir: Option<String>) -> bool {
-  let file = match fs::File::open(&bin_path) {
-    Ok(f) => f,
-    Err(e) => {
-      eprintln!("binToPy: failed to open bin file {}: {}", bin_path, e);
-      return false;
-    }
-  };
-  let mut reader = BufReader::new(file);
-  let tree = match Bin::from_reader(&mut reader) {
-    Ok(t) => t,
-    Err(e) => {
-      eprintln!("binToPy: failed to parse bin file: {:?}", e);
-      return false;
-    }
-  };
-
-  let mut hashes = HashMapProvider::new();
-  if let Some(dir) = hash_dir {
-    let p = Path::new(&dir);
-    if p.exists() {
-      hashes.load_from_directory(p);
-    }
-  }
-
-  let text = match write_with_hashes(&tree, &hashes) {
+  let hashes = ritobin::load_hash_provider(hash_dir.as_deref().map(Path::new));
+  let text = match ritobin::read_bin(Path::new(&bin_path)).and_then(|tree| ritobin::bin_to_text(&tree, &hashes)) {
     Ok(t) => t,
     Err(e) => {
-      eprintln!("binToPy: failed to format ritobin string: {:?}", e);
+      eprintln!("binToPy: {}", e);
       return false;
     }
   };
@@ -1155,30 +1737,13 @@ pub fn py_to_bin(py_path: String, bin_path: String) -> bool {
     }
   };
 
-  let file_ast = match parse(&text) {
-    Ok(ast) => ast,
-    Err(e) => {
-      eprintln!("pyToBin: failed to parse ritobin py file: {:?}", e);
-      return false;
-    }
-  };
-
-  let tree = file_ast.to_bin_tree();
-
-  let out_file = match fs::File::create(&bin_path) {
-    Ok(f) => f,
+  match ritobin::text_to_bin(&text).and_then(|tree| ritobin::write_bin(Path::new(&bin_path), &tree)) {
+    Ok(()) => true,
     Err(e) => {
-      eprintln!("pyToBin: failed to create bin file {}: {}", bin_path, e);
-      return false;
+      eprintln!("pyToBin: {}", e);
+      false
     }
-  };
-  let mut writer = BufWriter::new(out_file);
-  if let Err(e) = tree.to_writer(&mut writer) {
-    eprintln!("pyToBin: failed to write bin stream: {}", e);
-    return false;
   }
-
-  true
 }
 
 #[napi(object)]
@@ -1235,3 +1800,42 @@ pub fn decode_texture_to_png(file_path: String) -> napi::Result<DecodedTexturePn
     total_ms,
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wad_indexer_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn test_skip_checks_sniffed_extension() {
+    let dir = scratch("skip_sniffed");
+    let src = dir.join("src");
+    fs::create_dir_all(&src).unwrap();
+    // Unresolved chunk whose name gets `.dds` from its magic on extraction.
+    let mut dds = b"DDS ".to_vec();
+    dds.resize(128, 0);
+    fs::write(src.join("0123456789abcdef"), &dds).unwrap();
+    let (bytes, _) = wad_build::build_wad_bytes(&src).unwrap();
+    let wad = dir.join("Test.wad.client");
+    fs::write(&wad, bytes).unwrap();
+
+    let out = dir.join("out");
+    let first = extract_wad_to(&wad, &out, None, IfExists::Skip, None, None, None);
+    assert_eq!(first.extracted_count, 1);
+    let written = out.join("0123456789abcdef.dds");
+    assert_eq!(fs::read(&written).unwrap(), dds);
+
+    fs::write(&written, b"edited").unwrap();
+    let second = extract_wad_to(&wad, &out, None, IfExists::Skip, None, None, None);
+    assert_eq!(second.extracted_count, 0);
+    assert_eq!(second.skipped_count, 1);
+    assert_eq!(fs::read(&written).unwrap(), b"edited");
+    let _ = fs::remove_dir_all(&dir);
+  }
+}
@@ -0,0 +1,202 @@
+// ── Project lint ─────────────────────────────────────────────────────────────
+// One pass over a project collecting the problems other tools would only hit
+// later (in game, or when packing): bins naming assets that exist nowhere,
+// files left under their hex hash, uncompressed textures, VO banks moved off
+// the locale paths the game loads them from, and a missing skin bin. Each
+// finding carries a severity for the Problems panel.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use napi_derive::napi;
+use quartz_core::resolver::HashSource;
+
+use crate::asset_graph::build_graph;
+use crate::path_index::lookup_locations;
+use crate::project::read_project;
+use crate::skins::skin_bin_path;
+use crate::texture_report::{read_texture_header, TextureHeader};
+use crate::wad_build::{plan_wad_dir, project_wad_dirs};
+use crate::{xxhash_path, HashLayers};
+
+/// Uncompressed textures above this many pixels are reported (512x512).
+pub(crate) const MAX_UNCOMPRESSED_PIXELS: u64 = 512 * 512;
+/// Game folder VO banks are loaded from, per locale.
+const SOUNDS_ROOT: &str = "assets/sounds/";
+
+#[napi(object)]
+pub struct LintOptions {
+  /// Game path index (`buildGameIndex`); references found there aren't broken.
+  #[napi(js_name = "indexDir")]
+  pub index_dir: Option<String>,
+  /// Hash dir; without an index, references to known game paths aren't broken.
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+}
+
+#[napi(object)]
+pub struct LintProblem {
+  /// "error", "warning" or "info".
+  pub severity: String,
+  /// Stable identifier of the check, e.g. "brokenReference".
+  pub code: String,
+  pub message: String,
+  /// WAD folder of the offending file, e.g. "Ahri.wad.client".
+  pub wad: Option<String>,
+  /// File relative to the WAD folder.
+  pub file: Option<String>,
+}
+
+#[napi(object)]
+pub struct LintResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub problems: Vec<LintProblem>,
+  #[napi(js_name = "errorCount")]
+  pub error_count: u32,
+  #[napi(js_name = "warningCount")]
+  pub warning_count: u32,
+}
+
+fn problem(severity: &str, code: &str, message: String, wad: Option<&str>, file: Option<&str>) -> LintProblem {
+  LintProblem {
+    severity: severity.to_string(),
+    code: code.to_string(),
+    message,
+    wad: wad.map(str::to_string),
+    file: file.map(str::to_string),
+  }
+}
+
+fn is_vo_bank(path: &str) -> bool {
+  path.contains("/vo/") && (path.ends_with(".bnk") || path.ends_with(".wpk"))
+}
+
+fn lint(project: &Path, options: &LintOptions) -> Result<Vec<LintProblem>, String> {
+  let (data, _) = read_project(project)?;
+  let mut problems = Vec::new();
+  let mut present: HashSet<u64> = HashSet::new();
+  // Missing hash -> (path, referencing files)
+  let mut missing: HashMap<u64, (String, Vec<(String, String)>)> = HashMap::new();
+
+  for dir in project_wad_dirs(project) {
+    let wad = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let graph = build_graph(&dir, None)?;
+    let (plan, _) = plan_wad_dir(&dir)?;
+    let rel_of = |hash: u64| {
+      plan.get(&hash).map(|f| f.strip_prefix(&dir).unwrap_or(f).to_string_lossy().replace('\\', "/"))
+    };
+
+    for node in graph.nodes.iter().filter(|n| n.present) {
+      let (Some(file), Some(rel)) = (plan.get(&node.hash), rel_of(node.hash)) else { continue };
+      match node.path.as_deref() {
+        None => problems.push(problem(
+          "warning",
+          "unknownHash",
+          format!("{} is named by its path hash; it can't be repathed or previewed by type", rel),
+          Some(&wad),
+          Some(&rel),
+        )),
+        Some(path) if is_vo_bank(path) && !path.starts_with(SOUNDS_ROOT) => problems.push(problem(
+          "error",
+          "repathedVo",
+          format!("VO bank {} was moved out of {}; the game only loads VO from its locale path", path, SOUNDS_ROOT),
+          Some(&wad),
+          Some(&rel),
+        )),
+        _ => {}
+      }
+      if node.size == 0 {
+        problems.push(problem("error", "emptyFile", format!("{} is empty", rel), Some(&wad), Some(&rel)));
+      }
+      if let Some(TextureHeader { width: w, height: h, .. }) = read_texture_header(file).filter(|t| !t.compressed) {
+        if w as u64 * h as u64 > MAX_UNCOMPRESSED_PIXELS {
+          problems.push(problem(
+            "warning",
+            "uncompressedTexture",
+            format!("{} is an uncompressed {}x{} texture ({:.1} MB); use BC1/BC3", rel, w, h, node.size as f64 / 1_048_576.0),
+            Some(&wad),
+            Some(&rel),
+          ));
+        }
+      }
+      present.insert(node.hash);
+    }
+
+    for (from, to) in &graph.edges {
+      let target = &graph.nodes[*to as usize];
+      if target.present { continue; }
+      let Some(from_rel) = rel_of(graph.nodes[*from as usize].hash) else { continue };
+      let entry = missing.entry(target.hash).or_insert_with(|| (target.path.clone().unwrap_or_default(), Vec::new()));
+      entry.1.push((wad.clone(), from_rel));
+    }
+  }
+
+  // References into another project WAD folder are fine.
+  missing.retain(|h, _| !present.contains(h));
+  let mut hashes: Vec<u64> = missing.keys().copied().collect();
+  hashes.sort_unstable();
+  let in_game: HashSet<u64> = if let Some(index_dir) = options.index_dir.as_deref() {
+    let locations = lookup_locations(Path::new(index_dir), &hashes)?;
+    hashes.iter().zip(locations).filter(|(_, l)| !l.is_empty()).map(|(h, _)| *h).collect()
+  } else if let Some(hash_dir) = options.hash_dir.as_deref() {
+    let names = HashLayers::open(Some(hash_dir)).resolve(&hashes);
+    hashes.iter().zip(names).filter(|(_, n)| n.source != HashSource::Hex).map(|(h, _)| *h).collect()
+  } else {
+    // Nothing to check game assets against.
+    hashes.iter().copied().collect()
+  };
+  let checked = options.index_dir.is_some() || options.hash_dir.is_some();
+  for hash in hashes.iter().filter(|h| !in_game.contains(h)) {
+    let (path, referrers) = &missing[hash];
+    for (wad, from) in referrers {
+      let message = if is_vo_bank(path) && !path.starts_with(SOUNDS_ROOT) {
+        format!("{} references VO bank {} outside {}; the game only loads VO from its locale path", from, path, SOUNDS_ROOT)
+      } else {
+        format!("{} references {}, which is not in the project or the game", from, path)
+      };
+      problems.push(problem("error", "brokenReference", message, Some(wad), Some(from)));
+    }
+  }
+  if !checked && !hashes.is_empty() {
+    problems.push(problem(
+      "info",
+      "referencesNotChecked",
+      format!("{} referenced assets aren't in the project; pass an index or hash dir to check them against the game", hashes.len()),
+      None,
+      None,
+    ));
+  }
+
+  if let Some(champion) = data.champion.as_deref() {
+    let skin_bin = skin_bin_path(champion, data.skin_id.unwrap_or(0));
+    if !present.contains(&xxhash_path(&skin_bin)) {
+      problems.push(problem(
+        "error",
+        "missingSkinBin",
+        format!("The project has no {}; the skin won't load", skin_bin),
+        None,
+        None,
+      ));
+    }
+  }
+
+  let rank = |s: &str| match s { "error" => 0, "warning" => 1, _ => 2 };
+  problems.sort_by(|a, b| (rank(&a.severity), &a.wad, &a.file).cmp(&(rank(&b.severity), &b.wad, &b.file)));
+  Ok(problems)
+}
+
+/// Check a project for problems: broken bin references, files left under
+/// their hash, uncompressed textures, repathed VO and a missing skin bin.
+#[napi(js_name = "lintProject")]
+pub fn lint_project(project_path: String, options: Option<LintOptions>) -> LintResult {
+  let options = options.unwrap_or(LintOptions { index_dir: None, hash_dir: None });
+  match lint(Path::new(&project_path), &options) {
+    Ok(problems) => {
+      let count = |s: &str| problems.iter().filter(|p| p.severity == s).count() as u32;
+      let (error_count, warning_count) = (count("error"), count("warning"));
+      LintResult { success: true, error: None, problems, error_count, warning_count }
+    }
+    Err(e) => LintResult { success: false, error: Some(e), problems: Vec::new(), error_count: 0, warning_count: 0 },
+  }
+}
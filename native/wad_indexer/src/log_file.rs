@@ -0,0 +1,269 @@
+// ── Log file ─────────────────────────────────────────────────────────────────
+// `FileLogLayer` appends tracing events and span timings as JSON lines to
+// `{logDir}/quartz.log.jsonl`, rotating to `quartz.1.log.jsonl` ... when the
+// file grows past a size cap. `exportLogs` zips the logs together with
+// environment info so bug reports carry the native side of what happened.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use napi_derive::napi;
+use serde_json::{json, Map, Value};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::logging::{install_subscriber, span_path, FieldCollector};
+use crate::threads::get_thread_config;
+
+const LOG_FILE: &str = "quartz.log.jsonl";
+const DEFAULT_MAX_FILE_BYTES: u32 = 5 * 1024 * 1024;
+const DEFAULT_MAX_FILES: u32 = 5;
+
+struct RotatingLog {
+  dir: PathBuf,
+  file: File,
+  size: u64,
+  max_bytes: u64,
+  /// Rotated files kept besides the live one.
+  max_files: u32,
+  level: Level,
+}
+
+static LOG: Mutex<Option<RotatingLog>> = Mutex::new(None);
+
+fn rotated_name(n: u32) -> String {
+  format!("quartz.{}.log.jsonl", n)
+}
+
+impl RotatingLog {
+  fn open(dir: &Path) -> io::Result<(File, u64)> {
+    fs::create_dir_all(dir)?;
+    let file = OpenOptions::new().create(true).append(true).open(dir.join(LOG_FILE))?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+  }
+
+  fn rotate(&mut self) -> io::Result<()> {
+    let _ = fs::remove_file(self.dir.join(rotated_name(self.max_files)));
+    for n in (1..self.max_files).rev() {
+      let _ = fs::rename(self.dir.join(rotated_name(n)), self.dir.join(rotated_name(n + 1)));
+    }
+    if self.max_files > 0 {
+      fs::rename(self.dir.join(LOG_FILE), self.dir.join(rotated_name(1)))?;
+    } else {
+      fs::remove_file(self.dir.join(LOG_FILE))?;
+    }
+    let (file, size) = Self::open(&self.dir)?;
+    self.file = file;
+    self.size = size;
+    Ok(())
+  }
+
+  fn write_line(&mut self, line: &str) {
+    if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_bytes {
+      // Keep appending to the current file if rotation fails (e.g. it's locked).
+      let _ = self.rotate();
+    }
+    if writeln!(self.file, "{}", line).is_ok() {
+      self.size += line.len() as u64 + 1;
+    }
+  }
+}
+
+fn now_ms() -> f64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as f64).unwrap_or(0.0)
+}
+
+/// "key=value" pairs from `FieldCollector` as a JSON object.
+fn fields_object(fields: &[String]) -> Value {
+  let map: Map<String, Value> = fields
+    .iter()
+    .map(|f| match f.split_once('=') {
+      Some((k, v)) => (k.to_string(), Value::String(v.to_string())),
+      None => (f.clone(), Value::Null),
+    })
+    .collect();
+  Value::Object(map)
+}
+
+fn write_entry(level: &Level, entry: Value) {
+  let mut log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+  let Some(log) = log.as_mut() else { return };
+  // Level ordering in tracing: TRACE > DEBUG > INFO > WARN > ERROR.
+  if *level > log.level { return; }
+  log.write_line(&entry.to_string());
+}
+
+fn file_logging_enabled() -> bool {
+  LOG.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+struct SpanStart(Instant);
+
+/// Writes events and closed-span timings to the file set up by `enableFileLogging`.
+pub(crate) struct FileLogLayer;
+
+impl<S> Layer<S> for FileLogLayer
+where
+  S: Subscriber + for<'a> LookupSpan<'a>,
+{
+  fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+    let Some(span) = ctx.span(id) else { return };
+    span.extensions_mut().insert(SpanStart(Instant::now()));
+  }
+
+  fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+    if !file_logging_enabled() { return; }
+    let mut fields = FieldCollector::default();
+    event.record(&mut fields);
+    let meta = event.metadata();
+    let span = ctx.event_span(event).and_then(|s| span_path(&ctx, &s.id()));
+    write_entry(meta.level(), json!({
+      "ts": now_ms(),
+      "kind": "event",
+      "level": meta.level().as_str().to_ascii_lowercase(),
+      "target": meta.target(),
+      "message": fields.message,
+      "span": span,
+      "fields": fields_object(&fields.fields),
+    }));
+  }
+
+  fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+    if !file_logging_enabled() { return; }
+    let Some(span) = ctx.span(&id) else { return };
+    let Some(start) = span.extensions_mut().remove::<SpanStart>() else { return };
+    let meta = span.metadata();
+    write_entry(meta.level(), json!({
+      "ts": now_ms(),
+      "kind": "span",
+      "level": meta.level().as_str().to_ascii_lowercase(),
+      "target": meta.target(),
+      "span": span_path(&ctx, &id),
+      "durationMs": start.0.elapsed().as_secs_f64() * 1000.0,
+    }));
+  }
+}
+
+#[napi(object)]
+pub struct FileLoggingOptions {
+  /// Rotate once the live file would exceed this. Default 5 MB.
+  #[napi(js_name = "maxFileBytes")]
+  pub max_file_bytes: Option<u32>,
+  /// Rotated files to keep. Default 5.
+  #[napi(js_name = "maxFiles")]
+  pub max_files: Option<u32>,
+  /// Most verbose level written: "error", "warn", "info" (default), "debug" or "trace".
+  pub level: Option<String>,
+}
+
+#[napi(object)]
+pub struct LogFileResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// The live log file, or the written zip for `exportLogs`.
+  pub path: Option<String>,
+}
+
+fn parse_level(level: Option<&str>) -> Result<Level, String> {
+  match level.map(str::to_ascii_lowercase).as_deref() {
+    None | Some("info") => Ok(Level::INFO),
+    Some("error") => Ok(Level::ERROR),
+    Some("warn") => Ok(Level::WARN),
+    Some("debug") => Ok(Level::DEBUG),
+    Some("trace") => Ok(Level::TRACE),
+    Some(other) => Err(format!("Unknown log level: {}", other)),
+  }
+}
+
+fn enable(log_dir: &Path, options: Option<FileLoggingOptions>) -> Result<PathBuf, String> {
+  let options = options.unwrap_or(FileLoggingOptions { max_file_bytes: None, max_files: None, level: None });
+  let level = parse_level(options.level.as_deref())?;
+  let (file, size) = RotatingLog::open(log_dir).map_err(|e| format!("Failed to open log file in {}: {}", log_dir.display(), e))?;
+  install_subscriber();
+  *LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(RotatingLog {
+    dir: log_dir.to_path_buf(),
+    file,
+    size,
+    max_bytes: options.max_file_bytes.unwrap_or(DEFAULT_MAX_FILE_BYTES).max(1024) as u64,
+    max_files: options.max_files.unwrap_or(DEFAULT_MAX_FILES),
+    level,
+  });
+  Ok(log_dir.join(LOG_FILE))
+}
+
+/// Start appending native logs to `{logDir}/quartz.log.jsonl` (typically
+/// `{userData}/logs`). Calling again switches directory or options.
+#[napi(js_name = "enableFileLogging")]
+pub fn enable_file_logging(log_dir: String, options: Option<FileLoggingOptions>) -> LogFileResult {
+  match enable(Path::new(&log_dir), options) {
+    Ok(path) => LogFileResult { success: true, error: None, path: Some(path.to_string_lossy().into_owned()) },
+    Err(e) => LogFileResult { success: false, error: Some(e), path: None },
+  }
+}
+
+#[napi(js_name = "disableFileLogging")]
+pub fn disable_file_logging() {
+  *LOG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn environment_info(extra: Option<HashMap<String, String>>) -> Value {
+  let threads = get_thread_config();
+  json!({
+    "exportedAt": now_ms(),
+    "os": std::env::consts::OS,
+    "arch": std::env::consts::ARCH,
+    "family": std::env::consts::FAMILY,
+    "cpuCount": std::thread::available_parallelism().map(|n| n.get()).unwrap_or(0),
+    "ioThreads": threads.io,
+    "cpuThreads": threads.cpu,
+    "nativeVersion": env!("CARGO_PKG_VERSION"),
+    "app": extra.unwrap_or_default(),
+  })
+}
+
+fn export(log_dir: &Path, out_zip: &Path, extra: Option<HashMap<String, String>>) -> Result<(), String> {
+  let mut logs: Vec<PathBuf> = fs::read_dir(log_dir)
+    .map_err(|e| format!("Failed to read {}: {}", log_dir.display(), e))?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("quartz.") && n.ends_with(".log.jsonl")))
+    .collect();
+  logs.sort();
+
+  if let Some(parent) = out_zip.parent() { let _ = fs::create_dir_all(parent); }
+  let file = File::create(out_zip).map_err(|e| format!("Failed to create {}: {}", out_zip.display(), e))?;
+  let mut zip = ZipWriter::new(file);
+  let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+  let env = serde_json::to_string_pretty(&environment_info(extra)).unwrap_or_default();
+  zip.start_file("environment.json", deflated).map_err(|e| format!("Failed to write environment.json: {}", e))?;
+  zip.write_all(env.as_bytes()).map_err(|e| format!("Failed to write environment.json: {}", e))?;
+  for path in logs {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    zip.start_file(format!("logs/{}", name), deflated).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    zip.write_all(&data).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+  }
+  zip.finish().map_err(|e| format!("Failed to finish {}: {}", out_zip.display(), e))?;
+  Ok(())
+}
+
+/// Bundle the logs in `logDir` and environment info (OS, CPU, thread pools,
+/// plus `appInfo` from the frontend, e.g. app/Electron version) into `outZip`.
+#[napi(js_name = "exportLogs")]
+pub fn export_logs(log_dir: String, out_zip: String, app_info: Option<HashMap<String, String>>) -> LogFileResult {
+  match export(Path::new(&log_dir), Path::new(&out_zip), app_info) {
+    Ok(()) => LogFileResult { success: true, error: None, path: Some(out_zip) },
+    Err(e) => LogFileResult { success: false, error: Some(e), path: None },
+  }
+}
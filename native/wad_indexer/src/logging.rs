@@ -0,0 +1,166 @@
+// ── Frontend logging ─────────────────────────────────────────────────────────
+// `FrontendLogLayer` forwards tracing events and span timings to a JS listener
+// (the app's log panel). Spans report `{ span, durationMs }` when they close,
+// with the span path ("extract_wad/write") so a slow extraction can be broken
+// down into TOC parsing, hash resolution, decompression and disk writes.
+
+use std::fmt::Write as _;
+use std::sync::{OnceLock, RwLock};
+use std::time::Instant;
+
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::log_file::FileLogLayer;
+
+type LogListener = ThreadsafeFunction<FrontendLogEvent, ErrorStrategy::Fatal>;
+
+static LISTENER: RwLock<Option<LogListener>> = RwLock::new(None);
+static SUBSCRIBER_INSTALLED: OnceLock<bool> = OnceLock::new();
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct FrontendLogEvent {
+  /// "event" for log lines, "span" for a finished span.
+  pub kind: String,
+  /// "error", "warn", "info", "debug" or "trace".
+  pub level: String,
+  pub target: String,
+  pub message: String,
+  /// Span path, outermost first: "extract_wad/write".
+  pub span: Option<String>,
+  #[napi(js_name = "durationMs")]
+  pub duration_ms: Option<f64>,
+  /// Recorded fields as "key=value" pairs.
+  pub fields: Vec<String>,
+}
+
+struct SpanTiming {
+  started: Instant,
+  fields: Vec<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct FieldCollector {
+  pub(crate) message: String,
+  pub(crate) fields: Vec<String>,
+}
+
+impl Visit for FieldCollector {
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    if field.name() == "message" {
+      let _ = write!(self.message, "{:?}", value);
+    } else {
+      self.fields.push(format!("{}={:?}", field.name(), value));
+    }
+  }
+
+  fn record_str(&mut self, field: &Field, value: &str) {
+    if field.name() == "message" {
+      self.message.push_str(value);
+    } else {
+      self.fields.push(format!("{}={}", field.name(), value));
+    }
+  }
+}
+
+/// Forwards events and closed-span timings to the listener set by `setLogListener`.
+pub(crate) struct FrontendLogLayer;
+
+fn emit(event: FrontendLogEvent) {
+  if let Some(listener) = LISTENER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+    listener.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+  }
+}
+
+fn has_listener() -> bool {
+  LISTENER.read().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+pub(crate) fn span_path<S>(ctx: &Context<'_, S>, id: &Id) -> Option<String>
+where
+  S: Subscriber + for<'a> LookupSpan<'a>,
+{
+  let scope = ctx.span_scope(id)?;
+  let names: Vec<&str> = scope.from_root().map(|s| s.name()).collect();
+  Some(names.join("/"))
+}
+
+impl<S> Layer<S> for FrontendLogLayer
+where
+  S: Subscriber + for<'a> LookupSpan<'a>,
+{
+  fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+    let Some(span) = ctx.span(id) else { return };
+    let mut fields = FieldCollector::default();
+    attrs.record(&mut fields);
+    span.extensions_mut().insert(SpanTiming { started: Instant::now(), fields: fields.fields });
+  }
+
+  fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+    if !has_listener() { return; }
+    let mut fields = FieldCollector::default();
+    event.record(&mut fields);
+    let meta = event.metadata();
+    let span = ctx.event_span(event).and_then(|s| span_path(&ctx, &s.id()));
+    emit(FrontendLogEvent {
+      kind: "event".to_string(),
+      level: meta.level().as_str().to_ascii_lowercase(),
+      target: meta.target().to_string(),
+      message: fields.message,
+      span,
+      duration_ms: None,
+      fields: fields.fields,
+    });
+  }
+
+  fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+    if !has_listener() { return; }
+    let Some(span) = ctx.span(&id) else { return };
+    let Some(timing) = span.extensions_mut().remove::<SpanTiming>() else { return };
+    let meta = span.metadata();
+    emit(FrontendLogEvent {
+      kind: "span".to_string(),
+      level: meta.level().as_str().to_ascii_lowercase(),
+      target: meta.target().to_string(),
+      message: String::new(),
+      span: span_path(&ctx, &id),
+      duration_ms: Some(timing.started.elapsed().as_secs_f64() * 1000.0),
+      fields: timing.fields,
+    });
+  }
+}
+
+/// Install the global subscriber once, with both the frontend and the log file
+/// layer; each does nothing until `setLogListener` / `enableFileLogging` is called.
+pub(crate) fn install_subscriber() {
+  SUBSCRIBER_INSTALLED.get_or_init(|| {
+    let registry = tracing_subscriber::registry().with(FrontendLogLayer).with(FileLogLayer);
+    tracing::subscriber::set_global_default(registry).is_ok()
+  });
+}
+
+/// Route native log events and span timings to `callback(event)`.
+/// Pass `null` to stop forwarding.
+#[napi(js_name = "setLogListener")]
+pub fn set_log_listener(env: Env, callback: Option<JsFunction>) -> napi::Result<()> {
+  install_subscriber();
+  let listener = match callback {
+    Some(cb) => {
+      let mut tsfn: LogListener = cb.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+      // Don't keep the process alive just for logging.
+      tsfn.unref(&env)?;
+      Some(tsfn)
+    }
+    None => None,
+  };
+  *LISTENER.write().unwrap_or_else(|e| e.into_inner()) = listener;
+  Ok(())
+}
@@ -0,0 +1,242 @@
+// ── Mod archive import ───────────────────────────────────────────────────────
+// Unpacks a .fantome / .zip / .7z mod into a project's content layout so an existing
+// mod can be opened and edited. Packed WADs are spooled to disk and extracted
+// chunk-by-chunk with hash resolution; WAD folders shipped loose are copied
+// as-is. Nothing is held in memory beyond one chunk at a time.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ltk_file::LeagueFileKind;
+use ltk_wad::Wad;
+use napi_derive::napi;
+use xxhash_rust::xxh64::Xxh64;
+
+use crate::archive::for_each_archive_file;
+use crate::fantome::ModMeta;
+use crate::paths::{long_path, retry_on_lock, sanitize_rel_path, write_retrying};
+use crate::wad_build::{wad_file_name_for_dir, HASHED_FILES_JSON};
+use crate::{is_safe_relative_path, normalize_rel_path, unique_chunks, HashLayers};
+
+/// Written next to `content/` to remember where an imported project came from.
+pub(crate) const PROVENANCE_JSON: &str = "provenance.json";
+/// Largest uncompressed / compressed size ratio accepted from an imported
+/// WAD's TOC. Real chunks stay far below it; a forged size above it would
+/// otherwise be allocated as-is before decompression.
+const MAX_CHUNK_EXPANSION: usize = 1024;
+
+#[napi(object)]
+pub struct ModImportResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// WAD folder names created under `content/`.
+  pub wads: Vec<String>,
+  #[napi(js_name = "extractedCount")]
+  pub extracted_count: u32,
+  #[napi(js_name = "skippedCount")]
+  pub skipped_count: u32,
+  pub meta: Option<ModMeta>,
+}
+
+fn meta_from_info_json(content: &str) -> Option<ModMeta> {
+  let json: serde_json::Value = serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()?;
+  let field = |k: &str| json.get(k).and_then(|v| v.as_str()).map(|s| s.to_string());
+  Some(ModMeta {
+    name: field("Name").unwrap_or_default(),
+    author: field("Author"),
+    version: field("Version"),
+    description: field("Description"),
+    preview: None,
+  })
+}
+
+/// Split an archive entry into (WAD file name, path inside the WAD) if it belongs to one.
+/// "WAD/Ahri.wad.client" -> ("Ahri.wad.client", "")
+/// "WAD/Ahri.wad.client/data/x.bin" -> ("Ahri.wad.client", "data/x.bin")
+pub(crate) fn split_wad_entry(name: &str) -> Option<(String, String)> {
+  let name = normalize_rel_path(name);
+  let lower = name.to_ascii_lowercase();
+  for marker in [".wad.client", ".wad"] {
+    let Some(pos) = lower.find(marker) else { continue };
+    let end = pos + marker.len();
+    if end != lower.len() && lower.as_bytes()[end] != b'/' { continue; }
+    let wad_start = name[..pos].rfind('/').map(|i| i + 1).unwrap_or(0);
+    let wad_name = wad_file_name_for_dir(&name[wad_start..end]);
+    let inner = name[end..].trim_start_matches('/').to_string();
+    return Some((wad_name, inner));
+  }
+  None
+}
+
+/// Extract the packed WAD spooled at `wad_path` into `out_dir`. Chunks whose
+/// TOC entry points outside the file or claims an implausible uncompressed
+/// size are skipped. Returns (extracted, skipped).
+fn extract_wad_file(
+  wad_path: &Path,
+  out_dir: &Path,
+  hash_dir: Option<&str>,
+) -> Result<(u32, u32), String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let wad_len = file.metadata().map(|m| m.len()).unwrap_or(0);
+  let mut wad = Wad::mount(file).map_err(|e| format!("Failed to mount WAD: {}", e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+  let layers = HashLayers::open(hash_dir);
+  let resolved = layers.resolve_names(&hashes);
+
+  let mut hashed_files: BTreeMap<String, String> = BTreeMap::new();
+  let (mut extracted, mut skipped) = (0u32, 0u32);
+  for (chunk, resolved) in chunks.iter().zip(resolved) {
+    let in_file = (chunk.data_offset() as u64).saturating_add(chunk.compressed_size() as u64) <= wad_len;
+    if !in_file || chunk.uncompressed_size() > chunk.compressed_size().saturating_mul(MAX_CHUNK_EXPANSION) {
+      skipped += 1;
+      continue;
+    }
+    let data = match wad.load_chunk_decompressed(chunk) {
+      Ok(d) => d,
+      Err(_) => { skipped += 1; continue; }
+    };
+    let rel = normalize_rel_path(&resolved);
+    let name_too_long = Path::new(&rel).file_name().map(|n| n.len() > 255).unwrap_or(true);
+    let mut out_path = long_path(&if is_safe_relative_path(&rel) && !name_too_long {
+      let safe = sanitize_rel_path(&rel);
+      if let Cow::Owned(name) = &safe { hashed_files.insert(name.clone(), resolved.clone()); }
+      out_dir.join(safe.as_ref())
+    } else {
+      // Unresolved or unusable names go to the root as "{hash}.ext" so repacking keeps the hash.
+      let ext = Path::new(&rel).extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+      let name = format!("{:016x}{}", chunk.path_hash(), ext);
+      if rel != format!("{:016x}", chunk.path_hash()) { hashed_files.insert(name.clone(), resolved.clone()); }
+      out_dir.join(name)
+    });
+    if out_path.extension().is_none() {
+      if let Some(ext) = LeagueFileKind::identify_from_bytes_with_offset(&data, 64).extension() {
+        out_path.set_extension(ext);
+      }
+    }
+    if let Some(parent) = out_path.parent() {
+      let _ = fs::create_dir_all(parent);
+    }
+    if write_retrying(&out_path, &data).is_ok() { extracted += 1; } else { skipped += 1; }
+  }
+
+  if !hashed_files.is_empty() {
+    let json = serde_json::to_string_pretty(&hashed_files).unwrap_or_default();
+    let _ = fs::write(out_dir.join(HASHED_FILES_JSON), json);
+  }
+  Ok((extracted, skipped))
+}
+
+/// Size and xxh64 of a file, read in blocks.
+fn hash_file(path: &Path) -> io::Result<(u64, u64)> {
+  let mut file = fs::File::open(path)?;
+  let mut hasher = Xxh64::new(0);
+  let mut buf = vec![0u8; 1 << 20];
+  let mut size = 0u64;
+  loop {
+    let n = file.read(&mut buf)?;
+    if n == 0 { break; }
+    hasher.update(&buf[..n]);
+    size += n as u64;
+  }
+  Ok((size, hasher.digest()))
+}
+
+/// Copy `reader` into a new file at `path`.
+fn write_stream(path: &Path, reader: &mut dyn Read) -> io::Result<u64> {
+  let mut file = retry_on_lock(|| fs::File::create(path))?;
+  io::copy(reader, &mut file)
+}
+
+fn write_provenance(project: &Path, archive: &Path, meta: Option<&ModMeta>, wads: &[String]) {
+  let (size, hash) = hash_file(archive)
+    .map(|(size, hash)| (size, format!("{:016x}", hash)))
+    .unwrap_or((0, String::new()));
+  let imported_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+  let json = serde_json::json!({
+    "source": archive.to_string_lossy(),
+    "sourceSize": size,
+    "sourceXxh64": hash,
+    "importedAt": imported_at,
+    "meta": meta.map(|m| serde_json::json!({
+      "name": m.name,
+      "author": m.author,
+      "version": m.version,
+      "description": m.description,
+    })),
+    "wads": wads,
+  });
+  if let Ok(s) = serde_json::to_string_pretty(&json) {
+    let _ = fs::write(project.join(PROVENANCE_JSON), s);
+  }
+}
+
+fn import_archive(archive_path: &Path, project: &Path, hash_dir: Option<&str>) -> Result<ModImportResult, String> {
+  let content = project.join("content");
+  fs::create_dir_all(&content).map_err(|e| format!("Failed to create {}: {}", content.display(), e))?;
+
+  let mut meta = None;
+  let mut wads: Vec<String> = Vec::new();
+  let (mut extracted, mut skipped) = (0u32, 0u32);
+  for_each_archive_file(archive_path, |name, entry| {
+    if normalize_rel_path(name).eq_ignore_ascii_case("META/info.json") {
+      let mut s = String::new();
+      if entry.read_to_string(&mut s).is_ok() { meta = meta_from_info_json(&s); }
+      return Ok(());
+    }
+    let Some((wad_name, inner)) = split_wad_entry(name) else { skipped += 1; return Ok(()) };
+    let wad_dir = content.join(&wad_name);
+    if !wads.contains(&wad_name) { wads.push(wad_name.clone()); }
+
+    if inner.is_empty() {
+      // Archive entries can't seek, so the WAD is mounted from a spooled copy.
+      let spool = project.join(format!("{}.import", wad_name));
+      let result = write_stream(&spool, entry)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))
+        .and_then(|_| extract_wad_file(&spool, &wad_dir, hash_dir));
+      let _ = fs::remove_file(&spool);
+      let (e, s) = result.map_err(|e| format!("{}: {}", wad_name, e))?;
+      extracted += e;
+      skipped += s;
+    } else if is_safe_relative_path(&inner) {
+      let out = long_path(&wad_dir.join(&inner));
+      if let Some(parent) = out.parent() { let _ = fs::create_dir_all(parent); }
+      if write_stream(&out, entry).is_ok() {
+        extracted += 1;
+      } else {
+        let _ = fs::remove_file(&out);
+        skipped += 1;
+      }
+    } else {
+      skipped += 1;
+    }
+    Ok(())
+  })?;
+
+  if wads.is_empty() {
+    return Err(format!("No WADs found in {}", archive_path.display()));
+  }
+  write_provenance(project, archive_path, meta.as_ref(), &wads);
+  Ok(ModImportResult { success: true, error: None, wads, extracted_count: extracted, skipped_count: skipped, meta })
+}
+
+/// Unpack a .fantome / .zip / .7z mod into `{projectPath}/content/{Name}.wad.client/...`
+/// and record where it came from in `provenance.json`.
+#[napi(js_name = "importModArchive")]
+pub fn import_mod_archive(archive_path: String, project_path: String, hash_dir: Option<String>) -> ModImportResult {
+  match import_archive(Path::new(&archive_path), Path::new(&project_path), hash_dir.as_deref()) {
+    Ok(r) => r,
+    Err(e) => ModImportResult {
+      success: false,
+      error: Some(e),
+      wads: Vec::new(),
+      extracted_count: 0,
+      skipped_count: 0,
+      meta: None,
+    },
+  }
+}
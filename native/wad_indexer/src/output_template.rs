@@ -0,0 +1,82 @@
+// ── Output path templates ────────────────────────────────────────────────────
+// Lets extraction callers choose the output layout instead of always mirroring
+// the game path: `{wadName}/{path}` keeps a batch of WADs extracted into one
+// root apart, `{basename}` pulls single files without the deep tree. Names a
+// template makes collide get the path hash appended, like flat extraction.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use quartz_core::paths::normalize_rel_path;
+
+const PLACEHOLDERS: &[&str] = &["path", "dir", "basename", "stem", "ext", "wadName", "hash"];
+
+pub(crate) struct OutputTemplate {
+  template: String,
+  used: HashSet<String>,
+}
+
+impl OutputTemplate {
+  /// Validate `template`. Supported placeholders: `{path}` (full game path),
+  /// `{dir}`, `{basename}`, `{stem}`, `{ext}` (without dot), `{wadName}` (WAD
+  /// file name without `.wad.client`) and `{hash}` (16-digit path hash).
+  pub(crate) fn parse(template: &str) -> Result<Self, String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+      let end = rest[start..].find('}').ok_or_else(|| format!("Unclosed placeholder in output template: {}", template))?;
+      let name = &rest[start + 1..start + end];
+      if !PLACEHOLDERS.contains(&name) {
+        return Err(format!("Unknown placeholder {{{}}} in output template", name));
+      }
+      rest = &rest[start + end + 1..];
+    }
+    if template.trim().is_empty() {
+      return Err("Output template is empty".to_string());
+    }
+    Ok(OutputTemplate { template: template.to_string(), used: HashSet::new() })
+  }
+
+  /// Output path of the chunk `path_hash` (game path `rel`) from `wad_path`.
+  /// Renamed outputs are recorded in `hashed_files` so repacking finds the game path.
+  pub(crate) fn render(
+    &mut self,
+    wad_path: &Path,
+    rel: &str,
+    path_hash: u64,
+    hashed_files: &mut HashMap<String, String>,
+  ) -> String {
+    let (dir, basename) = rel.rsplit_once('/').unwrap_or(("", rel));
+    let (stem, ext) = match basename.rsplit_once('.') {
+      Some((s, e)) if !s.is_empty() => (s, e),
+      _ => (basename, ""),
+    };
+    let wad_name = wad_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let lower = wad_name.to_ascii_lowercase();
+    let wad_name = [".wad.client", ".wad.mobile", ".wad"].iter()
+      .find(|s| lower.ends_with(*s))
+      .map(|s| wad_name[..wad_name.len() - s.len()].to_string())
+      .unwrap_or(wad_name);
+    let hash = format!("{:016x}", path_hash);
+
+    let mut out = self.template
+      .replace("{path}", rel)
+      .replace("{dir}", dir)
+      .replace("{basename}", basename)
+      .replace("{stem}", stem)
+      .replace("{ext}", ext)
+      .replace("{wadName}", &wad_name)
+      .replace("{hash}", &hash);
+    out = normalize_rel_path(&out);
+
+    if self.used.contains(&out.to_ascii_lowercase()) {
+      let suffix = if ext.is_empty() { String::new() } else { format!(".{}", ext) };
+      let base = out.strip_suffix(&suffix).unwrap_or(&out);
+      out = format!("{}_{}{}", base, hash, suffix);
+    }
+    self.used.insert(out.to_ascii_lowercase());
+    if out != rel {
+      hashed_files.insert(out.clone(), rel.to_string());
+    }
+    out
+  }
+}
@@ -0,0 +1,149 @@
+// ── Overlay builder ──────────────────────────────────────────────────────────
+// Turns a project into a runnable overlay: every game WAD the project touches is
+// rebuilt with the project's files swapped in and written under
+// `{out}/DATA/FINAL/...`, mirroring the install so the mod runtime can mount it.
+// FINAL is built next to the old one and swapped in, so WADs from an earlier
+// build that the project no longer touches don't linger in the overlay.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use napi_derive::napi;
+
+use crate::game::walk_final_wads;
+use crate::parse_wad_toc;
+use crate::paths::{staging_dir, swap_dir_in};
+use crate::wad_build::{
+  build_merged_wad_to_writer, chunk_hash_for_rel_path, collect_files, plan_wad_dir, project_wad_dirs,
+  wad_file_name_for_dir, HASHED_FILES_JSON,
+};
+
+#[napi(object)]
+pub struct OverlayWad {
+  /// Game WAD path relative to DATA/FINAL, e.g. "Champions/Ahri.wad.client".
+  #[napi(js_name = "gameWad")]
+  pub game_wad: String,
+  #[napi(js_name = "outPath")]
+  pub out_path: String,
+  #[napi(js_name = "overriddenCount")]
+  pub overridden_count: u32,
+  #[napi(js_name = "addedCount")]
+  pub added_count: u32,
+}
+
+#[napi(object)]
+pub struct OverlayBuildResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub wads: Vec<OverlayWad>,
+  /// Project files that could not be mapped to any game WAD.
+  pub unmapped: Vec<String>,
+}
+
+/// Game WAD (absolute, rel-to-FINAL) -> chunk overrides.
+pub(crate) type OverlayPlan = BTreeMap<String, (PathBuf, HashMap<u64, PathBuf>)>;
+
+pub(crate) fn plan_overlay(project: &Path, league: &Path) -> Result<(OverlayPlan, Vec<String>), String> {
+  let game_wads = walk_final_wads(league);
+  if game_wads.is_empty() {
+    return Err(format!("No game WADs found under {}", league.display()));
+  }
+  let by_name: HashMap<String, &(PathBuf, String)> = game_wads.iter()
+    .filter_map(|w| w.0.file_name().map(|n| (n.to_string_lossy().to_ascii_lowercase(), w)))
+    .collect();
+
+  let mut plan: OverlayPlan = BTreeMap::new();
+  let mut unmapped = Vec::new();
+
+  // WAD folders target the game WAD with the same file name.
+  let wad_dirs = project_wad_dirs(project);
+  for dir in &wad_dirs {
+    let dir_name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let Some((abs, rel)) = by_name.get(&wad_file_name_for_dir(&dir_name).to_ascii_lowercase()).copied() else {
+      unmapped.push(dir_name);
+      continue;
+    };
+    let (index, _) = plan_wad_dir(dir)?;
+    plan.entry(rel.clone()).or_insert_with(|| (abs.clone(), HashMap::new())).1.extend(index);
+  }
+
+  // Loose files directly under `content/` go to every game WAD that already has that path.
+  let content = project.join("content");
+  if content.is_dir() {
+    let mut loose: HashMap<u64, (String, PathBuf)> = HashMap::new();
+    for (rel, path) in collect_files(&content)? {
+      if path.ancestors().any(|a| wad_dirs.iter().any(|d| d == a)) { continue; }
+      let first = rel.split('/').next().unwrap_or("");
+      if first.eq_ignore_ascii_case("RAW") || rel.eq_ignore_ascii_case(HASHED_FILES_JSON) { continue; }
+      loose.insert(chunk_hash_for_rel_path(&rel), (rel, path));
+    }
+    if !loose.is_empty() {
+      let mut found: HashMap<u64, bool> = loose.keys().map(|h| (*h, false)).collect();
+      for (abs, rel) in &game_wads {
+        let Ok(toc) = parse_wad_toc(abs) else { continue };
+        for h in toc.hashes {
+          let Some((_, path)) = loose.get(&h) else { continue };
+          plan.entry(rel.clone()).or_insert_with(|| (abs.clone(), HashMap::new())).1.insert(h, path.clone());
+          found.insert(h, true);
+        }
+      }
+      let mut missing: Vec<String> = found.into_iter()
+        .filter(|(_, ok)| !ok)
+        .filter_map(|(h, _)| loose.get(&h).map(|(rel, _)| rel.clone()))
+        .collect();
+      missing.sort();
+      unmapped.extend(missing);
+    }
+  }
+  Ok((plan, unmapped))
+}
+
+/// Write every planned WAD under `staging`, reporting paths under `final_out`.
+fn write_overlay_wads(plan: &OverlayPlan, staging: &Path, final_out: &Path) -> Result<Vec<OverlayWad>, String> {
+  let mut wads = Vec::with_capacity(plan.len());
+  for (rel, (game_wad, overrides)) in plan {
+    let out_path = staging.join(rel);
+    if let Some(parent) = out_path.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let file = fs::File::create(&out_path)
+      .map_err(|e| format!("Failed to create {}: {}", out_path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    let (overridden, added) = build_merged_wad_to_writer(game_wad, overrides, &mut writer)
+      .map_err(|e| format!("{}: {}", rel, e))?;
+    wads.push(OverlayWad {
+      game_wad: rel.clone(),
+      out_path: final_out.join(rel).to_string_lossy().into_owned(),
+      overridden_count: overridden,
+      added_count: added,
+    });
+  }
+  Ok(wads)
+}
+
+pub(crate) fn build_overlay_dir(project: &Path, out_dir: &Path, league: &Path) -> Result<OverlayBuildResult, String> {
+  let (plan, unmapped) = plan_overlay(project, league)?;
+  if plan.is_empty() {
+    return Err("Project does not override any game WAD".to_string());
+  }
+  let final_out = out_dir.join("DATA").join("FINAL");
+  let staging = staging_dir(&final_out).ok_or_else(|| format!("Invalid output folder: {}", out_dir.display()))?;
+  let _ = fs::remove_dir_all(&staging);
+  let wads = write_overlay_wads(&plan, &staging, &final_out).inspect_err(|_| {
+    let _ = fs::remove_dir_all(&staging);
+  })?;
+  swap_dir_in(&staging, &final_out)?;
+  Ok(OverlayBuildResult { success: true, error: None, wads, unmapped })
+}
+
+/// Build an overlay directory for the mod runtime from a project: each affected
+/// game WAD is rebuilt with the project's files and written to `{outDir}/DATA/FINAL`.
+#[napi(js_name = "buildOverlay")]
+pub fn build_overlay(project_path: String, out_dir: String, league_path: String) -> OverlayBuildResult {
+  match build_overlay_dir(Path::new(&project_path), Path::new(&out_dir), Path::new(&league_path)) {
+    Ok(r) => r,
+    Err(e) => OverlayBuildResult { success: false, error: Some(e), wads: Vec::new(), unmapped: Vec::new() },
+  }
+}
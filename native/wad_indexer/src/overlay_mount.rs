@@ -0,0 +1,156 @@
+// ── Overlay mounts ───────────────────────────────────────────────────────────
+// A logical, in-memory version of `buildOverlay` for previews: the game WADs a
+// project touches are mounted together with the project's files, and chunk
+// reads resolve to the project file when one shadows the path hash, otherwise
+// to the game chunk. Nothing is built or written. Project files are read at
+// read time, so edits show up immediately; files added after mounting need a
+// new mount to be picked up.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use napi::bindgen_prelude::{AsyncTask, Buffer};
+use napi::{Env, Task};
+use napi_derive::napi;
+
+use crate::chunk_read::read_chunks;
+use crate::overlay::{plan_overlay, OverlayPlan};
+use crate::parse_hash_hex;
+
+static NEXT_MOUNT_ID: AtomicU32 = AtomicU32::new(1);
+static MOUNTS: OnceLock<Mutex<HashMap<u32, Arc<OverlayPlan>>>> = OnceLock::new();
+
+fn mounts() -> &'static Mutex<HashMap<u32, Arc<OverlayPlan>>> {
+  MOUNTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_mount(id: u32) -> Result<Arc<OverlayPlan>, String> {
+  mounts().lock().unwrap_or_else(|e| e.into_inner()).get(&id).cloned()
+    .ok_or_else(|| format!("Unknown overlay mount: {}", id))
+}
+
+#[napi(object)]
+pub struct MountedWad {
+  /// Game WAD path relative to DATA/FINAL, e.g. "Champions/Ahri.wad.client".
+  #[napi(js_name = "gameWad")]
+  pub game_wad: String,
+  /// Number of path hashes served from project files.
+  #[napi(js_name = "shadowedCount")]
+  pub shadowed_count: u32,
+}
+
+#[napi(object)]
+pub struct OverlayMountResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Id for `readOverlayChunk` / `unmountOverlay`; 0 on failure.
+  #[napi(js_name = "mountId")]
+  pub mount_id: u32,
+  pub wads: Vec<MountedWad>,
+  /// Project files that could not be mapped to any game WAD.
+  pub unmapped: Vec<String>,
+}
+
+#[napi(object)]
+pub struct OverlayChunkReadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Chunk bytes; None when neither the project nor the game WAD has the hash.
+  pub data: Option<Buffer>,
+  /// "project" or "game"; None when the chunk was not found.
+  pub source: Option<String>,
+}
+
+/// Mount the game WADs a project overrides, shadowed by the project's files.
+#[napi(js_name = "mountOverlay")]
+pub fn mount_overlay(project_path: String, league_path: String) -> OverlayMountResult {
+  match plan_overlay(Path::new(&project_path), Path::new(&league_path)) {
+    Ok((plan, unmapped)) => {
+      let wads = plan.iter()
+        .map(|(rel, (_, overrides))| MountedWad { game_wad: rel.clone(), shadowed_count: overrides.len() as u32 })
+        .collect();
+      let id = NEXT_MOUNT_ID.fetch_add(1, Ordering::Relaxed);
+      mounts().lock().unwrap_or_else(|e| e.into_inner()).insert(id, Arc::new(plan));
+      OverlayMountResult { success: true, error: None, mount_id: id, wads, unmapped }
+    }
+    Err(e) => OverlayMountResult { success: false, error: Some(e), mount_id: 0, wads: Vec::new(), unmapped: Vec::new() },
+  }
+}
+
+/// Release a mount. Returns false if the id was not mounted.
+#[napi(js_name = "unmountOverlay")]
+pub fn unmount_overlay(mount_id: u32) -> bool {
+  mounts().lock().unwrap_or_else(|e| e.into_inner()).remove(&mount_id).is_some()
+}
+
+/// Game WAD (absolute) and shadowing project file of `hash` in `game_wad`
+/// (rel to DATA/FINAL, case-insensitive).
+fn locate(plan: &OverlayPlan, game_wad: &str, hash: u64) -> Result<(PathBuf, Option<PathBuf>), String> {
+  let wanted = game_wad.replace('\\', "/");
+  let (abs, overrides) = plan.iter()
+    .find(|(rel, _)| rel.eq_ignore_ascii_case(&wanted))
+    .map(|(_, v)| v)
+    .ok_or_else(|| format!("{} is not part of this overlay", game_wad))?;
+  Ok((abs.clone(), overrides.get(&hash).cloned()))
+}
+
+fn read_overlay(mount_id: u32, game_wad: &str, path_hash: &str) -> Result<Option<(Vec<u8>, &'static str)>, String> {
+  let hash = parse_hash_hex(path_hash).ok_or_else(|| format!("Invalid path hash: {}", path_hash))?;
+  let plan = get_mount(mount_id)?;
+  let (wad, shadow) = locate(&plan, game_wad, hash)?;
+  // A project file deleted since mounting falls back to the game chunk.
+  if let Some(path) = shadow.filter(|p| p.is_file()) {
+    let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    return Ok(Some((data, "project")));
+  }
+  Ok(read_chunks(&wad, &[hash])?.pop().transpose()?.flatten().map(|d| (d, "game")))
+}
+
+fn overlay_result(read: Result<Option<(Vec<u8>, &'static str)>, String>) -> OverlayChunkReadResult {
+  match read {
+    Ok(Some((data, source))) => OverlayChunkReadResult {
+      success: true,
+      error: None,
+      data: Some(Buffer::from(data)),
+      source: Some(source.to_string()),
+    },
+    Ok(None) => OverlayChunkReadResult { success: true, error: None, data: None, source: None },
+    Err(e) => OverlayChunkReadResult { success: false, error: Some(e), data: None, source: None },
+  }
+}
+
+/// Read a chunk through a mount: the project's file if it shadows `pathHash`,
+/// otherwise the decompressed chunk of `gameWad`.
+#[napi(js_name = "readOverlayChunk")]
+pub fn read_overlay_chunk(mount_id: u32, game_wad: String, path_hash: String) -> OverlayChunkReadResult {
+  overlay_result(read_overlay(mount_id, &game_wad, &path_hash))
+}
+
+pub struct ReadOverlayChunkTask {
+  mount_id: u32,
+  game_wad: String,
+  path_hash: String,
+}
+
+#[napi]
+impl Task for ReadOverlayChunkTask {
+  type Output = Result<Option<(Vec<u8>, &'static str)>, String>;
+  type JsValue = OverlayChunkReadResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(read_overlay(self.mount_id, &self.game_wad, &self.path_hash))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(overlay_result(output))
+  }
+}
+
+/// `readOverlayChunk` off the main thread, for large chunks.
+#[napi(js_name = "readOverlayChunkAsync")]
+pub fn read_overlay_chunk_async(mount_id: u32, game_wad: String, path_hash: String) -> AsyncTask<ReadOverlayChunkTask> {
+  AsyncTask::new(ReadOverlayChunkTask { mount_id, game_wad, path_hash })
+}
@@ -0,0 +1,396 @@
+// ── Game path index ──────────────────────────────────────────────────────────
+// Persistent LMDB index of where every chunk of a game install lives:
+// path hash -> WAD files containing it. "Open the original of this file" and
+// project validation become single lookups instead of rescanning ~1000 WADs.
+//
+// Layout of `{indexDir}/game-index.lmdb`:
+//   wads      rel WAD path (from DATA/FINAL) -> JSON { id, size, mtimeMs, chunkCount }
+//   wad_ids   WAD id (u32 BE)                -> rel WAD path
+//   wad_chunk WAD id (u32 BE)                -> packed u64 LE path hashes
+//   locations path hash (u64 BE)             -> packed u32 LE WAD ids
+//
+// Rebuilds are incremental: only WADs whose size or mtime changed are re-read.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use heed::types::{Bytes, Str};
+use heed::{Database, EnvOpenOptions, RoTxn};
+use napi_derive::napi;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+use crate::game::{game_dir, walk_final_wads};
+use crate::threads::run_io;
+use crate::wad_build::plan_wad_dir;
+use crate::{get_file_mtime_ms, normalize_rel_path, parse_hash_hex, parse_wad_toc, scan_bin_asset_paths, xxhash_path, WadLoadError, WadToc};
+
+const INDEX_DIR_NAME: &str = "game-index.lmdb";
+const FINAL_DIR_KEY: &str = "@finalDir";
+
+/// (rel WAD path, TOC hashes + chunk count or error)
+type TocRead = (String, Result<WadToc, WadLoadError>);
+
+static INDEX_ENVS: OnceLock<Mutex<HashMap<PathBuf, Arc<heed::Env>>>> = OnceLock::new();
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedWad {
+  id: u32,
+  size: u64,
+  mtime_ms: u64,
+  chunk_count: u32,
+}
+
+#[napi(object)]
+pub struct GameIndexResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "wadCount")]
+  pub wad_count: u32,
+  /// WADs (re)read because they were new or changed since the last build.
+  #[napi(js_name = "updatedWads")]
+  pub updated_wads: u32,
+  #[napi(js_name = "removedWads")]
+  pub removed_wads: u32,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+}
+
+#[napi(object)]
+pub struct GameFileLocation {
+  /// Absolute path of the WAD in the install the index was built from.
+  #[napi(js_name = "wadPath")]
+  pub wad_path: String,
+  /// WAD path relative to DATA/FINAL, e.g. "Champions/Ahri.wad.client".
+  #[napi(js_name = "wadRelPath")]
+  pub wad_rel_path: String,
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+}
+
+struct IndexDbs {
+  wads: Database<Str, Str>,
+  wad_ids: Database<Bytes, Str>,
+  wad_chunks: Database<Bytes, Bytes>,
+  locations: Database<Bytes, Bytes>,
+}
+
+fn open_index_env(index_dir: &Path) -> Result<Arc<heed::Env>, String> {
+  let dir = index_dir.join(INDEX_DIR_NAME);
+  let mut envs = INDEX_ENVS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap_or_else(|e| e.into_inner());
+  if let Some(env) = envs.get(&dir) { return Ok(Arc::clone(env)); }
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+  let env = unsafe {
+    EnvOpenOptions::new()
+      .map_size(2 * 1024 * 1024 * 1024) // 2GB virtual; a full install needs a few hundred MB
+      .max_dbs(4)
+      .open(&dir)
+  }
+  .map_err(|e| format!("Failed to open game index: {}", e))?;
+  let env = Arc::new(env);
+  envs.insert(dir, Arc::clone(&env));
+  Ok(env)
+}
+
+/// Drop the cached index envs; each closes once its last reader is done.
+pub(crate) fn close_index_envs() {
+  if let Some(envs) = INDEX_ENVS.get() {
+    envs.lock().unwrap_or_else(|e| e.into_inner()).clear();
+  }
+}
+
+fn create_dbs(env: &heed::Env) -> Result<IndexDbs, String> {
+  let mut wtxn = env.write_txn().map_err(|e| e.to_string())?;
+  let dbs = IndexDbs {
+    wads: env.create_database(&mut wtxn, Some("wads")).map_err(|e| e.to_string())?,
+    wad_ids: env.create_database(&mut wtxn, Some("wad_ids")).map_err(|e| e.to_string())?,
+    wad_chunks: env.create_database(&mut wtxn, Some("wad_chunk")).map_err(|e| e.to_string())?,
+    locations: env.create_database(&mut wtxn, Some("locations")).map_err(|e| e.to_string())?,
+  };
+  wtxn.commit().map_err(|e| e.to_string())?;
+  Ok(dbs)
+}
+
+fn open_dbs(env: &heed::Env, rtxn: &RoTxn) -> Result<Option<IndexDbs>, String> {
+  let wads = env.open_database(rtxn, Some("wads")).map_err(|e| e.to_string())?;
+  let wad_ids = env.open_database(rtxn, Some("wad_ids")).map_err(|e| e.to_string())?;
+  let wad_chunks = env.open_database(rtxn, Some("wad_chunk")).map_err(|e| e.to_string())?;
+  let locations = env.open_database(rtxn, Some("locations")).map_err(|e| e.to_string())?;
+  Ok(match (wads, wad_ids, wad_chunks, locations) {
+    (Some(wads), Some(wad_ids), Some(wad_chunks), Some(locations)) => Some(IndexDbs { wads, wad_ids, wad_chunks, locations }),
+    _ => None,
+  })
+}
+
+fn unpack_ids(bytes: &[u8]) -> Vec<u32> {
+  bytes.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+fn pack_ids(ids: &[u32]) -> Vec<u8> {
+  ids.iter().flat_map(|id| id.to_le_bytes()).collect()
+}
+
+fn build_index(league: &Path, index_dir: &Path) -> Result<GameIndexResult, String> {
+  let final_dir = game_dir(league).join("DATA").join("FINAL");
+  if !final_dir.is_dir() {
+    return Err(format!("Game data not found under {}", league.display()));
+  }
+  let _span = info_span!("build_game_index").entered();
+  let env = open_index_env(index_dir)?;
+  let dbs = create_dbs(&env)?;
+
+  let on_disk: BTreeMap<String, (PathBuf, u64, u64)> = walk_final_wads(league)
+    .into_iter()
+    .map(|(abs, rel)| {
+      let size = fs::metadata(&abs).map(|m| m.len()).unwrap_or(0);
+      let mtime = get_file_mtime_ms(&abs) as u64;
+      (rel, (abs, size, mtime))
+    })
+    .collect();
+
+  let mut wtxn = env.write_txn().map_err(|e| e.to_string())?;
+  let final_dir_str = final_dir.to_string_lossy().into_owned();
+  let stored_final = dbs.wads.get(&wtxn, FINAL_DIR_KEY).map_err(|e| e.to_string())?.map(str::to_string);
+  // A different install invalidates everything.
+  if stored_final.as_deref() != Some(final_dir_str.as_str()) {
+    dbs.wads.clear(&mut wtxn).map_err(|e| e.to_string())?;
+    dbs.wad_ids.clear(&mut wtxn).map_err(|e| e.to_string())?;
+    dbs.wad_chunks.clear(&mut wtxn).map_err(|e| e.to_string())?;
+    dbs.locations.clear(&mut wtxn).map_err(|e| e.to_string())?;
+    dbs.wads.put(&mut wtxn, FINAL_DIR_KEY, &final_dir_str).map_err(|e| e.to_string())?;
+  }
+
+  let mut indexed: BTreeMap<String, IndexedWad> = BTreeMap::new();
+  for item in dbs.wads.iter(&wtxn).map_err(|e| e.to_string())? {
+    let (rel, json) = item.map_err(|e| e.to_string())?;
+    if rel == FINAL_DIR_KEY { continue; }
+    if let Ok(w) = serde_json::from_str::<IndexedWad>(json) { indexed.insert(rel.to_string(), w); }
+  }
+
+  let stale: Vec<(String, u32)> = indexed
+    .iter()
+    .filter(|(rel, w)| on_disk.get(*rel).map(|(_, size, mtime)| *size != w.size || *mtime != w.mtime_ms).unwrap_or(true))
+    .map(|(rel, w)| (rel.clone(), w.id))
+    .collect();
+  let removed = stale.iter().filter(|(rel, _)| !on_disk.contains_key(rel)).count() as u32;
+
+  // Drop stale WADs from every location they were listed in.
+  let mut touched: HashMap<u64, Vec<u32>> = HashMap::new();
+  let stale_ids: HashSet<u32> = stale.iter().map(|(_, id)| *id).collect();
+  for (rel, id) in &stale {
+    let key = id.to_be_bytes();
+    let hashes: Vec<u64> = dbs.wad_chunks.get(&wtxn, &key[..]).map_err(|e| e.to_string())?
+      .map(|b| b.chunks_exact(8).map(|c| u64::from_le_bytes(c.try_into().unwrap_or([0; 8]))).collect())
+      .unwrap_or_default();
+    for h in hashes {
+      if touched.contains_key(&h) { continue; }
+      let ids = dbs.locations.get(&wtxn, &h.to_be_bytes()[..]).map_err(|e| e.to_string())?.map(unpack_ids).unwrap_or_default();
+      touched.insert(h, ids.into_iter().filter(|i| !stale_ids.contains(i)).collect());
+    }
+    dbs.wad_chunks.delete(&mut wtxn, &key[..]).map_err(|e| e.to_string())?;
+    dbs.wad_ids.delete(&mut wtxn, &key[..]).map_err(|e| e.to_string())?;
+    dbs.wads.delete(&mut wtxn, rel).map_err(|e| e.to_string())?;
+    indexed.remove(rel);
+  }
+
+  // Read TOCs of new/changed WADs in parallel.
+  let to_read: Vec<(&String, &PathBuf)> = on_disk.iter().filter(|(rel, _)| !indexed.contains_key(*rel)).map(|(rel, (abs, _, _))| (rel, abs)).collect();
+  let tocs: Vec<TocRead> = run_io(|| {
+    to_read.par_iter().map(|(rel, abs)| ((*rel).clone(), parse_wad_toc(abs))).collect()
+  });
+
+  let mut next_id = indexed.values().map(|w| w.id + 1).max().unwrap_or(0).max(
+    stale.iter().map(|(_, id)| id + 1).max().unwrap_or(0),
+  );
+  let mut updated = 0u32;
+  for (rel, toc) in tocs {
+    let Ok(WadToc { mut hashes, .. }) = toc else { continue };
+    let chunk_count = hashes.len() as u32;
+    let (_, size, mtime_ms) = on_disk[&rel];
+    let id = next_id;
+    next_id += 1;
+    hashes.sort_unstable();
+    hashes.dedup();
+    for h in &hashes {
+      let ids = match touched.get_mut(h) {
+        Some(ids) => ids,
+        None => {
+          let existing = dbs.locations.get(&wtxn, &h.to_be_bytes()[..]).map_err(|e| e.to_string())?.map(unpack_ids).unwrap_or_default();
+          touched.entry(*h).or_insert(existing)
+        }
+      };
+      ids.push(id);
+    }
+    let packed: Vec<u8> = hashes.iter().flat_map(|h| h.to_le_bytes()).collect();
+    let key = id.to_be_bytes();
+    dbs.wad_chunks.put(&mut wtxn, &key[..], &packed).map_err(|e| e.to_string())?;
+    dbs.wad_ids.put(&mut wtxn, &key[..], &rel).map_err(|e| e.to_string())?;
+    let meta = IndexedWad { id, size, mtime_ms, chunk_count };
+    let json = serde_json::to_string(&meta).map_err(|e| e.to_string())?;
+    dbs.wads.put(&mut wtxn, &rel, &json).map_err(|e| e.to_string())?;
+    indexed.insert(rel, meta);
+    updated += 1;
+  }
+
+  let mut touched: Vec<(u64, Vec<u32>)> = touched.into_iter().collect();
+  touched.sort_unstable_by_key(|(h, _)| *h);
+  for (h, ids) in touched {
+    let key = h.to_be_bytes();
+    if ids.is_empty() {
+      dbs.locations.delete(&mut wtxn, &key[..]).map_err(|e| e.to_string())?;
+    } else {
+      dbs.locations.put(&mut wtxn, &key[..], &pack_ids(&ids)).map_err(|e| e.to_string())?;
+    }
+  }
+  wtxn.commit().map_err(|e| format!("Failed to write game index: {}", e))?;
+
+  Ok(GameIndexResult {
+    success: true,
+    error: None,
+    wad_count: indexed.len() as u32,
+    updated_wads: updated,
+    removed_wads: removed,
+    chunk_count: indexed.values().map(|w| w.chunk_count).sum(),
+  })
+}
+
+/// Hash for a lookup key: 16 hex digits are taken as a path hash, anything else as a path.
+fn lookup_hash(path_or_hash: &str) -> u64 {
+  let trimmed = path_or_hash.trim();
+  if trimmed.len() == 16 {
+    if let Some(h) = parse_hash_hex(trimmed) { return h; }
+  }
+  xxhash_path(&normalize_rel_path(trimmed).to_ascii_lowercase())
+}
+
+pub(crate) fn lookup_locations(index_dir: &Path, hashes: &[u64]) -> Result<Vec<Vec<GameFileLocation>>, String> {
+  let env = open_index_env(index_dir)?;
+  let rtxn = env.read_txn().map_err(|e| e.to_string())?;
+  let Some(dbs) = open_dbs(&env, &rtxn)? else {
+    return Err("Game index has not been built; call buildGameIndex first".to_string());
+  };
+  let final_dir = dbs.wads.get(&rtxn, FINAL_DIR_KEY).map_err(|e| e.to_string())?.map(PathBuf::from).unwrap_or_default();
+  let mut rel_by_id: HashMap<u32, String> = HashMap::new();
+  hashes
+    .iter()
+    .map(|h| {
+      let ids = dbs.locations.get(&rtxn, &h.to_be_bytes()[..]).map_err(|e| e.to_string())?.map(unpack_ids).unwrap_or_default();
+      let mut out = Vec::with_capacity(ids.len());
+      for id in ids {
+        let rel = match rel_by_id.get(&id) {
+          Some(r) => r.clone(),
+          None => {
+            let Some(r) = dbs.wad_ids.get(&rtxn, &id.to_be_bytes()[..]).map_err(|e| e.to_string())? else { continue };
+            rel_by_id.insert(id, r.to_string());
+            r.to_string()
+          }
+        };
+        out.push(GameFileLocation {
+          wad_path: final_dir.join(&rel).to_string_lossy().into_owned(),
+          wad_rel_path: rel,
+          path_hash: format!("{:016x}", h),
+        });
+      }
+      Ok(out)
+    })
+    .collect()
+}
+
+/// Whether each hash is in some indexed WAD; cheaper than `lookup_locations`
+/// when only presence matters.
+pub(crate) fn contains_hashes(index_dir: &Path, hashes: &[u64]) -> Result<Vec<bool>, String> {
+  let env = open_index_env(index_dir)?;
+  let rtxn = env.read_txn().map_err(|e| e.to_string())?;
+  let Some(dbs) = open_dbs(&env, &rtxn)? else {
+    return Err("Game index has not been built; call buildGameIndex first".to_string());
+  };
+  hashes
+    .iter()
+    .map(|h| dbs.locations.get(&rtxn, &h.to_be_bytes()[..]).map(|v| v.is_some()).map_err(|e| e.to_string()))
+    .collect()
+}
+
+/// Build or incrementally refresh the path -> WAD index for a game install.
+#[napi(js_name = "buildGameIndex")]
+pub fn build_game_index(league_path: String, index_dir: String) -> GameIndexResult {
+  build_index(Path::new(&league_path), Path::new(&index_dir)).unwrap_or_else(|e| GameIndexResult {
+    success: false,
+    error: Some(e),
+    wad_count: 0,
+    updated_wads: 0,
+    removed_wads: 0,
+    chunk_count: 0,
+  })
+}
+
+/// WADs containing an asset path (or 16-digit hex path hash). Empty when the game has no such file.
+#[napi(js_name = "lookupGameFile")]
+pub fn lookup_game_file(index_dir: String, path_or_hash: String) -> napi::Result<Vec<GameFileLocation>> {
+  lookup_locations(Path::new(&index_dir), &[lookup_hash(&path_or_hash)])
+    .map(|mut v| v.pop().unwrap_or_default())
+    .map_err(napi::Error::from_reason)
+}
+
+/// Batch form of `lookupGameFile`; results follow the input order.
+#[napi(js_name = "lookupGameFiles")]
+pub fn lookup_game_files(index_dir: String, paths_or_hashes: Vec<String>) -> napi::Result<Vec<Vec<GameFileLocation>>> {
+  let hashes: Vec<u64> = paths_or_hashes.iter().map(|p| lookup_hash(p)).collect();
+  lookup_locations(Path::new(&index_dir), &hashes).map_err(napi::Error::from_reason)
+}
+
+#[napi(object)]
+pub struct BinAssetValidation {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Distinct asset paths referenced by the bin.
+  #[napi(js_name = "checkedCount")]
+  pub checked_count: u32,
+  /// Referenced paths found in neither the local folders nor the game.
+  pub missing: Vec<String>,
+  #[napi(js_name = "localCount")]
+  pub local_count: u32,
+  #[napi(js_name = "gameCount")]
+  pub game_count: u32,
+}
+
+fn validate_bin(bin_path: &Path, index_dir: &Path, local_dirs: &[String]) -> Result<BinAssetValidation, String> {
+  let data = fs::read(bin_path).map_err(|e| format!("Failed to read {}: {}", bin_path.display(), e))?;
+  let mut paths = scan_bin_asset_paths(&data);
+  paths.sort_unstable();
+  paths.dedup();
+
+  let mut local: HashSet<u64> = HashSet::new();
+  for dir in local_dirs {
+    local.extend(plan_wad_dir(Path::new(dir))?.0.into_keys());
+  }
+  let (in_local, rest): (Vec<&String>, Vec<&String>) = paths.iter().partition(|p| local.contains(&xxhash_path(p)));
+  let hashes: Vec<u64> = rest.iter().map(|p| xxhash_path(p)).collect();
+  let in_game = contains_hashes(index_dir, &hashes)?;
+  let missing: Vec<String> = rest.iter().zip(&in_game).filter(|(_, found)| !**found).map(|(p, _)| (*p).clone()).collect();
+  Ok(BinAssetValidation {
+    success: true,
+    error: None,
+    checked_count: paths.len() as u32,
+    local_count: in_local.len() as u32,
+    game_count: in_game.iter().filter(|f| **f).count() as u32,
+    missing,
+  })
+}
+
+/// Check every asset path a bin references against the installed game (via the
+/// path index) and optional local WAD folders, without shipping hash lists over IPC.
+#[napi(js_name = "validateBinAssets")]
+pub fn validate_bin_assets(bin_path: String, index_dir: String, local_dirs: Option<Vec<String>>) -> BinAssetValidation {
+  validate_bin(Path::new(&bin_path), Path::new(&index_dir), &local_dirs.unwrap_or_default()).unwrap_or_else(|e| BinAssetValidation {
+    success: false,
+    error: Some(e),
+    checked_count: 0,
+    missing: Vec::new(),
+    local_count: 0,
+    game_count: 0,
+  })
+}
@@ -0,0 +1,194 @@
+// ── Output path helpers ──────────────────────────────────────────────────────
+// Shared by every extraction path so the same asset lands at the same place no
+// matter which command wrote it.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use xxhash_rust::xxh3::xxh3_64;
+
+pub(crate) use quartz_core::paths::{rename_retrying, retry_on_lock, swap_dir_in, write_retrying};
+
+use crate::resume::RESUME_MANIFEST_JSON;
+use crate::wad_build::{collect_files, HASHED_FILES_JSON};
+
+/// Extended-length form of `path` for file IO on Windows (`\\?\C:\...`,
+/// `\\?\UNC\server\share\...`), so deep asset paths under a long output dir
+/// don't hit MAX_PATH. The prefix turns off Win32 path normalization, so the
+/// path is made absolute, `.`/`..` are resolved and `/` becomes `\` here.
+/// Other platforms get the path back unchanged.
+#[cfg(windows)]
+pub(crate) fn long_path(path: &Path) -> PathBuf {
+  use std::ffi::OsString;
+  use std::path::{Component, Prefix};
+
+  let Ok(abs) = std::path::absolute(path) else { return path.to_path_buf() };
+  let mut out = OsString::new();
+  let mut parts: Vec<&std::ffi::OsStr> = Vec::new();
+  for comp in abs.components() {
+    match comp {
+      Component::Prefix(p) => match p.kind() {
+        Prefix::Disk(letter) => out.push(format!(r"\\?\{}:", letter as char)),
+        Prefix::UNC(server, share) => {
+          out.push(r"\\?\UNC\");
+          out.push(server);
+          out.push(r"\");
+          out.push(share);
+        }
+        // Already verbatim (or a device path): leave it alone.
+        _ => return abs.clone(),
+      },
+      Component::RootDir | Component::CurDir => {}
+      Component::ParentDir => { parts.pop(); }
+      Component::Normal(name) => parts.push(name),
+    }
+  }
+  if parts.is_empty() { out.push(r"\"); }
+  for name in parts {
+    out.push(r"\");
+    out.push(name);
+  }
+  PathBuf::from(out)
+}
+
+#[cfg(not(windows))]
+pub(crate) fn long_path(path: &Path) -> PathBuf {
+  path.to_path_buf()
+}
+
+/// Device names Windows refuses as file names, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+  "con", "prn", "aux", "nul",
+  "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9",
+  "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+fn sanitize_component(name: &str) -> Cow<'_, str> {
+  let needs_char_fix = name.chars().any(|c| c < ' ' || matches!(c, '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*'));
+  let trimmed = name.trim_end_matches(['.', ' ']);
+  let stem = trimmed.split('.').next().unwrap_or(trimmed).trim_end();
+  let reserved = RESERVED_NAMES.iter().any(|r| stem.eq_ignore_ascii_case(r));
+  if !needs_char_fix && !reserved && trimmed.len() == name.len() && !trimmed.is_empty() {
+    return Cow::Borrowed(name);
+  }
+  let mut out: String = trimmed
+    .chars()
+    .map(|c| if c < ' ' || matches!(c, '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*') { '_' } else { c })
+    .collect();
+  if reserved { out.insert(0, '_'); }
+  if out.is_empty() { out.push('_'); }
+  Cow::Owned(out)
+}
+
+/// Make a `/`-separated relative path writable on every platform: reserved
+/// device names get a `_` prefix (`con` -> `_con`, `aux.dds` -> `_aux.dds`),
+/// trailing dots/spaces are stripped and invalid characters become `_`.
+/// Applied on all platforms so an extraction looks the same everywhere.
+pub(crate) fn sanitize_rel_path(rel: &str) -> Cow<'_, str> {
+  if rel.split('/').all(|c| matches!(sanitize_component(c), Cow::Borrowed(_))) {
+    return Cow::Borrowed(rel);
+  }
+  Cow::Owned(rel.split('/').map(sanitize_component).collect::<Vec<_>>().join("/"))
+}
+
+/// Merge `entries` (output name -> original asset path) into the
+/// `hashed_files.json` sidecar of `dir`, which repacking uses to restore hashes.
+pub(crate) fn merge_hashed_files_sidecar(dir: &Path, entries: HashMap<String, String>) {
+  if entries.is_empty() { return; }
+  let json_path = dir.join(HASHED_FILES_JSON);
+  let mut existing: BTreeMap<String, String> = fs::read_to_string(&json_path)
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default();
+  existing.extend(entries);
+  if let Ok(json) = serde_json::to_string_pretty(&existing) {
+    let _ = fs::write(&json_path, json);
+  }
+}
+
+/// Staging dir for an atomic extraction into `output`: `<output>.partial`.
+pub(crate) fn staging_dir(output: &Path) -> Option<PathBuf> {
+  let mut name = output.file_name()?.to_os_string();
+  name.push(".partial");
+  Some(output.with_file_name(name))
+}
+
+/// What an extraction does with files that are already in the output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum IfExists {
+  Overwrite,
+  Skip,
+  /// Rewrite only when size or checksum differ from the chunk, so re-running
+  /// an extraction after a patch touches just the changed files.
+  OverwriteIfDifferent,
+}
+
+impl IfExists {
+  /// `ifExists` ("overwrite" | "skip" | "overwriteIfDifferent") takes precedence
+  /// over the older `replaceExisting` flag; neither means overwrite.
+  pub(crate) fn resolve(if_exists: Option<&str>, replace_existing: Option<bool>) -> Result<Self, String> {
+    match if_exists {
+      Some("overwrite") => Ok(IfExists::Overwrite),
+      Some("skip") => Ok(IfExists::Skip),
+      Some("overwriteIfDifferent") => Ok(IfExists::OverwriteIfDifferent),
+      Some(other) => Err(format!("Unknown ifExists policy: {}", other)),
+      None if replace_existing == Some(false) => Ok(IfExists::Skip),
+      None => Ok(IfExists::Overwrite),
+    }
+  }
+}
+
+/// True when `path` already holds exactly `data`: sizes are compared first,
+/// then xxh3 checksums.
+pub(crate) fn same_contents(path: &Path, data: &[u8]) -> bool {
+  match fs::metadata(path) {
+    Ok(m) if m.is_file() && m.len() == data.len() as u64 => {}
+    _ => return false,
+  }
+  fs::read(path).is_ok_and(|existing| xxh3_64(&existing) == xxh3_64(data))
+}
+
+/// Move a finished staging dir into `output`. A missing output is a single
+/// rename; otherwise staged files are moved in one by one and existing files
+/// are handled per `if_exists`. Returns how many staged files were dropped.
+pub(crate) fn commit_staging(staging: &Path, output: &Path, if_exists: IfExists) -> Result<u32, String> {
+  // A committed extraction is complete; there is nothing left to resume.
+  let _ = fs::remove_file(staging.join(RESUME_MANIFEST_JSON));
+  if !output.exists() {
+    if let Some(parent) = output.parent() { let _ = fs::create_dir_all(parent); }
+    rename_retrying(staging, output)
+      .map_err(|e| format!("Failed to move {} into place: {}", staging.display(), e))?;
+    return Ok(0);
+  }
+
+  let mut kept = 0u32;
+  for (rel, src) in collect_files(staging)? {
+    if rel.eq_ignore_ascii_case(HASHED_FILES_JSON) {
+      let staged: HashMap<String, String> = fs::read_to_string(&src)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+      merge_hashed_files_sidecar(output, staged);
+      continue;
+    }
+    // Join the native relative path, not the display string, so non-UTF-8 names survive.
+    let Ok(native_rel) = src.strip_prefix(staging) else { continue };
+    let dest = long_path(&output.join(native_rel));
+    if dest.exists() {
+      let keep = match if_exists {
+        IfExists::Overwrite => false,
+        IfExists::Skip => true,
+        IfExists::OverwriteIfDifferent => fs::read(&src).is_ok_and(|staged| same_contents(&dest, &staged)),
+      };
+      if keep { kept += 1; continue; }
+      let _ = retry_on_lock(|| fs::remove_file(&dest));
+    }
+    if let Some(parent) = dest.parent() { let _ = fs::create_dir_all(parent); }
+    rename_retrying(&long_path(&src), &dest)
+      .map_err(|e| format!("Failed to move {} into {}: {}", rel, output.display(), e))?;
+  }
+  let _ = fs::remove_dir_all(staging);
+  Ok(kept)
+}
@@ -0,0 +1,212 @@
+// ── Patch porting ────────────────────────────────────────────────────────────
+// Brings a project up to date after a game patch, using the freshness check:
+// files the creator never touched are replaced with the new game data (with
+// the project's repath rules re-applied to bins), edited files get the new
+// version staged under `.quartz/port/` for a manual merge, and assets the
+// game removed are reported.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ltk_meta::Bin;
+use napi_derive::napi;
+use quartz_core::repath::rewrite_bin;
+
+use crate::freshness::{
+  apply_repath, check_freshness, data_checksum, mount, project_file_for, project_state, read_origins, write_origins,
+  RepathRule,
+};
+use crate::game::game_dir;
+use crate::paths::write_retrying;
+use crate::version::detect_game_version;
+use crate::wad_build::{plan_wad_dir, project_wad_dirs};
+use crate::parse_hash_hex;
+
+// ── Repathing bins ───────────────────────────────────────────────────────────
+
+/// Rewrite asset paths in a bin with the repath rules. Non-bins and bins with
+/// nothing to rewrite come back unchanged.
+fn repath_bin(data: Vec<u8>, rules: &[RepathRule]) -> Vec<u8> {
+  if rules.is_empty() || !data.starts_with(b"PROP") { return data; }
+  let Ok(mut bin) = Bin::from_reader(&mut Cursor::new(&data)) else { return data };
+  if rewrite_bin(&mut bin, &|p| apply_repath(rules, p)) == 0 { return data; }
+  let mut out = Cursor::new(Vec::new());
+  match bin.to_writer(&mut out) {
+    Ok(()) => out.into_inner(),
+    Err(_) => data,
+  }
+}
+
+// ── portProject ──────────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct PortedAsset {
+  pub wad: String,
+  /// Game asset path.
+  pub path: String,
+  /// "updated" (replaced with the new game version), "needsMerge" (edited in
+  /// the project; new version staged), "removed" (gone from the game) or
+  /// "skipped" (deleted from the project).
+  pub action: String,
+  /// Project file that was updated, or the staged new version for "needsMerge".
+  pub file: Option<String>,
+}
+
+#[napi(object)]
+pub struct PortProjectResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "currentPatch")]
+  pub current_patch: Option<String>,
+  pub assets: Vec<PortedAsset>,
+  #[napi(js_name = "updatedCount")]
+  pub updated_count: u32,
+  #[napi(js_name = "needsMergeCount")]
+  pub needs_merge_count: u32,
+}
+
+fn port(project: &Path, league: &Path) -> Result<PortProjectResult, String> {
+  let freshness = check_freshness(project, league)?;
+  let mut origins = read_origins(project)?;
+  let rules = origins.repath.clone();
+  let final_dir = game_dir(league).join("DATA").join("FINAL");
+  let patch = detect_game_version(league).patch;
+  let staging = project.join(".quartz").join("port");
+  let dirs: HashMap<String, PathBuf> = project_wad_dirs(project)
+    .into_iter()
+    .filter_map(|d| Some((d.file_name()?.to_string_lossy().into_owned(), d)))
+    .collect();
+
+  let mut assets = Vec::new();
+  let (mut updated, mut needs_merge) = (0u32, 0u32);
+  let mut mounted = HashMap::new();
+  let mut planned: HashMap<String, HashMap<u64, PathBuf>> = HashMap::new();
+  for stale in freshness.stale {
+    let asset = |action: &str, file: Option<&Path>| PortedAsset {
+      wad: stale.wad.clone(),
+      path: stale.path.clone(),
+      action: action.to_string(),
+      file: file.map(|f| f.to_string_lossy().into_owned()),
+    };
+    if stale.change == "removed" {
+      assets.push(asset("removed", None));
+      continue;
+    }
+    let (Some(hash), Some(wad_origin), Some(dir)) =
+      (parse_hash_hex(&stale.path_hash), origins.wads.get_mut(&stale.wad), dirs.get(&stale.wad))
+    else {
+      assets.push(asset("skipped", None));
+      continue;
+    };
+    let Some(origin) = wad_origin.chunks.get_mut(&stale.path_hash) else { continue };
+    if !planned.contains_key(&stale.wad) { planned.insert(stale.wad.clone(), plan_wad_dir(dir)?.0); }
+    let Some(target) = project_file_for(&planned[&stale.wad], hash, origin, &rules).cloned() else {
+      assets.push(asset("skipped", None));
+      continue;
+    };
+    if !mounted.contains_key(&wad_origin.game_wad) {
+      mounted.insert(wad_origin.game_wad.clone(), mount(&final_dir.join(&wad_origin.game_wad))?);
+    }
+    let Some(data) = mounted[&wad_origin.game_wad].read(hash) else {
+      assets.push(asset("removed", None));
+      continue;
+    };
+    let checksum = data_checksum(&data);
+    let size = data.len() as u64;
+    let ported = repath_bin(data, &rules);
+
+    if project_state(Some(&target), origin) == "unmodified" {
+      write_retrying(&target, &ported).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+      origin.synced = Some(data_checksum(&ported));
+      origin.checksum = checksum;
+      origin.size = size;
+      origin.patch = patch.clone();
+      updated += 1;
+      assets.push(asset("updated", Some(&target)));
+    } else {
+      // The recorded origin stays as is, so the asset keeps showing as stale until merged.
+      let rel = target.strip_prefix(dir).unwrap_or(&target);
+      let staged = staging.join(&stale.wad).join(rel);
+      if let Some(parent) = staged.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+      }
+      write_retrying(&staged, &ported).map_err(|e| format!("Failed to write {}: {}", staged.display(), e))?;
+      needs_merge += 1;
+      assets.push(asset("needsMerge", Some(&staged)));
+    }
+  }
+  write_origins(project, &origins)?;
+  Ok(PortProjectResult {
+    success: true,
+    error: None,
+    current_patch: freshness.current_patch,
+    assets,
+    updated_count: updated,
+    needs_merge_count: needs_merge,
+  })
+}
+
+/// Port a project to the installed patch: untouched files are replaced with
+/// the new game data (repath rules re-applied), edited files get the new
+/// version staged in `.quartz/port/` and are flagged for a manual merge.
+#[napi(js_name = "portProject")]
+pub fn port_project(project_path: String, league_path: String) -> PortProjectResult {
+  port(Path::new(&project_path), Path::new(&league_path)).unwrap_or_else(|e| PortProjectResult {
+    success: false,
+    error: Some(e),
+    current_patch: None,
+    assets: Vec::new(),
+    updated_count: 0,
+    needs_merge_count: 0,
+  })
+}
+
+// ── setProjectRepathRules ────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct SetRepathRulesResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Tracked files whose current (repathed) content was recorded as unmodified.
+  #[napi(js_name = "syncedCount")]
+  pub synced_count: u32,
+}
+
+fn set_rules(project: &Path, rules: Vec<RepathRule>) -> Result<u32, String> {
+  let mut origins = read_origins(project)?;
+  origins.repath = rules
+    .into_iter()
+    .map(|r| RepathRule { from: r.from.to_ascii_lowercase(), to: r.to.to_ascii_lowercase() })
+    .collect();
+  let dirs: HashMap<String, PathBuf> = project_wad_dirs(project)
+    .into_iter()
+    .filter_map(|d| Some((d.file_name()?.to_string_lossy().into_owned(), d)))
+    .collect();
+  let mut synced = 0u32;
+  for (wad, wad_origin) in origins.wads.iter_mut() {
+    let Some(dir) = dirs.get(wad) else { continue };
+    let (files, _) = plan_wad_dir(dir)?;
+    for (hex, origin) in wad_origin.chunks.iter_mut() {
+      let Some(hash) = parse_hash_hex(hex) else { continue };
+      let Some(file) = project_file_for(&files, hash, origin, &origins.repath) else { continue };
+      let Ok(data) = fs::read(file) else { continue };
+      origin.synced = Some(data_checksum(&data));
+      synced += 1;
+    }
+  }
+  write_origins(project, &origins)?;
+  Ok(synced)
+}
+
+/// Record the repath rules applied to a project so `portProject` can re-apply
+/// them. Call right after repathing: the project's current files are taken as
+/// the unmodified, repathed originals.
+#[napi(js_name = "setProjectRepathRules")]
+pub fn set_project_repath_rules(project_path: String, rules: Vec<RepathRule>) -> SetRepathRulesResult {
+  match set_rules(Path::new(&project_path), rules) {
+    Ok(synced_count) => SetRepathRulesResult { success: true, error: None, synced_count },
+    Err(e) => SetRepathRulesResult { success: false, error: Some(e), synced_count: 0 },
+  }
+}
@@ -0,0 +1,294 @@
+// ── Preferences ──────────────────────────────────────────────────────────────
+// Typed view over `{userData}/preferences.json`. Keys keep the PascalCase names
+// the app already stores, unknown keys are preserved on write, and updates are
+// a validated patch applied under one lock and saved atomically, so settings
+// no longer need hand-written read-modify-write code. Listeners registered
+// with `onPreferencesChanged` get the changed keys after every update.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+pub(crate) const PREFERENCES_JSON: &str = "preferences.json";
+
+type ChangeListener = ThreadsafeFunction<PreferencesChangedEvent, ErrorStrategy::Fatal>;
+
+static LISTENER: RwLock<Option<ChangeListener>> = RwLock::new(None);
+/// Serializes read-modify-write cycles on the preferences file.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A field that fails to parse (e.g. written with the wrong type by an older
+/// build) reads as unset instead of failing the whole file.
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: serde::de::DeserializeOwned,
+{
+  let value = Value::deserialize(deserializer)?;
+  Ok(serde_json::from_value(value).ok())
+}
+
+/// Every field is optional so the same shape doubles as an update patch;
+/// `getPreferences` fills in the defaults.
+#[napi(object)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Preferences {
+  #[napi(js_name = "PreferredMode")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub preferred_mode: Option<String>,
+  #[napi(js_name = "IgnoreBW")]
+  #[serde(rename = "IgnoreBW", default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub ignore_bw: Option<bool>,
+  /// Five flags, one per upscale target.
+  #[napi(js_name = "Targets")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub targets: Option<Vec<bool>>,
+  #[napi(js_name = "Regenerate")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub regenerate: Option<bool>,
+  #[napi(js_name = "NavExpandEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub nav_expand_enabled: Option<bool>,
+  #[napi(js_name = "HUDEditorEnabled")]
+  #[serde(rename = "HUDEditorEnabled", default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub hud_editor_enabled: Option<bool>,
+  #[napi(js_name = "FrogImgEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub frog_img_enabled: Option<bool>,
+  #[napi(js_name = "UpscaleEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub upscale_enabled: Option<bool>,
+  #[napi(js_name = "RGBAEnabled")]
+  #[serde(rename = "RGBAEnabled", default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub rgba_enabled: Option<bool>,
+  #[napi(js_name = "ToolsEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub tools_enabled: Option<bool>,
+  #[napi(js_name = "FileRandomizerEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub file_randomizer_enabled: Option<bool>,
+  #[napi(js_name = "WallpaperEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub wallpaper_enabled: Option<bool>,
+  #[napi(js_name = "WallpaperPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub wallpaper_path: Option<String>,
+  /// 0..=1
+  #[napi(js_name = "WallpaperOpacity")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub wallpaper_opacity: Option<f64>,
+  #[napi(js_name = "WallpaperVignetteEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub wallpaper_vignette_enabled: Option<bool>,
+  /// 0..=1
+  #[napi(js_name = "WallpaperVignetteStrength")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub wallpaper_vignette_strength: Option<f64>,
+  /// Blur radius in px, 0..=40.
+  #[napi(js_name = "GlassBlur")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub glass_blur: Option<f64>,
+  #[napi(js_name = "ThemeVariant")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub theme_variant: Option<String>,
+  #[napi(js_name = "InterfaceStyle")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub interface_style: Option<String>,
+  #[napi(js_name = "SelectedFont")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub selected_font: Option<String>,
+  #[napi(js_name = "UseNativeFileBrowser")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub use_native_file_browser: Option<bool>,
+  #[napi(js_name = "AutoLoadEnabled")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub auto_load_enabled: Option<bool>,
+  #[napi(js_name = "ExpandSystemsOnLoad")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub expand_systems_on_load: Option<bool>,
+  #[napi(js_name = "RitoBinPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub rito_bin_path: Option<String>,
+  #[napi(js_name = "JadeExecutablePath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub jade_executable_path: Option<String>,
+  #[napi(js_name = "SharedLastBinPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub shared_last_bin_path: Option<String>,
+  #[napi(js_name = "PaintLastBinPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub paint_last_bin_path: Option<String>,
+  #[napi(js_name = "PortLastTargetBinPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub port_last_target_bin_path: Option<String>,
+  #[napi(js_name = "PortLastDonorBinPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub port_last_donor_bin_path: Option<String>,
+  #[napi(js_name = "VFXHubLastBinPath")]
+  #[serde(rename = "VFXHubLastBinPath", default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub vfx_hub_last_bin_path: Option<String>,
+  #[napi(js_name = "BinEditorLastBinPath")]
+  #[serde(default, deserialize_with = "lenient", skip_serializing_if = "Option::is_none")]
+  pub bin_editor_last_bin_path: Option<String>,
+}
+
+impl Preferences {
+  /// Defaults for a fresh install; matches what the renderer used to hardcode.
+  fn with_defaults(mut self) -> Self {
+    self.preferred_mode.get_or_insert_with(|| "empress".to_string());
+    self.ignore_bw.get_or_insert(true);
+    self.targets.get_or_insert_with(|| vec![false, false, false, false, true]);
+    self.regenerate.get_or_insert(false);
+    self.nav_expand_enabled.get_or_insert(false);
+    self.hud_editor_enabled.get_or_insert(false);
+    self.frog_img_enabled.get_or_insert(false);
+    self.upscale_enabled.get_or_insert(false);
+    self.rgba_enabled.get_or_insert(false);
+    self.tools_enabled.get_or_insert(false);
+    self.file_randomizer_enabled.get_or_insert(false);
+    self.wallpaper_opacity.get_or_insert(0.75);
+    self.wallpaper_vignette_enabled.get_or_insert(true);
+    self.wallpaper_vignette_strength.get_or_insert(0.30);
+    self.glass_blur.get_or_insert(2.0);
+    self
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    let unit = |name: &str, v: Option<f64>| match v {
+      Some(x) if !(0.0..=1.0).contains(&x) => Err(format!("{} must be between 0 and 1", name)),
+      _ => Ok(()),
+    };
+    unit("WallpaperOpacity", self.wallpaper_opacity)?;
+    unit("WallpaperVignetteStrength", self.wallpaper_vignette_strength)?;
+    if let Some(blur) = self.glass_blur {
+      if !(0.0..=40.0).contains(&blur) { return Err("GlassBlur must be between 0 and 40".to_string()); }
+    }
+    if let Some(targets) = &self.targets {
+      if targets.len() != 5 { return Err("Targets must have exactly 5 entries".to_string()); }
+    }
+    if self.preferred_mode.as_deref().is_some_and(|m| m.trim().is_empty()) {
+      return Err("PreferredMode must not be empty".to_string());
+    }
+    Ok(())
+  }
+}
+
+#[napi(object)]
+pub struct PreferencesResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub preferences: Option<Preferences>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct PreferencesChangedEvent {
+  #[napi(js_name = "changedKeys")]
+  pub changed_keys: Vec<String>,
+  pub preferences: Preferences,
+}
+
+fn preferences_path(user_data_dir: &Path) -> PathBuf {
+  user_data_dir.join(PREFERENCES_JSON)
+}
+
+/// Raw key/value map, including keys the typed struct doesn't know about.
+fn read_raw(path: &Path) -> Result<Map<String, Value>, String> {
+  let text = match fs::read_to_string(path) {
+    Ok(t) => t,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+    Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+  };
+  if text.trim().is_empty() { return Ok(Map::new()); }
+  match serde_json::from_str(&text) {
+    Ok(Value::Object(map)) => Ok(map),
+    Ok(_) => Err(format!("{} is not a JSON object", path.display())),
+    Err(e) => Err(format!("Invalid {}: {}", PREFERENCES_JSON, e)),
+  }
+}
+
+fn write_raw(path: &Path, map: &Map<String, Value>) -> Result<(), String> {
+  let text = serde_json::to_string_pretty(map).map_err(|e| format!("Failed to serialize preferences: {}", e))?;
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  // Write next to the target and rename so a crash never leaves truncated preferences.
+  let tmp = path.with_extension("json.tmp");
+  fs::write(&tmp, text).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn typed(map: &Map<String, Value>) -> Preferences {
+  serde_json::from_value::<Preferences>(Value::Object(map.clone())).unwrap_or_default().with_defaults()
+}
+
+fn update(user_data_dir: &Path, patch: &Preferences) -> Result<(Preferences, Vec<String>), String> {
+  patch.validate()?;
+  let Value::Object(patch_map) = serde_json::to_value(patch).map_err(|e| e.to_string())? else {
+    return Err("Invalid preferences patch".to_string());
+  };
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let path = preferences_path(user_data_dir);
+  let mut map = read_raw(&path)?;
+  let mut changed = Vec::new();
+  for (key, value) in patch_map {
+    if map.get(&key) != Some(&value) {
+      changed.push(key.clone());
+      map.insert(key, value);
+    }
+  }
+  if !changed.is_empty() { write_raw(&path, &map)?; }
+  Ok((typed(&map), changed))
+}
+
+/// Read preferences from `{userDataDir}/preferences.json` with defaults applied.
+#[napi(js_name = "getPreferences")]
+pub fn get_preferences(user_data_dir: String) -> PreferencesResult {
+  match read_raw(&preferences_path(Path::new(&user_data_dir))) {
+    Ok(map) => PreferencesResult { success: true, error: None, preferences: Some(typed(&map)) },
+    Err(e) => PreferencesResult { success: false, error: Some(e), preferences: None },
+  }
+}
+
+/// Validate and apply `patch` (set fields only), save atomically and notify
+/// `onPreferencesChanged` listeners. Keys not covered by the typed struct are kept.
+#[napi(js_name = "updatePreferences")]
+pub fn update_preferences(user_data_dir: String, patch: Preferences) -> PreferencesResult {
+  match update(Path::new(&user_data_dir), &patch) {
+    Ok((preferences, changed_keys)) => {
+      if !changed_keys.is_empty() {
+        if let Some(listener) = LISTENER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+          listener.call(
+            PreferencesChangedEvent { changed_keys, preferences: preferences.clone() },
+            ThreadsafeFunctionCallMode::NonBlocking,
+          );
+        }
+      }
+      PreferencesResult { success: true, error: None, preferences: Some(preferences) }
+    }
+    Err(e) => PreferencesResult { success: false, error: Some(e), preferences: None },
+  }
+}
+
+/// Call `callback(event)` after every `updatePreferences` that changed something.
+/// Pass `null` to stop.
+#[napi(js_name = "onPreferencesChanged")]
+pub fn on_preferences_changed(env: Env, callback: Option<JsFunction>) -> napi::Result<()> {
+  let listener = match callback {
+    Some(cb) => {
+      let mut tsfn: ChangeListener = cb.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+      tsfn.unref(&env)?;
+      Some(tsfn)
+    }
+    None => None,
+  };
+  *LISTENER.write().unwrap_or_else(|e| e.into_inner()) = listener;
+  Ok(())
+}
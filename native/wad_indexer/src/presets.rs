@@ -0,0 +1,169 @@
+// ── Extraction presets ───────────────────────────────────────────────────────
+// One-call extraction of everything that belongs to a champion: the champion
+// WAD (base + every skin), its localized VO WADs and the champion's files from
+// shared WADs. Chunks present in several WADs are extracted once, from the WAD
+// the game would load them from.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi_derive::napi;
+
+use crate::game::{champions_dir, find_champion_wad, game_dir, wad_locale};
+use crate::paths::IfExists;
+use crate::{extract_atomically, extract_selected_to, HashLayers};
+
+/// Shared WADs (relative to DATA/FINAL) that carry champion-specific files.
+const SHARED_WADS: &[&str] = &["Global.wad.client", "Maps/Shipping/Common.wad.client"];
+
+#[napi(object)]
+pub struct ExtractChampionOptions {
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+  /// Locales whose VO WADs to include ("en_US", ...). `["*"]` takes every installed locale.
+  pub locales: Option<Vec<String>>,
+  /// Also pull the champion's files out of shared WADs. Defaults to true.
+  #[napi(js_name = "includeShared")]
+  pub include_shared: Option<bool>,
+  #[napi(js_name = "replaceExisting")]
+  pub replace_existing: Option<bool>,
+  /// "overwrite", "skip" or "overwriteIfDifferent"; takes precedence over `replaceExisting`.
+  #[napi(js_name = "ifExists")]
+  pub if_exists: Option<String>,
+  /// Skip chunks already written by an interrupted run into the same `outDir`.
+  pub resume: Option<bool>,
+  /// Extract into `<outDir>.partial` and move it into place only on success.
+  pub atomic: Option<bool>,
+}
+
+#[napi(object)]
+pub struct ExtractChampionResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// WADs read, highest priority first.
+  pub wads: Vec<String>,
+  #[napi(js_name = "extractedCount")]
+  pub extracted_count: u32,
+  #[napi(js_name = "skippedCount")]
+  pub skipped_count: u32,
+  /// Chunks also present in a lower-priority WAD; only the winning copy is written.
+  #[napi(js_name = "overriddenCount")]
+  pub overridden_count: u32,
+}
+
+/// Localized WADs for a champion, e.g. "Ahri.en_US.wad.client".
+fn champion_locale_wads(league: &Path, champion: &str, locales: &[String]) -> Vec<PathBuf> {
+  if locales.is_empty() { return Vec::new(); }
+  let all = locales.iter().any(|l| l == "*");
+  let wanted: HashSet<String> = locales.iter().map(|l| l.to_ascii_lowercase()).collect();
+  let prefix = format!("{}.", champion.to_ascii_lowercase());
+  let Ok(entries) = fs::read_dir(champions_dir(league)) else { return Vec::new() };
+  let mut out: Vec<PathBuf> = entries
+    .flatten()
+    .filter_map(|e| {
+      let name = e.file_name().to_string_lossy().into_owned();
+      if !name.to_ascii_lowercase().starts_with(&prefix) { return None; }
+      let locale = wad_locale(&name)?;
+      (all || wanted.contains(&locale)).then(|| e.path())
+    })
+    .collect();
+  out.sort();
+  out
+}
+
+fn extract_champion_impl(
+  league: &Path,
+  champion: &str,
+  out_dir: &str,
+  options: &ExtractChampionOptions,
+) -> Result<ExtractChampionResult, String> {
+  let if_exists = IfExists::resolve(options.if_exists.as_deref(), options.replace_existing)?;
+  let champion_wad = find_champion_wad(league, champion)
+    .ok_or_else(|| format!("No WAD found for champion {}", champion))?;
+
+  // Priority order: localized WADs override the base champion WAD, which overrides shared WADs.
+  let mut sources: Vec<(PathBuf, bool)> = champion_locale_wads(league, champion, options.locales.as_deref().unwrap_or(&[]))
+    .into_iter()
+    .map(|p| (p, false))
+    .collect();
+  sources.push((champion_wad, false));
+  if options.include_shared.unwrap_or(true) {
+    let final_dir = game_dir(league).join("DATA").join("FINAL");
+    sources.extend(SHARED_WADS.iter().map(|rel| final_dir.join(rel)).filter(|p| p.is_file()).map(|p| (p, true)));
+  }
+
+  let hash_dir = options.hash_dir.as_deref();
+  let layers = HashLayers::open(hash_dir);
+  let champ_marker = format!("characters/{}/", champion.to_ascii_lowercase());
+
+  let mut seen: HashSet<u64> = HashSet::new();
+  let mut items = Vec::new();
+  let mut wads = Vec::new();
+  let mut overridden = 0u32;
+  for (wad_path, shared) in sources {
+    let file = fs::File::open(&wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+    let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+    let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+    let hashes: Vec<u64> = wad.chunks().iter().map(|c| c.path_hash()).collect();
+    let resolved = layers.resolve_names(&hashes);
+    for (hash, path) in hashes.into_iter().zip(resolved) {
+      // Shared WADs are huge; only the champion's own folders are wanted from them.
+      if shared && !path.to_ascii_lowercase().contains(&champ_marker) { continue; }
+      if !seen.insert(hash) { overridden += 1; continue; }
+      items.push((wad_path.clone(), hash, path));
+    }
+    wads.push(wad_path.to_string_lossy().into_owned());
+  }
+
+  let out_dir = Path::new(out_dir);
+  let result = if options.atomic.unwrap_or(false) {
+    extract_atomically(out_dir, options.resume.unwrap_or(false), if_exists, |staging| {
+      extract_selected_to(items, staging, if_exists, Some(true), options.resume, None, None)
+    })
+  } else {
+    extract_selected_to(items, out_dir, if_exists, Some(true), options.resume, None, None)
+  };
+  if !result.success {
+    return Err(result.error.unwrap_or_else(|| "Extraction failed".to_string()));
+  }
+  Ok(ExtractChampionResult {
+    success: true,
+    error: None,
+    wads,
+    extracted_count: result.extracted_count,
+    skipped_count: result.skipped_count,
+    overridden_count: overridden,
+  })
+}
+
+/// Extract a champion's base WAD, requested VO WADs and its files from shared
+/// WADs into `outDir` in one pass, keeping the game's override order.
+#[napi(js_name = "extractChampion")]
+pub fn extract_champion(
+  league_path: String,
+  champion: String,
+  out_dir: String,
+  options: Option<ExtractChampionOptions>,
+) -> ExtractChampionResult {
+  let options = options.unwrap_or(ExtractChampionOptions {
+    hash_dir: None,
+    locales: None,
+    include_shared: None,
+    replace_existing: None,
+    if_exists: None,
+    resume: None,
+    atomic: None,
+  });
+  extract_champion_impl(Path::new(&league_path), &champion, &out_dir, &options).unwrap_or_else(|e| ExtractChampionResult {
+    success: false,
+    error: Some(e),
+    wads: Vec::new(),
+    extracted_count: 0,
+    skipped_count: 0,
+    overridden_count: 0,
+  })
+}
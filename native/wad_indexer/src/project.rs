@@ -0,0 +1,360 @@
+// ── Projects ─────────────────────────────────────────────────────────────────
+// A Quartz project is a folder with `project.json` and a `content/` dir holding
+// one folder per WAD it modifies (`content/{Champion}.wad.client/...`). `project.json`
+// is versioned; older files and layouts are migrated when loaded.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::freshness::{data_checksum, final_rel_path, record_origins, ChunkOrigin};
+use crate::game::find_champion_wad;
+use crate::paths::{long_path, merge_hashed_files_sidecar, rename_retrying, sanitize_rel_path, write_retrying};
+use crate::skins::skin_bin_path;
+use crate::version::detect_game_version;
+use crate::{is_safe_relative_path, normalize_rel_path, unique_chunks, HashLayers};
+
+pub(crate) const PROJECT_JSON: &str = "project.json";
+
+/// Bumped whenever the shape of `project.json` changes; older files are migrated on load.
+pub(crate) const PROJECT_SCHEMA_VERSION: u32 = 1;
+
+#[napi(object)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+  #[napi(js_name = "schemaVersion")]
+  pub schema_version: u32,
+  pub name: String,
+  #[serde(default)]
+  pub creator: String,
+  #[serde(default = "default_project_version")]
+  pub version: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub template: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub champion: Option<String>,
+  #[napi(js_name = "skinId")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub skin_id: Option<u32>,
+  /// Game patch the project was made against, e.g. "14.20".
+  #[napi(js_name = "targetPatch")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub target_patch: Option<String>,
+  /// Unix seconds.
+  #[serde(default)]
+  pub created: i64,
+}
+
+fn default_project_version() -> String {
+  "1.0.0".to_string()
+}
+
+impl Project {
+  pub(crate) fn validate(&self) -> Result<(), String> {
+    if self.name.trim().is_empty() { return Err("Project name is required".to_string()); }
+    if self.version.trim().is_empty() { return Err("Project version is required".to_string()); }
+    if self.schema_version > PROJECT_SCHEMA_VERSION {
+      return Err(format!(
+        "project.json uses schema {} but this version of Quartz only understands up to {}",
+        self.schema_version, PROJECT_SCHEMA_VERSION
+      ));
+    }
+    if self.skin_id.is_some_and(|id| id > 999) { return Err("Skin id must be between 0 and 999".to_string()); }
+    Ok(())
+  }
+}
+
+/// Upgrade an older `project.json` value to the current schema.
+/// Schema 0 (no `schemaVersion`) used fantome-style keys ("Name", "Author", ...) or `author`.
+fn migrate_project_value(mut v: Value) -> Result<(Value, bool), String> {
+  let obj = v.as_object_mut().ok_or("project.json is not an object")?;
+  let schema = obj.get("schemaVersion").and_then(|s| s.as_u64()).unwrap_or(0);
+  if schema >= PROJECT_SCHEMA_VERSION as u64 { return Ok((v, false)); }
+
+  for (old, new) in [
+    ("Name", "name"),
+    ("Author", "creator"),
+    ("author", "creator"),
+    ("Version", "version"),
+    ("Description", "description"),
+    ("skin", "skinId"),
+  ] {
+    if let Some(val) = obj.remove(old) {
+      obj.entry(new).or_insert(val);
+    }
+  }
+  if let Some(Value::String(id)) = obj.get("skinId") {
+    let parsed = id.trim().parse::<u32>().ok();
+    obj.insert("skinId".to_string(), parsed.map(Value::from).unwrap_or(Value::Null));
+  }
+  obj.insert("schemaVersion".to_string(), Value::from(PROJECT_SCHEMA_VERSION));
+  Ok((v, true))
+}
+
+/// Older projects kept WAD folders next to project.json; move them under `content/`.
+fn migrate_project_layout(project: &Path) -> Result<bool, String> {
+  let content = project.join("content");
+  if content.is_dir() { return Ok(false); }
+  let wad_dirs: Vec<PathBuf> = fs::read_dir(project)
+    .map_err(|e| format!("Failed to read {}: {}", project.display(), e))?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| {
+      let name = p.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+      p.is_dir() && (name.ends_with(".wad.client") || name.ends_with(".wad"))
+    })
+    .collect();
+  if wad_dirs.is_empty() { return Ok(false); }
+  fs::create_dir_all(&content).map_err(|e| format!("Failed to create {}: {}", content.display(), e))?;
+  for dir in wad_dirs {
+    let Some(name) = dir.file_name() else { continue };
+    rename_retrying(&dir, &content.join(name)).map_err(|e| format!("Failed to move {}: {}", dir.display(), e))?;
+  }
+  Ok(true)
+}
+
+/// Read, migrate and validate `{project}/project.json`. Returns the project and
+/// whether anything was migrated (in which case the upgraded file is written back).
+pub(crate) fn read_project(project: &Path) -> Result<(Project, bool), String> {
+  let path = project.join(PROJECT_JSON);
+  let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  let raw: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+    .map_err(|e| format!("Invalid {}: {}", PROJECT_JSON, e))?;
+  let (value, json_migrated) = migrate_project_value(raw)?;
+  let project_data: Project = serde_json::from_value(value)
+    .map_err(|e| format!("Invalid {}: {}", PROJECT_JSON, e))?;
+  project_data.validate()?;
+  let layout_migrated = migrate_project_layout(project)?;
+  if json_migrated {
+    write_project(project, &project_data)?;
+  }
+  Ok((project_data, json_migrated || layout_migrated))
+}
+
+pub(crate) fn write_project(project: &Path, data: &Project) -> Result<(), String> {
+  data.validate()?;
+  let text = serde_json::to_string_pretty(data).map_err(|e| format!("Failed to serialize project: {}", e))?;
+  fs::create_dir_all(project).map_err(|e| format!("Failed to create {}: {}", project.display(), e))?;
+  // Write next to the target and rename so a crash never leaves a truncated project.json.
+  let tmp = project.join(format!("{}.tmp", PROJECT_JSON));
+  write_retrying(&tmp, text.as_bytes()).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  rename_retrying(&tmp, &project.join(PROJECT_JSON)).map_err(|e| format!("Failed to write {}: {}", PROJECT_JSON, e))
+}
+
+#[napi(object)]
+pub struct LoadProjectResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub project: Option<Project>,
+  /// True when an older project.json or folder layout was upgraded on load.
+  pub migrated: bool,
+}
+
+#[napi(object)]
+pub struct SaveProjectResult {
+  pub success: bool,
+  pub error: Option<String>,
+}
+
+/// Load `project.json`, migrating older schemas and layouts in place.
+#[napi(js_name = "loadProject")]
+pub fn load_project(project_path: String) -> LoadProjectResult {
+  match read_project(Path::new(&project_path)) {
+    Ok((project, migrated)) => LoadProjectResult { success: true, error: None, project: Some(project), migrated },
+    Err(e) => LoadProjectResult { success: false, error: Some(e), project: None, migrated: false },
+  }
+}
+
+/// Validate and write `project.json`. The schema version is always set to the current one.
+#[napi(js_name = "saveProject")]
+pub fn save_project(project_path: String, mut project: Project) -> SaveProjectResult {
+  project.schema_version = PROJECT_SCHEMA_VERSION;
+  match write_project(Path::new(&project_path), &project) {
+    Ok(()) => SaveProjectResult { success: true, error: None },
+    Err(e) => SaveProjectResult { success: false, error: Some(e) },
+  }
+}
+
+#[napi(object)]
+pub struct CreateProjectOptions {
+  pub name: String,
+  pub champion: Option<String>,
+  #[napi(js_name = "skinId")]
+  pub skin_id: Option<u32>,
+  pub creator: Option<String>,
+  /// Needed to pre-extract the target skin.
+  #[napi(js_name = "leaguePath")]
+  pub league_path: Option<String>,
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+  /// Copy the skin's bins and assets out of the champion WAD. Defaults to false.
+  #[napi(js_name = "extractSkin")]
+  pub extract_skin: Option<bool>,
+}
+
+#[napi(object)]
+pub struct CreateProjectResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "projectPath")]
+  pub project_path: String,
+  #[napi(js_name = "wadDir")]
+  pub wad_dir: Option<String>,
+  #[napi(js_name = "extractedCount")]
+  pub extracted_count: u32,
+}
+
+/// Asset folder name the game uses for a skin: "base" for 0, "skin01".."skin99" otherwise.
+pub(crate) fn skin_asset_folder(skin_id: u32) -> String {
+  if skin_id == 0 { "base".to_string() } else { format!("skin{:02}", skin_id) }
+}
+
+/// Lowercased path prefixes (and exact paths) that belong to one skin.
+fn skin_path_filters(champion: &str, skin_id: u32) -> (Vec<String>, Vec<String>) {
+  let c = champion.to_ascii_lowercase();
+  let prefixes = vec![format!("assets/characters/{}/skins/{}/", c, skin_asset_folder(skin_id))];
+  let exact = vec![
+    skin_bin_path(champion, skin_id).to_ascii_lowercase(),
+    format!("data/characters/{}/animations/skin{}.bin", c, skin_id),
+  ];
+  (prefixes, exact)
+}
+
+/// Extract every chunk of `wad_path` whose resolved path matches the skin
+/// filters. Returns the origin of each file written.
+fn extract_skin_files(
+  wad_path: &Path,
+  out_dir: &Path,
+  champion: &str,
+  skin_id: u32,
+  hash_dir: Option<&str>,
+  patch: Option<&str>,
+) -> Result<Vec<(u64, ChunkOrigin)>, String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let mut wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+  let layers = HashLayers::open(hash_dir);
+  let resolved = layers.resolve_names(&hashes);
+
+  let (prefixes, exact) = skin_path_filters(champion, skin_id);
+  let mut origins = Vec::new();
+  let mut renamed: HashMap<String, String> = HashMap::new();
+  for (chunk, path) in chunks.iter().zip(resolved) {
+    let rel = normalize_rel_path(&path);
+    let lower = rel.to_ascii_lowercase();
+    let wanted = exact.contains(&lower) || prefixes.iter().any(|p| lower.starts_with(p));
+    if !wanted || !is_safe_relative_path(&rel) { continue; }
+    let Ok(data) = wad.load_chunk_decompressed(chunk) else { continue };
+    let safe = sanitize_rel_path(&rel);
+    if let Cow::Owned(name) = &safe { renamed.insert(name.clone(), path.clone()); }
+    let out = long_path(&out_dir.join(safe.as_ref()));
+    if let Some(parent) = out.parent() { let _ = fs::create_dir_all(parent); }
+    if write_retrying(&out, &data).is_ok() {
+      let checksum = data_checksum(&data);
+      let origin = ChunkOrigin { checksum, size: data.len() as u64, path, patch: patch.map(str::to_string), synced: None };
+      origins.push((chunk.path_hash(), origin));
+    }
+  }
+  merge_hashed_files_sidecar(out_dir, renamed);
+  Ok(origins)
+}
+
+fn scaffold(project: &Path, template: &str, opts: &CreateProjectOptions) -> Result<(Option<PathBuf>, u32), String> {
+  if project.join(PROJECT_JSON).exists() {
+    return Err(format!("A project already exists at {}", project.display()));
+  }
+  let content = project.join("content");
+  fs::create_dir_all(&content).map_err(|e| format!("Failed to create {}: {}", content.display(), e))?;
+
+  let champion = opts.champion.as_deref().map(str::trim).filter(|c| !c.is_empty());
+  let skin_id = opts.skin_id.unwrap_or(0);
+  let mut wad_dir = None;
+  let mut extracted = 0u32;
+
+  match template {
+    "empty" => {}
+    "skin" => {
+      let champion = champion.ok_or("The skin template requires a champion")?;
+      let league = opts.league_path.as_deref().map(Path::new);
+      // Use the game's file name casing when the install is known.
+      let game_wad = league.and_then(|l| find_champion_wad(l, champion));
+      let wad_name = game_wad.as_ref()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| format!("{}.wad.client", champion));
+      let dir = content.join(&wad_name);
+      let c = champion.to_ascii_lowercase();
+      for sub in [
+        format!("data/characters/{}/skins", c),
+        format!("assets/characters/{}/skins/{}", c, skin_asset_folder(skin_id)),
+      ] {
+        let d = dir.join(sub);
+        fs::create_dir_all(&d).map_err(|e| format!("Failed to create {}: {}", d.display(), e))?;
+      }
+      if opts.extract_skin.unwrap_or(false) {
+        let game_wad = game_wad.ok_or_else(|| format!("Champion WAD not found for {}", champion))?;
+        let patch = league.and_then(|l| detect_game_version(l).patch);
+        let origins = extract_skin_files(&game_wad, &dir, champion, skin_id, opts.hash_dir.as_deref(), patch.as_deref())?;
+        extracted = origins.len() as u32;
+        record_origins(project, &wad_name, &final_rel_path(league.unwrap_or(Path::new("")), &game_wad), origins)?;
+      }
+      wad_dir = Some(dir);
+    }
+    other => return Err(format!("Unknown project template: {}", other)),
+  }
+
+  let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+  let target_patch = opts.league_path.as_deref().and_then(|l| detect_game_version(Path::new(l)).patch);
+  write_project(project, &Project {
+    schema_version: PROJECT_SCHEMA_VERSION,
+    name: opts.name.trim().to_string(),
+    creator: opts.creator.clone().unwrap_or_default(),
+    version: default_project_version(),
+    description: None,
+    template: Some(template.to_string()),
+    champion: champion.map(str::to_string),
+    skin_id: champion.map(|_| skin_id),
+    target_patch,
+    created,
+  })?;
+  Ok((wad_dir, extracted))
+}
+
+/// Create a project folder from a template ("skin" or "empty"): content layout,
+/// `project.json`, and optionally the target skin's files pre-extracted.
+#[napi(js_name = "createProject")]
+pub fn create_project(project_path: String, template: String, options: CreateProjectOptions) -> CreateProjectResult {
+  let fail = |project_path: String, e: String| CreateProjectResult {
+    success: false,
+    error: Some(e),
+    project_path,
+    wad_dir: None,
+    extracted_count: 0,
+  };
+  if options.name.trim().is_empty() {
+    return fail(project_path, "Project name is required".to_string());
+  }
+  match scaffold(Path::new(&project_path), &template.to_ascii_lowercase(), &options) {
+    Ok((wad_dir, extracted_count)) => CreateProjectResult {
+      success: true,
+      error: None,
+      project_path,
+      wad_dir: wad_dir.map(|d| d.to_string_lossy().into_owned()),
+      extracted_count,
+    },
+    Err(e) => fail(project_path, e),
+  }
+}
@@ -0,0 +1,276 @@
+// ── Project search ───────────────────────────────────────────────────────────
+// In-memory index over a project folder: file names plus the text of
+// .py/.json/.txt files and of .bin files converted to ritobin text, so "where
+// is skin42's color defined" is one query instead of opening files one by one.
+// Re-indexing only re-reads files whose size or mtime changed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Instant, UNIX_EPOCH};
+
+use ltk_meta::Bin;
+use ltk_ritobin::{write_with_hashes, HashMapProvider};
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::threads::run_io;
+use crate::wad_build::collect_files;
+
+/// Larger files are indexed by name only.
+const MAX_CONTENT_BYTES: u64 = 16 * 1024 * 1024;
+const MAX_MATCHES_PER_FILE: usize = 5;
+const MAX_LINE_CHARS: usize = 240;
+const DEFAULT_LIMIT: u32 = 100;
+
+struct IndexedFile {
+  rel: String,
+  rel_lower: String,
+  size: u64,
+  mtime_ms: f64,
+  /// Original lines, and lowercased copies for matching.
+  lines: Vec<String>,
+  lines_lower: Vec<String>,
+}
+
+#[derive(Default)]
+struct ProjectIndex {
+  files: Vec<Arc<IndexedFile>>,
+}
+
+fn indexes() -> &'static Mutex<HashMap<PathBuf, Arc<ProjectIndex>>> {
+  static INDEXES: OnceLock<Mutex<HashMap<PathBuf, Arc<ProjectIndex>>>> = OnceLock::new();
+  INDEXES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Loading a hash dir takes seconds, so providers are kept per dir.
+pub(crate) fn hash_provider(hash_dir: Option<&str>) -> Arc<HashMapProvider> {
+  static PROVIDERS: OnceLock<Mutex<HashMap<String, Arc<HashMapProvider>>>> = OnceLock::new();
+  let Some(dir) = hash_dir else { return Arc::new(HashMapProvider::new()) };
+  let mut providers = PROVIDERS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap_or_else(|e| e.into_inner());
+  providers
+    .entry(dir.to_string())
+    .or_insert_with(|| Arc::new(quartz_core::ritobin::load_hash_provider(Some(Path::new(dir)))))
+    .clone()
+}
+
+pub(crate) fn extract_text(path: &Path, rel_lower: &str, hashes: &HashMapProvider) -> Option<String> {
+  let ext = rel_lower.rsplit('.').next().unwrap_or("");
+  match ext {
+    "py" | "json" | "txt" | "ritobin" => fs::read(path).ok().map(|b| String::from_utf8_lossy(&b).into_owned()),
+    "bin" => {
+      let data = fs::read(path).ok()?;
+      let tree = Bin::from_reader(&mut Cursor::new(&data)).ok()?;
+      write_with_hashes(&tree, hashes).ok()
+    }
+    _ => None,
+  }
+}
+
+fn index_file(rel: String, path: &Path, size: u64, mtime_ms: f64, hashes: &HashMapProvider) -> IndexedFile {
+  let rel_lower = rel.to_lowercase();
+  let text = if size <= MAX_CONTENT_BYTES { extract_text(path, &rel_lower, hashes) } else { None };
+  let lines: Vec<String> = text.map(|t| t.lines().map(str::to_string).collect()).unwrap_or_default();
+  let lines_lower = lines.iter().map(|l| l.to_lowercase()).collect();
+  IndexedFile { rel, rel_lower, size, mtime_ms, lines, lines_lower }
+}
+
+#[napi(object)]
+pub struct ProjectIndexResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "fileCount")]
+  pub file_count: u32,
+  /// Files (re)read this run; unchanged files are reused from the previous index.
+  #[napi(js_name = "reindexedCount")]
+  pub reindexed_count: u32,
+  #[napi(js_name = "elapsedMs")]
+  pub elapsed_ms: f64,
+}
+
+fn build_index(project_path: &Path, hash_dir: Option<&str>) -> Result<(Arc<ProjectIndex>, u32), String> {
+  if !project_path.is_dir() {
+    return Err(format!("Project folder not found: {}", project_path.display()));
+  }
+  let previous: HashMap<String, Arc<IndexedFile>> = indexes()
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .get(project_path)
+    .map(|idx| idx.files.iter().map(|f| (f.rel.clone(), f.clone())).collect())
+    .unwrap_or_default();
+
+  let files = collect_files(project_path)?;
+  let hashes = hash_provider(hash_dir);
+  let entries: Vec<(Arc<IndexedFile>, bool)> = run_io(|| {
+    files
+      .into_par_iter()
+      .filter(|(rel, _)| !rel.starts_with(".git/") && !rel.starts_with("node_modules/"))
+      .filter_map(|(rel, path)| {
+        let meta = fs::metadata(&path).ok()?;
+        let mtime_ms = meta
+          .modified()
+          .ok()
+          .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+          .map(|d| d.as_millis() as f64)
+          .unwrap_or(0.0);
+        if let Some(prev) = previous.get(&rel) {
+          if prev.size == meta.len() && prev.mtime_ms == mtime_ms {
+            return Some((prev.clone(), false));
+          }
+        }
+        Some((Arc::new(index_file(rel, &path, meta.len(), mtime_ms, &hashes)), true))
+      })
+      .collect()
+  });
+  let reindexed = entries.iter().filter(|(_, fresh)| *fresh).count() as u32;
+  let mut files: Vec<Arc<IndexedFile>> = entries.into_iter().map(|(f, _)| f).collect();
+  files.sort_by(|a, b| a.rel.cmp(&b.rel));
+  let index = Arc::new(ProjectIndex { files });
+  indexes().lock().unwrap_or_else(|e| e.into_inner()).insert(project_path.to_path_buf(), index.clone());
+  Ok((index, reindexed))
+}
+
+fn index_project_impl(project_path: &str, hash_dir: Option<&str>) -> ProjectIndexResult {
+  let start = Instant::now();
+  match build_index(Path::new(project_path), hash_dir) {
+    Ok((index, reindexed)) => ProjectIndexResult {
+      success: true,
+      error: None,
+      file_count: index.files.len() as u32,
+      reindexed_count: reindexed,
+      elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+    },
+    Err(e) => ProjectIndexResult {
+      success: false,
+      error: Some(e),
+      file_count: 0,
+      reindexed_count: 0,
+      elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+    },
+  }
+}
+
+pub struct IndexProjectTask {
+  project_path: String,
+  hash_dir: Option<String>,
+}
+
+#[napi]
+impl Task for IndexProjectTask {
+  type Output = ProjectIndexResult;
+  type JsValue = ProjectIndexResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(index_project_impl(&self.project_path, self.hash_dir.as_deref()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// (Re)build the search index for `projectPath` off the main thread. Call
+/// again after edits (e.g. from `watchPaths`); unchanged files are reused.
+/// `hashDir` names hashes in converted .bin text so field names are searchable.
+#[napi(js_name = "indexProject")]
+pub fn index_project(project_path: String, hash_dir: Option<String>) -> AsyncTask<IndexProjectTask> {
+  AsyncTask::new(IndexProjectTask { project_path, hash_dir })
+}
+
+/// Drop the cached index for `projectPath`, e.g. when the project is closed.
+#[napi(js_name = "clearProjectIndex")]
+pub fn clear_project_index(project_path: String) {
+  indexes().lock().unwrap_or_else(|e| e.into_inner()).remove(Path::new(&project_path));
+}
+
+#[napi(object)]
+pub struct SearchLineMatch {
+  /// 1-based.
+  pub line: u32,
+  pub text: String,
+}
+
+#[napi(object)]
+pub struct ProjectSearchHit {
+  /// Relative to the project folder, `/`-separated.
+  pub path: String,
+  pub score: f64,
+  #[napi(js_name = "nameMatch")]
+  pub name_match: bool,
+  pub matches: Vec<SearchLineMatch>,
+  /// Matching lines in the file, including ones beyond `matches`.
+  #[napi(js_name = "matchCount")]
+  pub match_count: u32,
+}
+
+#[napi(object)]
+pub struct ProjectSearchResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Best first.
+  pub hits: Vec<ProjectSearchHit>,
+}
+
+fn truncate_line(line: &str) -> String {
+  let trimmed = line.trim();
+  match trimmed.char_indices().nth(MAX_LINE_CHARS) {
+    Some((i, _)) => format!("{}…", &trimmed[..i]),
+    None => trimmed.to_string(),
+  }
+}
+
+/// Every query term must appear in a line (or the path) for it to match. Path
+/// matches outrank content matches; whole-phrase matches outrank scattered terms.
+fn score_file(file: &IndexedFile, phrase: &str, terms: &[&str]) -> Option<ProjectSearchHit> {
+  let name_match = terms.iter().all(|t| file.rel_lower.contains(t));
+  let mut score = 0.0;
+  if name_match {
+    let file_name = file.rel_lower.rsplit('/').next().unwrap_or(&file.rel_lower);
+    score += if file_name.contains(phrase) { 100.0 } else { 50.0 };
+  }
+  let mut matches = Vec::new();
+  let mut match_count = 0u32;
+  let mut content_score: f64 = 0.0;
+  for (i, line) in file.lines_lower.iter().enumerate() {
+    if !terms.iter().all(|t| line.contains(t)) { continue; }
+    match_count += 1;
+    content_score += if line.contains(phrase) { 10.0 } else { 5.0 };
+    if matches.len() < MAX_MATCHES_PER_FILE {
+      matches.push(SearchLineMatch { line: i as u32 + 1, text: truncate_line(&file.lines[i]) });
+    }
+  }
+  if !name_match && match_count == 0 { return None; }
+  // Capped so one huge bin with hundreds of hits doesn't bury a file-name match.
+  score += content_score.min(50.0);
+  Some(ProjectSearchHit { path: file.rel.clone(), score, name_match, matches, match_count })
+}
+
+fn search(project_path: &str, query: &str, limit: u32) -> Result<Vec<ProjectSearchHit>, String> {
+  let phrase = query.trim().to_lowercase();
+  let terms: Vec<&str> = phrase.split_whitespace().collect();
+  if terms.is_empty() { return Ok(Vec::new()); }
+  let cached = indexes().lock().unwrap_or_else(|e| e.into_inner()).get(Path::new(project_path)).cloned();
+  let index = match cached {
+    Some(index) => index,
+    None => build_index(Path::new(project_path), None)?.0,
+  };
+  let mut hits: Vec<ProjectSearchHit> = index.files.par_iter().filter_map(|f| score_file(f, &phrase, &terms)).collect();
+  hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+  hits.truncate(limit as usize);
+  Ok(hits)
+}
+
+/// Search file names and contents of `projectPath` (case-insensitive, all
+/// terms must match). Uses the index from `indexProject`, building one without
+/// hash names if there is none yet.
+#[napi(js_name = "searchProject")]
+pub fn search_project(project_path: String, query: String, limit: Option<u32>) -> ProjectSearchResult {
+  match search(&project_path, &query, limit.unwrap_or(DEFAULT_LIMIT)) {
+    Ok(hits) => ProjectSearchResult { success: true, error: None, hits },
+    Err(e) => ProjectSearchResult { success: false, error: Some(e), hits: Vec::new() },
+  }
+}
@@ -0,0 +1,167 @@
+// ── Recent projects ──────────────────────────────────────────────────────────
+// `{userData}/recent-projects.json`: projects the user opened, with enough
+// metadata (name, champion, skin) for a launcher screen to render without
+// opening every project. Pinned entries are never evicted; unpinned ones are
+// capped at `MAX_UNPINNED`, oldest first.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::project::read_project;
+
+const RECENT_PROJECTS_JSON: &str = "recent-projects.json";
+const MAX_UNPINNED: usize = 20;
+
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[napi(object)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProject {
+  pub path: String,
+  pub name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub champion: Option<String>,
+  #[napi(js_name = "skinId")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub skin_id: Option<u32>,
+  /// Unix seconds.
+  #[napi(js_name = "lastOpened")]
+  pub last_opened: i64,
+  #[serde(default)]
+  pub pinned: bool,
+  /// False when the folder is gone; filled in when listing, not stored.
+  #[serde(skip, default = "default_exists")]
+  pub exists: bool,
+}
+
+fn default_exists() -> bool {
+  true
+}
+
+#[napi(object)]
+pub struct RecentProjectsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Pinned first, then most recently opened.
+  pub projects: Vec<RecentProject>,
+}
+
+fn now_secs() -> i64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+fn list_path(user_data_dir: &Path) -> PathBuf {
+  user_data_dir.join(RECENT_PROJECTS_JSON)
+}
+
+fn same_project(a: &str, b: &str) -> bool {
+  let a = a.trim_end_matches(['/', '\\']);
+  let b = b.trim_end_matches(['/', '\\']);
+  if cfg!(windows) { a.replace('/', "\\").eq_ignore_ascii_case(&b.replace('/', "\\")) } else { a == b }
+}
+
+fn read_list(user_data_dir: &Path) -> Vec<RecentProject> {
+  fs::read_to_string(list_path(user_data_dir))
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default()
+}
+
+fn write_list(user_data_dir: &Path, list: &[RecentProject]) -> Result<(), String> {
+  let text = serde_json::to_string_pretty(list).map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+  fs::create_dir_all(user_data_dir).map_err(|e| format!("Failed to create {}: {}", user_data_dir.display(), e))?;
+  let path = list_path(user_data_dir);
+  let tmp = path.with_extension("json.tmp");
+  fs::write(&tmp, text).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn sorted(mut list: Vec<RecentProject>) -> Vec<RecentProject> {
+  list.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.last_opened.cmp(&a.last_opened)));
+  list
+}
+
+/// Apply `change` to the stored list under the write lock and save it.
+fn modify(user_data_dir: &Path, change: impl FnOnce(&mut Vec<RecentProject>) -> Result<(), String>) -> Result<Vec<RecentProject>, String> {
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let mut list = read_list(user_data_dir);
+  change(&mut list)?;
+  let mut list = sorted(list);
+  let mut unpinned = 0;
+  list.retain(|p| {
+    if p.pinned { return true; }
+    unpinned += 1;
+    unpinned <= MAX_UNPINNED
+  });
+  write_list(user_data_dir, &list)?;
+  Ok(list)
+}
+
+fn to_result(result: Result<Vec<RecentProject>, String>) -> RecentProjectsResult {
+  match result {
+    Ok(list) => RecentProjectsResult {
+      success: true,
+      error: None,
+      projects: list.into_iter().map(|mut p| { p.exists = Path::new(&p.path).is_dir(); p }).collect(),
+    },
+    Err(e) => RecentProjectsResult { success: false, error: Some(e), projects: Vec::new() },
+  }
+}
+
+/// Recent projects, pinned first, then by last opened.
+#[napi(js_name = "listRecentProjects")]
+pub fn list_recent_projects(user_data_dir: String) -> RecentProjectsResult {
+  to_result(Ok(sorted(read_list(Path::new(&user_data_dir)))))
+}
+
+fn record(user_data_dir: &Path, project_path: &str) -> Result<Vec<RecentProject>, String> {
+  let (project, _) = read_project(Path::new(project_path))?;
+  modify(user_data_dir, |list| {
+    let pinned = list.iter().any(|p| p.pinned && same_project(&p.path, project_path));
+    list.retain(|p| !same_project(&p.path, project_path));
+    list.push(RecentProject {
+      path: project_path.to_string(),
+      name: project.name,
+      champion: project.champion,
+      skin_id: project.skin_id,
+      last_opened: now_secs(),
+      pinned,
+      exists: true,
+    });
+    Ok(())
+  })
+}
+
+/// Record that `projectPath` was opened now, refreshing its name/champion/skin
+/// from project.json. Keeps the pinned flag of an existing entry.
+#[napi(js_name = "recordRecentProject")]
+pub fn record_recent_project(user_data_dir: String, project_path: String) -> RecentProjectsResult {
+  to_result(record(Path::new(&user_data_dir), &project_path))
+}
+
+#[napi(js_name = "pinRecentProject")]
+pub fn pin_recent_project(user_data_dir: String, project_path: String, pinned: bool) -> RecentProjectsResult {
+  to_result(modify(Path::new(&user_data_dir), |list| {
+    let entry = list
+      .iter_mut()
+      .find(|p| same_project(&p.path, &project_path))
+      .ok_or_else(|| format!("{} is not in the recent projects list", project_path))?;
+    entry.pinned = pinned;
+    Ok(())
+  }))
+}
+
+/// Forget `projectPath`. The project folder itself is left alone.
+#[napi(js_name = "removeRecentProject")]
+pub fn remove_recent_project(user_data_dir: String, project_path: String) -> RecentProjectsResult {
+  to_result(modify(Path::new(&user_data_dir), |list| {
+    list.retain(|p| !same_project(&p.path, &project_path));
+    Ok(())
+  }))
+}
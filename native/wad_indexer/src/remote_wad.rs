@@ -0,0 +1,167 @@
+// ── Remote WADs ──────────────────────────────────────────────────────────────
+// Mounts a WAD served over HTTP (a CDragon or mirror-hosted archive) without
+// downloading it: the header and TOC are fetched with one range request each
+// and cached, and chunk reads fetch only the chunks' raw bytes, with
+// neighbouring chunks grouped into one request. `readWadChunk(s)` accept such
+// URLs in place of a local path.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use ltk_wad::{Wad, WadChunk};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::chunk_decode::decompress_chunk_bytes;
+use crate::chunk_read::ChunkRead;
+use crate::game::{wad_kind, WadKind};
+use crate::downloader::http_get;
+use crate::rman::RANGE_MERGE_GAP;
+use crate::threads::run_io;
+use crate::{resolve_wad_hashes, unique_chunks, HashLayers};
+
+/// Remote TOCs kept in memory.
+const MAX_CACHED_TOCS: usize = 8;
+/// WAD 3.x header up to and including the chunk count.
+const V3_HEADER_SIZE: u64 = 272;
+const V3_TOC_ENTRY_SIZE: u64 = 32;
+
+pub(crate) struct RemoteWad {
+  url: String,
+  chunks: HashMap<u64, WadChunk>,
+}
+
+/// (path hash, decompressed data or why it couldn't be read)
+type FetchedChunk = (u64, Result<Vec<u8>, String>);
+/// Least recently used first.
+type TocCache = Vec<(String, Arc<RemoteWad>)>;
+
+fn tocs() -> &'static Mutex<TocCache> {
+  static TOCS: OnceLock<Mutex<TocCache>> = OnceLock::new();
+  TOCS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn fetch_toc(url: &str) -> Result<RemoteWad, String> {
+  let header = http_get(url, Some((0, V3_HEADER_SIZE)))?;
+  if &header[..2] != b"RW" || header[2] != 3 {
+    return Err(format!("Not a WAD 3.x archive: {}", url));
+  }
+  let count = u32::from_le_bytes([header[268], header[269], header[270], header[271]]) as u64;
+  let toc = http_get(url, Some((0, V3_HEADER_SIZE + count * V3_TOC_ENTRY_SIZE)))?;
+  // Mounting only reads the header and TOC, so the prefix is all it needs.
+  let wad = Wad::mount(Cursor::new(toc)).map_err(|e| format!("Failed to mount {}: {}", url, e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  Ok(RemoteWad { url: url.to_string(), chunks: chunks.into_iter().map(|c| (c.path_hash(), c)).collect() })
+}
+
+pub(crate) fn mount_remote(url: &str) -> Result<Arc<RemoteWad>, String> {
+  {
+    let mut cached = tocs().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(pos) = cached.iter().position(|(k, _)| k == url) {
+      let entry = cached.remove(pos);
+      let wad = entry.1.clone();
+      cached.push(entry);
+      return Ok(wad);
+    }
+  }
+  let wad = Arc::new(fetch_toc(url)?);
+  let mut cached = tocs().lock().unwrap_or_else(|e| e.into_inner());
+  cached.retain(|(k, _)| k != url);
+  if cached.len() >= MAX_CACHED_TOCS { cached.remove(0); }
+  cached.push((url.to_string(), wad.clone()));
+  Ok(wad)
+}
+
+impl RemoteWad {
+  /// Decompressed chunks for `hashes`, in order; missing ones are `None`. A
+  /// failed range request fails only the chunks it covered.
+  pub(crate) fn read(&self, hashes: &[u64]) -> Vec<ChunkRead> {
+    let mut wanted: Vec<WadChunk> = hashes.iter().filter_map(|h| self.chunks.get(h).copied()).collect();
+    wanted.sort_by_key(|c| c.data_offset());
+    wanted.dedup_by_key(|c| c.path_hash());
+    let mut groups: Vec<Vec<WadChunk>> = Vec::new();
+    let mut group: Vec<WadChunk> = Vec::new();
+    for c in wanted {
+      if let Some(last) = group.last() {
+        if c.data_offset() as u64 > (last.data_offset() + last.compressed_size()) as u64 + RANGE_MERGE_GAP {
+          groups.push(std::mem::take(&mut group));
+        }
+      }
+      group.push(c);
+    }
+    if !group.is_empty() { groups.push(group); }
+
+    let fetched: Vec<Vec<FetchedChunk>> = run_io(|| {
+      groups
+        .par_iter()
+        .map(|group| {
+          let start = group[0].data_offset() as u64;
+          let end = group.iter().map(|c| (c.data_offset() + c.compressed_size()) as u64).max().unwrap_or(start);
+          match http_get(&self.url, Some((start, end))) {
+            Ok(data) => group
+              .iter()
+              .map(|c| {
+                let rel = c.data_offset() - start as usize;
+                (c.path_hash(), decompress_chunk_bytes(&data[rel..rel + c.compressed_size()], c))
+              })
+              .collect(),
+            Err(e) => group.iter().map(|c| (c.path_hash(), Err(e.clone()))).collect(),
+          }
+        })
+        .collect()
+    });
+    let out: HashMap<u64, Result<Vec<u8>, String>> = fetched.into_iter().flatten().collect();
+    hashes.iter().map(|h| out.get(h).cloned().transpose()).collect()
+  }
+}
+
+// ── listRemoteWad ────────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct RemoteWadChunk {
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// Resolved path, or the hex hash when unknown.
+  pub path: String,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+}
+
+#[napi(object)]
+pub struct RemoteWadListing {
+  pub success: bool,
+  pub error: Option<String>,
+  pub chunks: Vec<RemoteWadChunk>,
+}
+
+fn list(url: &str, hash_dir: Option<&str>) -> Result<Vec<RemoteWadChunk>, String> {
+  let wad = mount_remote(url)?;
+  let mut chunks: Vec<&WadChunk> = wad.chunks.values().collect();
+  chunks.sort_by_key(|c| c.path_hash());
+  let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+  let url_path = url.split(['?', '#']).next().unwrap_or(url);
+  let kind = wad_kind(Path::new(url_path)).unwrap_or(WadKind::Game);
+  let paths = resolve_wad_hashes(kind, &hashes, &HashLayers::open(hash_dir), hash_dir);
+  Ok(chunks
+    .iter()
+    .zip(paths)
+    .map(|(c, path)| RemoteWadChunk {
+      path_hash: format!("{:016x}", c.path_hash()),
+      path,
+      size: c.uncompressed_size() as f64,
+      compressed_size: c.compressed_size() as f64,
+    })
+    .collect())
+}
+
+/// List the chunks of a WAD served over HTTP, fetching only its TOC.
+#[napi(js_name = "listRemoteWad")]
+pub fn list_remote_wad(url: String, hash_dir: Option<String>) -> RemoteWadListing {
+  match list(&url, hash_dir.as_deref()) {
+    Ok(chunks) => RemoteWadListing { success: true, error: None, chunks },
+    Err(e) => RemoteWadListing { success: false, error: Some(e), chunks: Vec::new() },
+  }
+}
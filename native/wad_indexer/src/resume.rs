@@ -0,0 +1,124 @@
+// ── Resumable extraction ─────────────────────────────────────────────────────
+// Extractions record the chunks they have written in `.quartz-extract.json`
+// in the output dir. With `resume: true` a later run skips those chunks, so an
+// interrupted full-game extraction continues where it stopped instead of
+// relying on "file exists" checks (which trust half-written files).
+//
+// Entries are keyed by WAD path and invalidated when the WAD's size or mtime
+// changes (e.g. the game patched in between).
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::get_file_mtime_ms;
+
+pub(crate) const RESUME_MANIFEST_JSON: &str = ".quartz-extract.json";
+const RESUME_MANIFEST_VERSION: u32 = 1;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumeWadEntry {
+  size: u64,
+  mtime_ms: u64,
+  #[serde(default)]
+  complete: bool,
+  /// Hex path hashes written so far.
+  #[serde(default)]
+  done: Vec<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ResumeManifest {
+  version: u32,
+  #[serde(default)]
+  wads: BTreeMap<String, ResumeWadEntry>,
+}
+
+struct TrackerState {
+  manifest: ResumeManifest,
+  dirty: bool,
+  last_flush: Instant,
+}
+
+/// Shared by the workers of one extraction; `mark` is cheap and flushes at most every few seconds.
+pub(crate) struct ResumeTracker {
+  path: PathBuf,
+  state: Mutex<TrackerState>,
+}
+
+fn wad_stamp(wad_path: &Path) -> (u64, u64) {
+  let size = fs::metadata(wad_path).map(|m| m.len()).unwrap_or(0);
+  (size, get_file_mtime_ms(wad_path) as u64)
+}
+
+/// Manifest key for a WAD. Only used as a JSON key, never to reopen the file.
+fn wad_key(wad_path: &Path) -> String {
+  wad_path.to_string_lossy().into_owned()
+}
+
+impl ResumeTracker {
+  pub(crate) fn open(output_dir: &Path) -> Self {
+    let path = output_dir.join(RESUME_MANIFEST_JSON);
+    let manifest = fs::read_to_string(&path)
+      .ok()
+      .and_then(|s| serde_json::from_str::<ResumeManifest>(&s).ok())
+      .filter(|m| m.version == RESUME_MANIFEST_VERSION)
+      .unwrap_or(ResumeManifest { version: RESUME_MANIFEST_VERSION, wads: BTreeMap::new() });
+    ResumeTracker { path, state: Mutex::new(TrackerState { manifest, dirty: false, last_flush: Instant::now() }) }
+  }
+
+  /// Start (or continue) extracting `wad_path`. Returns the chunks to skip:
+  /// the recorded ones when `resume` is set and the WAD is unchanged, else none.
+  pub(crate) fn begin(&self, wad_path: &Path, resume: bool) -> HashSet<u64> {
+    let (size, mtime_ms) = wad_stamp(wad_path);
+    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = state.manifest.wads.entry(wad_key(wad_path)).or_default();
+    if resume && entry.size == size && entry.mtime_ms == mtime_ms {
+      return entry.done.iter().filter_map(|h| u64::from_str_radix(h, 16).ok()).collect();
+    }
+    *entry = ResumeWadEntry { size, mtime_ms, complete: false, done: Vec::new() };
+    state.dirty = true;
+    HashSet::new()
+  }
+
+  pub(crate) fn mark(&self, wad_path: &Path, path_hash: u64) {
+    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(entry) = state.manifest.wads.get_mut(&wad_key(wad_path)) {
+      entry.done.push(format!("{:016x}", path_hash));
+      state.dirty = true;
+    }
+    if state.last_flush.elapsed() >= FLUSH_INTERVAL {
+      self.write(&mut state);
+    }
+  }
+
+  /// Record that every chunk of `wad_path` was handled.
+  pub(crate) fn finish(&self, wad_path: &Path) {
+    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(entry) = state.manifest.wads.get_mut(&wad_key(wad_path)) {
+      entry.complete = true;
+      state.dirty = true;
+    }
+  }
+
+  pub(crate) fn flush(&self) {
+    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+    self.write(&mut state);
+  }
+
+  fn write(&self, state: &mut TrackerState) {
+    state.last_flush = Instant::now();
+    if !state.dirty { return; }
+    let Ok(json) = serde_json::to_string(&state.manifest) else { return };
+    let tmp = self.path.with_extension("json.tmp");
+    if fs::write(&tmp, json).is_ok() && fs::rename(&tmp, &self.path).is_ok() {
+      state.dirty = false;
+    }
+  }
+}
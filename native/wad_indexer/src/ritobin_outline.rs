@@ -0,0 +1,202 @@
+// ── Ritobin outline ──────────────────────────────────────────────────────────
+// Structure of ritobin text for folding and minimap navigation in the editor:
+// every block that spans more than one line (sections, bin objects, embeds,
+// lists and maps) with its line and byte range. Converted bins run to 100k+
+// lines, so this is one linear pass over the text that follows the ritobin
+// lexical rules (strings with escapes, `#` comments, braces) rather than a
+// full parse into a bin tree; it also outlines text that doesn't parse yet.
+
+use std::fs;
+use std::path::Path;
+
+use napi_derive::napi;
+use quartz_core::ritobin;
+
+#[napi(object)]
+pub struct RitobinOutlineNode {
+  /// "section" (top level, e.g. `entries`), "object" (bin entry), "embed"
+  /// (block with a class name) or "container" (list, map or option).
+  pub kind: String,
+  /// Field name or object key, without quotes.
+  pub name: Option<String>,
+  /// Declared type, e.g. "list[embed]" or "map[hash,embed]".
+  #[napi(js_name = "valueType")]
+  pub value_type: Option<String>,
+  /// Class of an object or embed, e.g. "SkinCharacterDataProperties".
+  pub class: Option<String>,
+  /// 1-based lines of the header (with the opening brace) and the closing brace.
+  #[napi(js_name = "startLine")]
+  pub start_line: u32,
+  #[napi(js_name = "endLine")]
+  pub end_line: u32,
+  /// Byte range from the start of the header to just past the closing brace.
+  #[napi(js_name = "startByte")]
+  pub start_byte: u32,
+  #[napi(js_name = "endByte")]
+  pub end_byte: u32,
+  pub depth: u32,
+  /// Index of the enclosing node in `nodes`; -1 at top level.
+  pub parent: i32,
+}
+
+#[napi(object)]
+pub struct RitobinOutlineResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Document order (parents before their children).
+  pub nodes: Vec<RitobinOutlineNode>,
+  #[napi(js_name = "lineCount")]
+  pub line_count: u32,
+  /// False when braces don't balance; unclosed blocks then end at the last line.
+  pub balanced: bool,
+}
+
+/// (name, type, class) of the text before a `{`, e.g. `skinMeshProperties: embed = SkinMeshDataProperties`,
+/// `"Characters/Ahri/Skins/Skin0" = SkinCharacterDataProperties` or `VfxEmitterDefinitionData`.
+fn parse_header(header: &str) -> (Option<String>, Option<String>, Option<String>) {
+  let non_empty = |s: &str| Some(s.trim()).filter(|s| !s.is_empty()).map(str::to_string);
+  let Some((lhs, rhs)) = header.rsplit_once('=') else { return (None, None, non_empty(header)) };
+  let class = non_empty(rhs);
+  let lhs = lhs.trim();
+  if lhs.starts_with('"') {
+    return (non_empty(lhs.trim_matches('"')), None, class);
+  }
+  match lhs.split_once(':') {
+    Some((name, ty)) => (non_empty(name), non_empty(ty), class),
+    None => (non_empty(lhs), None, class),
+  }
+}
+
+fn outline(text: &str) -> (Vec<RitobinOutlineNode>, u32, bool) {
+  let bytes = text.as_bytes();
+  let mut raw: Vec<RitobinOutlineNode> = Vec::new();
+  // Indices into `raw` of the blocks still open.
+  let mut stack: Vec<usize> = Vec::new();
+  let mut line = 1u32;
+  // Where the text of the current statement begins (after the last brace, newline or
+  // comma outside the brackets of a type like `map[hash,embed]`).
+  let mut segment_start = 0usize;
+  let mut brackets = 0u32;
+  let mut balanced = true;
+  let mut i = 0usize;
+
+  while i < bytes.len() {
+    match bytes[i] {
+      b'"' => {
+        i += 1;
+        while i < bytes.len() && bytes[i] != b'"' {
+          if bytes[i] == b'\\' { i += 1; }
+          if bytes.get(i) == Some(&b'\n') { line += 1; }
+          i += 1;
+        }
+      }
+      b'#' => {
+        while i < bytes.len() && bytes[i] != b'\n' { i += 1; }
+        continue;
+      }
+      b'\n' => {
+        line += 1;
+        segment_start = i + 1;
+        brackets = 0;
+      }
+      b'[' => brackets += 1,
+      b']' => brackets = brackets.saturating_sub(1),
+      b',' if brackets == 0 => segment_start = i + 1,
+      b'{' => {
+        let segment = &text[segment_start..i];
+        let (name, value_type, class) = parse_header(segment.trim());
+        let parent = stack.last().map(|&n| n as i32).unwrap_or(-1);
+        let depth = stack.len() as u32;
+        let in_entries = parent >= 0
+          && raw[parent as usize].depth == 0
+          && raw[parent as usize].name.as_deref() == Some("entries");
+        let kind = if depth == 0 && name.is_some() {
+          "section"
+        } else if in_entries {
+          "object"
+        } else if class.is_some() {
+          "embed"
+        } else {
+          "container"
+        };
+        let header_start = segment_start + (segment.len() - segment.trim_start().len());
+        raw.push(RitobinOutlineNode {
+          kind: kind.to_string(),
+          name,
+          value_type,
+          class,
+          start_line: line,
+          end_line: line,
+          start_byte: header_start as u32,
+          end_byte: header_start as u32,
+          depth,
+          parent,
+        });
+        stack.push(raw.len() - 1);
+        segment_start = i + 1;
+        brackets = 0;
+      }
+      b'}' => {
+        match stack.pop() {
+          Some(open) => {
+            raw[open].end_line = line;
+            raw[open].end_byte = (i + 1) as u32;
+          }
+          None => balanced = false,
+        }
+        segment_start = i + 1;
+        brackets = 0;
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  if !stack.is_empty() {
+    balanced = false;
+    for open in stack {
+      raw[open].end_line = line;
+      raw[open].end_byte = bytes.len() as u32;
+    }
+  }
+
+  // Single-line blocks (`{ 1, 2, 3 }`) can't fold; drop them and renumber parents.
+  // A multi-line block's parent is always multi-line too, so parents survive.
+  let mut remap = vec![-1i32; raw.len()];
+  let mut nodes = Vec::with_capacity(raw.len());
+  for (old, mut node) in raw.into_iter().enumerate() {
+    if node.end_line == node.start_line { continue; }
+    node.parent = if node.parent >= 0 { remap[node.parent as usize] } else { -1 };
+    remap[old] = nodes.len() as i32;
+    nodes.push(node);
+  }
+  (nodes, line, balanced)
+}
+
+/// Ritobin text from `text_or_path`: a path to a `.bin` (converted, naming
+/// hashes from `hash_dir`) or a text file, otherwise the text itself.
+fn load_text(text_or_path: String, hash_dir: Option<&str>) -> Result<String, String> {
+  if text_or_path.contains('\n') || text_or_path.contains('{') {
+    return Ok(text_or_path);
+  }
+  let path = Path::new(&text_or_path);
+  if !path.is_file() {
+    return Ok(text_or_path);
+  }
+  if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("bin")) {
+    let hashes = ritobin::load_hash_provider(hash_dir.map(Path::new));
+    return ritobin::read_bin(path).and_then(|tree| ritobin::bin_to_text(&tree, &hashes));
+  }
+  fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Outline ritobin text (or a `.bin`/text file path) for folding and navigation.
+#[napi(js_name = "outlineRitobin")]
+pub fn outline_ritobin(text_or_path: String, hash_dir: Option<String>) -> RitobinOutlineResult {
+  match load_text(text_or_path, hash_dir.as_deref()) {
+    Ok(text) => {
+      let (nodes, line_count, balanced) = outline(&text);
+      RitobinOutlineResult { success: true, error: None, nodes, line_count, balanced }
+    }
+    Err(e) => RitobinOutlineResult { success: false, error: Some(e), nodes: Vec::new(), line_count: 0, balanced: false },
+  }
+}
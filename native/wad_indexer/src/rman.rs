@@ -0,0 +1,517 @@
+// ── Patcher manifests (RMAN) ─────────────────────────────────────────────────
+// Riot's patcher describes each release as a manifest: files split into
+// zstd-compressed chunks that live in "bundles" on the CDN. Reading the
+// manifest and fetching only the chunks of the files we want lets hash
+// extraction and patch diffing run against a WAD from a patch that isn't
+// installed, without downloading the whole game.
+//
+// The manifest body is a zstd-compressed flatbuffer. The schema isn't
+// published, so tables are read by field index (see `Table`).
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::downloader::http_get;
+use crate::is_safe_relative_path;
+use crate::paths::rename_retrying;
+use crate::threads::{reserve_memory, run_io};
+
+pub(crate) const DEFAULT_BUNDLE_URL: &str = "https://lol.secure.dyn.riotcdn.net/channels/public/bundles";
+/// Chunks in one bundle closer than this are fetched with a single range request.
+pub(crate) const RANGE_MERGE_GAP: u64 = 64 * 1024;
+
+// ── HTTP ─────────────────────────────────────────────────────────────────────
+
+pub(crate) fn is_url(s: &str) -> bool {
+  s.starts_with("http://") || s.starts_with("https://")
+}
+
+// ── Flatbuffer reading ──────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+struct Table<'a> {
+  buf: &'a [u8],
+  pos: usize,
+  vtable: usize,
+  vtable_len: usize,
+}
+
+fn read_u16(buf: &[u8], pos: usize) -> Result<u16, String> {
+  buf.get(pos..pos + 2).map(|b| u16::from_le_bytes([b[0], b[1]])).ok_or_else(|| "Manifest is truncated".to_string())
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> Result<u32, String> {
+  buf.get(pos..pos + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap())).ok_or_else(|| "Manifest is truncated".to_string())
+}
+
+fn read_u64(buf: &[u8], pos: usize) -> Result<u64, String> {
+  buf.get(pos..pos + 8).map(|b| u64::from_le_bytes(b.try_into().unwrap())).ok_or_else(|| "Manifest is truncated".to_string())
+}
+
+/// Follow a uoffset stored at `pos`.
+fn deref(buf: &[u8], pos: usize) -> Result<usize, String> {
+  Ok(pos + read_u32(buf, pos)? as usize)
+}
+
+impl<'a> Table<'a> {
+  fn at(buf: &'a [u8], pos: usize) -> Result<Table<'a>, String> {
+    let soffset = read_u32(buf, pos)? as i32;
+    let vtable = (pos as i64 - soffset as i64) as usize;
+    let vtable_len = read_u16(buf, vtable)? as usize;
+    Ok(Table { buf, pos, vtable, vtable_len })
+  }
+
+  /// Absolute position of field `index`, or None if it's absent (default value).
+  fn field(&self, index: usize) -> Result<Option<usize>, String> {
+    let entry = 4 + index * 2;
+    if entry + 2 > self.vtable_len { return Ok(None); }
+    let offset = read_u16(self.buf, self.vtable + entry)? as usize;
+    Ok((offset != 0).then_some(self.pos + offset))
+  }
+
+  fn u8(&self, index: usize) -> Result<u8, String> {
+    Ok(self.field(index)?.and_then(|p| self.buf.get(p).copied()).unwrap_or(0))
+  }
+
+  fn u32(&self, index: usize) -> Result<u32, String> {
+    self.field(index)?.map_or(Ok(0), |p| read_u32(self.buf, p))
+  }
+
+  fn u64(&self, index: usize) -> Result<u64, String> {
+    self.field(index)?.map_or(Ok(0), |p| read_u64(self.buf, p))
+  }
+
+  fn string(&self, index: usize) -> Result<String, String> {
+    let Some(p) = self.field(index)? else { return Ok(String::new()) };
+    let start = deref(self.buf, p)?;
+    let len = read_u32(self.buf, start)? as usize;
+    let bytes = self.buf.get(start + 4..start + 4 + len).ok_or("Manifest is truncated")?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+  }
+
+  /// (element count, position of the first element)
+  fn vector(&self, index: usize) -> Result<(usize, usize), String> {
+    let Some(p) = self.field(index)? else { return Ok((0, 0)) };
+    let start = deref(self.buf, p)?;
+    Ok((read_u32(self.buf, start)? as usize, start + 4))
+  }
+
+  fn tables(&self, index: usize) -> Result<Vec<Table<'a>>, String> {
+    let (len, first) = self.vector(index)?;
+    (0..len).map(|i| Table::at(self.buf, deref(self.buf, first + i * 4)?)).collect()
+  }
+
+  fn u64s(&self, index: usize) -> Result<Vec<u64>, String> {
+    let (len, first) = self.vector(index)?;
+    (0..len).map(|i| read_u64(self.buf, first + i * 8)).collect()
+  }
+}
+
+// ── Manifest model ───────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+pub(crate) struct ChunkLocation {
+  pub(crate) bundle_id: u64,
+  /// Byte offset of the compressed chunk inside its bundle.
+  pub(crate) offset: u64,
+  pub(crate) compressed_size: u32,
+  pub(crate) uncompressed_size: u32,
+}
+
+pub(crate) struct ManifestFile {
+  /// "/"-separated path relative to the install root.
+  pub(crate) path: String,
+  pub(crate) size: u64,
+  pub(crate) languages: Vec<String>,
+  pub(crate) chunk_ids: Vec<u64>,
+}
+
+pub(crate) struct Manifest {
+  pub(crate) id: u64,
+  pub(crate) files: Vec<ManifestFile>,
+  pub(crate) chunks: HashMap<u64, ChunkLocation>,
+  pub(crate) bundle_count: u32,
+}
+
+fn parse_body(body: &[u8], id: u64) -> Result<Manifest, String> {
+  let root = Table::at(body, deref(body, 0)?)?;
+
+  let bundles = root.tables(0)?;
+  let mut chunks = HashMap::new();
+  for bundle in &bundles {
+    let bundle_id = bundle.u64(0)?;
+    let mut offset = 0u64;
+    for chunk in bundle.tables(1)? {
+      let compressed_size = chunk.u32(1)?;
+      chunks.insert(chunk.u64(0)?, ChunkLocation { bundle_id, offset, compressed_size, uncompressed_size: chunk.u32(2)? });
+      offset += compressed_size as u64;
+    }
+  }
+
+  let languages: HashMap<u8, String> =
+    root.tables(1)?.iter().map(|l| Ok((l.u8(0)?, l.string(1)?))).collect::<Result<_, String>>()?;
+
+  // id -> (parent id, name)
+  let directories: HashMap<u64, (u64, String)> =
+    root.tables(3)?.iter().map(|d| Ok((d.u64(0)?, (d.u64(1)?, d.string(2)?)))).collect::<Result<_, String>>()?;
+  let dir_path = |mut id: u64| {
+    let mut parts = Vec::new();
+    // Bounded so a malformed parent cycle can't loop forever.
+    for _ in 0..64 {
+      let Some((parent, name)) = directories.get(&id) else { break };
+      if !name.is_empty() { parts.push(name.as_str()); }
+      if *parent == id || *parent == 0 { break; }
+      id = *parent;
+    }
+    parts.reverse();
+    parts.join("/")
+  };
+
+  let mut files = Vec::new();
+  for file in root.tables(2)? {
+    let name = file.string(3)?;
+    let dir = dir_path(file.u64(1)?);
+    let mask = file.u64(4)?;
+    let mut file_languages: Vec<String> = languages
+      .iter()
+      .filter(|(id, _)| **id > 0 && **id <= 64 && mask & (1u64 << (**id - 1)) != 0)
+      .map(|(_, name)| name.clone())
+      .collect();
+    file_languages.sort();
+    files.push(ManifestFile {
+      path: if dir.is_empty() { name } else { format!("{}/{}", dir, name) },
+      size: file.u32(2)? as u64,
+      languages: file_languages,
+      chunk_ids: file.u64s(7)?,
+    });
+  }
+  files.sort_by(|a, b| a.path.cmp(&b.path));
+  Ok(Manifest { id, files, chunks, bundle_count: bundles.len() as u32 })
+}
+
+/// Parse a manifest file's bytes (header + compressed body).
+pub(crate) fn parse_manifest(data: &[u8]) -> Result<Manifest, String> {
+  if data.len() < 28 || &data[..4] != b"RMAN" {
+    return Err("Not an RMAN manifest".to_string());
+  }
+  let major = data[4];
+  if major != 2 {
+    return Err(format!("Unsupported manifest version {}.{}", major, data[5]));
+  }
+  let offset = read_u32(data, 8)? as usize;
+  let length = read_u32(data, 12)? as usize;
+  let id = read_u64(data, 16)?;
+  let uncompressed = read_u32(data, 24)? as usize;
+  let compressed = data.get(offset..offset + length).ok_or("Manifest body is truncated")?;
+  let body = zstd::bulk::decompress(compressed, uncompressed).map_err(|e| format!("Failed to decompress manifest: {}", e))?;
+  parse_body(&body, id)
+}
+
+/// Load a manifest from a local path or an http(s) URL.
+pub(crate) fn load_manifest(source: &str) -> Result<Manifest, String> {
+  let data = if is_url(source) {
+    http_get(source, None)?
+  } else {
+    fs::read(source).map_err(|e| format!("Failed to read {}: {}", source, e))?
+  };
+  parse_manifest(&data)
+}
+
+/// Exact path, or a trailing path component match ("Champions/Ahri.wad.client"), case-insensitive.
+fn matches_selection(path: &str, patterns: &[String]) -> bool {
+  let lower = path.to_ascii_lowercase();
+  patterns.iter().any(|p| {
+    let p = p.replace('\\', "/").trim_start_matches('/').to_ascii_lowercase();
+    lower == p || lower.ends_with(&format!("/{}", p))
+  })
+}
+
+// ── Downloading ──────────────────────────────────────────────────────────────
+
+/// Fetch `file`'s chunks and write each at its offset in `out`, which must
+/// already be sized. Neighbouring chunks of a bundle are fetched with one range
+/// request, and each range is written as soon as it arrives, so memory use is
+/// bounded by the range size rather than the file size.
+fn fetch_chunks_into(manifest: &Manifest, file: &ManifestFile, bundle_url: &str, out: &Path) -> Result<(), String> {
+  // A chunk can appear more than once in a file.
+  let mut placements: HashMap<u64, Vec<u64>> = HashMap::new();
+  let mut by_bundle: HashMap<u64, Vec<(u64, ChunkLocation)>> = HashMap::new();
+  let mut offset = 0u64;
+  for id in &file.chunk_ids {
+    let loc = *manifest.chunks.get(id).ok_or_else(|| format!("Chunk {:016X} is not in the manifest", id))?;
+    placements.entry(*id).or_default().push(offset);
+    by_bundle.entry(loc.bundle_id).or_default().push((*id, loc));
+    offset += loc.uncompressed_size as u64;
+  }
+  if offset != file.size {
+    return Err(format!("{}: chunks add up to {} bytes, manifest says {}", file.path, offset, file.size));
+  }
+  let mut requests: Vec<(u64, Vec<(u64, ChunkLocation)>)> = Vec::new();
+  for (bundle_id, mut chunks) in by_bundle {
+    chunks.sort_by_key(|(_, l)| l.offset);
+    chunks.dedup_by_key(|(id, _)| *id);
+    let mut group: Vec<(u64, ChunkLocation)> = Vec::new();
+    for c in chunks {
+      if let Some((_, last)) = group.last() {
+        if c.1.offset > last.offset + last.compressed_size as u64 + RANGE_MERGE_GAP {
+          requests.push((bundle_id, std::mem::take(&mut group)));
+        }
+      }
+      group.push(c);
+    }
+    if !group.is_empty() { requests.push((bundle_id, group)); }
+  }
+
+  let write_err = |e: std::io::Error| format!("Failed to write {}: {}", out.display(), e);
+  let results: Vec<Result<(), String>> = run_io(|| {
+    requests
+      .par_iter()
+      .map(|(bundle_id, group)| {
+        let start = group[0].1.offset;
+        let end = group.iter().map(|(_, l)| l.offset + l.compressed_size as u64).max().unwrap_or(start);
+        let largest = group.iter().map(|(_, l)| l.uncompressed_size as u64).max().unwrap_or(0);
+        // The range body plus the one chunk decompressed from it at a time.
+        let _reserved = reserve_memory(end - start + largest);
+        let url = format!("{}/{:016X}.bundle", bundle_url.trim_end_matches('/'), bundle_id);
+        let data = http_get(&url, Some((start, end)))?;
+        let mut dest = fs::OpenOptions::new().write(true).open(out).map_err(write_err)?;
+        for (id, l) in group {
+          let rel = (l.offset - start) as usize;
+          let compressed = data
+            .get(rel..rel + l.compressed_size as usize)
+            .ok_or_else(|| format!("Bundle {:016X} ended before chunk {:016X}", bundle_id, id))?;
+          let chunk = zstd::bulk::decompress(compressed, l.uncompressed_size as usize)
+            .map_err(|e| format!("Failed to decompress chunk {:016X}: {}", id, e))?;
+          if chunk.len() != l.uncompressed_size as usize {
+            return Err(format!("Chunk {:016X} has the wrong size", id));
+          }
+          for at in &placements[id] {
+            dest.seek(SeekFrom::Start(*at)).map_err(write_err)?;
+            dest.write_all(&chunk).map_err(write_err)?;
+          }
+        }
+        Ok(())
+      })
+      .collect()
+  });
+  results.into_iter().collect()
+}
+
+fn download_file(manifest: &Manifest, file: &ManifestFile, out_dir: &Path, bundle_url: &str) -> Result<PathBuf, String> {
+  // Paths come from the manifest, which may be fetched from any URL.
+  if file.path.is_empty() || !is_safe_relative_path(&file.path) {
+    return Err(format!("Unsafe path in manifest: {}", file.path));
+  }
+  let target = out_dir.join(&file.path);
+  if let Some(parent) = target.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let mut tmp = target.clone().into_os_string();
+  tmp.push(".part");
+  let tmp = PathBuf::from(tmp);
+  let out = fs::File::create(&tmp).map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+  out.set_len(file.size).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  drop(out);
+  if let Err(e) = fetch_chunks_into(manifest, file, bundle_url, &tmp) {
+    let _ = fs::remove_file(&tmp);
+    return Err(e);
+  }
+  rename_retrying(&tmp, &target).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+  Ok(target)
+}
+
+// ── napi ─────────────────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct RmanFileInfo {
+  pub path: String,
+  pub size: f64,
+  /// Locales the file belongs to; empty for files every install gets.
+  pub languages: Vec<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+}
+
+#[napi(object)]
+pub struct RmanManifestResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// 16-digit hex manifest id.
+  #[napi(js_name = "manifestId")]
+  pub manifest_id: String,
+  #[napi(js_name = "bundleCount")]
+  pub bundle_count: u32,
+  pub files: Vec<RmanFileInfo>,
+}
+
+/// List the files in a patcher manifest (`.manifest` path or URL). `filter`
+/// keeps paths containing the given text, case-insensitive.
+#[napi(js_name = "readRmanManifest")]
+pub fn read_rman_manifest(source: String, filter: Option<String>) -> RmanManifestResult {
+  let filter = filter.map(|f| f.to_ascii_lowercase()).filter(|f| !f.is_empty());
+  match load_manifest(&source) {
+    Ok(manifest) => RmanManifestResult {
+      success: true,
+      error: None,
+      manifest_id: format!("{:016X}", manifest.id),
+      bundle_count: manifest.bundle_count,
+      files: manifest
+        .files
+        .into_iter()
+        .filter(|f| filter.as_ref().is_none_or(|q| f.path.to_ascii_lowercase().contains(q.as_str())))
+        .map(|f| RmanFileInfo { size: f.size as f64, chunk_count: f.chunk_ids.len() as u32, languages: f.languages, path: f.path })
+        .collect(),
+    },
+    Err(e) => RmanManifestResult { success: false, error: Some(e), manifest_id: String::new(), bundle_count: 0, files: Vec::new() },
+  }
+}
+
+#[napi(object)]
+pub struct RmanDownloadOptions {
+  /// Bundle CDN base URL. Defaults to Riot's public League bundles.
+  #[napi(js_name = "bundleUrl")]
+  pub bundle_url: Option<String>,
+}
+
+#[napi(object)]
+pub struct RmanDownloadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Absolute paths of the files written.
+  pub files: Vec<String>,
+  #[napi(js_name = "bytesWritten")]
+  pub bytes_written: f64,
+}
+
+fn download(source: &str, files: &[String], out_dir: &Path, bundle_url: &str) -> Result<RmanDownloadResult, String> {
+  let manifest = load_manifest(source)?;
+  let selected: Vec<&ManifestFile> = manifest.files.iter().filter(|f| matches_selection(&f.path, files)).collect();
+  if selected.is_empty() {
+    return Err(format!("None of the requested files are in manifest {:016X}", manifest.id));
+  }
+  let mut written = Vec::new();
+  let mut bytes = 0u64;
+  for file in selected {
+    written.push(download_file(&manifest, file, out_dir, bundle_url)?.to_string_lossy().into_owned());
+    bytes += file.size;
+  }
+  Ok(RmanDownloadResult { success: true, error: None, files: written, bytes_written: bytes as f64 })
+}
+
+pub struct RmanDownloadTask {
+  source: String,
+  files: Vec<String>,
+  out_dir: String,
+  bundle_url: String,
+}
+
+#[napi]
+impl Task for RmanDownloadTask {
+  type Output = RmanDownloadResult;
+  type JsValue = RmanDownloadResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(download(&self.source, &self.files, Path::new(&self.out_dir), &self.bundle_url).unwrap_or_else(|e| RmanDownloadResult {
+      success: false,
+      error: Some(e),
+      files: Vec::new(),
+      bytes_written: 0.0,
+    }))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+/// Download and reassemble selected files (e.g. "DATA/FINAL/Champions/Ahri.wad.client",
+/// or just "Champions/Ahri.wad.client") from the manifest of any patch into
+/// `outDir`, keeping their install-relative paths. Only the chunks those files
+/// need are fetched.
+#[napi(js_name = "downloadRmanFiles")]
+pub fn download_rman_files(
+  source: String,
+  files: Vec<String>,
+  out_dir: String,
+  options: Option<RmanDownloadOptions>,
+) -> AsyncTask<RmanDownloadTask> {
+  let bundle_url = options.and_then(|o| o.bundle_url).unwrap_or_else(|| DEFAULT_BUNDLE_URL.to_string());
+  AsyncTask::new(RmanDownloadTask { source, files, out_dir, bundle_url })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_download_rejects_paths_outside_out_dir() {
+    let out_dir = std::env::temp_dir().join(format!("wad_indexer_rman_{}", std::process::id()));
+    let manifest = Manifest { id: 0, files: Vec::new(), chunks: HashMap::new(), bundle_count: 0 };
+    for path in ["../escape.wad.client", "/abs/escape.wad.client", "a/../../escape.wad.client"] {
+      let file = ManifestFile { path: path.to_string(), size: 0, languages: Vec::new(), chunk_ids: Vec::new() };
+      let err = download_file(&manifest, &file, &out_dir, "http://127.0.0.1:9").unwrap_err();
+      assert!(err.starts_with("Unsafe path"), "{}: {}", path, err);
+    }
+    assert!(!out_dir.exists());
+  }
+
+  /// Serve `bundle` for every request, honouring `Range` headers.
+  fn serve_bundle(bundle: Vec<u8>) -> String {
+    use std::io::{BufRead, BufReader};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+      for stream in listener.incoming().flatten() {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut range = (0, bundle.len());
+        let mut line = String::new();
+        while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+          if let Some(r) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+            let (a, b) = r.trim().split_once('-').unwrap();
+            range = (a.parse().unwrap(), b.parse::<usize>().unwrap() + 1);
+          }
+          line.clear();
+        }
+        let body = &bundle[range.0..range.1];
+        let mut stream = stream;
+        let _ = write!(stream, "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+        let _ = stream.write_all(body);
+      }
+    });
+    url
+  }
+
+  #[test]
+  fn test_download_writes_repeated_chunks_at_their_offsets() {
+    let (a, b) = (b"first chunk ".repeat(10), b"second".repeat(7));
+    let (za, zb) = (zstd::encode_all(&a[..], 3).unwrap(), zstd::encode_all(&b[..], 3).unwrap());
+    let loc = |offset: usize, z: &[u8], raw: &[u8]| ChunkLocation {
+      bundle_id: 1,
+      offset: offset as u64,
+      compressed_size: z.len() as u32,
+      uncompressed_size: raw.len() as u32,
+    };
+    let chunks = HashMap::from([(10, loc(0, &za, &a)), (20, loc(za.len(), &zb, &b))]);
+    let bundle_url = serve_bundle([za.clone(), zb.clone()].concat());
+    let manifest = Manifest { id: 0, files: Vec::new(), chunks, bundle_count: 1 };
+    let expected = [a.clone(), b.clone(), a.clone()].concat();
+    let file = ManifestFile {
+      path: "DATA/Test.wad.client".to_string(),
+      size: expected.len() as u64,
+      languages: Vec::new(),
+      chunk_ids: vec![10, 20, 10],
+    };
+    let out_dir = std::env::temp_dir().join(format!("wad_indexer_rman_stream_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&out_dir);
+    let written = download_file(&manifest, &file, &out_dir, &bundle_url).unwrap();
+    assert_eq!(fs::read(&written).unwrap(), expected);
+    let _ = fs::remove_dir_all(&out_dir);
+  }
+}
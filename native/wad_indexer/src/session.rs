@@ -0,0 +1,132 @@
+// ── Session restore ──────────────────────────────────────────────────────────
+// Editor sessions (open bins/WADs, tabs, scroll positions...) persisted so
+// reopening the app restores them. The state itself is opaque JSON owned by
+// the frontend; this module only scopes it per project and saves it atomically.
+// Sessions live in `{userData}/sessions/`: `global.json` when no project is
+// open, otherwise `project-{hash}.json` keyed by the project folder.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use xxhash_rust::xxh64::xxh64;
+
+const SESSIONS_DIR: &str = "sessions";
+const SESSION_SCHEMA_VERSION: u32 = 1;
+
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredSession {
+  schema_version: u32,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  project_path: Option<String>,
+  saved_at: i64,
+  state: Value,
+}
+
+#[napi(object)]
+pub struct SessionResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// The saved state as JSON text; `None` when nothing was saved for this scope.
+  pub state: Option<String>,
+  /// Unix seconds.
+  #[napi(js_name = "savedAt")]
+  pub saved_at: Option<i64>,
+}
+
+fn now_secs() -> i64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+/// Same folder, same session: separators and trailing slashes (and case, on
+/// Windows) don't change the key.
+fn session_path(user_data_dir: &Path, project_path: Option<&str>) -> PathBuf {
+  let dir = user_data_dir.join(SESSIONS_DIR);
+  match project_path.map(|p| p.trim_end_matches(['/', '\\'])).filter(|p| !p.is_empty()) {
+    None => dir.join("global.json"),
+    Some(p) => {
+      let key = p.replace('\\', "/");
+      let key = if cfg!(windows) { key.to_lowercase() } else { key };
+      dir.join(format!("project-{:016x}.json", xxh64(key.as_bytes(), 0)))
+    }
+  }
+}
+
+fn save(user_data_dir: &Path, state_json: &str, project_path: Option<&str>) -> Result<i64, String> {
+  let state: Value = serde_json::from_str(state_json).map_err(|e| format!("Session state is not valid JSON: {}", e))?;
+  let saved_at = now_secs();
+  let stored = StoredSession {
+    schema_version: SESSION_SCHEMA_VERSION,
+    project_path: project_path.map(str::to_string),
+    saved_at,
+    state,
+  };
+  let text = serde_json::to_string_pretty(&stored).map_err(|e| format!("Failed to serialize session: {}", e))?;
+  let path = session_path(user_data_dir, project_path);
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  let tmp = path.with_extension("json.tmp");
+  fs::write(&tmp, text).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+  Ok(saved_at)
+}
+
+/// Save `stateJson` as the session for `projectPath` (or the global session
+/// when omitted), replacing the previous one.
+#[napi(js_name = "saveSession")]
+pub fn save_session(user_data_dir: String, state_json: String, project_path: Option<String>) -> SessionResult {
+  match save(Path::new(&user_data_dir), &state_json, project_path.as_deref()) {
+    Ok(saved_at) => SessionResult { success: true, error: None, state: None, saved_at: Some(saved_at) },
+    Err(e) => SessionResult { success: false, error: Some(e), state: None, saved_at: None },
+  }
+}
+
+fn load(user_data_dir: &Path, project_path: Option<&str>) -> Result<Option<StoredSession>, String> {
+  let path = session_path(user_data_dir, project_path);
+  let text = match fs::read_to_string(&path) {
+    Ok(t) => t,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+    Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+  };
+  let stored: StoredSession = serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+  if stored.schema_version > SESSION_SCHEMA_VERSION {
+    return Err(format!("{} was saved by a newer version of Quartz", path.display()));
+  }
+  Ok(Some(stored))
+}
+
+/// Load the session saved for `projectPath` (or the global session). A
+/// missing session is a success with `state: null`.
+#[napi(js_name = "loadSession")]
+pub fn load_session(user_data_dir: String, project_path: Option<String>) -> SessionResult {
+  match load(Path::new(&user_data_dir), project_path.as_deref()) {
+    Ok(Some(stored)) => SessionResult {
+      success: true,
+      error: None,
+      state: Some(stored.state.to_string()),
+      saved_at: Some(stored.saved_at),
+    },
+    Ok(None) => SessionResult { success: true, error: None, state: None, saved_at: None },
+    Err(e) => SessionResult { success: false, error: Some(e), state: None, saved_at: None },
+  }
+}
+
+#[napi(js_name = "clearSession")]
+pub fn clear_session(user_data_dir: String, project_path: Option<String>) -> SessionResult {
+  let path = session_path(Path::new(&user_data_dir), project_path.as_deref());
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  match fs::remove_file(&path) {
+    Ok(()) => SessionResult { success: true, error: None, state: None, saved_at: None },
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => SessionResult { success: true, error: None, state: None, saved_at: None },
+    Err(e) => SessionResult { success: false, error: Some(format!("Failed to remove {}: {}", path.display(), e)), state: None, saved_at: None },
+  }
+}
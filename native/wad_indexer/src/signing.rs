@@ -0,0 +1,288 @@
+// ── Package signing ──────────────────────────────────────────────────────────
+// Optional ed25519 signatures for exported .fantome/.zip packages. The signer's
+// public key and the signature are stored in `META/signature.json` inside the
+// package, so a re-upload that changes any file (or re-signs with another key)
+// can be told apart from the author's original.
+//
+// The signed message is a version line and a "signed-at\t{unix secs}\n" line,
+// then every other entry as "{name}\t{size}\t{sha256}\n", sorted by name.
+// Entry order and compression do not affect it, only names, contents and the
+// signing time. Names containing a tab or line break are refused, since they
+// could forge extra lines.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use napi_derive::napi;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::normalize_rel_path;
+
+const SIGNATURE_ENTRY: &str = "META/signature.json";
+const SIGNED_MESSAGE_HEADER: &str = "quartz-package-signature-v1\n";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageSignature {
+  algorithm: String,
+  public_key: String,
+  signature: String,
+  signed_at: u64,
+}
+
+#[napi(object)]
+pub struct SigningKeyPair {
+  /// 32-byte ed25519 seed, hex. Keep this private.
+  #[napi(js_name = "privateKey")]
+  pub private_key: String,
+  #[napi(js_name = "publicKey")]
+  pub public_key: String,
+}
+
+#[napi(object)]
+pub struct SignPackageResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "publicKey")]
+  pub public_key: Option<String>,
+}
+
+#[napi(object)]
+pub struct VerifyPackageResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// The package carries META/signature.json.
+  pub signed: bool,
+  /// The signature matches the package contents.
+  pub valid: bool,
+  /// `publicKey` is one of the keys passed in `trustedKeys`.
+  pub trusted: bool,
+  #[napi(js_name = "publicKey")]
+  pub public_key: Option<String>,
+  #[napi(js_name = "signedAt")]
+  pub signed_at: Option<i64>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex<const N: usize>(s: &str, what: &str) -> Result<[u8; N], String> {
+  let s = s.trim();
+  if s.len() != N * 2 || !s.is_ascii() {
+    return Err(format!("Invalid {}: expected {} hex characters", what, N * 2));
+  }
+  let mut out = [0u8; N];
+  for (i, byte) in out.iter_mut().enumerate() {
+    *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| format!("Invalid {}: not hex", what))?;
+  }
+  Ok(out)
+}
+
+fn is_signature_entry(name: &str) -> bool {
+  normalize_rel_path(name).eq_ignore_ascii_case(SIGNATURE_ENTRY)
+}
+
+/// Canonical message covering `signed_at` and every entry except the signature itself.
+fn package_message(path: &Path, signed_at: u64) -> Result<Vec<u8>, String> {
+  let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let mut zip = ZipArchive::new(file).map_err(|e| format!("Failed to read package {}: {}", path.display(), e))?;
+  let mut lines = Vec::with_capacity(zip.len());
+  for i in 0..zip.len() {
+    let mut entry = zip.by_index(i).map_err(|e| format!("Failed to read package entry: {}", e))?;
+    if entry.is_dir() || is_signature_entry(entry.name()) { continue; }
+    let name = normalize_rel_path(entry.name());
+    if name.contains(['\t', '\n', '\r']) {
+      return Err(format!("Package entry name contains a tab or line break: {:?}", name));
+    }
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut entry, &mut hasher).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    lines.push(format!("{}\t{}\t{}\n", name, size, to_hex(&hasher.finalize())));
+  }
+  lines.sort();
+  let mut message = format!("{}signed-at\t{}\n", SIGNED_MESSAGE_HEADER, signed_at).into_bytes();
+  for line in lines {
+    message.extend_from_slice(line.as_bytes());
+  }
+  Ok(message)
+}
+
+fn read_signature(path: &Path) -> Result<Option<PackageSignature>, String> {
+  let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let mut zip = ZipArchive::new(file).map_err(|e| format!("Failed to read package {}: {}", path.display(), e))?;
+  let Some(name) = zip.file_names().find(|n| is_signature_entry(n)).map(str::to_string) else { return Ok(None) };
+  let mut entry = zip.by_name(&name).map_err(|e| format!("Failed to read {}: {}", SIGNATURE_ENTRY, e))?;
+  let mut text = String::new();
+  entry.read_to_string(&mut text).map_err(|e| format!("Failed to read {}: {}", SIGNATURE_ENTRY, e))?;
+  serde_json::from_str(&text).map(Some).map_err(|e| format!("Invalid {}: {}", SIGNATURE_ENTRY, e))
+}
+
+fn sign(path: &Path, private_key: &str) -> Result<String, String> {
+  let key = SigningKey::from_bytes(&from_hex::<32>(private_key, "private key")?);
+  let signed_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+  let signature = key.sign(&package_message(path, signed_at)?);
+  let public_key = to_hex(key.verifying_key().as_bytes());
+  let record = PackageSignature {
+    algorithm: "ed25519".to_string(),
+    public_key: public_key.clone(),
+    signature: to_hex(&signature.to_bytes()),
+    signed_at,
+  };
+  let json = serde_json::to_string_pretty(&record).map_err(|e| format!("Failed to serialize signature: {}", e))?;
+
+  // Rewrite the package with entries copied as-is and the signature replaced.
+  let tmp = path.with_extension("signing.tmp");
+  let result = (|| -> Result<(), String> {
+    let src = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut zip = ZipArchive::new(src).map_err(|e| format!("Failed to read package {}: {}", path.display(), e))?;
+    let out = fs::File::create(&tmp).map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+    let mut writer = ZipWriter::new(out);
+    for i in 0..zip.len() {
+      let entry = zip.by_index_raw(i).map_err(|e| format!("Failed to read package entry: {}", e))?;
+      if is_signature_entry(entry.name()) { continue; }
+      writer.raw_copy_file(entry).map_err(|e| format!("Failed to copy package entry: {}", e))?;
+    }
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    writer.start_file(SIGNATURE_ENTRY, deflated).map_err(|e| format!("Failed to write {}: {}", SIGNATURE_ENTRY, e))?;
+    writer.write_all(json.as_bytes()).map_err(|e| format!("Failed to write {}: {}", SIGNATURE_ENTRY, e))?;
+    writer.finish().map_err(|e| format!("Failed to finalize {}: {}", tmp.display(), e))?;
+    Ok(())
+  })();
+  if let Err(e) = result {
+    let _ = fs::remove_file(&tmp);
+    return Err(e);
+  }
+  fs::rename(&tmp, path).map_err(|e| {
+    let _ = fs::remove_file(&tmp);
+    format!("Failed to replace {}: {}", path.display(), e)
+  })?;
+  Ok(public_key)
+}
+
+fn verify(path: &Path, trusted_keys: &[String]) -> Result<VerifyPackageResult, String> {
+  let Some(record) = read_signature(path)? else {
+    return Ok(VerifyPackageResult {
+      success: true,
+      error: None,
+      signed: false,
+      valid: false,
+      trusted: false,
+      public_key: None,
+      signed_at: None,
+    });
+  };
+  if !record.algorithm.eq_ignore_ascii_case("ed25519") {
+    return Err(format!("Unsupported signature algorithm: {}", record.algorithm));
+  }
+  let key = VerifyingKey::from_bytes(&from_hex::<32>(&record.public_key, "public key")?)
+    .map_err(|e| format!("Invalid public key: {}", e))?;
+  let signature = Signature::from_bytes(&from_hex::<64>(&record.signature, "signature")?);
+  let valid = key.verify(&package_message(path, record.signed_at)?, &signature).is_ok();
+  let public_key = to_hex(key.as_bytes());
+  let trusted = valid && trusted_keys.iter().any(|k| k.trim().eq_ignore_ascii_case(&public_key));
+  Ok(VerifyPackageResult {
+    success: true,
+    error: None,
+    signed: true,
+    valid,
+    trusted,
+    public_key: Some(public_key),
+    signed_at: Some(record.signed_at as i64),
+  })
+}
+
+/// Create a new ed25519 key pair for signing packages.
+#[napi(js_name = "generateSigningKey")]
+pub fn generate_signing_key() -> SigningKeyPair {
+  let key = SigningKey::generate(&mut OsRng);
+  SigningKeyPair {
+    private_key: to_hex(&key.to_bytes()),
+    public_key: to_hex(key.verifying_key().as_bytes()),
+  }
+}
+
+/// Sign an exported .fantome/.zip in place, replacing any previous signature.
+#[napi(js_name = "signPackage")]
+pub fn sign_package(package_path: String, private_key: String) -> SignPackageResult {
+  match sign(Path::new(&package_path), &private_key) {
+    Ok(public_key) => SignPackageResult { success: true, error: None, public_key: Some(public_key) },
+    Err(e) => SignPackageResult { success: false, error: Some(e), public_key: None },
+  }
+}
+
+/// Check a package's signature. `valid` is false for unsigned or tampered
+/// packages; `trusted` additionally requires the key to be in `trustedKeys`.
+#[napi(js_name = "verifyPackage")]
+pub fn verify_package(package_path: String, trusted_keys: Option<Vec<String>>) -> VerifyPackageResult {
+  verify(Path::new(&package_path), trusted_keys.as_deref().unwrap_or(&[])).unwrap_or_else(|e| VerifyPackageResult {
+    success: false,
+    error: Some(e),
+    signed: false,
+    valid: false,
+    trusted: false,
+    public_key: None,
+    signed_at: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::path::PathBuf;
+
+  fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wad_indexer_signing_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_package(path: &Path, entries: &[(&str, &[u8])]) {
+    let mut writer = ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, data) in entries {
+      writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+      writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap();
+  }
+
+  #[test]
+  fn test_changed_signing_time_invalidates_signature() {
+    let dir = scratch("signed_at");
+    let package = dir.join("mod.fantome");
+    write_package(&package, &[("META/info.json", b"{}"), ("WAD/a.wad.client", b"data")]);
+    let keys = generate_signing_key();
+    sign(&package, &keys.private_key).unwrap();
+    let result = verify(&package, std::slice::from_ref(&keys.public_key)).unwrap();
+    assert!(result.valid && result.trusted);
+
+    let mut record = read_signature(&package).unwrap().unwrap();
+    record.signed_at += 3600;
+    let json = serde_json::to_string(&record).unwrap();
+    write_package(&package, &[
+      ("META/info.json", b"{}"),
+      ("WAD/a.wad.client", b"data"),
+      (SIGNATURE_ENTRY, json.as_bytes()),
+    ]);
+    assert!(!verify(&package, &[keys.public_key]).unwrap().valid);
+    let _ = fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn test_entry_names_with_line_breaks_are_refused() {
+    let dir = scratch("names");
+    let package = dir.join("mod.fantome");
+    write_package(&package, &[("WAD/a\t4\tforged\nb.wad.client", b"data")]);
+    let keys = generate_signing_key();
+    let Err(err) = sign(&package, &keys.private_key) else { panic!("signed a package with a forged entry name") };
+    assert!(err.contains("tab or line break"), "{}", err);
+    let _ = fs::remove_dir_all(&dir);
+  }
+}
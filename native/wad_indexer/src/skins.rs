@@ -0,0 +1,456 @@
+// ── Champion skins ───────────────────────────────────────────────────────────
+// Skin IDs come from skins.json in the LCU game-data WAD and, given a hash dir,
+// from the skin bin paths in the champion WAD; display names and chroma
+// groupings come from skins.json, falling back to `championSkinName` from the
+// skin bin itself.
+// Skin families group those skins by skin line (skinlines.json), so a "Star
+// Guardian" skin and its chromas end up together regardless of skin number.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ltk_meta::{Bin, PropertyValueEnum};
+use ltk_wad::Wad;
+use napi_derive::napi;
+use serde_json::Value;
+
+use crate::{fnv1a_lower, HashLayers};
+use crate::game::{find_champion_wad, lcu_game_data_path, lcu_game_data_wad, read_wad_chunk_by_path, wad_path_hash};
+use crate::wad_build::{collect_files, read_hashed_files};
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct ChromaInfo {
+  pub id: u32,
+  pub name: Option<String>,
+  /// Hex colors ("#RRGGBB") shown on the chroma swatch.
+  pub colors: Vec<String>,
+  #[napi(js_name = "hasBin")]
+  pub has_bin: bool,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct SkinInfo {
+  pub id: u32,
+  pub name: Option<String>,
+  #[napi(js_name = "championSkinName")]
+  pub champion_skin_name: Option<String>,
+  #[napi(js_name = "binPath")]
+  pub bin_path: Option<String>,
+  /// Why the skin bin couldn't be read or parsed, when it exists but failed.
+  #[napi(js_name = "binError")]
+  pub bin_error: Option<String>,
+  #[napi(js_name = "isBase")]
+  pub is_base: bool,
+  pub chromas: Vec<ChromaInfo>,
+}
+
+#[napi(object)]
+pub struct ChampionSkinsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub champion: String,
+  #[napi(js_name = "championId")]
+  pub champion_id: Option<u32>,
+  pub skins: Vec<SkinInfo>,
+  /// False when the LCU game-data WAD was not found and names come from bins only.
+  #[napi(js_name = "hasMetadata")]
+  pub has_metadata: bool,
+}
+
+pub(crate) fn skin_bin_path(champion: &str, skin_id: u32) -> String {
+  format!("data/characters/{}/skins/skin{}.bin", champion.to_ascii_lowercase(), skin_id)
+}
+
+/// Skin number of a skin bin path for `champion`, e.g. 12 for ".../skins/skin12.bin".
+fn skin_id_of_path(path: &str, champion: &str) -> Option<u32> {
+  let prefix = format!("data/characters/{}/skins/skin", champion.to_ascii_lowercase());
+  let path = path.to_ascii_lowercase();
+  let digits = path.strip_prefix(&prefix)?.strip_suffix(".bin")?;
+  if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) { return None; }
+  digits.parse().ok()
+}
+
+/// `championSkinName` of the SkinCharacterDataProperties object in a skin bin.
+fn read_champion_skin_name(data: &[u8]) -> Result<Option<String>, String> {
+  let bin = Bin::from_reader(&mut Cursor::new(data)).map_err(|e| format!("Failed to parse skin bin: {}", e))?;
+  let scdp = fnv1a_lower("SkinCharacterDataProperties");
+  let field = fnv1a_lower("championSkinName");
+  Ok(bin.objects.values()
+    .filter(|o| o.class_hash == scdp)
+    .find_map(|o| match o.properties.get(&field).map(|p| &p.value) {
+      Some(PropertyValueEnum::String(s)) => Some(s.value.clone()),
+      _ => None,
+    }))
+}
+
+pub(crate) struct LcuSkinMeta {
+  pub name: Option<String>,
+  pub is_base: bool,
+  pub chromas: Vec<(u32, Option<String>, Vec<String>)>,
+  pub skin_lines: Vec<u32>,
+}
+
+pub(crate) struct LcuChampionMeta {
+  pub champion_id: u32,
+  pub skins: HashMap<u32, LcuSkinMeta>,
+}
+
+fn read_lcu_json(league_path: &Path, locale: Option<&str>, file: &str) -> Option<Value> {
+  let wad = lcu_game_data_wad(league_path, locale)?;
+  let data = read_wad_chunk_by_path(&wad, &lcu_game_data_path(locale, file)).ok()??;
+  serde_json::from_slice(&data).ok()
+}
+
+/// Champion ID for an alias ("MonkeyKing") or display name ("Wukong").
+pub(crate) fn lcu_champion_id(league_path: &Path, champion: &str) -> Option<u32> {
+  let summary = read_lcu_json(league_path, None, "champion-summary.json")?;
+  summary.as_array()?.iter().find_map(|c| {
+    let alias = c.get("alias").and_then(Value::as_str).unwrap_or("");
+    let name = c.get("name").and_then(Value::as_str).unwrap_or("");
+    let matches = alias.eq_ignore_ascii_case(champion) || name.eq_ignore_ascii_case(champion);
+    if matches { c.get("id").and_then(Value::as_u64).map(|v| v as u32) } else { None }
+  })
+}
+
+/// Skin metadata for one champion from skins.json, keyed by per-champion skin number.
+pub(crate) fn lcu_champion_skins(league_path: &Path, champion: &str, locale: Option<&str>) -> Option<LcuChampionMeta> {
+  let champion_id = lcu_champion_id(league_path, champion)?;
+  let skins_json = read_lcu_json(league_path, locale, "skins.json")
+    .or_else(|| read_lcu_json(league_path, None, "skins.json"))?;
+  let entries: Vec<&Value> = match &skins_json {
+    Value::Object(map) => map.values().collect(),
+    Value::Array(arr) => arr.iter().collect(),
+    _ => return None,
+  };
+
+  let mut skins = HashMap::new();
+  for entry in entries {
+    let Some(full_id) = entry.get("id").and_then(Value::as_u64) else { continue };
+    if full_id / 1000 != champion_id as u64 { continue; }
+    let chromas = entry.get("chromas").and_then(Value::as_array)
+      .map(|arr| arr.iter().filter_map(|c| {
+        let id = c.get("id").and_then(Value::as_u64)?;
+        let name = c.get("name").and_then(Value::as_str).map(str::to_string);
+        let colors = c.get("colors").and_then(Value::as_array)
+          .map(|cs| cs.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+          .unwrap_or_default();
+        Some(((id % 1000) as u32, name, colors))
+      }).collect())
+      .unwrap_or_default();
+    skins.insert((full_id % 1000) as u32, LcuSkinMeta {
+      name: entry.get("name").and_then(Value::as_str).map(str::to_string),
+      is_base: entry.get("isBase").and_then(Value::as_bool).unwrap_or(full_id % 1000 == 0),
+      chromas,
+      skin_lines: entry.get("skinLines").and_then(Value::as_array)
+        .map(|arr| arr.iter().filter_map(|l| l.get("id").and_then(Value::as_u64).map(|v| v as u32)).collect())
+        .unwrap_or_default(),
+    });
+  }
+  Some(LcuChampionMeta { champion_id, skins })
+}
+
+/// (skin number, bin bytes or why they couldn't be read)
+type SkinBin = (u32, Result<Vec<u8>, String>);
+
+/// Skin numbers that have a skin bin in the champion WAD, with the raw bin
+/// bytes or why they couldn't be read. Numbers are taken from skins.json and,
+/// with a hash dir, from the WAD's resolved chunk paths.
+fn read_skin_bins(
+  wad_path: &Path,
+  champion: &str,
+  meta: Option<&LcuChampionMeta>,
+  hash_dir: Option<&str>,
+) -> Result<Vec<SkinBin>, String> {
+  if meta.is_none() && hash_dir.is_none() {
+    return Err("Listing skins needs the LCU game-data WAD or a hash dir".to_string());
+  }
+  let file = std::fs::File::open(wad_path)
+    .map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mut wad = Wad::mount(file)
+    .map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+
+  let mut ids: Vec<u32> = meta
+    .map(|m| m.skins.iter().flat_map(|(id, s)| std::iter::once(*id).chain(s.chromas.iter().map(|c| c.0))).collect())
+    .unwrap_or_default();
+  if hash_dir.is_some() {
+    let hashes: Vec<u64> = wad.chunks().iter().map(|c| c.path_hash()).collect();
+    let paths = HashLayers::open(hash_dir).resolve_names(&hashes);
+    ids.extend(paths.iter().filter_map(|p| skin_id_of_path(p, champion)));
+  }
+  ids.sort_unstable();
+  ids.dedup();
+
+  let mut out = Vec::new();
+  for id in ids {
+    let path = skin_bin_path(champion, id);
+    let Some(chunk) = wad.chunks().get(wad_path_hash(&path)).copied() else { continue };
+    let data = wad.load_chunk_decompressed(&chunk)
+      .map(|d| d.into_vec())
+      .map_err(|e| format!("Failed to read {}: {}", path, e));
+    out.push((id, data));
+  }
+  Ok(out)
+}
+
+/// A champion's skins, with the skins.json metadata they were built from
+/// (`None` when the LCU game-data WAD isn't installed).
+pub(crate) fn collect_champion_skins(
+  league_path: &Path,
+  champion: &str,
+  locale: Option<&str>,
+  hash_dir: Option<&str>,
+) -> Result<(Vec<SkinInfo>, Option<LcuChampionMeta>), String> {
+  let wad_path = find_champion_wad(league_path, champion)
+    .ok_or_else(|| format!("Champion WAD not found for {}", champion))?;
+  let meta = lcu_champion_skins(league_path, champion, locale);
+  let bins = read_skin_bins(&wad_path, champion, meta.as_ref(), hash_dir)?;
+
+  let chroma_parent: HashMap<u32, u32> = meta.as_ref()
+    .map(|m| m.skins.iter()
+      .flat_map(|(parent, s)| s.chromas.iter().map(move |(id, _, _)| (*id, *parent)))
+      .collect())
+    .unwrap_or_default();
+  let bin_ids: HashMap<u32, Result<Option<String>, String>> = bins.into_iter()
+    .map(|(id, data)| (id, data.and_then(|d| read_champion_skin_name(&d))))
+    .collect();
+
+  let mut skins: Vec<SkinInfo> = Vec::new();
+  let mut ids: Vec<u32> = bin_ids.keys().copied().collect();
+  if let Some(m) = meta.as_ref() {
+    ids.extend(m.skins.keys().copied());
+  }
+  ids.sort_unstable();
+  ids.dedup();
+
+  for id in ids {
+    // Chromas are listed under their parent skin instead of as top-level entries.
+    if chroma_parent.contains_key(&id) { continue; }
+    let lcu = meta.as_ref().and_then(|m| m.skins.get(&id));
+    let bin = bin_ids.get(&id);
+    let champion_skin_name = bin.and_then(|b| b.clone().ok().flatten());
+    let name = lcu.and_then(|s| s.name.clone())
+      .or_else(|| if id == 0 { Some(champion.to_string()) } else { None });
+    let chromas = lcu.map(|s| s.chromas.iter().map(|(cid, cname, colors)| ChromaInfo {
+      id: *cid,
+      name: cname.clone(),
+      colors: colors.clone(),
+      has_bin: bin_ids.contains_key(cid),
+    }).collect()).unwrap_or_default();
+    skins.push(SkinInfo {
+      id,
+      name,
+      champion_skin_name,
+      bin_path: bin.map(|_| skin_bin_path(champion, id)),
+      bin_error: bin.and_then(|b| b.clone().err()),
+      is_base: lcu.map(|s| s.is_base).unwrap_or(id == 0),
+      chromas,
+    });
+  }
+
+  Ok((skins, meta))
+}
+
+/// List a champion's skins with display names and chroma groupings.
+/// `locale` selects the LCU string locale (e.g. "en_gb"); defaults to the client default.
+/// `hashDir` also finds skin bins skins.json doesn't list, and is required when
+/// the LCU game-data WAD is not installed.
+#[napi(js_name = "getChampionSkins")]
+pub fn get_champion_skins(
+  league_path: String,
+  champion: String,
+  locale: Option<String>,
+  hash_dir: Option<String>,
+) -> ChampionSkinsResult {
+  match collect_champion_skins(Path::new(&league_path), &champion, locale.as_deref(), hash_dir.as_deref()) {
+    Ok((skins, meta)) => ChampionSkinsResult {
+      success: true,
+      error: None,
+      champion,
+      champion_id: meta.as_ref().map(|m| m.champion_id),
+      skins,
+      has_metadata: meta.is_some(),
+    },
+    Err(e) => ChampionSkinsResult {
+      success: false,
+      error: Some(e),
+      champion,
+      champion_id: None,
+      skins: Vec::new(),
+      has_metadata: false,
+    },
+  }
+}
+
+#[napi(object)]
+pub struct SkinFamily {
+  /// Skin line ID from skinlines.json; `None` for skins outside any line (base skin, one-offs).
+  #[napi(js_name = "skinLineId")]
+  pub skin_line_id: Option<u32>,
+  pub name: Option<String>,
+  pub skins: Vec<SkinInfo>,
+}
+
+#[napi(object)]
+pub struct SkinFamiliesResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub champion: String,
+  pub families: Vec<SkinFamily>,
+  #[napi(js_name = "hasMetadata")]
+  pub has_metadata: bool,
+}
+
+/// Skin line ID -> localized line name.
+fn lcu_skin_line_names(league_path: &Path, locale: Option<&str>) -> HashMap<u32, String> {
+  let Some(Value::Array(lines)) = read_lcu_json(league_path, locale, "skinlines.json")
+    .or_else(|| read_lcu_json(league_path, None, "skinlines.json")) else { return HashMap::new() };
+  lines.iter().filter_map(|l| {
+    let id = l.get("id").and_then(Value::as_u64)? as u32;
+    let name = l.get("name").and_then(Value::as_str).filter(|n| !n.is_empty())?;
+    Some((id, name.to_string()))
+  }).collect()
+}
+
+fn collect_skin_families(
+  league_path: &Path,
+  champion: &str,
+  locale: Option<&str>,
+  hash_dir: Option<&str>,
+) -> Result<(Vec<SkinFamily>, bool), String> {
+  let (skins, meta) = collect_champion_skins(league_path, champion, locale, hash_dir)?;
+  let has_metadata = meta.is_some();
+  let line_names = if has_metadata { lcu_skin_line_names(league_path, locale) } else { HashMap::new() };
+
+  // A skin in several lines is filed under its first one.
+  let mut families: Vec<SkinFamily> = Vec::new();
+  for skin in skins {
+    let line = meta.as_ref()
+      .and_then(|m| m.skins.get(&skin.id))
+      .and_then(|s| s.skin_lines.first().copied());
+    match families.iter_mut().find(|f| f.skin_line_id == line) {
+      Some(family) => family.skins.push(skin),
+      None => families.push(SkinFamily {
+        skin_line_id: line,
+        name: line.and_then(|l| line_names.get(&l).cloned()),
+        skins: vec![skin],
+      }),
+    }
+  }
+  // Ungrouped skins first, then lines by their lowest skin number.
+  families.sort_by_key(|f| (f.skin_line_id.is_some(), f.skins.first().map(|s| s.id)));
+  Ok((families, has_metadata))
+}
+
+/// Group a champion's skins by skin line, each skin carrying its chroma IDs and colors.
+#[napi(js_name = "getSkinFamilies")]
+pub fn get_skin_families(
+  league_path: String,
+  champion: String,
+  locale: Option<String>,
+  hash_dir: Option<String>,
+) -> SkinFamiliesResult {
+  match collect_skin_families(Path::new(&league_path), &champion, locale.as_deref(), hash_dir.as_deref()) {
+    Ok((families, has_metadata)) => SkinFamiliesResult { success: true, error: None, champion, families, has_metadata },
+    Err(e) => SkinFamiliesResult { success: false, error: Some(e), champion, families: Vec::new(), has_metadata: false },
+  }
+}
+
+#[napi(object)]
+pub struct SkinBinLocation {
+  pub success: bool,
+  pub error: Option<String>,
+  pub found: bool,
+  /// Absolute path of the skin bin on disk.
+  #[napi(js_name = "binPath")]
+  pub bin_path: Option<String>,
+  /// Path relative to the searched folder, with forward slashes.
+  #[napi(js_name = "relPath")]
+  pub rel_path: Option<String>,
+  /// False when the file exists but has no SkinCharacterDataProperties object.
+  #[napi(js_name = "isSkinBin")]
+  pub is_skin_bin: bool,
+  #[napi(js_name = "championSkinName")]
+  pub champion_skin_name: Option<String>,
+  /// Linked bins from the bin header, in file order.
+  pub dependencies: Vec<String>,
+}
+
+fn not_found() -> SkinBinLocation {
+  SkinBinLocation {
+    success: true,
+    error: None,
+    found: false,
+    bin_path: None,
+    rel_path: None,
+    is_skin_bin: false,
+    champion_skin_name: None,
+    dependencies: Vec::new(),
+  }
+}
+
+/// Find `skin{id}.bin` of `champion` under `dir`: an extraction folder or a
+/// project (whose files are under `content/`). Matches the game path case-
+/// insensitively anywhere in the tree, and hashed names (`{hash}.bin` or an
+/// entry in the hashed-files sidecar) from extractions without hash lists.
+fn find_skin_bin(dir: &Path, champion: &str, skin_id: u32) -> Result<Option<(String, PathBuf)>, String> {
+  let wanted = skin_bin_path(champion, skin_id);
+  let hashed = format!("{:016x}.bin", wad_path_hash(&wanted));
+  for root in [dir.to_path_buf(), dir.join("content")] {
+    for rel in [wanted.clone(), hashed.clone()] {
+      let path = root.join(&rel);
+      if path.is_file() {
+        let rel = path.strip_prefix(dir).map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or(rel);
+        return Ok(Some((rel, path)));
+      }
+    }
+    let sidecar = read_hashed_files(&root);
+    if let Some((renamed, _)) = sidecar.iter().find(|(_, original)| original.eq_ignore_ascii_case(&wanted)) {
+      let path = root.join(renamed);
+      if path.is_file() {
+        let rel = path.strip_prefix(dir).map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_else(|_| renamed.clone());
+        return Ok(Some((rel, path)));
+      }
+    }
+  }
+  // Case or folder layout differs from the game's: look through the whole tree.
+  Ok(collect_files(dir)?.into_iter().find(|(rel, _)| {
+    let lower = rel.to_ascii_lowercase();
+    lower.ends_with(&wanted) && (lower.len() == wanted.len() || lower.as_bytes()[lower.len() - wanted.len() - 1] == b'/')
+  }))
+}
+
+fn locate(dir: &Path, champion: &str, skin_id: u32) -> Result<SkinBinLocation, String> {
+  if !dir.is_dir() {
+    return Err(format!("Folder not found: {}", dir.display()));
+  }
+  let Some((rel, path)) = find_skin_bin(dir, champion, skin_id)? else { return Ok(not_found()) };
+  let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+  let bin = Bin::from_reader(&mut Cursor::new(&data)).map_err(|e| format!("Failed to parse {}: {:?}", path.display(), e))?;
+  let scdp = fnv1a_lower("SkinCharacterDataProperties");
+  Ok(SkinBinLocation {
+    success: true,
+    error: None,
+    found: true,
+    bin_path: Some(path.to_string_lossy().into_owned()),
+    rel_path: Some(rel),
+    is_skin_bin: bin.objects.values().any(|o| o.class_hash == scdp),
+    champion_skin_name: read_champion_skin_name(&data)?,
+    dependencies: bin.dependencies.clone(),
+  })
+}
+
+/// Locate the main skin bin for `champion` / `skinId` in a project or
+/// extraction folder, with its `championSkinName` and bin dependencies, so a
+/// repath can check it targets the intended skin. `found` is false (not an
+/// error) when the folder has no such bin.
+#[napi(js_name = "locateSkinBin")]
+pub fn locate_skin_bin(dir: String, champion: String, skin_id: u32) -> SkinBinLocation {
+  locate(Path::new(&dir), &champion, skin_id).unwrap_or_else(|e| SkinBinLocation {
+    success: false,
+    error: Some(e),
+    ..not_found()
+  })
+}
@@ -0,0 +1,172 @@
+// ── Stringtable diff ─────────────────────────────────────────────────────────
+// Localized strings live in RST stringtables inside `Localized/Global.{locale}`.
+// Entries are keyed by a truncated xxh64 of the (unknown) string key, so a
+// diff is done on those hashes; names come from `hashes.rst.txt` when the hash
+// dir has one. The number of hash bits shrinks with newer RST versions, so
+// tables are compared on the bits both versions keep.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use napi_derive::napi;
+use xxhash_rust::xxh64::xxh64;
+
+use crate::game::{find_file_ci, game_dir, read_wad_chunks, wad_path_hash};
+use crate::parse_hash_text_file;
+
+pub(crate) const RST_HASH_FILE: &str = "hashes.rst.txt";
+/// Hex digits per hash in hashes.rst.txt (40-bit hashes, masked down as needed).
+pub(crate) const RST_HASH_LEN: usize = 10;
+
+pub(crate) struct Stringtable {
+  pub hash_bits: u32,
+  /// Truncated key hash -> text.
+  pub entries: HashMap<u64, String>,
+}
+
+impl Stringtable {
+  /// Entry text for a (case-insensitive) key.
+  pub(crate) fn get(&self, key: &str) -> Option<&String> {
+    let hash = xxh64(key.to_ascii_lowercase().as_bytes(), 0) & ((1u64 << self.hash_bits) - 1);
+    self.entries.get(&hash)
+  }
+}
+
+fn hash_bits(version: u8) -> Option<u32> {
+  match version {
+    2 | 3 => Some(40),
+    4 => Some(39),
+    5 => Some(38),
+    _ => None,
+  }
+}
+
+fn parse_rst(data: &[u8]) -> Result<Stringtable, String> {
+  if data.len() < 4 || &data[..3] != b"RST" { return Err("Not an RST stringtable".to_string()); }
+  let version = data[3];
+  let bits = hash_bits(version).ok_or_else(|| format!("Unsupported RST version {}", version))?;
+  let truncated = || "Truncated RST stringtable".to_string();
+  let mut pos = 4usize;
+  let read_u32 = |pos: usize| data.get(pos..pos + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+  if version == 2 && data.get(pos).copied().ok_or_else(truncated)? != 0 {
+    // Font config string.
+    let len = read_u32(pos + 1).ok_or_else(truncated)? as usize;
+    pos += 5 + len;
+  } else if version == 2 {
+    pos += 1;
+  }
+  let count = read_u32(pos).ok_or_else(truncated)? as usize;
+  pos += 4;
+  let table = data.get(pos..pos + count * 8).ok_or_else(truncated)?;
+  pos += count * 8;
+  if version < 5 { pos += 1; }
+  let strings = data.get(pos..).ok_or_else(truncated)?;
+
+  let mask = (1u64 << bits) - 1;
+  let mut entries = HashMap::with_capacity(count);
+  for raw in table.chunks_exact(8) {
+    let v = u64::from_le_bytes(raw.try_into().unwrap_or_default());
+    let offset = (v >> bits) as usize;
+    let Some(rest) = strings.get(offset..) else { continue };
+    let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+    // Encrypted entries (leading 0xFF) aren't text; skip them.
+    if rest.first() == Some(&0xff) { continue; }
+    entries.insert(v & mask, String::from_utf8_lossy(&rest[..end]).into_owned());
+  }
+  Ok(Stringtable { hash_bits: bits, entries })
+}
+
+/// Read the main stringtable of one install for a locale ("en_US").
+pub(crate) fn load_stringtable(league: &Path, locale: &str) -> Result<Stringtable, String> {
+  let localized = game_dir(league).join("DATA").join("FINAL").join("Localized");
+  let wad = find_file_ci(&localized, &format!("Global.{}.wad.client", locale))
+    .ok_or_else(|| format!("No Global.{}.wad.client in {}", locale, localized.display()))?;
+  let lower = locale.to_ascii_lowercase();
+  // Current location first, then the layouts of older patches.
+  let candidates = [
+    format!("data/menu/{}/lol.stringtable", lower),
+    format!("data/menu/{}/main.stringtable", lower),
+    format!("data/menu/main_{}.stringtable", lower),
+  ];
+  let hashes: Vec<u64> = candidates.iter().map(|p| wad_path_hash(p)).collect();
+  let data = read_wad_chunks(&wad, &hashes)?
+    .into_iter()
+    .flatten()
+    .next()
+    .ok_or_else(|| format!("No stringtable found in {}", wad.display()))?;
+  parse_rst(&data).map_err(|e| format!("{}: {}", wad.display(), e))
+}
+
+#[napi(object)]
+pub struct StringtableEntry {
+  /// Entry key hash, hex.
+  pub hash: String,
+  /// Key name when known from hashes.rst.txt.
+  pub key: Option<String>,
+  pub text: String,
+}
+
+#[napi(object)]
+pub struct ChangedStringtableEntry {
+  pub hash: String,
+  pub key: Option<String>,
+  #[napi(js_name = "oldText")]
+  pub old_text: String,
+  #[napi(js_name = "newText")]
+  pub new_text: String,
+}
+
+#[napi(object)]
+pub struct StringtableDiff {
+  pub success: bool,
+  pub error: Option<String>,
+  pub added: Vec<StringtableEntry>,
+  pub removed: Vec<StringtableEntry>,
+  pub changed: Vec<ChangedStringtableEntry>,
+}
+
+fn diff(old_game: &Path, new_game: &Path, locale: &str, hash_dir: Option<&str>) -> Result<StringtableDiff, String> {
+  let old = load_stringtable(old_game, locale)?;
+  let new = load_stringtable(new_game, locale)?;
+  let bits = old.hash_bits.min(new.hash_bits);
+  let mask = (1u64 << bits) - 1;
+  let normalize = |t: Stringtable| -> HashMap<u64, String> { t.entries.into_iter().map(|(h, s)| (h & mask, s)).collect() };
+  let (old, new) = (normalize(old), normalize(new));
+
+  let names: HashMap<u64, String> = hash_dir
+    .map(|d| parse_hash_text_file(&Path::new(d).join(RST_HASH_FILE), RST_HASH_LEN))
+    .unwrap_or_default()
+    .into_iter()
+    .map(|(h, k)| (h & mask, k))
+    .collect();
+  let width = bits.div_ceil(4) as usize;
+  let hex = |h: u64| format!("{:0width$x}", h, width = width);
+  let entry = |h: u64, text: &str| StringtableEntry { hash: hex(h), key: names.get(&h).cloned(), text: text.to_string() };
+
+  let mut added: Vec<_> = new.iter().filter(|(h, _)| !old.contains_key(h)).map(|(h, t)| entry(*h, t)).collect();
+  let mut removed: Vec<_> = old.iter().filter(|(h, _)| !new.contains_key(h)).map(|(h, t)| entry(*h, t)).collect();
+  let mut changed: Vec<_> = new
+    .iter()
+    .filter_map(|(h, t)| {
+      let before = old.get(h).filter(|o| *o != t)?;
+      Some(ChangedStringtableEntry { hash: hex(*h), key: names.get(h).cloned(), old_text: before.clone(), new_text: t.clone() })
+    })
+    .collect();
+  added.sort_by(|a, b| a.hash.cmp(&b.hash));
+  removed.sort_by(|a, b| a.hash.cmp(&b.hash));
+  changed.sort_by(|a, b| a.hash.cmp(&b.hash));
+  Ok(StringtableDiff { success: true, error: None, added, removed, changed })
+}
+
+/// Added, removed and changed localized strings between two installs (e.g. the
+/// live game and a PBE or previous-patch copy) for one locale ("en_US").
+#[napi(js_name = "diffStringtables")]
+pub fn diff_stringtables(old_game: String, new_game: String, locale: String, hash_dir: Option<String>) -> StringtableDiff {
+  diff(Path::new(&old_game), Path::new(&new_game), &locale, hash_dir.as_deref()).unwrap_or_else(|e| StringtableDiff {
+    success: false,
+    error: Some(e),
+    added: Vec::new(),
+    removed: Vec::new(),
+    changed: Vec::new(),
+  })
+}
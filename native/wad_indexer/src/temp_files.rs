@@ -0,0 +1,216 @@
+// ── Managed temp files ───────────────────────────────────────────────────────
+// Every temp file Quartz creates goes under one session folder,
+// `{temp}/quartz/session-{pid}-{ms}/{category}/`, instead of being scattered
+// over the system temp dir. The session holds an exclusive lock on its
+// `.lock` file while the process runs: `cleanupTempFiles` removes the session
+// on exit, and `cleanupOrphanTempFiles` (run on startup) removes sessions
+// whose lock is free, i.e. left behind by a crash. Persistent caches that
+// happen to live in the temp dir (e.g. `wad-build`) are not sessions and are
+// never touched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use napi_derive::napi;
+
+use crate::paths::sanitize_rel_path;
+
+const SESSION_PREFIX: &str = "session-";
+const LOCK_FILE: &str = ".lock";
+/// Unmanaged folders older builds wrote straight under the root.
+const LEGACY_DIRS: &[&str] = &["wad-bins"];
+const STARTUP_GRACE: Duration = Duration::from_secs(60);
+
+struct Session {
+  dir: PathBuf,
+  /// Held open (and locked) for the life of the session.
+  _lock: fs::File,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+static NEXT_NAME: AtomicU32 = AtomicU32::new(1);
+
+fn temp_root() -> PathBuf {
+  std::env::temp_dir().join("quartz")
+}
+
+fn start_session() -> Result<Session, String> {
+  let ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+  let dir = temp_root().join(format!("{}{}-{}", SESSION_PREFIX, std::process::id(), ms));
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+  let lock_path = dir.join(LOCK_FILE);
+  let lock = fs::File::create(&lock_path).map_err(|e| format!("Failed to create {}: {}", lock_path.display(), e))?;
+  lock.try_lock().map_err(|e| format!("Failed to lock {}: {}", lock_path.display(), e))?;
+  Ok(Session { dir, _lock: lock })
+}
+
+/// This process's session folder, started on first use.
+fn session_dir() -> Result<PathBuf, String> {
+  let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+  if session.is_none() {
+    *session = Some(start_session()?);
+  }
+  Ok(session.as_ref().map(|s| s.dir.clone()).unwrap_or_default())
+}
+
+/// A folder for temp files of `category` (e.g. "wad-bins", "previews",
+/// "downloads") inside this session; created if needed.
+pub(crate) fn temp_subdir(category: &str) -> Result<PathBuf, String> {
+  let category = sanitize_rel_path(category.trim_matches(['/', '\\'])).replace(['/', '\\'], "_");
+  if category.is_empty() || category == "." || category == ".." {
+    return Err(format!("Invalid temp category: {}", category));
+  }
+  let dir = session_dir()?.join(category);
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+  Ok(dir)
+}
+
+/// True for paths inside any Quartz temp session (this one or an older one).
+pub(crate) fn is_managed(path: &Path) -> bool {
+  path.strip_prefix(temp_root()).ok()
+    .and_then(|rel| rel.components().next())
+    .is_some_and(|c| c.as_os_str().to_string_lossy().starts_with(SESSION_PREFIX))
+}
+
+fn dir_size(dir: &Path) -> u64 {
+  let Ok(entries) = fs::read_dir(dir) else { return 0 };
+  entries.flatten().map(|e| match e.file_type() {
+    Ok(t) if t.is_dir() => dir_size(&e.path()),
+    _ => e.metadata().map(|m| m.len()).unwrap_or(0),
+  }).sum()
+}
+
+#[napi(object)]
+pub struct TempFileResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Path to write to; its folder exists, the file itself is not created.
+  pub path: Option<String>,
+}
+
+#[napi(object)]
+pub struct TempCleanupResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Session folders (or files, for `releaseTempFile`) removed.
+  #[napi(js_name = "removedCount")]
+  pub removed_count: u32,
+  #[napi(js_name = "freedBytes")]
+  pub freed_bytes: f64,
+}
+
+/// Reserve a managed temp path under `category`. Without a `name`, a unique
+/// one is generated; `extension` (without dot) is appended to generated names.
+#[napi(js_name = "createTempFile")]
+pub fn create_temp_file(category: String, name: Option<String>, extension: Option<String>) -> TempFileResult {
+  let create = || -> Result<String, String> {
+    let dir = temp_subdir(&category)?;
+    let name = match name.as_deref().map(|n| sanitize_rel_path(n).replace(['/', '\\'], "_")) {
+      Some(n) if !n.is_empty() => n,
+      _ => {
+        let n = format!("{:08x}", NEXT_NAME.fetch_add(1, Ordering::Relaxed));
+        match extension.as_deref().map(|e| e.trim_start_matches('.')).filter(|e| !e.is_empty()) {
+          Some(ext) => format!("{}.{}", n, ext),
+          None => n,
+        }
+      }
+    };
+    Ok(dir.join(name).to_string_lossy().into_owned())
+  };
+  match create() {
+    Ok(path) => TempFileResult { success: true, error: None, path: Some(path) },
+    Err(e) => TempFileResult { success: false, error: Some(e), path: None },
+  }
+}
+
+/// Delete a managed temp file or folder early. Paths outside the Quartz temp
+/// sessions are refused.
+#[napi(js_name = "releaseTempFile")]
+pub fn release_temp_file(path: String) -> TempCleanupResult {
+  let path = Path::new(&path);
+  if !is_managed(path) || path.file_name().is_some_and(|n| n == LOCK_FILE) {
+    return TempCleanupResult {
+      success: false,
+      error: Some(format!("{} is not a managed temp file", path.display())),
+      removed_count: 0,
+      freed_bytes: 0.0,
+    };
+  }
+  let (size, removed) = if path.is_dir() {
+    (dir_size(path), fs::remove_dir_all(path))
+  } else {
+    (fs::metadata(path).map(|m| m.len()).unwrap_or(0), fs::remove_file(path))
+  };
+  match removed {
+    Ok(()) => TempCleanupResult { success: true, error: None, removed_count: 1, freed_bytes: size as f64 },
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      TempCleanupResult { success: true, error: None, removed_count: 0, freed_bytes: 0.0 }
+    }
+    Err(e) => TempCleanupResult {
+      success: false,
+      error: Some(format!("Failed to remove {}: {}", path.display(), e)),
+      removed_count: 0,
+      freed_bytes: 0.0,
+    },
+  }
+}
+
+/// Remove this process's session (call on app exit). A later temp file starts a new one.
+#[napi(js_name = "cleanupTempFiles")]
+pub fn cleanup_temp_files() -> TempCleanupResult {
+  let session = SESSION.lock().unwrap_or_else(|e| e.into_inner()).take();
+  let Some(Session { dir, _lock: lock }) = session else {
+    return TempCleanupResult { success: true, error: None, removed_count: 0, freed_bytes: 0.0 };
+  };
+  // The lock file can't be deleted on Windows while it's open.
+  drop(lock);
+  let size = dir_size(&dir);
+  match fs::remove_dir_all(&dir) {
+    Ok(()) => TempCleanupResult { success: true, error: None, removed_count: 1, freed_bytes: size as f64 },
+    Err(e) => TempCleanupResult {
+      success: false,
+      error: Some(format!("Failed to remove {}: {}", dir.display(), e)),
+      removed_count: 0,
+      freed_bytes: 0.0,
+    },
+  }
+}
+
+/// Remove sessions left behind by Quartz processes that are gone (their lock
+/// is free), plus legacy unmanaged temp folders. Safe with several instances open.
+#[napi(js_name = "cleanupOrphanTempFiles")]
+pub fn cleanup_orphan_temp_files() -> TempCleanupResult {
+  let root = temp_root();
+  let own = SESSION.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|s| s.dir.clone());
+  let Ok(entries) = fs::read_dir(&root) else {
+    return TempCleanupResult { success: true, error: None, removed_count: 0, freed_bytes: 0.0 };
+  };
+  let (mut removed_count, mut freed) = (0u32, 0u64);
+  for entry in entries.flatten() {
+    let path = entry.path();
+    let name = entry.file_name().to_string_lossy().into_owned();
+    if !path.is_dir() || own.as_deref() == Some(path.as_path()) { continue; }
+    let orphaned = if name.starts_with(SESSION_PREFIX) {
+      // A live session keeps its lock; a crashed one left it free (or never made it).
+      match fs::OpenOptions::new().write(true).open(path.join(LOCK_FILE)) {
+        Ok(lock) => lock.try_lock().is_ok(),
+        // No lock yet: either a session starting right now or one that died creating it.
+        Err(_) => entry.metadata().and_then(|m| m.modified())
+          .map(|t| t.elapsed().unwrap_or_default() > STARTUP_GRACE)
+          .unwrap_or(true),
+      }
+    } else {
+      LEGACY_DIRS.contains(&name.as_str())
+    };
+    if !orphaned { continue; }
+    let size = dir_size(&path);
+    if fs::remove_dir_all(&path).is_ok() {
+      removed_count += 1;
+      freed += size;
+    }
+  }
+  TempCleanupResult { success: true, error: None, removed_count, freed_bytes: freed as f64 }
+}
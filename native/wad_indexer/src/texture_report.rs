@@ -0,0 +1,217 @@
+// ── Texture usage report ─────────────────────────────────────────────────────
+// Every texture in a project with the bins that reference it and what it costs:
+// format, dimensions, mip count and size on disk. Textures larger than the game
+// needs or stored uncompressed are flagged, so an "optimize my mod" pass knows
+// where the megabytes are. Only headers are read; nothing is decoded.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::asset_graph::{asset_kind, build_graph};
+use crate::lint::MAX_UNCOMPRESSED_PIXELS;
+use crate::wad_build::{plan_wad_dir, project_wad_dirs};
+
+/// Textures with a side above this are flagged oversized unless overridden.
+const DEFAULT_MAX_DIMENSION: u32 = 2048;
+
+/// Header facts of a DDS or TEX texture.
+pub(crate) struct TextureHeader {
+  /// "BC1", "BC3", "BC7", "BGRA8", "ETC1", ...
+  pub(crate) format: String,
+  pub(crate) width: u32,
+  pub(crate) height: u32,
+  pub(crate) mip_count: u32,
+  pub(crate) compressed: bool,
+}
+
+fn dxgi_format(dxgi: u32) -> (&'static str, bool) {
+  match dxgi {
+    70..=72 => ("BC1", true),
+    73..=75 => ("BC2", true),
+    76..=78 => ("BC3", true),
+    79..=81 => ("BC4", true),
+    82..=84 => ("BC5", true),
+    94..=96 => ("BC6H", true),
+    97..=99 => ("BC7", true),
+    27..=29 => ("RGBA8", false),
+    87 | 88 | 90..=93 => ("BGRA8", false),
+    10 => ("RGBA16F", false),
+    2 => ("RGBA32F", false),
+    _ => ("DX10", false),
+  }
+}
+
+fn four_cc_format(four_cc: &[u8]) -> Option<&'static str> {
+  Some(match four_cc {
+    b"DXT1" => "BC1",
+    b"DXT2" | b"DXT3" => "BC2",
+    b"DXT4" | b"DXT5" => "BC3",
+    b"ATI1" | b"BC4U" | b"BC4S" => "BC4",
+    b"ATI2" | b"BC5U" | b"BC5S" => "BC5",
+    _ => return None,
+  })
+}
+
+/// Format, size and mips from the first bytes of a DDS or TEX file; `None` for anything else.
+pub(crate) fn read_texture_header(path: &Path) -> Option<TextureHeader> {
+  let mut header = [0u8; 148];
+  let mut file = fs::File::open(path).ok()?;
+  let n = file.read(&mut header).ok()?;
+  let header = &header[..n];
+  let u32_at = |pos: usize| header.get(pos..pos + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+  if header.starts_with(b"DDS ") {
+    let (height, width) = (u32_at(12)?, u32_at(16)?);
+    let mip_count = u32_at(28)?.max(1);
+    let flags = u32_at(80)?;
+    let four_cc = header.get(84..88)?;
+    let (format, compressed) = if four_cc == b"DX10" {
+      let (name, compressed) = dxgi_format(u32_at(128)?);
+      (name.to_string(), compressed)
+    } else if flags & 0x4 != 0 {
+      let name = four_cc_format(four_cc).map(str::to_string).unwrap_or_else(|| String::from_utf8_lossy(four_cc).into_owned());
+      (name, true)
+    } else {
+      let bits = u32_at(88)?;
+      (if bits == 32 { "BGRA8".to_string() } else { format!("RGB{}", bits) }, false)
+    };
+    return Some(TextureHeader { format, width, height, mip_count, compressed });
+  }
+  if header.starts_with(b"TEX\0") {
+    let width = u16::from_le_bytes([*header.get(4)?, *header.get(5)?]) as u32;
+    let height = u16::from_le_bytes([*header.get(6)?, *header.get(7)?]) as u32;
+    let format = match *header.get(9)? {
+      1 => "ETC1",
+      2 => "ETC2",
+      10 => "BC1",
+      12 => "BC3",
+      20 => "BGRA8",
+      _ => "unknown",
+    };
+    // Mip chains always run down to 1x1.
+    let mip_count = if header.get(11)? & 1 != 0 { width.max(height).max(1).ilog2() + 1 } else { 1 };
+    return Some(TextureHeader { format: format.to_string(), width, height, mip_count, compressed: format != "BGRA8" });
+  }
+  None
+}
+
+#[napi(object)]
+pub struct TextureReportOptions {
+  /// Largest width or height not flagged as oversized. Default 2048.
+  #[napi(js_name = "maxDimension")]
+  pub max_dimension: Option<u32>,
+}
+
+#[napi(object)]
+pub struct TextureUsage {
+  /// WAD folder the texture lives in, e.g. "Ahri.wad.client".
+  pub wad: String,
+  /// Relative to the WAD folder.
+  pub path: String,
+  /// None when the header couldn't be read.
+  pub format: Option<String>,
+  pub width: u32,
+  pub height: u32,
+  #[napi(js_name = "mipCount")]
+  pub mip_count: u32,
+  pub size: f64,
+  /// Project bins naming the texture, as "{wad}/{path}".
+  #[napi(js_name = "referencedBy")]
+  pub referenced_by: Vec<String>,
+  /// Any of "oversized", "uncompressed", "unreferenced", "unreadable".
+  pub flags: Vec<String>,
+}
+
+#[napi(object)]
+pub struct TextureReportResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Largest first.
+  pub textures: Vec<TextureUsage>,
+  #[napi(js_name = "totalBytes")]
+  pub total_bytes: f64,
+  /// Textures with at least one flag other than "unreferenced".
+  #[napi(js_name = "flaggedCount")]
+  pub flagged_count: u32,
+  #[napi(js_name = "flaggedBytes")]
+  pub flagged_bytes: f64,
+}
+
+fn report(project: &Path, options: &TextureReportOptions) -> Result<TextureReportResult, String> {
+  let max_dimension = options.max_dimension.filter(|d| *d > 0).unwrap_or(DEFAULT_MAX_DIMENSION);
+  let mut textures: Vec<(u64, TextureUsage)> = Vec::new();
+  // Texture hash -> referencing bins; merged across WAD folders like findOrphans.
+  let mut referrers: HashMap<u64, Vec<String>> = HashMap::new();
+
+  for dir in project_wad_dirs(project) {
+    let wad = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let graph = build_graph(&dir, None)?;
+    let (plan, _) = plan_wad_dir(&dir)?;
+    let rel_of = |hash: u64| plan.get(&hash).map(|f| f.strip_prefix(&dir).unwrap_or(f).to_string_lossy().replace('\\', "/"));
+
+    for (from, to) in &graph.edges {
+      let Some(bin) = rel_of(graph.nodes[*from as usize].hash) else { continue };
+      let list = referrers.entry(graph.nodes[*to as usize].hash).or_default();
+      let name = format!("{}/{}", wad, bin);
+      if !list.contains(&name) { list.push(name); }
+    }
+    for node in graph.nodes.iter().filter(|n| n.present) {
+      let Some(file) = plan.get(&node.hash) else { continue };
+      let header = read_texture_header(file);
+      // Unnamed chunks count when their content is a texture.
+      if asset_kind(node.path.as_deref()) != "texture" && header.is_none() { continue; }
+      let rel = rel_of(node.hash).unwrap_or_default();
+      let size = fs::metadata(file).map(|m| m.len()).unwrap_or(node.size);
+      let mut flags = Vec::new();
+      let usage = match header {
+        Some(h) => {
+          if h.width.max(h.height) > max_dimension { flags.push("oversized".to_string()); }
+          if !h.compressed && h.width as u64 * h.height as u64 > MAX_UNCOMPRESSED_PIXELS { flags.push("uncompressed".to_string()); }
+          TextureUsage { wad: wad.clone(), path: rel, format: Some(h.format), width: h.width, height: h.height, mip_count: h.mip_count, size: size as f64, referenced_by: Vec::new(), flags }
+        }
+        None => {
+          flags.push("unreadable".to_string());
+          TextureUsage { wad: wad.clone(), path: rel, format: None, width: 0, height: 0, mip_count: 0, size: size as f64, referenced_by: Vec::new(), flags }
+        }
+      };
+      textures.push((node.hash, usage));
+    }
+  }
+
+  let mut textures: Vec<TextureUsage> = textures.into_iter()
+    .map(|(hash, mut t)| {
+      t.referenced_by = referrers.remove(&hash).unwrap_or_default();
+      t.referenced_by.sort();
+      if t.referenced_by.is_empty() { t.flags.push("unreferenced".to_string()); }
+      t
+    })
+    .collect();
+  textures.sort_by(|a, b| b.size.total_cmp(&a.size).then_with(|| (&a.wad, &a.path).cmp(&(&b.wad, &b.path))));
+  let flagged: Vec<&TextureUsage> = textures.iter().filter(|t| t.flags.iter().any(|f| f != "unreferenced")).collect();
+  Ok(TextureReportResult {
+    success: true,
+    error: None,
+    total_bytes: textures.iter().map(|t| t.size).sum(),
+    flagged_count: flagged.len() as u32,
+    flagged_bytes: flagged.iter().map(|t| t.size).sum(),
+    textures,
+  })
+}
+
+/// Every texture in a project with its format, size, mips and referencing
+/// bins, flagging oversized and uncompressed ones. Read-only.
+#[napi(js_name = "textureUsageReport")]
+pub fn texture_usage_report(project_path: String, options: Option<TextureReportOptions>) -> TextureReportResult {
+  let options = options.unwrap_or(TextureReportOptions { max_dimension: None });
+  report(Path::new(&project_path), &options).unwrap_or_else(|e| TextureReportResult {
+    success: false,
+    error: Some(e),
+    textures: Vec::new(),
+    total_bytes: 0.0,
+    flagged_count: 0,
+    flagged_bytes: 0.0,
+  })
+}
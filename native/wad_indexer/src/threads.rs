@@ -0,0 +1,143 @@
+// ── Thread pools ─────────────────────────────────────────────────────────────
+// Extraction (disk bound) and hashing/scanning (CPU bound) can run on separate
+// rayon pools so a heavy batch job can be throttled without starving the
+// other. Until `configureThreads` is called both use rayon's global pool.
+//
+// Workers of both pools also share a budget for decompressed chunk bytes in
+// flight. Each worker holds a whole chunk while it writes or scans it, so a WAD
+// with a few huge chunks (map geometry, audio banks) could otherwise have every
+// worker holding one at once; with the budget, workers wait for room instead.
+
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+
+use napi_derive::napi;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+static IO_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+static CPU_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+const MAX_THREADS: u32 = 64;
+/// Decompressed bytes extraction workers may hold at once, unless configured.
+const DEFAULT_MEMORY_BUDGET_MB: u32 = 1024;
+
+struct MemoryBudget {
+  /// Bytes; u64::MAX when unlimited.
+  limit: u64,
+  in_flight: u64,
+}
+
+static MEMORY_BUDGET: Mutex<MemoryBudget> = Mutex::new(MemoryBudget {
+  limit: DEFAULT_MEMORY_BUDGET_MB as u64 * 1024 * 1024,
+  in_flight: 0,
+});
+static MEMORY_FREED: Condvar = Condvar::new();
+
+#[napi(object)]
+pub struct ThreadPoolOptions {
+  /// Threads for WAD extraction and TOC loading. 0 resets to the global pool.
+  pub io: Option<u32>,
+  /// Threads for hash extraction and other CPU-heavy scans. 0 resets to the global pool.
+  pub cpu: Option<u32>,
+  /// Decompressed chunk data extraction may hold in memory at once, in MB
+  /// (default 1024). 0 removes the limit.
+  #[napi(js_name = "memoryBudgetMb")]
+  pub memory_budget_mb: Option<u32>,
+}
+
+#[napi(object)]
+pub struct ThreadPoolConfig {
+  pub io: u32,
+  pub cpu: u32,
+  /// 0 when unlimited.
+  #[napi(js_name = "memoryBudgetMb")]
+  pub memory_budget_mb: u32,
+}
+
+/// Bytes reserved from the memory budget; given back when dropped.
+pub(crate) struct MemoryReservation(u64);
+
+impl Drop for MemoryReservation {
+  fn drop(&mut self) {
+    if self.0 == 0 { return; }
+    let mut budget = MEMORY_BUDGET.lock().unwrap_or_else(|e| e.into_inner());
+    budget.in_flight = budget.in_flight.saturating_sub(self.0);
+    MEMORY_FREED.notify_all();
+  }
+}
+
+/// Reserve `bytes` of the memory budget, waiting until they fit. A chunk larger
+/// than the whole budget waits for the others to finish and then runs alone.
+/// Hold the reservation for as long as the data is in memory, and don't start
+/// rayon work while holding it.
+pub(crate) fn reserve_memory(bytes: u64) -> MemoryReservation {
+  let mut budget = MEMORY_BUDGET.lock().unwrap_or_else(|e| e.into_inner());
+  let bytes = bytes.min(budget.limit);
+  while budget.in_flight > 0 && budget.in_flight.saturating_add(bytes) > budget.limit {
+    budget = MEMORY_FREED.wait(budget).unwrap_or_else(|e| e.into_inner());
+  }
+  budget.in_flight += bytes;
+  MemoryReservation(bytes)
+}
+
+fn current(slot: &RwLock<Option<Arc<ThreadPool>>>) -> Option<Arc<ThreadPool>> {
+  slot.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn run_in<R: Send>(slot: &RwLock<Option<Arc<ThreadPool>>>, f: impl FnOnce() -> R + Send) -> R {
+  match current(slot) {
+    Some(pool) => pool.install(f),
+    None => f(),
+  }
+}
+
+/// Run `f` (and any rayon work it starts) on the extraction pool.
+pub(crate) fn run_io<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+  run_in(&IO_POOL, f)
+}
+
+/// Run `f` (and any rayon work it starts) on the CPU pool.
+pub(crate) fn run_cpu<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+  run_in(&CPU_POOL, f)
+}
+
+fn pool_size(slot: &RwLock<Option<Arc<ThreadPool>>>) -> u32 {
+  current(slot).map(|p| p.current_num_threads()).unwrap_or_else(rayon::current_num_threads) as u32
+}
+
+fn configure(slot: &RwLock<Option<Arc<ThreadPool>>>, threads: u32, name: &'static str) -> napi::Result<()> {
+  let pool = if threads == 0 {
+    None
+  } else {
+    let pool = ThreadPoolBuilder::new()
+      .num_threads(threads.min(MAX_THREADS) as usize)
+      .thread_name(move |i| format!("quartz-{}-{}", name, i))
+      .build()
+      .map_err(|e| napi::Error::from_reason(format!("Failed to create {} thread pool: {}", name, e)))?;
+    Some(Arc::new(pool))
+  };
+  // Jobs already running keep their Arc to the old pool and finish on it.
+  *slot.write().unwrap_or_else(|e| e.into_inner()) = pool;
+  Ok(())
+}
+
+/// Size the extraction (`io`) and hashing (`cpu`) pools. Omitted fields are
+/// left unchanged. Returns the thread counts now in effect.
+#[napi(js_name = "configureThreads")]
+pub fn configure_threads(options: ThreadPoolOptions) -> napi::Result<ThreadPoolConfig> {
+  if let Some(io) = options.io { configure(&IO_POOL, io, "io")?; }
+  if let Some(cpu) = options.cpu { configure(&CPU_POOL, cpu, "cpu")?; }
+  if let Some(mb) = options.memory_budget_mb {
+    let limit = if mb == 0 { u64::MAX } else { mb as u64 * 1024 * 1024 };
+    MEMORY_BUDGET.lock().unwrap_or_else(|e| e.into_inner()).limit = limit;
+    // A larger budget may let waiting workers go.
+    MEMORY_FREED.notify_all();
+  }
+  Ok(get_thread_config())
+}
+
+#[napi(js_name = "getThreadConfig")]
+pub fn get_thread_config() -> ThreadPoolConfig {
+  let limit = MEMORY_BUDGET.lock().unwrap_or_else(|e| e.into_inner()).limit;
+  let memory_budget_mb = if limit == u64::MAX { 0 } else { (limit / (1024 * 1024)) as u32 };
+  ThreadPoolConfig { io: pool_size(&IO_POOL), cpu: pool_size(&CPU_POOL), memory_budget_mb }
+}
@@ -0,0 +1,101 @@
+// ── Game version detection ───────────────────────────────────────────────────
+// Prefers Game/content-metadata.json (written by the patcher); falls back to the
+// VS_FIXEDFILEINFO block of "League of Legends.exe".
+
+use std::fs;
+use std::path::Path;
+
+use memmap2::Mmap;
+use napi_derive::napi;
+
+use crate::game::{find_file_ci, game_dir};
+
+const VS_FIXEDFILEINFO_SIGNATURE: [u8; 4] = [0xBD, 0x04, 0xEF, 0xFE];
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct GameVersion {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Full version string, e.g. "14.20.625.4391+branch.releases-14-20...".
+  pub version: Option<String>,
+  /// Major.minor patch, e.g. "14.20".
+  pub patch: Option<String>,
+  /// File version from the game executable, e.g. "14.20.625.4391".
+  #[napi(js_name = "exeVersion")]
+  pub exe_version: Option<String>,
+  /// "content-metadata", "exe" or "none".
+  pub source: String,
+}
+
+/// "14.20.625.4391+branch..." -> "14.20"
+pub(crate) fn patch_from_version(version: &str) -> Option<String> {
+  let mut parts = version.split(['.', '+']);
+  let major = parts.next()?.trim();
+  let minor = parts.next()?.trim();
+  if major.is_empty() || minor.is_empty() { return None; }
+  if !major.bytes().all(|b| b.is_ascii_digit()) || !minor.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+  Some(format!("{}.{}", major, minor))
+}
+
+fn read_content_metadata_version(game: &Path) -> Option<String> {
+  let path = find_file_ci(game, "content-metadata.json")?;
+  let content = fs::read_to_string(path).ok()?;
+  let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+  json.get("version").and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// File version from the first VS_FIXEDFILEINFO block in a PE image.
+fn read_exe_file_version(exe: &Path) -> Option<String> {
+  let file = fs::File::open(exe).ok()?;
+  let mmap = unsafe { Mmap::map(&file) }.ok()?;
+  let data = &mmap[..];
+  let pos = data.windows(4).position(|w| w == VS_FIXEDFILEINFO_SIGNATURE)?;
+  let read_u32 = |at: usize| -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+  };
+  let ms = read_u32(pos + 8)?;
+  let ls = read_u32(pos + 12)?;
+  Some(format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF))
+}
+
+pub(crate) fn detect_game_version(league_path: &Path) -> GameVersion {
+  let game = game_dir(league_path);
+  if !game.is_dir() {
+    return GameVersion {
+      success: false,
+      error: Some(format!("Game folder not found: {}", game.display())),
+      version: None,
+      patch: None,
+      exe_version: None,
+      source: "none".to_string(),
+    };
+  }
+
+  let exe_version = find_file_ci(&game, "League of Legends.exe").and_then(|p| read_exe_file_version(&p));
+  let metadata_version = read_content_metadata_version(&game);
+
+  let (version, source) = match (metadata_version, exe_version.clone()) {
+    (Some(v), _) => (Some(v), "content-metadata"),
+    (None, Some(v)) => (Some(v), "exe"),
+    (None, None) => (None, "none"),
+  };
+  let patch = version.as_deref().and_then(patch_from_version);
+
+  GameVersion {
+    success: version.is_some(),
+    error: if version.is_none() { Some("Could not determine game version".to_string()) } else { None },
+    version,
+    patch,
+    exe_version,
+    source: source.to_string(),
+  }
+}
+
+/// Detect the installed game version so projects can record their target patch.
+#[napi(js_name = "getGameVersion")]
+pub fn get_game_version(league_path: String) -> GameVersion {
+  detect_game_version(Path::new(&league_path))
+}
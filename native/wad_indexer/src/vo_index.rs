@@ -0,0 +1,92 @@
+// ── VO index ─────────────────────────────────────────────────────────────────
+// Lists a champion's voice-over events for one locale with the WEM IDs each
+// plays (from the locale WAD's banks) and, where the stringtable has an entry
+// keyed after the event, the line's localized text. VO modders use it to find
+// which WEM in the locale package holds the line they want to replace.
+
+use std::path::Path;
+
+use napi_derive::napi;
+
+use crate::game::{champions_dir, find_champion_wad, find_file_ci};
+use crate::parse_hash_text_file;
+use crate::stringtable::{load_stringtable, Stringtable, RST_HASH_FILE, RST_HASH_LEN};
+use crate::wwise::audio_events;
+
+#[napi(object)]
+pub struct VoLine {
+  /// Event name, when known.
+  pub event: Option<String>,
+  #[napi(js_name = "eventId")]
+  pub event_id: u32,
+  pub bank: Option<String>,
+  #[napi(js_name = "wemIds")]
+  pub wem_ids: Vec<u32>,
+  /// Localized text of the line, when a stringtable key matches the event.
+  pub text: Option<String>,
+  #[napi(js_name = "textKey")]
+  pub text_key: Option<String>,
+}
+
+#[napi(object)]
+pub struct VoIndexResult {
+  pub success: bool,
+  pub error: Option<String>,
+  pub lines: Vec<VoLine>,
+  /// Lines with localized text.
+  #[napi(js_name = "textCount")]
+  pub text_count: u32,
+}
+
+/// Stringtable keys to try for an event: the name itself, without the
+/// "Play_" verb, and the game's VO key prefixes; then known keys (from
+/// hashes.rst.txt) ending with the event's stem.
+fn find_text<'a>(table: &'a Stringtable, known_keys: &[String], event: &str) -> Option<(String, &'a String)> {
+  let lower = event.to_ascii_lowercase();
+  let stem = lower.strip_prefix("play_").unwrap_or(&lower);
+  let guesses = [lower.clone(), stem.to_string(), format!("game_{}", stem), format!("subtitle_{}", stem)];
+  guesses
+    .into_iter()
+    .chain(known_keys.iter().filter(|k| k.to_ascii_lowercase().ends_with(stem)).cloned())
+    .find_map(|key| table.get(&key).map(|text| (key, text)))
+}
+
+fn index(champion: &str, locale: &str, league: &Path, hash_dir: Option<&str>) -> Result<Vec<VoLine>, String> {
+  let main_wad = find_champion_wad(league, champion)
+    .ok_or_else(|| format!("Champion WAD not found for {}", champion))?;
+  let vo_wad = find_file_ci(&champions_dir(league), &format!("{}.{}.wad.client", champion, locale))
+    .ok_or_else(|| format!("No {} VO WAD for {}", locale, champion))?;
+  let table = load_stringtable(league, locale)?;
+  let known_keys: Vec<String> = hash_dir
+    .map(|d| parse_hash_text_file(&Path::new(d).join(RST_HASH_FILE), RST_HASH_LEN).into_values().collect())
+    .unwrap_or_default();
+
+  let rows = audio_events(&[&vo_wad], &[&main_wad], hash_dir)?;
+  Ok(rows
+    .into_iter()
+    .map(|row| {
+      let found = row.event.as_deref().and_then(|e| find_text(&table, &known_keys, e));
+      VoLine {
+        event: row.event,
+        event_id: row.event_id,
+        bank: row.bank,
+        wem_ids: row.wems,
+        text_key: found.as_ref().map(|(k, _)| k.clone()),
+        text: found.map(|(_, t)| t.clone()),
+      }
+    })
+    .collect())
+}
+
+/// A champion's VO events for `locale` ("en_US") with the WEM IDs they play
+/// and, where available, each line's localized text.
+#[napi(js_name = "exportVoIndex")]
+pub fn export_vo_index(champion: String, locale: String, league_path: String, hash_dir: Option<String>) -> VoIndexResult {
+  match index(&champion, &locale, Path::new(&league_path), hash_dir.as_deref()) {
+    Ok(lines) => {
+      let text_count = lines.iter().filter(|l| l.text.is_some()).count() as u32;
+      VoIndexResult { success: true, error: None, lines, text_count }
+    }
+    Err(e) => VoIndexResult { success: false, error: Some(e), lines: Vec::new(), text_count: 0 },
+  }
+}
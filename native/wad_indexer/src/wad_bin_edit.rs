@@ -0,0 +1,176 @@
+// ── Bins inside WADs ─────────────────────────────────────────────────────────
+// Open a bin straight out of a WAD for editing: the chunk is extracted to a
+// managed temp folder next to a `source.json` recording where it came from,
+// and its ritobin text is returned. Saving converts the text back into that
+// temp bin and, when asked, patches it into the source WAD. The sidecar keeps
+// the link across reloads, so an open game bin can still be written back.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use napi_derive::napi;
+use quartz_core::resolver::HashSource;
+use quartz_core::ritobin;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::game::read_wad_chunks;
+use crate::paths::write_retrying;
+use crate::project_search::hash_provider;
+use crate::temp_files::{is_managed, temp_subdir};
+use crate::wad_patch::save_chunk;
+use crate::{normalize_rel_path, parse_hash_hex, xxhash_path, HashLayers};
+
+const SOURCE_JSON: &str = "source.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinSource {
+  wad_path: String,
+  chunk_hash: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  asset_path: Option<String>,
+}
+
+#[napi(object)]
+pub struct OpenWadBinResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Ritobin text of the bin.
+  pub text: Option<String>,
+  /// Extracted bin; pass it back to `saveWadBin`.
+  #[napi(js_name = "tempPath")]
+  pub temp_path: Option<String>,
+  /// Asset path of the chunk when the hash resolves.
+  #[napi(js_name = "assetPath")]
+  pub asset_path: Option<String>,
+}
+
+#[napi(object)]
+pub struct SaveWadBinResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// Source WAD of the bin.
+  #[napi(js_name = "wadPath")]
+  pub wad_path: Option<String>,
+  /// True when the bin was patched into the source WAD.
+  #[napi(js_name = "writtenBack")]
+  pub written_back: bool,
+}
+
+/// One folder per (WAD, chunk), so reopening the same bin reuses it.
+fn bin_dir(wad_path: &Path, chunk_hash: u64) -> Result<PathBuf, String> {
+  let key = wad_path.to_string_lossy().replace('\\', "/");
+  Ok(temp_subdir("wad-bins")?.join(format!("{:016x}-{:016x}", xxh64(key.as_bytes(), 0), chunk_hash)))
+}
+
+fn open(wad_path: &Path, chunk: &str, hash_dir: Option<&str>) -> Result<OpenWadBinResult, String> {
+  let (hash, mut asset_path) = match parse_hash_hex(chunk) {
+    Some(h) => (h, None),
+    None => {
+      let path = normalize_rel_path(chunk).to_ascii_lowercase();
+      (xxhash_path(&path), Some(path))
+    }
+  };
+  if asset_path.is_none() {
+    if let Some(dir) = hash_dir {
+      asset_path = HashLayers::open(Some(dir)).resolve(&[hash])
+        .pop()
+        .filter(|r| r.source != HashSource::Hex)
+        .map(|r| r.name);
+    }
+  }
+  let data = read_wad_chunks(wad_path, &[hash])?
+    .pop()
+    .flatten()
+    .ok_or_else(|| format!("{} is not in {}", chunk, wad_path.display()))?;
+  let bin = ritobin::bin_from_bytes(&data).map_err(|e| format!("{} is not a bin: {}", chunk, e))?;
+  let text = ritobin::bin_to_text(&bin, &hash_provider(hash_dir)).map_err(|e| format!("Failed to convert {}: {}", chunk, e))?;
+
+  let dir = bin_dir(wad_path, hash)?;
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+  let file_name = asset_path.as_deref()
+    .and_then(|p| p.rsplit('/').next())
+    .filter(|n| n.ends_with(".bin"))
+    .map(str::to_string)
+    .unwrap_or_else(|| format!("{:016x}.bin", hash));
+  let temp_path = dir.join(file_name);
+  write_retrying(&temp_path, &data).map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+  let source = BinSource {
+    wad_path: wad_path.to_string_lossy().into_owned(),
+    chunk_hash: format!("{:016x}", hash),
+    asset_path: asset_path.clone(),
+  };
+  let json = serde_json::to_string_pretty(&source).map_err(|e| format!("Failed to serialize bin source: {}", e))?;
+  write_retrying(&dir.join(SOURCE_JSON), json.as_bytes())
+    .map_err(|e| format!("Failed to write {}: {}", dir.join(SOURCE_JSON).display(), e))?;
+
+  Ok(OpenWadBinResult {
+    success: true,
+    error: None,
+    text: Some(text),
+    temp_path: Some(temp_path.to_string_lossy().into_owned()),
+    asset_path,
+  })
+}
+
+/// Extract a bin chunk (hex hash or asset path) from `wadPath` to a managed
+/// temp location and return its ritobin text.
+#[napi(js_name = "openBinFromWad")]
+pub fn open_bin_from_wad(wad_path: String, chunk_hash: String, hash_dir: Option<String>) -> OpenWadBinResult {
+  open(Path::new(&wad_path), &chunk_hash, hash_dir.as_deref()).unwrap_or_else(|e| OpenWadBinResult {
+    success: false,
+    error: Some(e),
+    text: None,
+    temp_path: None,
+    asset_path: None,
+  })
+}
+
+fn read_source(temp_path: &Path) -> Result<BinSource, String> {
+  let managed = temp_path.parent().filter(|d| is_managed(d));
+  let sidecar = managed.map(|d| d.join(SOURCE_JSON))
+    .ok_or_else(|| format!("{} was not opened from a WAD", temp_path.display()))?;
+  let text = fs::read_to_string(&sidecar).map_err(|e| format!("Failed to read {}: {}", sidecar.display(), e))?;
+  serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", sidecar.display(), e))
+}
+
+fn save(temp_path: &Path, text: &str, write_back: bool) -> Result<SaveWadBinResult, String> {
+  let source = read_source(temp_path)?;
+  let data = ritobin::bin_to_bytes(&ritobin::text_to_bin(text)?)?;
+  write_retrying(temp_path, &data).map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+  if write_back {
+    let hash = parse_hash_hex(&source.chunk_hash)
+      .ok_or_else(|| format!("Invalid chunk hash {} in bin source", source.chunk_hash))?;
+    save_chunk(Path::new(&source.wad_path), hash, &data, false)?;
+  }
+  Ok(SaveWadBinResult { success: true, error: None, wad_path: Some(source.wad_path), written_back: write_back })
+}
+
+/// Save edited text of a bin opened with `openBinFromWad`. With `writeBack`
+/// the bin is also patched into its source WAD.
+#[napi(js_name = "saveWadBin")]
+pub fn save_wad_bin(temp_path: String, text: String, write_back: Option<bool>) -> SaveWadBinResult {
+  save(Path::new(&temp_path), &text, write_back.unwrap_or(false)).unwrap_or_else(|e| SaveWadBinResult {
+    success: false,
+    error: Some(e),
+    wad_path: None,
+    written_back: false,
+  })
+}
+
+/// Remove the temp copy of a bin opened with `openBinFromWad`.
+#[napi(js_name = "closeWadBin")]
+pub fn close_wad_bin(temp_path: String) -> SaveWadBinResult {
+  let path = Path::new(&temp_path);
+  match read_source(path) {
+    Ok(source) => {
+      let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+      match fs::remove_dir_all(&dir) {
+        Ok(()) => SaveWadBinResult { success: true, error: None, wad_path: Some(source.wad_path), written_back: false },
+        Err(e) => SaveWadBinResult { success: false, error: Some(format!("Failed to remove {}: {}", dir.display(), e)), wad_path: None, written_back: false },
+      }
+    }
+    Err(e) => SaveWadBinResult { success: false, error: Some(e), wad_path: None, written_back: false },
+  }
+}
@@ -0,0 +1,286 @@
+// ── WAD building ─────────────────────────────────────────────────────────────
+// Packs a folder of loose assets into a .wad.client. Paths are hashed the same
+// way the game does (xxh64 of the lowercased relative path); hex-named files at
+// the folder root (unresolved chunks from an earlier extraction) keep their hash.
+// Files with identical content are stored once and share a data region, as in
+// the game's own WADs, so chroma mods that copy textures per skin stay small.
+//
+// Output is deterministic: chunks are ordered by path hash, hash collisions are
+// settled by relative path, header signature and padding are zeroed, and every
+// writer compresses through `compress_chunk` with fixed zstd parameters. The
+// same folder therefore always packs to the same bytes, full or delta build.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Cursor, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use ltk_file::LeagueFileKind;
+use ltk_wad::{FileExt, Wad, WadBuilder, WadBuilderError, WadChunk, WadChunkBuilder, WadChunkCompression};
+use memmap2::Mmap;
+use napi_derive::napi;
+use xxhash_rust::xxh3::{xxh3_128, xxh3_64};
+
+use crate::wad_delta::build_wad_delta;
+use crate::xxhash_path;
+
+/// Sidecar written by extraction that maps hashed file names back to original paths.
+pub(crate) const HASHED_FILES_JSON: &str = "hashed_files.json";
+/// Zstd level of ltk_wad's builder.
+pub(crate) const ZSTD_LEVEL: i32 = 3;
+const V3_TOC_ENTRY_SIZE: u64 = 32;
+
+fn parse_hex_name_from_root(rel: &str) -> Option<u64> {
+  if rel.contains('/') { return None; }
+  let stem = rel.split('.').next().unwrap_or(rel);
+  if stem.len() != 16 || !stem.bytes().all(|b| b.is_ascii_hexdigit()) { return None; }
+  u64::from_str_radix(stem, 16).ok()
+}
+
+/// Path hash for a file relative to a WAD folder.
+pub(crate) fn chunk_hash_for_rel_path(rel: &str) -> u64 {
+  parse_hex_name_from_root(rel).unwrap_or_else(|| xxhash_path(&rel.to_ascii_lowercase()))
+}
+
+pub(crate) use quartz_core::paths::collect_files;
+
+/// Compress chunk data the way ltk_wad's builder does: the game's codec for the
+/// file type, zstd as a single stream at `ZSTD_LEVEL`. WAD writers that bypass
+/// the builder use this so their chunks come out byte-identical to its.
+pub(crate) fn compress_chunk(data: &[u8]) -> Result<(Vec<u8>, WadChunkCompression), String> {
+  match LeagueFileKind::identify_from_bytes(data).ideal_compression() {
+    WadChunkCompression::Zstd => {
+      let mut encoder = zstd::Encoder::new(Vec::new(), ZSTD_LEVEL)
+        .map_err(|e| format!("Failed to compress chunk: {}", e))?;
+      encoder.write_all(data).map_err(|e| format!("Failed to compress chunk: {}", e))?;
+      let compressed = encoder.finish().map_err(|e| format!("Failed to compress chunk: {}", e))?;
+      Ok((compressed, WadChunkCompression::Zstd))
+    }
+    _ => Ok((data.to_vec(), WadChunkCompression::None)),
+  }
+}
+
+/// Renamed output files (hashed, flattened or sanitized names) -> original asset path.
+pub(crate) fn read_hashed_files(dir: &Path) -> HashMap<String, String> {
+  fs::read_to_string(dir.join(HASHED_FILES_JSON))
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default()
+}
+
+/// Map of path hash -> source file for a WAD folder. On hash collisions the
+/// first file by relative path wins; the returned count is how many were dropped.
+pub(crate) fn plan_wad_dir(dir: &Path) -> Result<(HashMap<u64, PathBuf>, usize), String> {
+  let renamed = read_hashed_files(dir);
+  let mut index: HashMap<u64, PathBuf> = HashMap::new();
+  let mut duplicates = 0usize;
+  for (rel, path) in collect_files(dir)? {
+    if rel.eq_ignore_ascii_case(HASHED_FILES_JSON) { continue; }
+    // Files renamed on extraction hash as their original path.
+    let hash = match renamed.get(&rel) {
+      Some(original) => chunk_hash_for_rel_path(original),
+      None => chunk_hash_for_rel_path(&rel),
+    };
+    if index.contains_key(&hash) { duplicates += 1; continue; }
+    index.insert(hash, path);
+  }
+  Ok((index, duplicates))
+}
+
+/// Build a WAD from a hash -> source file map into any seekable writer.
+pub(crate) fn build_wad_to_writer<W: Write + Seek>(
+  index: &HashMap<u64, PathBuf>,
+  writer: &mut W,
+) -> Result<(), String> {
+  let mut builder = WadBuilder::default();
+  for hash in index.keys() {
+    builder = builder.with_chunk(WadChunkBuilder::default().with_path_hash(*hash));
+  }
+  builder
+    .build_to_writer(writer, |path_hash, cursor: &mut Cursor<Vec<u8>>| {
+      let src = index.get(&path_hash).ok_or_else(|| WadBuilderError::IoError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("Missing source for chunk {:016x}", path_hash),
+      )))?;
+      let data = fs::read(src)?;
+      cursor.write_all(&data)?;
+      Ok(())
+    })
+    .map_err(|e| format!("Failed to build WAD: {}", e))
+}
+
+/// Rebuild `base_wad` with chunks replaced (or added) from `overrides`.
+/// Untouched base chunks are copied as stored, keeping their compression and
+/// subchunk fields, so only the override files are compressed.
+/// Returns (overridden, added) chunk counts.
+pub(crate) fn build_merged_wad_to_writer<W: Write + Seek>(
+  base_wad: &Path,
+  overrides: &HashMap<u64, PathBuf>,
+  writer: &mut W,
+) -> Result<(u32, u32), String> {
+  let file = fs::File::open(base_wad)
+    .map_err(|e| format!("Failed to open {}: {}", base_wad.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }
+    .map_err(|e| format!("Failed to mmap {}: {}", base_wad.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..]))
+    .map_err(|e| format!("Failed to mount {}: {}", base_wad.display(), e))?;
+  let base_chunks = wad.chunks().clone();
+  let overridden = base_chunks.iter().filter(|c| overrides.contains_key(&c.path_hash())).count() as u32;
+  let added = overrides.keys().filter(|h| base_chunks.get(**h).is_none()).count() as u32;
+
+  let mut hashes: Vec<u64> = base_chunks.iter().map(|c| c.path_hash()).collect();
+  hashes.extend(overrides.keys().filter(|h| base_chunks.get(**h).is_none()));
+  hashes.sort_unstable();
+
+  // Same layout as ltk_wad's builder: v3.4 header, TOC sorted by path hash, then data.
+  let io_err = |e: std::io::Error| format!("Failed to write WAD: {}", e);
+  writer.write_all(&[b'R', b'W', 3, 4]).map_err(io_err)?;
+  writer.write_all(&[0; 256 + 8]).map_err(io_err)?;
+  writer.write_all(&(hashes.len() as u32).to_le_bytes()).map_err(io_err)?;
+  let toc_start = writer.stream_position().map_err(io_err)?;
+  writer.seek(SeekFrom::Start(toc_start + V3_TOC_ENTRY_SIZE * hashes.len() as u64)).map_err(io_err)?;
+
+  let mut toc: Vec<WadChunk> = Vec::with_capacity(hashes.len());
+  // Base chunks that shared a data region keep sharing it; identical override
+  // files are stored once, keyed like the builder does it.
+  let mut copied: HashMap<(usize, usize), usize> = HashMap::new();
+  let mut written: HashMap<(u128, usize), usize> = HashMap::new();
+  for hash in hashes {
+    let offset = writer.stream_position().map_err(io_err)? as usize;
+    if let Some(src) = overrides.get(&hash) {
+      let data = fs::read(src).map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+      let key = (xxh3_128(&data), data.len());
+      if let Some(&owner) = written.get(&key) {
+        toc.push(WadChunk { path_hash: hash, ..toc[owner] });
+        continue;
+      }
+      written.insert(key, toc.len());
+      let (stored, compression_type) = compress_chunk(&data)?;
+      writer.write_all(&stored).map_err(io_err)?;
+      toc.push(WadChunk {
+        path_hash: hash,
+        data_offset: offset,
+        compressed_size: stored.len(),
+        uncompressed_size: data.len(),
+        compression_type,
+        is_duplicated: false,
+        frame_count: 0,
+        start_frame: 0,
+        checksum: xxh3_64(&stored),
+      });
+      continue;
+    }
+    let Some(chunk) = base_chunks.get(hash).copied() else { continue };
+    let region = (chunk.data_offset(), chunk.compressed_size());
+    if let Some(&owner) = copied.get(&region) {
+      toc.push(WadChunk { path_hash: hash, ..toc[owner] });
+      continue;
+    }
+    let stored = mmap.get(region.0..region.0 + region.1)
+      .ok_or_else(|| format!("Chunk {:016x} lies outside {}", hash, base_wad.display()))?;
+    writer.write_all(stored).map_err(io_err)?;
+    copied.insert(region, toc.len());
+    // Older minors checksum differently; a v3.4 TOC expects xxh3 of the stored bytes.
+    toc.push(WadChunk { data_offset: offset, is_duplicated: false, checksum: xxh3_64(stored), ..chunk });
+  }
+
+  writer.seek(SeekFrom::Start(toc_start)).map_err(io_err)?;
+  for chunk in &toc {
+    chunk.write_v3_4(writer).map_err(|e| format!("Failed to write WAD: {}", e))?;
+  }
+  writer.seek(SeekFrom::End(0)).map_err(io_err)?;
+  writer.flush().map_err(io_err)?;
+  Ok((overridden, added))
+}
+
+/// Pack a folder into WAD bytes. Returns (bytes, chunk count).
+pub(crate) fn build_wad_bytes(dir: &Path) -> Result<(Vec<u8>, u32), String> {
+  let (index, _) = plan_wad_dir(dir)?;
+  if index.is_empty() {
+    return Err(format!("No files found in {}", dir.display()));
+  }
+  let mut cursor = Cursor::new(Vec::new());
+  build_wad_to_writer(&index, &mut cursor)?;
+  Ok((cursor.into_inner(), index.len() as u32))
+}
+
+/// "Ahri.wad.client" / "Ahri.wad" / "Ahri" -> "Ahri.wad.client"
+pub(crate) fn wad_file_name_for_dir(dir_name: &str) -> String {
+  let lower = dir_name.to_ascii_lowercase();
+  if lower.ends_with(".wad.client") {
+    dir_name.to_string()
+  } else if lower.ends_with(".wad") {
+    format!("{}.client", dir_name)
+  } else {
+    format!("{}.wad.client", dir_name)
+  }
+}
+
+/// Folder holding a project's WAD directories (`{project}/content`, or the project itself).
+pub(crate) fn project_content_dir(project_path: &Path) -> PathBuf {
+  let content = project_path.join("content");
+  if content.is_dir() { content } else { project_path.to_path_buf() }
+}
+
+/// WAD folders of a project: children of the content dir named `*.wad.client` / `*.wad`.
+pub(crate) fn project_wad_dirs(project_path: &Path) -> Vec<PathBuf> {
+  let content = project_content_dir(project_path);
+  let mut dirs: Vec<PathBuf> = fs::read_dir(&content)
+    .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| {
+      let name = p.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+      p.is_dir() && (name.ends_with(".wad.client") || name.ends_with(".wad"))
+    }).collect())
+    .unwrap_or_default();
+  dirs.sort();
+  dirs
+}
+
+#[napi(object)]
+pub struct PackWadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  #[napi(js_name = "duplicateCount")]
+  pub duplicate_count: u32,
+  /// Chunks copied unchanged from the previous build (delta builds only).
+  #[napi(js_name = "reusedCount")]
+  pub reused_count: u32,
+}
+
+/// Pack a folder of loose assets into a .wad.client file. With `delta`, a
+/// rebuild of the same output only recompresses files that changed.
+#[napi(js_name = "packWadDir")]
+pub fn pack_wad_dir(input_dir: String, output_wad: String, delta: Option<bool>) -> PackWadResult {
+  let fail = |e: String| PackWadResult { success: false, error: Some(e), chunk_count: 0, duplicate_count: 0, reused_count: 0 };
+  let dir = Path::new(&input_dir);
+  if !dir.is_dir() { return fail(format!("Input is not a folder: {}", input_dir)); }
+  let (index, duplicates) = match plan_wad_dir(dir) {
+    Ok(v) => v,
+    Err(e) => return fail(e),
+  };
+  if index.is_empty() { return fail(format!("No files found in {}", input_dir)); }
+  if let Some(parent) = Path::new(&output_wad).parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  if delta.unwrap_or(false) {
+    return match build_wad_delta(&index, Path::new(&output_wad)) {
+      Ok((reused, _)) => PackWadResult {
+        success: true,
+        error: None,
+        chunk_count: index.len() as u32,
+        duplicate_count: duplicates as u32,
+        reused_count: reused,
+      },
+      Err(e) => fail(e),
+    };
+  }
+  let mut file = match fs::File::create(&output_wad) {
+    Ok(f) => f,
+    Err(e) => return fail(format!("Failed to create {}: {}", output_wad, e)),
+  };
+  if let Err(e) = build_wad_to_writer(&index, &mut file) {
+    return fail(e);
+  }
+  PackWadResult { success: true, error: None, chunk_count: index.len() as u32, duplicate_count: duplicates as u32, reused_count: 0 }
+}
@@ -0,0 +1,305 @@
+// ── WAD compression ──────────────────────────────────────────────────────────
+// Community tools often write WADs with every chunk stored raw, with legacy
+// gzip, or with audio compressed (which the game then has to inflate before
+// streaming). `analyzeWadCompression` reports how each file type is stored
+// and which chunks are off the game's own choice; `repackWad` rewrites a WAD
+// under a compression policy.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use ltk_file::LeagueFileKind;
+use ltk_wad::{FileExt, Wad, WadBuilder, WadBuilderError, WadChunkBuilder, WadChunkCompression};
+use memmap2::Mmap;
+use napi::bindgen_prelude::AsyncTask;
+use napi::{Env, Task};
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::paths::rename_retrying;
+use crate::threads::run_cpu;
+use crate::unique_chunks;
+use crate::wad_build::ZSTD_LEVEL;
+
+/// Raw chunks smaller than this aren't worth compressing.
+const MIN_COMPRESSIBLE: usize = 512;
+
+fn compression_name(c: WadChunkCompression) -> &'static str {
+  match c {
+    WadChunkCompression::None => "none",
+    WadChunkCompression::GZip => "gzip",
+    WadChunkCompression::Satellite => "satellite",
+    WadChunkCompression::Zstd => "zstd",
+    WadChunkCompression::ZstdMulti => "zstdMulti",
+  }
+}
+
+fn map_file(wad_path: &Path) -> Result<Mmap, String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))
+}
+
+// ── analyzeWadCompression ────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct ExtensionCompression {
+  /// File type by content, e.g. "dds", "bin"; "unknown" when unrecognized.
+  pub extension: String,
+  pub count: u32,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+  /// compressedSize / size.
+  pub ratio: f64,
+}
+
+#[napi(object)]
+pub struct SuboptimalChunk {
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  pub extension: String,
+  /// How the chunk is stored now.
+  pub compression: String,
+  /// "uncompressed" (raw but compressible), "gzip" (legacy codec),
+  /// "compressedAudio" (Wwise data the game expects raw) or "noGain"
+  /// (compressed no smaller than raw).
+  pub issue: String,
+  #[napi(js_name = "storedSize")]
+  pub stored_size: f64,
+  /// Stored size under the game's choice of compression.
+  #[napi(js_name = "idealSize")]
+  pub ideal_size: f64,
+}
+
+#[napi(object)]
+pub struct WadCompressionReport {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+  pub extensions: Vec<ExtensionCompression>,
+  pub suboptimal: Vec<SuboptimalChunk>,
+  /// Bytes saved by repacking with the "ideal" policy (negative if it grows).
+  #[napi(js_name = "potentialSavings")]
+  pub potential_savings: f64,
+}
+
+struct ChunkReport {
+  extension: &'static str,
+  size: u64,
+  compressed_size: u64,
+  suboptimal: Option<SuboptimalChunk>,
+}
+
+fn analyze(wad_path: &Path) -> Result<WadCompressionReport, String> {
+  let mmap = map_file(wad_path)?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let wad_data = &mmap[..];
+
+  let reports: Vec<ChunkReport> = run_cpu(|| chunks
+    .par_iter()
+    .filter_map(|chunk| {
+      let data = decompress_chunk(wad_data, chunk).ok()?;
+      let kind = LeagueFileKind::identify_from_bytes(&data);
+      let stored = chunk.compression_type();
+      let (size, compressed_size) = (chunk.uncompressed_size() as u64, chunk.compressed_size() as u64);
+      let ideal = kind.ideal_compression();
+      let zstd_size = || zstd::bulk::compress(&data, ZSTD_LEVEL).map(|c| c.len() as u64).unwrap_or(size);
+      let issue = match (stored, ideal) {
+        (WadChunkCompression::None, WadChunkCompression::Zstd) if data.len() >= MIN_COMPRESSIBLE => {
+          let z = zstd_size();
+          (z < size).then_some(("uncompressed", z))
+        }
+        (WadChunkCompression::GZip, WadChunkCompression::Zstd) => Some(("gzip", zstd_size())),
+        (s, WadChunkCompression::None) if s != WadChunkCompression::None => Some(("compressedAudio", size)),
+        (s, _) if s != WadChunkCompression::None && compressed_size >= size => Some(("noGain", size)),
+        _ => None,
+      };
+      let extension = kind.extension().unwrap_or("unknown");
+      Some(ChunkReport {
+        extension,
+        size,
+        compressed_size,
+        suboptimal: issue.map(|(issue, ideal_size)| SuboptimalChunk {
+          path_hash: format!("{:016x}", chunk.path_hash()),
+          extension: extension.to_string(),
+          compression: compression_name(stored).to_string(),
+          issue: issue.to_string(),
+          stored_size: compressed_size as f64,
+          ideal_size: ideal_size as f64,
+        }),
+      })
+    })
+    .collect());
+
+  let mut by_ext: BTreeMap<&str, (u32, u64, u64)> = BTreeMap::new();
+  let (mut size, mut compressed_size) = (0u64, 0u64);
+  let mut suboptimal = Vec::new();
+  for r in reports {
+    let e = by_ext.entry(r.extension).or_default();
+    e.0 += 1;
+    e.1 += r.size;
+    e.2 += r.compressed_size;
+    size += r.size;
+    compressed_size += r.compressed_size;
+    suboptimal.extend(r.suboptimal);
+  }
+  suboptimal.sort_by(|a, b| (b.stored_size - b.ideal_size).total_cmp(&(a.stored_size - a.ideal_size)));
+  let potential_savings = suboptimal.iter().map(|s| s.stored_size - s.ideal_size).sum();
+  Ok(WadCompressionReport {
+    success: true,
+    error: None,
+    chunk_count: chunks.len() as u32,
+    size: size as f64,
+    compressed_size: compressed_size as f64,
+    extensions: by_ext
+      .into_iter()
+      .map(|(ext, (count, size, compressed))| ExtensionCompression {
+        extension: ext.to_string(),
+        count,
+        size: size as f64,
+        compressed_size: compressed as f64,
+        ratio: if size == 0 { 1.0 } else { compressed as f64 / size as f64 },
+      })
+      .collect(),
+    suboptimal,
+    potential_savings,
+  })
+}
+
+/// Per-file-type compression stats of a WAD and the chunks stored differently
+/// from how the game itself would store them.
+#[napi(js_name = "analyzeWadCompression")]
+pub fn analyze_wad_compression(wad_path: String) -> WadCompressionReport {
+  analyze(Path::new(&wad_path)).unwrap_or_else(|e| WadCompressionReport {
+    success: false,
+    error: Some(e),
+    chunk_count: 0,
+    size: 0.0,
+    compressed_size: 0.0,
+    extensions: Vec::new(),
+    suboptimal: Vec::new(),
+    potential_savings: 0.0,
+  })
+}
+
+// ── repackWad ────────────────────────────────────────────────────────────────
+
+#[napi(object)]
+pub struct RepackWadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  #[napi(js_name = "inputSize")]
+  pub input_size: f64,
+  #[napi(js_name = "outputSize")]
+  pub output_size: f64,
+}
+
+/// Compression forced on every chunk by a policy; `None` lets the builder pick
+/// the game's choice per file type.
+fn policy_compression(policy: &str) -> Result<Option<WadChunkCompression>, String> {
+  match policy {
+    "" | "ideal" => Ok(None),
+    "zstd" => Ok(Some(WadChunkCompression::Zstd)),
+    "none" => Ok(Some(WadChunkCompression::None)),
+    other => Err(format!("Unknown compression policy: {} (expected ideal, zstd or none)", other)),
+  }
+}
+
+fn repack(input: &Path, output: &Path, policy: &str) -> Result<RepackWadResult, String> {
+  let force = policy_compression(policy)?;
+  let in_place = input == output;
+  let target: PathBuf = if in_place { output.with_extension("client.tmp") } else { output.to_path_buf() };
+  let chunk_count = {
+    let mmap = map_file(input)?;
+    let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", input.display(), e))?;
+    let (chunks, _) = unique_chunks(wad.chunks());
+    let mut builder = WadBuilder::default();
+    for c in &chunks {
+      let mut chunk = WadChunkBuilder::default().with_path_hash(c.path_hash());
+      if let Some(compression) = force { chunk = chunk.with_force_compression(compression); }
+      builder = builder.with_chunk(chunk);
+    }
+    let by_hash: HashMap<u64, _> = chunks.iter().map(|c| (c.path_hash(), *c)).collect();
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let wad_data = &mmap[..];
+    let mut out = fs::File::create(&target).map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    let built = builder
+      .build_to_writer(&mut out, |path_hash, cursor: &mut Cursor<Vec<u8>>| {
+        let chunk = by_hash.get(&path_hash).ok_or_else(|| WadBuilderError::IoError(std::io::Error::new(
+          std::io::ErrorKind::NotFound,
+          format!("Missing source for chunk {:016x}", path_hash),
+        )))?;
+        let data = decompress_chunk(wad_data, chunk)
+          .map_err(|e| WadBuilderError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+        cursor.write_all(&data)?;
+        Ok(())
+      })
+      .map_err(|e| format!("Failed to build WAD: {}", e))
+      .and_then(|_| out.flush().map_err(|e| format!("Failed to write {}: {}", target.display(), e)));
+    if let Err(e) = built {
+      let _ = fs::remove_file(&target);
+      return Err(e);
+    }
+    chunks.len() as u32
+  };
+  // The source mmap is dropped by now, so the original can be replaced (Windows).
+  if in_place {
+    rename_retrying(&target, output).map_err(|e| format!("Failed to replace {}: {}", output.display(), e))?;
+  }
+  let output_size = fs::metadata(output).map(|m| m.len() as f64).unwrap_or(0.0);
+  Ok(RepackWadResult { success: true, error: None, chunk_count, input_size: 0.0, output_size })
+}
+
+fn repack_result(input: &str, output: &str, policy: &str) -> RepackWadResult {
+  // Measured up front: an in-place repack replaces the input.
+  let input_size = fs::metadata(input).map(|m| m.len() as f64).unwrap_or(0.0);
+  match repack(Path::new(input), Path::new(output), policy) {
+    Ok(r) => RepackWadResult { input_size, ..r },
+    Err(e) => RepackWadResult { success: false, error: Some(e), chunk_count: 0, input_size, output_size: 0.0 },
+  }
+}
+
+/// Rewrite a WAD with every chunk recompressed under `policy`: "ideal" (the
+/// game's choice per file type: zstd, Wwise audio raw), "zstd" or "none".
+/// `output` may equal `input` to repack in place.
+#[napi(js_name = "repackWad")]
+pub fn repack_wad(input: String, output: String, policy: Option<String>) -> RepackWadResult {
+  repack_result(&input, &output, policy.as_deref().unwrap_or("ideal"))
+}
+
+pub struct RepackWadTask {
+  input: String,
+  output: String,
+  policy: Option<String>,
+}
+
+#[napi]
+impl Task for RepackWadTask {
+  type Output = RepackWadResult;
+  type JsValue = RepackWadResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(repack_result(&self.input, &self.output, self.policy.as_deref().unwrap_or("ideal")))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+#[napi(js_name = "repackWadAsync")]
+pub fn repack_wad_async(input: String, output: String, policy: Option<String>) -> AsyncTask<RepackWadTask> {
+  AsyncTask::new(RepackWadTask { input, output, policy })
+}
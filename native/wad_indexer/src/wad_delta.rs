@@ -0,0 +1,182 @@
+// ── Delta WAD builds ─────────────────────────────────────────────────────────
+// Rebuilding a project WAD after a one-file edit shouldn't recompress every
+// chunk. A delta build remembers, per output WAD, which path hash each source
+// content hash (xxh3 of the uncompressed file) was stored under. On the next
+// build, chunks whose content is unchanged are copied from the previous WAD
+// as-is, compressed bytes and checksum included; only new or edited files are
+// compressed again. The cache lives in the temp dir: if it's gone, or the
+// previous WAD changed since it was written, the build is simply a full one.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufWriter, Cursor, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use ltk_wad::{Wad, WadChunk, WadChunkCompression};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::{xxh3_128, xxh3_64};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::paths::rename_retrying;
+use crate::wad_build::compress_chunk;
+use crate::{get_file_mtime_ms, unique_chunks};
+
+/// Bumped when chunk compression changes, so stored bytes from older builds
+/// aren't reused into a WAD a full build would write differently.
+const CACHE_FORMAT: u32 = 1;
+/// Header (magic, version, signature, checksum) plus the chunk count.
+const HEADER_SIZE: u64 = 4 + 256 + 8 + 4;
+const TOC_ENTRY_SIZE: u64 = 32;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildCache {
+  #[serde(default)]
+  format: u32,
+  wad_size: u64,
+  wad_modified: u128,
+  /// Content hash (hex) -> path hash (hex) of the chunk holding it.
+  chunks: HashMap<String, String>,
+}
+
+fn cache_path(output: &Path) -> PathBuf {
+  let key = output.to_string_lossy().replace('\\', "/");
+  let key = if cfg!(windows) { key.to_lowercase() } else { key };
+  std::env::temp_dir().join("quartz").join("wad-build").join(format!("{:016x}.json", xxh64(key.as_bytes(), 0)))
+}
+
+/// The cache of `output`, if it still describes the WAD on disk.
+fn read_cache(output: &Path) -> Option<BuildCache> {
+  let cache: BuildCache = serde_json::from_str(&fs::read_to_string(cache_path(output)).ok()?).ok()?;
+  let size = fs::metadata(output).ok()?.len();
+  (cache.format == CACHE_FORMAT && cache.wad_size == size && cache.wad_modified == get_file_mtime_ms(output)).then_some(cache)
+}
+
+fn write_cache(output: &Path, chunks: HashMap<String, String>) {
+  let Ok(meta) = fs::metadata(output) else { return };
+  let cache = BuildCache { format: CACHE_FORMAT, wad_size: meta.len(), wad_modified: get_file_mtime_ms(output), chunks };
+  let path = cache_path(output);
+  if let (Some(parent), Ok(json)) = (path.parent(), serde_json::to_string(&cache)) {
+    let _ = fs::create_dir_all(parent);
+    let _ = fs::write(&path, json);
+  }
+}
+
+/// Previous build of `output` with its chunks by path hash.
+struct Previous {
+  mmap: Mmap,
+  chunks: HashMap<u64, WadChunk>,
+  cache: BuildCache,
+}
+
+impl Previous {
+  fn open(output: &Path) -> Option<Self> {
+    let cache = read_cache(output)?;
+    let file = fs::File::open(output).ok()?;
+    let mmap = unsafe { Mmap::map(&file) }.ok()?;
+    let wad = Wad::mount(Cursor::new(&mmap[..])).ok()?;
+    let (chunks, _) = unique_chunks(wad.chunks());
+    let chunks = chunks.into_iter().map(|c| (c.path_hash(), c)).collect();
+    Some(Previous { mmap, chunks, cache })
+  }
+
+  /// Stored bytes of the chunk that held `content_hash`, when they can be
+  /// copied verbatim (multi-frame chunks reference a subchunk table and aren't).
+  fn stored(&self, content_hash: &str) -> Option<(&WadChunk, &[u8])> {
+    let path_hash = u64::from_str_radix(self.cache.chunks.get(content_hash)?, 16).ok()?;
+    let chunk = self.chunks.get(&path_hash)?;
+    if chunk.frame_count != 0 || chunk.compression_type() == WadChunkCompression::ZstdMulti { return None; }
+    let data = self.mmap.get(chunk.data_offset()..chunk.data_offset() + chunk.compressed_size())?;
+    (xxh3_64(data) == chunk.checksum()).then_some((chunk, data))
+  }
+}
+
+/// Build a WAD from a hash -> source file map into `output`, reusing the
+/// compressed data of unchanged files from the previous build there.
+/// Returns (reused, compressed) chunk counts.
+pub(crate) fn build_wad_delta(index: &HashMap<u64, PathBuf>, output: &Path) -> Result<(u32, u32), String> {
+  let previous = Previous::open(output);
+  let tmp = output.with_extension("client.tmp");
+  let written = write_delta(index, previous.as_ref(), &tmp);
+  // The previous WAD's mmap has to be gone before it is replaced (Windows).
+  drop(previous);
+  let (reused, compressed, chunks) = match written {
+    Ok(v) => v,
+    Err(e) => {
+      let _ = fs::remove_file(&tmp);
+      return Err(e);
+    }
+  };
+  rename_retrying(&tmp, output).map_err(|e| format!("Failed to replace {}: {}", output.display(), e))?;
+  write_cache(output, chunks);
+  Ok((reused, compressed))
+}
+
+fn write_delta(
+  index: &HashMap<u64, PathBuf>,
+  previous: Option<&Previous>,
+  tmp: &Path,
+) -> Result<(u32, u32, HashMap<String, String>), String> {
+  let file = fs::File::create(tmp).map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+  let mut writer = BufWriter::new(file);
+  let io_err = |e: std::io::Error| format!("Failed to write {}: {}", tmp.display(), e);
+
+  // Same layout as ltk_wad's builder: v3.4 header, TOC sorted by path hash, then data.
+  writer.write_all(&[b'R', b'W', 3, 4]).map_err(io_err)?;
+  writer.write_all(&[0; 256 + 8]).map_err(io_err)?;
+  writer.write_all(&(index.len() as u32).to_le_bytes()).map_err(io_err)?;
+  writer.seek(SeekFrom::Start(HEADER_SIZE + TOC_ENTRY_SIZE * index.len() as u64)).map_err(io_err)?;
+
+  let mut hashes: Vec<u64> = index.keys().copied().collect();
+  hashes.sort_unstable();
+  let mut toc = Vec::with_capacity(hashes.len());
+  let mut cache = HashMap::with_capacity(hashes.len());
+  // Identical files share one data region, keyed like the builder does it.
+  let mut written: HashMap<(u128, usize), usize> = HashMap::new();
+  let (mut reused, mut compressed) = (0u32, 0u32);
+  for hash in hashes {
+    let src = &index[&hash];
+    let data = fs::read(src).map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+    let key = (xxh3_128(&data), data.len());
+    if let Some(&owner) = written.get(&key) {
+      toc.push(WadChunk { path_hash: hash, ..toc[owner] });
+      continue;
+    }
+    written.insert(key, toc.len());
+    let content_hash = format!("{:016x}", xxh3_64(&data));
+    let offset = writer.stream_position().map_err(io_err)? as usize;
+    let chunk = match previous.and_then(|p| p.stored(&content_hash)) {
+      Some((old, stored)) => {
+        writer.write_all(stored).map_err(io_err)?;
+        reused += 1;
+        WadChunk { path_hash: hash, data_offset: offset, ..*old }
+      }
+      None => {
+        let (stored, compression_type) = compress_chunk(&data)?;
+        writer.write_all(&stored).map_err(io_err)?;
+        compressed += 1;
+        WadChunk {
+          path_hash: hash,
+          data_offset: offset,
+          compressed_size: stored.len(),
+          uncompressed_size: data.len(),
+          compression_type,
+          is_duplicated: false,
+          frame_count: 0,
+          start_frame: 0,
+          checksum: xxh3_64(&stored),
+        }
+      }
+    };
+    cache.insert(content_hash, format!("{:016x}", hash));
+    toc.push(chunk);
+  }
+
+  writer.seek(SeekFrom::Start(HEADER_SIZE)).map_err(io_err)?;
+  for chunk in &toc {
+    chunk.write_v3_4(&mut writer).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  }
+  writer.flush().map_err(io_err)?;
+  Ok((reused, compressed, cache))
+}
@@ -0,0 +1,240 @@
+// ── WAD merging ──────────────────────────────────────────────────────────────
+// Combines several mod WADs for the same game WAD (e.g. two Ahri skins that
+// touch different files) into one, so they install as a single mod. Inputs are
+// given in priority order. A chunk only one input has is taken as is; where
+// inputs disagree, the "priority" strategy takes the earliest input's copy, as
+// the mod loader would, while "select" leaves the choice to the caller: the
+// first call lists the conflicts without writing, and a second call with a
+// choice for each of them writes the merge. Identical copies never conflict.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use ltk_wad::{Wad, WadBuilder, WadBuilderError, WadChunk, WadChunkBuilder};
+use memmap2::Mmap;
+use napi::{Env, Task, bindgen_prelude::AsyncTask};
+use napi_derive::napi;
+
+use crate::chunk_decode::{decompress_chunk, raw_chunk_slice};
+use crate::paths::rename_retrying;
+use crate::{parse_hash_hex, unique_chunks, HashLayers};
+
+#[napi(object)]
+pub struct MergeChunkChoice {
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// The input whose copy to use.
+  pub input: String,
+}
+
+#[napi(object)]
+pub struct MergeWadsOptions {
+  /// "priority" (default): earlier inputs win conflicts. "select": conflicts
+  /// are settled by `choices`, and nothing is written while any is open.
+  pub strategy: Option<String>,
+  pub choices: Option<Vec<MergeChunkChoice>>,
+  /// Resolves conflicting chunks to paths in the result.
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+}
+
+#[napi(object)]
+pub struct MergeConflict {
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// Resolved path, or the hex hash.
+  pub path: String,
+  /// Inputs with differing copies of the chunk, in priority order.
+  pub inputs: Vec<String>,
+  /// Input whose copy was used; None while unresolved.
+  pub chosen: Option<String>,
+}
+
+#[napi(object)]
+pub struct MergeWadsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// The output was written; false when "select" left conflicts open.
+  pub merged: bool,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  pub conflicts: Vec<MergeConflict>,
+  #[napi(js_name = "unresolvedCount")]
+  pub unresolved_count: u32,
+}
+
+struct Input {
+  path: PathBuf,
+  mmap: Mmap,
+  chunks: HashMap<u64, WadChunk>,
+}
+
+fn open_input(path: &Path) -> Result<Input, String> {
+  let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let chunks = chunks.into_iter().map(|c| (c.path_hash(), c)).collect();
+  Ok(Input { path: path.to_path_buf(), mmap, chunks })
+}
+
+fn same_data(a: &Input, ca: &WadChunk, b: &Input, cb: &WadChunk) -> bool {
+  if ca.compression_type() == cb.compression_type() && raw_chunk_slice(&a.mmap, ca) == raw_chunk_slice(&b.mmap, cb) {
+    return true;
+  }
+  matches!((decompress_chunk(&a.mmap, ca), decompress_chunk(&b.mmap, cb)), (Ok(x), Ok(y)) if x == y)
+}
+
+/// Inputs whose copies of `hash` differ, in priority order: the first of each
+/// group of identical copies stands for the group.
+fn distinct_owners(inputs: &[Input], owners: &[usize], hash: u64) -> Vec<usize> {
+  let mut distinct: Vec<usize> = Vec::new();
+  for &i in owners {
+    let chunk = &inputs[i].chunks[&hash];
+    if !distinct.iter().any(|&d| same_data(&inputs[d], &inputs[d].chunks[&hash], &inputs[i], chunk)) {
+      distinct.push(i);
+    }
+  }
+  distinct
+}
+
+fn write_merged(inputs: &[Input], sources: &BTreeMap<u64, usize>, output: &Path) -> Result<(), String> {
+  let mut builder = WadBuilder::default();
+  for hash in sources.keys() {
+    builder = builder.with_chunk(WadChunkBuilder::default().with_path_hash(*hash));
+  }
+  let mut out = fs::File::create(output).map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+  builder
+    .build_to_writer(&mut out, |path_hash, cursor: &mut Cursor<Vec<u8>>| {
+      let input = sources.get(&path_hash).map(|&i| &inputs[i]).ok_or_else(|| WadBuilderError::IoError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("Missing source for chunk {:016x}", path_hash),
+      )))?;
+      let data = decompress_chunk(&input.mmap, &input.chunks[&path_hash])
+        .map_err(|e| WadBuilderError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+      cursor.write_all(&data)?;
+      Ok(())
+    })
+    .map_err(|e| format!("Failed to build WAD: {}", e))?;
+  out.flush().map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+}
+
+fn merge(inputs: &[String], output: &Path, options: MergeWadsOptions) -> Result<MergeWadsResult, String> {
+  if inputs.len() < 2 { return Err("At least two WADs are required".to_string()); }
+  let select = match options.strategy.as_deref().unwrap_or("priority") {
+    "priority" => false,
+    "select" => true,
+    other => return Err(format!("Unknown merge strategy: {}", other)),
+  };
+  let opened = inputs.iter().map(|p| open_input(Path::new(p))).collect::<Result<Vec<_>, _>>()?;
+  let input_index = |path: &str| opened.iter().position(|i| i.path == Path::new(path));
+  let mut choices: HashMap<u64, usize> = HashMap::new();
+  for c in options.choices.unwrap_or_default() {
+    let hash = parse_hash_hex(&c.path_hash).ok_or_else(|| format!("Invalid path hash: {}", c.path_hash))?;
+    let index = input_index(&c.input).ok_or_else(|| format!("{} is not one of the inputs", c.input))?;
+    choices.insert(hash, index);
+  }
+
+  // hash -> inputs providing it, in priority order.
+  let mut owners: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+  for (i, input) in opened.iter().enumerate() {
+    for hash in input.chunks.keys() {
+      owners.entry(*hash).or_default().push(i);
+    }
+  }
+
+  let mut sources: BTreeMap<u64, usize> = BTreeMap::new();
+  // (hash, differing inputs, chosen input)
+  let mut conflicts: Vec<(u64, Vec<usize>, Option<usize>)> = Vec::new();
+  for (hash, owned_by) in &owners {
+    let distinct = if owned_by.len() > 1 { distinct_owners(&opened, owned_by, *hash) } else { owned_by.clone() };
+    if distinct.len() == 1 {
+      sources.insert(*hash, distinct[0]);
+      continue;
+    }
+    // A choice naming an input with an identical copy picks that copy's group.
+    let chosen = match choices.get(hash) {
+      Some(&c) => distinct.iter().copied().find(|&d| {
+        d == c || same_data(&opened[d], &opened[d].chunks[hash], &opened[c], &opened[c].chunks[hash])
+      }),
+      None if select => None,
+      None => Some(distinct[0]),
+    };
+    if let Some(c) = chosen { sources.insert(*hash, c); }
+    conflicts.push((*hash, distinct, chosen));
+  }
+
+  let unresolved_count = conflicts.iter().filter(|(_, _, c)| c.is_none()).count() as u32;
+  let merged = unresolved_count == 0;
+  if merged {
+    let tmp = output.with_extension("client.tmp");
+    if let Some(parent) = output.parent() { let _ = fs::create_dir_all(parent); }
+    if let Err(e) = write_merged(&opened, &sources, &tmp) {
+      let _ = fs::remove_file(&tmp);
+      return Err(e);
+    }
+    // The output may be one of the inputs; unmap them before replacing it (Windows).
+    drop(opened);
+    rename_retrying(&tmp, output).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+  }
+
+  let hashes: Vec<u64> = conflicts.iter().map(|(h, _, _)| *h).collect();
+  let names = HashLayers::open(options.hash_dir.as_deref()).resolve_names(&hashes);
+  let conflicts = conflicts
+    .into_iter()
+    .zip(names)
+    .map(|((hash, distinct, chosen), path)| MergeConflict {
+      path_hash: format!("{:016x}", hash),
+      path,
+      inputs: distinct.iter().map(|&i| inputs[i].clone()).collect(),
+      chosen: chosen.map(|i| inputs[i].clone()),
+    })
+    .collect();
+  Ok(MergeWadsResult {
+    success: true,
+    error: None,
+    merged,
+    chunk_count: sources.len() as u32,
+    conflicts,
+    unresolved_count,
+  })
+}
+
+fn failed(e: String) -> MergeWadsResult {
+  MergeWadsResult { success: false, error: Some(e), merged: false, chunk_count: 0, conflicts: Vec::new(), unresolved_count: 0 }
+}
+
+/// Merge mod WADs (highest priority first) into `output`, which may be one of
+/// the inputs. See the module notes for the strategies.
+#[napi(js_name = "mergeWads")]
+pub fn merge_wads(inputs: Vec<String>, output: String, options: Option<MergeWadsOptions>) -> MergeWadsResult {
+  let options = options.unwrap_or(MergeWadsOptions { strategy: None, choices: None, hash_dir: None });
+  merge(&inputs, Path::new(&output), options).unwrap_or_else(failed)
+}
+
+pub struct MergeWadsTask {
+  inputs: Vec<String>,
+  output: String,
+  options: Option<MergeWadsOptions>,
+}
+
+#[napi]
+impl Task for MergeWadsTask {
+  type Output = MergeWadsResult;
+  type JsValue = MergeWadsResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    Ok(merge_wads(std::mem::take(&mut self.inputs), self.output.clone(), self.options.take()))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+#[napi(js_name = "mergeWadsAsync")]
+pub fn merge_wads_async(inputs: Vec<String>, output: String, options: Option<MergeWadsOptions>) -> AsyncTask<MergeWadsTask> {
+  AsyncTask::new(MergeWadsTask { inputs, output, options })
+}
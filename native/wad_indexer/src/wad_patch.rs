@@ -0,0 +1,533 @@
+// ── WAD patching ─────────────────────────────────────────────────────────────
+// Edits a packaged WAD directly instead of extract -> edit -> repack. Renaming
+// reassigns chunks to new path hashes with their data unchanged, so assets can
+// be relocated inside a mod WAD; new paths are recorded in `hashes.custom.txt`
+// so the renamed chunks still resolve to names. Patching replaces (or adds)
+// chunks with the contents of files on disk, leaving the rest untouched.
+//
+// Saving a single chunk skips the rebuild: the new data is written over the old
+// when it fits and no other entry shares that region, otherwise it is appended.
+// Either way only that TOC entry changes, so no chunk is recompressed. The edit
+// is made to a copy of the WAD that then replaces the original, so a crash
+// mid-save never leaves a WAD whose TOC and data disagree.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use ltk_wad::{Wad, WadBuilder, WadBuilderError, WadChunkBuilder, WadChunkCompression};
+use memmap2::Mmap;
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::paths::{rename_retrying, write_retrying};
+use crate::wad_build::compress_chunk;
+use crate::{normalize_rel_path, parse_hash_hex, unique_chunks, xxhash_path};
+
+/// User-maintained path names, loaded alongside the downloaded hash lists.
+pub(crate) const CUSTOM_HASHES_TXT: &str = "hashes.custom.txt";
+/// v3 header: magic, version, signature, checksum and chunk count.
+const V3_HEADER_SIZE: usize = 4 + 256 + 8 + 4;
+const V3_TOC_ENTRY_SIZE: usize = 32;
+/// First v3 minor whose TOC checksums are xxh3 of the compressed data, as
+/// `write_toc_entry` writes them. 3.0 used truncated SHA-256 (see
+/// `chunk_decode::checksum_matches`), so those WADs are saved with a rebuild.
+const XXH3_CHECKSUM_MINOR: u8 = 1;
+
+#[napi(object)]
+pub struct WadChunkRename {
+  /// Current asset path or 16-digit hex path hash.
+  pub from: String,
+  /// New asset path (recorded in hashes.custom.txt) or hex path hash.
+  pub to: String,
+}
+
+#[napi(object)]
+pub struct RenameWadChunksOptions {
+  /// Write here instead of replacing `wadPath`.
+  #[napi(js_name = "outputWad")]
+  pub output_wad: Option<String>,
+  /// Hash dir whose hashes.custom.txt receives the new paths.
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+}
+
+#[napi(object)]
+pub struct RenameWadChunksResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "renamedCount")]
+  pub renamed_count: u32,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  /// Entries added to hashes.custom.txt.
+  #[napi(js_name = "customHashCount")]
+  pub custom_hash_count: u32,
+}
+
+/// (hash, path if the value was a path rather than a hex hash)
+fn parse_target(value: &str) -> (u64, Option<String>) {
+  match parse_hash_hex(value) {
+    Some(h) => (h, None),
+    None => {
+      let path = normalize_rel_path(value).to_ascii_lowercase();
+      (xxhash_path(&path), Some(path))
+    }
+  }
+}
+
+/// Merge `entries` into `{hash_dir}/hashes.custom.txt`, sorted by path like the
+/// other hash lists. Returns how many hashes were new.
+pub(crate) fn merge_custom_hashes(hash_dir: &Path, entries: &[(u64, String)]) -> Result<u32, String> {
+  if entries.is_empty() { return Ok(0); }
+  let path = hash_dir.join(CUSTOM_HASHES_TXT);
+  let mut existing: HashMap<u64, String> = HashMap::new();
+  if let Ok(content) = fs::read_to_string(&path) {
+    for line in content.lines() {
+      let Some((h, p)) = line.trim().split_once(' ') else { continue };
+      if let Ok(hash) = u64::from_str_radix(h, 16) { existing.insert(hash, p.to_string()); }
+    }
+  }
+  let mut added = 0u32;
+  for (hash, p) in entries {
+    if existing.insert(*hash, p.clone()).is_none() { added += 1; }
+  }
+  let sorted: BTreeMap<&String, u64> = existing.iter().map(|(h, p)| (p, *h)).collect();
+  let mut out = String::with_capacity(sorted.len() * 60);
+  for (p, h) in sorted {
+    let _ = writeln!(out, "{:016x} {}", h, p);
+  }
+  fs::create_dir_all(hash_dir).map_err(|e| format!("Failed to create {}: {}", hash_dir.display(), e))?;
+  let tmp = path.with_extension("txt.tmp");
+  write_retrying(&tmp, out.as_bytes()).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+  rename_retrying(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+  Ok(added)
+}
+
+struct RenameOutcome {
+  renamed: u32,
+  chunk_count: u32,
+  /// (new hash, new path) for targets given as paths.
+  custom: Vec<(u64, String)>,
+}
+
+fn rename_chunks(wad_path: &Path, renames: &[WadChunkRename], output: &Path) -> Result<RenameOutcome, String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let present: HashSet<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+
+  // new hash -> old hash
+  let mut moved: HashMap<u64, u64> = HashMap::new();
+  let mut sources: HashSet<u64> = HashSet::new();
+  let mut custom = Vec::new();
+  for r in renames {
+    let (from, _) = parse_target(&r.from);
+    let (to, to_path) = parse_target(&r.to);
+    if !present.contains(&from) {
+      return Err(format!("{} is not in {}", r.from, wad_path.display()));
+    }
+    if from == to { continue; }
+    if !sources.insert(from) {
+      return Err(format!("{} is renamed more than once", r.from));
+    }
+    if moved.insert(to, from).is_some() {
+      return Err(format!("More than one chunk renamed to {}", r.to));
+    }
+    if let Some(p) = to_path { custom.push((to, p)); }
+  }
+  // A target may only reuse a hash that is itself being renamed away.
+  for to in moved.keys() {
+    if present.contains(to) && !sources.contains(to) {
+      return Err(format!("{:016x} already exists in {}", to, wad_path.display()));
+    }
+  }
+
+  let mut builder = WadBuilder::default();
+  let mut chunk_count = 0u32;
+  for c in &chunks {
+    if sources.contains(&c.path_hash()) { continue; }
+    builder = builder.with_chunk(WadChunkBuilder::default().with_path_hash(c.path_hash()));
+    chunk_count += 1;
+  }
+  for to in moved.keys() {
+    builder = builder.with_chunk(WadChunkBuilder::default().with_path_hash(*to));
+    chunk_count += 1;
+  }
+
+  let by_hash: HashMap<u64, _> = chunks.iter().map(|c| (c.path_hash(), *c)).collect();
+  let wad_data = &mmap[..];
+  let mut out = fs::File::create(output).map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+  builder
+    .build_to_writer(&mut out, |path_hash, cursor: &mut Cursor<Vec<u8>>| {
+      let source = moved.get(&path_hash).copied().unwrap_or(path_hash);
+      let chunk = by_hash.get(&source).ok_or_else(|| WadBuilderError::IoError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("Missing source for chunk {:016x}", path_hash),
+      )))?;
+      let data = decompress_chunk(wad_data, chunk)
+        .map_err(|e| WadBuilderError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+      cursor.write_all(&data)?;
+      Ok(())
+    })
+    .map_err(|e| format!("Failed to build WAD: {}", e))?;
+  out.flush().map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+  Ok(RenameOutcome { renamed: moved.len() as u32, chunk_count, custom })
+}
+
+fn run(wad_path: &Path, renames: &[WadChunkRename], options: &RenameWadChunksOptions) -> Result<RenameWadChunksResult, String> {
+  let in_place = options.output_wad.is_none();
+  let output: PathBuf = match &options.output_wad {
+    Some(o) => PathBuf::from(o),
+    None => wad_path.with_extension("client.tmp"),
+  };
+  let outcome = match rename_chunks(wad_path, renames, &output) {
+    Ok(o) => o,
+    Err(e) => {
+      if in_place { let _ = fs::remove_file(&output); }
+      return Err(e);
+    }
+  };
+  // The source mmap is dropped by now, so the original can be replaced (Windows).
+  if in_place {
+    rename_retrying(&output, wad_path).map_err(|e| format!("Failed to replace {}: {}", wad_path.display(), e))?;
+  }
+  let custom_hash_count = match &options.hash_dir {
+    Some(dir) => merge_custom_hashes(Path::new(dir), &outcome.custom)?,
+    None => 0,
+  };
+  Ok(RenameWadChunksResult {
+    success: true,
+    error: None,
+    renamed_count: outcome.renamed,
+    chunk_count: outcome.chunk_count,
+    custom_hash_count,
+  })
+}
+
+/// Reassign chunks of `wadPath` to new path hashes, keeping their data. Fails
+/// without writing anything if a source is missing or a target already exists.
+#[napi(js_name = "renameWadChunks")]
+pub fn rename_wad_chunks(
+  wad_path: String,
+  renames: Vec<WadChunkRename>,
+  options: Option<RenameWadChunksOptions>,
+) -> RenameWadChunksResult {
+  let options = options.unwrap_or(RenameWadChunksOptions { output_wad: None, hash_dir: None });
+  run(Path::new(&wad_path), &renames, &options).unwrap_or_else(|e| RenameWadChunksResult {
+    success: false,
+    error: Some(e),
+    renamed_count: 0,
+    chunk_count: 0,
+    custom_hash_count: 0,
+  })
+}
+
+#[napi(object)]
+pub struct WadChunkPatch {
+  /// Asset path or 16-digit hex path hash of the chunk to replace or add.
+  pub target: String,
+  /// File whose contents become the chunk data.
+  pub file: String,
+}
+
+#[napi(object)]
+pub struct PatchWadResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "replacedCount")]
+  pub replaced_count: u32,
+  #[napi(js_name = "addedCount")]
+  pub added_count: u32,
+}
+
+/// Write `wad_path` to `output` with `patches` (path hash -> new data) replacing
+/// or adding chunks. Returns (replaced, added).
+fn write_patched(wad_path: &Path, patches: &HashMap<u64, Vec<u8>>, output: &Path) -> Result<(u32, u32), String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let by_hash: HashMap<u64, _> = chunks.iter().map(|c| (c.path_hash(), *c)).collect();
+  let replaced = patches.keys().filter(|h| by_hash.contains_key(h)).count() as u32;
+
+  let mut builder = WadBuilder::default();
+  for c in &chunks {
+    builder = builder.with_chunk(WadChunkBuilder::default().with_path_hash(c.path_hash()));
+  }
+  for hash in patches.keys().filter(|h| !by_hash.contains_key(h)) {
+    builder = builder.with_chunk(WadChunkBuilder::default().with_path_hash(*hash));
+  }
+  let wad_data = &mmap[..];
+  let mut out = fs::File::create(output).map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+  builder
+    .build_to_writer(&mut out, |path_hash, cursor: &mut Cursor<Vec<u8>>| {
+      if let Some(data) = patches.get(&path_hash) {
+        cursor.write_all(data)?;
+        return Ok(());
+      }
+      let chunk = by_hash.get(&path_hash).ok_or_else(|| WadBuilderError::IoError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("Missing source for chunk {:016x}", path_hash),
+      )))?;
+      let data = decompress_chunk(wad_data, chunk)
+        .map_err(|e| WadBuilderError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+      cursor.write_all(&data)?;
+      Ok(())
+    })
+    .map_err(|e| format!("Failed to build WAD: {}", e))?;
+  out.flush().map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+  Ok((replaced, patches.len() as u32 - replaced))
+}
+
+/// Rewrite `wad_path` in place with `patches` replacing or adding chunks.
+pub(crate) fn patch_chunks(wad_path: &Path, patches: &HashMap<u64, Vec<u8>>) -> Result<(u32, u32), String> {
+  let tmp = wad_path.with_extension("client.tmp");
+  let built = write_patched(wad_path, patches, &tmp);
+  if built.is_err() {
+    let _ = fs::remove_file(&tmp);
+    return built;
+  }
+  // The source mmap is dropped by now, so the original can be replaced (Windows).
+  rename_retrying(&tmp, wad_path).map_err(|e| format!("Failed to replace {}: {}", wad_path.display(), e))?;
+  built
+}
+
+fn patch(wad_path: &Path, patches: &[WadChunkPatch]) -> Result<(u32, u32), String> {
+  let mut data = HashMap::new();
+  for p in patches {
+    let (hash, _) = parse_target(&p.target);
+    let bytes = fs::read(&p.file).map_err(|e| format!("Failed to read {}: {}", p.file, e))?;
+    if data.insert(hash, bytes).is_some() {
+      return Err(format!("{} is patched more than once", p.target));
+    }
+  }
+  patch_chunks(wad_path, &data)
+}
+
+/// Replace or add chunks of `wadPath` with file contents, rewriting the WAD in place.
+#[napi(js_name = "patchWad")]
+pub fn patch_wad(wad_path: String, patches: Vec<WadChunkPatch>) -> PatchWadResult {
+  match patch(Path::new(&wad_path), &patches) {
+    Ok((replaced_count, added_count)) => PatchWadResult { success: true, error: None, replaced_count, added_count },
+    Err(e) => PatchWadResult { success: false, error: Some(e), replaced_count: 0, added_count: 0 },
+  }
+}
+
+#[napi(object)]
+pub struct SaveChunkOptions {
+  /// Copy the WAD to `{wadPath}.bak` before writing (default true). An existing
+  /// .bak is kept, so it holds the WAD as it was before the first save.
+  pub backup: Option<bool>,
+}
+
+#[napi(object)]
+pub struct SaveChunkResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// "inPlace" (written over the old data), "appended" (added to the end) or
+  /// "rebuilt" (the chunk was new, or the WAD predates xxh3 checksums).
+  pub mode: String,
+  #[napi(js_name = "backupPath")]
+  pub backup_path: Option<String>,
+}
+
+/// One TOC entry of a v3 WAD.
+struct TocEntry {
+  index: usize,
+  path_hash: u64,
+  data_offset: u32,
+  compressed_size: u32,
+}
+
+/// The minor version and TOC of a v3 WAD, read without touching the chunk data.
+fn read_toc(file: &mut fs::File, wad_path: &Path) -> Result<(u8, Vec<TocEntry>), String> {
+  let read_err = |e: std::io::Error| format!("Failed to read {}: {}", wad_path.display(), e);
+  let mut header = [0u8; V3_HEADER_SIZE];
+  file.read_exact(&mut header).map_err(read_err)?;
+  if &header[..2] != b"RW" { return Err(format!("{} is not a WAD", wad_path.display())); }
+  if header[2] != 3 {
+    return Err(format!("{} is a v{}.{} WAD; only v3 can be saved to", wad_path.display(), header[2], header[3]));
+  }
+  let count = u32::from_le_bytes(header[V3_HEADER_SIZE - 4..].try_into().unwrap_or_default()) as usize;
+  // A damaged count must not turn into a huge allocation.
+  let len = file.metadata().map_err(read_err)?.len();
+  if (V3_HEADER_SIZE + count * V3_TOC_ENTRY_SIZE) as u64 > len {
+    return Err(format!("{} is damaged: its TOC lists {} chunks but the file is only {} bytes", wad_path.display(), count, len));
+  }
+  let mut toc = vec![0u8; count * V3_TOC_ENTRY_SIZE];
+  file.read_exact(&mut toc).map_err(read_err)?;
+  let u32_at = |e: &[u8], at: usize| u32::from_le_bytes(e[at..at + 4].try_into().unwrap_or_default());
+  let entries = toc.chunks_exact(V3_TOC_ENTRY_SIZE).enumerate().map(|(index, e)| TocEntry {
+    index,
+    path_hash: u64::from_le_bytes(e[..8].try_into().unwrap_or_default()),
+    data_offset: u32_at(e, 8),
+    compressed_size: u32_at(e, 12),
+  }).collect();
+  Ok((header[3], entries))
+}
+
+/// Point TOC entry `index` at `compressed` stored at `offset`, as a single
+/// frame that shares no data. Only valid from `XXH3_CHECKSUM_MINOR` on: the
+/// zeroed frame bytes read the same in every v3 layout, the checksum does not.
+fn write_toc_entry(
+  file: &mut fs::File,
+  index: usize,
+  offset: u32,
+  compressed: &[u8],
+  uncompressed_size: usize,
+  compression: WadChunkCompression,
+) -> std::io::Result<()> {
+  let mut entry = [0u8; V3_TOC_ENTRY_SIZE - 8];
+  entry[..4].copy_from_slice(&offset.to_le_bytes());
+  entry[4..8].copy_from_slice(&(compressed.len() as u32).to_le_bytes());
+  entry[8..12].copy_from_slice(&(uncompressed_size as u32).to_le_bytes());
+  entry[12] = compression as u8;
+  entry[16..].copy_from_slice(&xxh3_64(compressed).to_le_bytes());
+  file.seek(SeekFrom::Start((V3_HEADER_SIZE + index * V3_TOC_ENTRY_SIZE + 8) as u64))?;
+  file.write_all(&entry)
+}
+
+/// Write `compressed` to a copy of the WAD, over the old data at `over` or
+/// appended when `None`, repoint `targets` at it and swap the copy in. `None`
+/// when appended data would land past the 4 GB offset limit.
+fn save_to_copy(
+  wad_path: &Path,
+  targets: &[&TocEntry],
+  over: Option<u32>,
+  compressed: &[u8],
+  uncompressed_size: usize,
+  compression: WadChunkCompression,
+) -> Result<Option<()>, String> {
+  let tmp = wad_path.with_extension("client.tmp");
+  let write = || -> Result<Option<()>, String> {
+    let write_err = |e: std::io::Error| format!("Failed to write {}: {}", tmp.display(), e);
+    fs::copy(wad_path, &tmp).map_err(|e| format!("Failed to copy {}: {}", wad_path.display(), e))?;
+    let mut file = fs::OpenOptions::new().write(true).open(&tmp).map_err(write_err)?;
+    let start = match over {
+      Some(offset) => file.seek(SeekFrom::Start(offset as u64)).map_err(write_err)?,
+      None => file.seek(SeekFrom::End(0)).map_err(write_err)?,
+    };
+    let Ok(offset) = u32::try_from(start) else { return Ok(None) };
+    if u32::try_from(start + compressed.len() as u64).is_err() { return Ok(None); }
+    file.write_all(compressed).map_err(write_err)?;
+    for entry in targets {
+      write_toc_entry(&mut file, entry.index, offset, compressed, uncompressed_size, compression).map_err(write_err)?;
+    }
+    file.sync_all().map_err(write_err)?;
+    Ok(Some(()))
+  };
+  match write() {
+    Ok(Some(())) => {
+      rename_retrying(&tmp, wad_path).map_err(|e| format!("Failed to replace {}: {}", wad_path.display(), e))?;
+      Ok(Some(()))
+    }
+    other => {
+      let _ = fs::remove_file(&tmp);
+      other
+    }
+  }
+}
+
+pub(crate) fn save_chunk(wad_path: &Path, hash: u64, data: &[u8], backup: bool) -> Result<SaveChunkResult, String> {
+  let mut file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let (minor, toc) = read_toc(&mut file, wad_path)?;
+  drop(file);
+
+  let backup_path = if backup {
+    let bak = PathBuf::from(format!("{}.bak", wad_path.display()));
+    if !bak.exists() {
+      fs::copy(wad_path, &bak).map_err(|e| format!("Failed to back up {}: {}", wad_path.display(), e))?;
+    }
+    Some(bak.to_string_lossy().into_owned())
+  } else {
+    None
+  };
+  let done = |mode: &str| SaveChunkResult { success: true, error: None, mode: mode.to_string(), backup_path: backup_path.clone() };
+
+  // Duplicate TOC entries for the hash all move to the new data.
+  let targets: Vec<&TocEntry> = toc.iter().filter(|e| e.path_hash == hash).collect();
+  if targets.is_empty() || minor < XXH3_CHECKSUM_MINOR {
+    patch_chunks(wad_path, &HashMap::from([(hash, data.to_vec())]))?;
+    return Ok(done("rebuilt"));
+  }
+  let (compressed, compression) = compress_chunk(data)?;
+  let target = targets[0];
+  let shared = toc.iter().any(|e| e.data_offset == target.data_offset && e.path_hash != hash);
+  let fits = compressed.len() <= target.compressed_size as usize;
+  if targets.len() == 1 && !shared && fits {
+    save_to_copy(wad_path, &targets, Some(target.data_offset), &compressed, data.len(), compression)?;
+    return Ok(done("inPlace"));
+  }
+  if save_to_copy(wad_path, &targets, None, &compressed, data.len(), compression)?.is_some() {
+    return Ok(done("appended"));
+  }
+  patch_chunks(wad_path, &HashMap::from([(hash, data.to_vec())]))?;
+  Ok(done("rebuilt"))
+}
+
+/// Replace one chunk (asset path or hex hash) of `wadPath` with `data`, editing
+/// the WAD where it is instead of rebuilding it. A chunk the WAD doesn't have
+/// yet is added with a rebuild.
+#[napi(js_name = "saveChunkToWad")]
+pub fn save_chunk_to_wad(wad_path: String, target: String, data: Buffer, options: Option<SaveChunkOptions>) -> SaveChunkResult {
+  let backup = options.and_then(|o| o.backup).unwrap_or(true);
+  let (hash, _) = parse_target(&target);
+  save_chunk(Path::new(&wad_path), hash, &data, backup).unwrap_or_else(|e| SaveChunkResult {
+    success: false,
+    error: Some(e),
+    mode: String::new(),
+    backup_path: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wad_indexer_patch_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn test_read_toc_rejects_count_past_end_of_file() {
+    let dir = scratch("huge_count");
+    let wad = dir.join("Broken.wad.client");
+    let mut header = vec![0u8; V3_HEADER_SIZE];
+    header[..4].copy_from_slice(&[b'R', b'W', 3, 4]);
+    header[V3_HEADER_SIZE - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&wad, header).unwrap();
+    let Err(err) = read_toc(&mut fs::File::open(&wad).unwrap(), &wad) else { panic!("damaged TOC was read") };
+    assert!(err.contains("is damaged"), "{}", err);
+    let _ = fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn test_v3_1_chunk_saves_without_rebuild() {
+    let dir = scratch("v3_1");
+    let src = dir.join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("0123456789abcdef"), b"original").unwrap();
+    let (mut bytes, _) = crate::wad_build::build_wad_bytes(&src).unwrap();
+    bytes[3] = 1;
+    let wad = dir.join("Test.wad.client");
+    fs::write(&wad, bytes).unwrap();
+
+    let saved = save_chunk(&wad, 0x0123456789abcdef, b"replaced", false).unwrap();
+    assert_eq!(saved.mode, "inPlace");
+    let data = fs::read(&wad).unwrap();
+    let (minor, toc) = read_toc(&mut fs::File::open(&wad).unwrap(), &wad).unwrap();
+    assert_eq!(minor, 1);
+    let raw = &data[toc[0].data_offset as usize..][..toc[0].compressed_size as usize];
+    assert_eq!(zstd::decode_all(raw).unwrap(), b"replaced");
+    let _ = fs::remove_dir_all(&dir);
+  }
+}
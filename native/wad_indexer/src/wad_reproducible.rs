@@ -0,0 +1,167 @@
+// ── Reproducible WAD check ───────────────────────────────────────────────────
+// WAD builds are deterministic (see wad_build), so a released WAD can be
+// checked against the folder it claims to come from: rebuild the folder in
+// memory and compare bytes. When they differ, chunks are matched by path hash
+// to say which files changed and whether only their encoding differs (same
+// data, different compressor) or the data itself.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_wad::{Wad, WadChunk};
+use memmap2::Mmap;
+use napi_derive::napi;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::unique_chunks;
+use crate::wad_build::{build_wad_to_writer, plan_wad_dir};
+
+/// Magic, version, signature and checksum, before the chunk count.
+const HEADER_SIZE: usize = 4 + 256 + 8;
+/// Differences listed before the rest are only counted.
+const MAX_DIFFERENCES: usize = 1000;
+
+#[napi(object)]
+pub struct ChunkDifference {
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  /// Relative path in the input folder; None for chunks only the WAD has.
+  pub path: Option<String>,
+  /// "missing" (not in the WAD), "extra" (only in the WAD), "encoding" (same
+  /// data stored differently) or "content" (different data).
+  pub kind: String,
+}
+
+#[napi(object)]
+pub struct ReproducibleResult {
+  pub success: bool,
+  pub error: Option<String>,
+  /// The WAD is byte-identical to a fresh build of the folder.
+  pub reproducible: bool,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  /// xxh3 of the fresh build and of the WAD, 16-digit hex.
+  #[napi(js_name = "expectedHash")]
+  pub expected_hash: String,
+  #[napi(js_name = "actualHash")]
+  pub actual_hash: String,
+  #[napi(js_name = "expectedSize")]
+  pub expected_size: f64,
+  #[napi(js_name = "actualSize")]
+  pub actual_size: f64,
+  /// Signature or checksum bytes are set (e.g. a signed WAD).
+  #[napi(js_name = "headerDiffers")]
+  pub header_differs: bool,
+  pub differences: Vec<ChunkDifference>,
+  #[napi(js_name = "differenceCount")]
+  pub difference_count: u32,
+}
+
+fn chunk_map(data: &[u8], name: &str) -> Result<HashMap<u64, WadChunk>, String> {
+  let wad = Wad::mount(Cursor::new(data)).map_err(|e| format!("Failed to mount {}: {}", name, e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  Ok(chunks.into_iter().map(|c| (c.path_hash(), c)).collect())
+}
+
+fn stored<'a>(data: &'a [u8], chunk: &WadChunk) -> &'a [u8] {
+  data.get(chunk.data_offset()..chunk.data_offset() + chunk.compressed_size()).unwrap_or_default()
+}
+
+/// Chunk-level differences between a fresh build and the WAD on disk.
+fn diff_chunks(
+  expected: &[u8],
+  actual: &[u8],
+  wad_path: &Path,
+  paths: &HashMap<u64, String>,
+) -> Result<Vec<ChunkDifference>, String> {
+  let want = chunk_map(expected, "rebuilt WAD")?;
+  let have = chunk_map(actual, &wad_path.display().to_string())?;
+  let mut hashes: Vec<u64> = want.keys().chain(have.keys()).copied().collect();
+  hashes.sort_unstable();
+  hashes.dedup();
+
+  let mut out = Vec::new();
+  for hash in hashes {
+    let kind = match (want.get(&hash), have.get(&hash)) {
+      (Some(_), None) => "missing",
+      (None, Some(_)) => "extra",
+      (Some(w), Some(h)) => {
+        if w.compression_type() == h.compression_type() && stored(expected, w) == stored(actual, h) { continue; }
+        let same_data = match (decompress_chunk(expected, w), decompress_chunk(actual, h)) {
+          (Ok(a), Ok(b)) => a == b,
+          _ => false,
+        };
+        if same_data { "encoding" } else { "content" }
+      }
+      (None, None) => continue,
+    };
+    out.push(ChunkDifference {
+      path_hash: format!("{:016x}", hash),
+      path: paths.get(&hash).cloned(),
+      kind: kind.to_string(),
+    });
+  }
+  Ok(out)
+}
+
+fn verify(input_dir: &Path, wad_path: &Path) -> Result<ReproducibleResult, String> {
+  if !input_dir.is_dir() { return Err(format!("Input is not a folder: {}", input_dir.display())); }
+  let (index, _) = plan_wad_dir(input_dir)?;
+  if index.is_empty() { return Err(format!("No files found in {}", input_dir.display())); }
+  let mut cursor = Cursor::new(Vec::new());
+  build_wad_to_writer(&index, &mut cursor)?;
+  let expected = cursor.into_inner();
+
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let actual = &mmap[..];
+
+  let reproducible = expected[..] == *actual;
+  let header_differs = !reproducible && actual.get(4..HEADER_SIZE).is_some_and(|h| h.iter().any(|b| *b != 0));
+  let differences = if reproducible {
+    Vec::new()
+  } else {
+    let paths: HashMap<u64, String> = index.iter()
+      .map(|(hash, path)| {
+        (*hash, path.strip_prefix(input_dir).unwrap_or(path).to_string_lossy().replace('\\', "/"))
+      })
+      .collect();
+    diff_chunks(&expected, actual, wad_path, &paths)?
+  };
+  let difference_count = differences.len() as u32;
+  Ok(ReproducibleResult {
+    success: true,
+    error: None,
+    reproducible,
+    chunk_count: index.len() as u32,
+    expected_hash: format!("{:016x}", xxh3_64(&expected)),
+    actual_hash: format!("{:016x}", xxh3_64(actual)),
+    expected_size: expected.len() as f64,
+    actual_size: actual.len() as f64,
+    header_differs,
+    differences: differences.into_iter().take(MAX_DIFFERENCES).collect(),
+    difference_count,
+  })
+}
+
+/// Check that `wadPath` is exactly what packing `inputDir` produces. Read-only;
+/// nothing is written to disk.
+#[napi(js_name = "verifyReproducible")]
+pub fn verify_reproducible(input_dir: String, wad_path: String) -> ReproducibleResult {
+  verify(Path::new(&input_dir), Path::new(&wad_path)).unwrap_or_else(|e| ReproducibleResult {
+    success: false,
+    error: Some(e),
+    reproducible: false,
+    chunk_count: 0,
+    expected_hash: String::new(),
+    actual_hash: String::new(),
+    expected_size: 0.0,
+    actual_size: 0.0,
+    header_differs: false,
+    differences: Vec::new(),
+    difference_count: 0,
+  })
+}
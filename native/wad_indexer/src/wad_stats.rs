@@ -0,0 +1,107 @@
+// ── WAD type statistics ──────────────────────────────────────────────────────
+// A quick "what's in this WAD" overview before extracting it: chunks grouped by
+// detected file type with counts and byte totals. Only the first bytes of each
+// chunk are decompressed to sniff the type, so this stays fast on map WADs.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use ltk_file::LeagueFileKind;
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::chunk_decode::decompress_chunk_prefix;
+use crate::threads::run_cpu;
+use crate::unique_chunks;
+
+/// Bytes decompressed per chunk; some chunks carry a short prefix before the magic.
+const SNIFF_LEN: usize = 128;
+const SNIFF_OFFSET: usize = 64;
+
+#[napi(object)]
+pub struct WadTypeStat {
+  /// File type by content ("bin", "dds", "tex", "skn", "anm", "bnk", ...), or
+  /// "unknown"; "unreadable" for chunks that failed to decompress.
+  #[napi(js_name = "type")]
+  pub kind: String,
+  pub count: u32,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+}
+
+#[napi(object)]
+pub struct WadTypeStatsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "chunkCount")]
+  pub chunk_count: u32,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+  /// Largest (uncompressed) first.
+  pub types: Vec<WadTypeStat>,
+}
+
+fn type_stats(wad_path: &Path) -> Result<WadTypeStatsResult, String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let wad_data = &mmap[..];
+
+  let kinds: Vec<&'static str> = run_cpu(|| chunks
+    .par_iter()
+    .map(|chunk| match decompress_chunk_prefix(wad_data, chunk, SNIFF_LEN) {
+      Ok(head) => LeagueFileKind::identify_from_bytes_with_offset(&head, SNIFF_OFFSET).extension().unwrap_or("unknown"),
+      Err(_) => "unreadable",
+    })
+    .collect());
+
+  let mut by_kind: HashMap<&str, (u32, u64, u64)> = HashMap::new();
+  let (mut size, mut compressed_size) = (0u64, 0u64);
+  for (chunk, kind) in chunks.iter().zip(kinds) {
+    let e = by_kind.entry(kind).or_default();
+    e.0 += 1;
+    e.1 += chunk.uncompressed_size() as u64;
+    e.2 += chunk.compressed_size() as u64;
+    size += chunk.uncompressed_size() as u64;
+    compressed_size += chunk.compressed_size() as u64;
+  }
+  let mut types: Vec<WadTypeStat> = by_kind
+    .into_iter()
+    .map(|(kind, (count, size, compressed))| WadTypeStat {
+      kind: kind.to_string(),
+      count,
+      size: size as f64,
+      compressed_size: compressed as f64,
+    })
+    .collect();
+  types.sort_by(|a, b| b.size.total_cmp(&a.size).then_with(|| a.kind.cmp(&b.kind)));
+
+  Ok(WadTypeStatsResult {
+    success: true,
+    error: None,
+    chunk_count: chunks.len() as u32,
+    size: size as f64,
+    compressed_size: compressed_size as f64,
+    types,
+  })
+}
+
+/// Chunk counts and byte totals per detected file type.
+#[napi(js_name = "wadTypeStats")]
+pub fn wad_type_stats(wad_path: String) -> WadTypeStatsResult {
+  type_stats(Path::new(&wad_path)).unwrap_or_else(|e| WadTypeStatsResult {
+    success: false,
+    error: Some(e),
+    chunk_count: 0,
+    size: 0.0,
+    compressed_size: 0.0,
+    types: Vec::new(),
+  })
+}
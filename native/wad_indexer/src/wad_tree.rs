@@ -0,0 +1,229 @@
+// ── WAD tree view ────────────────────────────────────────────────────────────
+// Folder hierarchy of a WAD's resolved paths for the WAD browser. The tree is
+// built once per WAD (and rebuilt when the file or hash dir changes) and
+// handed out one folder at a time, so the frontend only materializes what the
+// user expands instead of 100k nodes up front. Unresolved chunks sit at the
+// root under their hex hash, the same place extraction writes them.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use ltk_wad::Wad;
+use napi_derive::napi;
+
+use crate::game::{wad_kind, WadKind};
+use crate::{resolve_wad_hashes, unique_chunks, HashLayers};
+
+/// Trees kept in memory; browsing rarely spans more WADs than this at once.
+const MAX_CACHED_TREES: usize = 8;
+
+struct TreeFile {
+  name: String,
+  hash: u64,
+  size: u64,
+  compressed_size: u64,
+}
+
+#[derive(Default)]
+struct TreeFolder {
+  name: String,
+  folders: BTreeMap<String, usize>,
+  files: Vec<TreeFile>,
+  /// Files anywhere below this folder.
+  file_count: u32,
+  size: u64,
+  compressed_size: u64,
+}
+
+struct WadTree {
+  folders: Vec<TreeFolder>,
+}
+
+impl WadTree {
+  fn build(entries: Vec<(String, u64, u64, u64)>) -> WadTree {
+    let mut folders = vec![TreeFolder::default()];
+    for (path, hash, size, compressed_size) in entries {
+      let mut parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+      let Some(name) = parts.pop() else { continue };
+      let mut current = 0usize;
+      let mut chain = vec![0usize];
+      for part in parts {
+        current = match folders[current].folders.get(part) {
+          Some(i) => *i,
+          None => {
+            let i = folders.len();
+            folders.push(TreeFolder { name: part.to_string(), ..TreeFolder::default() });
+            folders[current].folders.insert(part.to_string(), i);
+            i
+          }
+        };
+        chain.push(current);
+      }
+      for i in chain {
+        let f = &mut folders[i];
+        f.file_count += 1;
+        f.size += size;
+        f.compressed_size += compressed_size;
+      }
+      folders[current].files.push(TreeFile { name: name.to_string(), hash, size, compressed_size });
+    }
+    for f in &mut folders {
+      f.files.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    WadTree { folders }
+  }
+
+  /// Folder index for a "/"-separated path; "" is the root.
+  fn find(&self, dir: &str) -> Option<usize> {
+    let mut current = 0usize;
+    for part in dir.split('/').filter(|p| !p.is_empty()) {
+      current = *self.folders[current].folders.get(part)?;
+    }
+    Some(current)
+  }
+}
+
+/// (wad path, hash dir, wad mtime, wad size)
+type TreeKey = (PathBuf, Option<String>, Option<SystemTime>, u64);
+/// Least recently used first.
+type TreeCache = Vec<(TreeKey, Arc<WadTree>)>;
+
+fn trees() -> &'static Mutex<TreeCache> {
+  static TREES: OnceLock<Mutex<TreeCache>> = OnceLock::new();
+  TREES.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn load_tree(wad_path: &Path, hash_dir: Option<&str>) -> Result<Arc<WadTree>, String> {
+  let meta = fs::metadata(wad_path).map_err(|e| format!("Failed to read {}: {}", wad_path.display(), e))?;
+  let key: TreeKey = (wad_path.to_path_buf(), hash_dir.map(str::to_string), meta.modified().ok(), meta.len());
+  {
+    let mut cached = trees().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(pos) = cached.iter().position(|(k, _)| *k == key) {
+      let entry = cached.remove(pos);
+      let tree = entry.1.clone();
+      cached.push(entry);
+      return Ok(tree);
+    }
+  }
+
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(file).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let hashes: Vec<u64> = chunks.iter().map(|c| c.path_hash()).collect();
+  let kind = wad_kind(wad_path).unwrap_or(WadKind::Game);
+  let paths = resolve_wad_hashes(kind, &hashes, &HashLayers::open(hash_dir), hash_dir);
+  let entries = chunks
+    .iter()
+    .zip(paths)
+    .map(|(c, p)| (p, c.path_hash(), c.uncompressed_size() as u64, c.compressed_size() as u64))
+    .collect();
+  let tree = Arc::new(WadTree::build(entries));
+
+  let mut cached = trees().lock().unwrap_or_else(|e| e.into_inner());
+  cached.retain(|(k, _)| k.0 != key.0);
+  if cached.len() >= MAX_CACHED_TREES { cached.remove(0); }
+  cached.push((key, tree.clone()));
+  Ok(tree)
+}
+
+#[napi(object)]
+pub struct WadTreeFolder {
+  pub name: String,
+  /// Full "/"-separated path, to pass back to `listWadTree`.
+  pub path: String,
+  /// Files anywhere below this folder.
+  #[napi(js_name = "fileCount")]
+  pub file_count: u32,
+  /// Direct subfolders, so the UI knows whether to show an expander.
+  #[napi(js_name = "folderCount")]
+  pub folder_count: u32,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+}
+
+#[napi(object)]
+pub struct WadTreeFile {
+  pub name: String,
+  pub path: String,
+  #[napi(js_name = "pathHash")]
+  pub path_hash: String,
+  pub size: f64,
+  #[napi(js_name = "compressedSize")]
+  pub compressed_size: f64,
+}
+
+#[napi(object)]
+pub struct WadTreeListing {
+  pub success: bool,
+  pub error: Option<String>,
+  /// The listed folder; `folders` and `files` are its direct children.
+  pub folder: Option<WadTreeFolder>,
+  pub folders: Vec<WadTreeFolder>,
+  pub files: Vec<WadTreeFile>,
+}
+
+fn join(dir: &str, name: &str) -> String {
+  if dir.is_empty() { name.to_string() } else { format!("{}/{}", dir, name) }
+}
+
+fn folder_info(folder: &TreeFolder, path: String) -> WadTreeFolder {
+  WadTreeFolder {
+    name: folder.name.clone(),
+    path,
+    file_count: folder.file_count,
+    folder_count: folder.folders.len() as u32,
+    size: folder.size as f64,
+    compressed_size: folder.compressed_size as f64,
+  }
+}
+
+fn list(wad_path: &Path, dir: &str, hash_dir: Option<&str>) -> Result<WadTreeListing, String> {
+  let tree = load_tree(wad_path, hash_dir)?;
+  let dir = dir.trim_matches('/');
+  let index = tree.find(dir).ok_or_else(|| format!("Folder not found in WAD: {}", dir))?;
+  let folder = &tree.folders[index];
+  Ok(WadTreeListing {
+    success: true,
+    error: None,
+    folder: Some(folder_info(folder, dir.to_string())),
+    folders: folder.folders.iter().map(|(name, i)| folder_info(&tree.folders[*i], join(dir, name))).collect(),
+    files: folder
+      .files
+      .iter()
+      .map(|f| WadTreeFile {
+        name: f.name.clone(),
+        path: join(dir, &f.name),
+        path_hash: format!("{:016x}", f.hash),
+        size: f.size as f64,
+        compressed_size: f.compressed_size as f64,
+      })
+      .collect(),
+  })
+}
+
+/// Direct children of `dirPath` ("" for the root) in the WAD's resolved folder
+/// tree, with recursive file counts and sizes for each subfolder.
+#[napi(js_name = "listWadTree")]
+pub fn list_wad_tree(wad_path: String, dir_path: String, hash_dir: Option<String>) -> WadTreeListing {
+  list(Path::new(&wad_path), &dir_path, hash_dir.as_deref()).unwrap_or_else(|e| WadTreeListing {
+    success: false,
+    error: Some(e),
+    folder: None,
+    folders: Vec::new(),
+    files: Vec::new(),
+  })
+}
+
+/// Drop the cached tree for `wadPath`, or every cached tree.
+#[napi(js_name = "clearWadTree")]
+pub fn clear_wad_tree(wad_path: Option<String>) {
+  let mut cached = trees().lock().unwrap_or_else(|e| e.into_inner());
+  match wad_path {
+    Some(p) => cached.retain(|(k, _)| k.0 != Path::new(&p)),
+    None => cached.clear(),
+  }
+}
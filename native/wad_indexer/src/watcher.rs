@@ -0,0 +1,255 @@
+// ── Filesystem watcher ───────────────────────────────────────────────────────
+// Watches the game install and hash directory and reports debounced change
+// batches to JS, so the app can prompt for a hash refresh / re-extract after a
+// patch instead of relying on users noticing. `watchFile` follows a single
+// file edited in an external tool (texture preview, bin auto-reload).
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::game::{game_dir, is_wad_file_name};
+use crate::get_file_mtime_ms;
+
+const DEFAULT_DEBOUNCE_MS: u32 = 1500;
+/// Editors save in bursts (truncate + write, or temp file + rename).
+const DEFAULT_FILE_DEBOUNCE_MS: u32 = 150;
+/// A batch is reported at most this many debounce periods after its first
+/// change, even while changes keep arriving (a patch, an editor autosaving).
+const MAX_BATCH_DEBOUNCES: u32 = 4;
+
+static NEXT_WATCHER_ID: AtomicU32 = AtomicU32::new(1);
+static WATCHERS: OnceLock<Mutex<HashMap<u32, RecommendedWatcher>>> = OnceLock::new();
+
+fn watchers() -> &'static Mutex<HashMap<u32, RecommendedWatcher>> {
+  WATCHERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[napi(object)]
+pub struct WatchOptions {
+  /// League install root or its Game folder.
+  #[napi(js_name = "gamePath")]
+  pub game_path: Option<String>,
+  #[napi(js_name = "hashDir")]
+  pub hash_dir: Option<String>,
+  /// Quiet period before a batch of changes is reported, capped at four periods
+  /// after the first change. Defaults to 1500ms.
+  #[napi(js_name = "debounceMs")]
+  pub debounce_ms: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct WatchEvent {
+  /// "game" for WAD/metadata changes, "hashes" for hash table changes.
+  pub kind: String,
+  pub paths: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+  Game,
+  Hashes,
+}
+
+fn classify(path: &Path, game_root: Option<&Path>, hash_root: Option<&Path>) -> Option<ChangeKind> {
+  let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+  if let Some(root) = hash_root {
+    // Ignore our own LMDB writes; only the text sources matter.
+    if path.starts_with(root) && !path.starts_with(root.join("hashes.lmdb")) {
+      if name.starts_with("hashes.") && name.ends_with(".txt") { return Some(ChangeKind::Hashes); }
+      if name.ends_with(".bin") && name.starts_with("hashes") { return Some(ChangeKind::Hashes); }
+      return None;
+    }
+  }
+  if let Some(root) = game_root {
+    if path.starts_with(root) {
+      let is_game_file = is_wad_file_name(&name) || name == "content-metadata.json";
+      if is_game_file { return Some(ChangeKind::Game); }
+    }
+  }
+  None
+}
+
+/// Collect events from `rx` into `S` and hand the batch to `flush` once no
+/// event has arrived for `debounce`, or `MAX_BATCH_DEBOUNCES` periods after the
+/// batch started. `add` returns false for events that don't belong in a batch.
+/// Whatever is pending is flushed before returning when `rx` disconnects.
+fn debounce_events<T, S: Default>(
+  rx: Receiver<T>,
+  debounce: Duration,
+  mut add: impl FnMut(&mut S, T) -> bool,
+  mut flush: impl FnMut(S),
+) {
+  let mut pending = S::default();
+  let mut deadline: Option<Instant> = None;
+  loop {
+    let wait = match deadline {
+      Some(d) => debounce.min(d.saturating_duration_since(Instant::now())),
+      None => debounce,
+    };
+    match rx.recv_timeout(wait) {
+      Ok(event) => {
+        if add(&mut pending, event) && deadline.is_none() {
+          deadline = Some(Instant::now() + debounce * MAX_BATCH_DEBOUNCES);
+        }
+        if deadline.is_some_and(|d| Instant::now() < d) { continue; }
+      }
+      Err(RecvTimeoutError::Timeout) => {}
+      Err(RecvTimeoutError::Disconnected) => {
+        if deadline.is_some() { flush(pending); }
+        break;
+      }
+    }
+    if deadline.take().is_some() { flush(std::mem::take(&mut pending)); }
+  }
+}
+
+/// Start watching the game folder and/or hash dir. `callback` receives
+/// `WatchEvent` batches. Returns a watcher id for `unwatchPaths`.
+#[napi(js_name = "watchPaths")]
+pub fn watch_paths(env: Env, options: WatchOptions, callback: JsFunction) -> napi::Result<u32> {
+  let game_root = options.game_path.as_deref().map(|p| game_dir(Path::new(p)));
+  let hash_root = options.hash_dir.as_deref().map(PathBuf::from);
+  if game_root.is_none() && hash_root.is_none() {
+    return Err(napi::Error::from_reason("Nothing to watch: pass gamePath and/or hashDir"));
+  }
+  let debounce = Duration::from_millis(options.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS).max(50) as u64);
+
+  let mut tsfn: ThreadsafeFunction<WatchEvent, ErrorStrategy::Fatal> = callback
+    .create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+  // Don't keep the Node event loop alive just because a watcher exists.
+  tsfn.unref(&env)?;
+
+  let (tx, rx) = channel::<PathBuf>();
+  let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    if let Ok(event) = res {
+      for p in event.paths {
+        let _ = tx.send(p);
+      }
+    }
+  })
+  .map_err(|e| napi::Error::from_reason(format!("Failed to create watcher: {}", e)))?;
+
+  if let Some(root) = game_root.as_ref() {
+    watcher.watch(root, RecursiveMode::Recursive)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to watch {}: {}", root.display(), e)))?;
+  }
+  if let Some(root) = hash_root.as_ref() {
+    watcher.watch(root, RecursiveMode::NonRecursive)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to watch {}: {}", root.display(), e)))?;
+  }
+
+  // Debounce thread: exits once the watcher (and with it the sender) is dropped.
+  thread::spawn(move || {
+    debounce_events(
+      rx,
+      debounce,
+      |pending: &mut Vec<(ChangeKind, BTreeSet<String>)>, path: PathBuf| {
+        let Some(kind) = classify(&path, game_root.as_deref(), hash_root.as_deref()) else { return false };
+        let p = path.to_string_lossy().into_owned();
+        match pending.iter_mut().find(|(k, _)| *k == kind) {
+          Some((_, set)) => { set.insert(p); }
+          None => pending.push((kind, BTreeSet::from([p]))),
+        }
+        true
+      },
+      |pending| {
+        for (kind, paths) in pending {
+          let kind = match kind { ChangeKind::Game => "game", ChangeKind::Hashes => "hashes" };
+          tsfn.call(
+            WatchEvent { kind: kind.to_string(), paths: paths.into_iter().collect() },
+            ThreadsafeFunctionCallMode::NonBlocking,
+          );
+        }
+      },
+    );
+  });
+
+  let id = NEXT_WATCHER_ID.fetch_add(1, Ordering::Relaxed);
+  watchers().lock().unwrap_or_else(|e| e.into_inner()).insert(id, watcher);
+  Ok(id)
+}
+
+/// Stop a watcher started by `watchPaths`. Returns false for unknown ids.
+#[napi(js_name = "unwatchPaths")]
+pub fn unwatch_paths(id: u32) -> bool {
+  watchers().lock().unwrap_or_else(|e| e.into_inner()).remove(&id).is_some()
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct FileChangedEvent {
+  /// Always "file-changed".
+  pub event: String,
+  pub path: String,
+  /// False when the file is gone after the burst of changes (deleted or renamed away).
+  pub exists: bool,
+  #[napi(js_name = "mtimeMs")]
+  pub mtime_ms: f64,
+}
+
+/// Watch one file and call `callback` with a `FileChangedEvent` after each
+/// burst of writes. The parent folder is watched rather than the file, so
+/// editors that save via temp file + rename keep being followed. Returns a
+/// watcher id for `unwatchFile`.
+#[napi(js_name = "watchFile")]
+pub fn watch_file(env: Env, path: String, callback: JsFunction, debounce_ms: Option<u32>) -> napi::Result<u32> {
+  let target = PathBuf::from(&path);
+  let (Some(parent), Some(file_name)) = (target.parent(), target.file_name()) else {
+    return Err(napi::Error::from_reason(format!("Not a file path: {}", path)));
+  };
+  let parent = if parent.as_os_str().is_empty() { PathBuf::from(".") } else { parent.to_path_buf() };
+  let file_name = file_name.to_os_string();
+  let debounce = Duration::from_millis(debounce_ms.unwrap_or(DEFAULT_FILE_DEBOUNCE_MS).max(10) as u64);
+
+  let mut tsfn: ThreadsafeFunction<FileChangedEvent, ErrorStrategy::Fatal> = callback
+    .create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+  tsfn.unref(&env)?;
+
+  let (tx, rx) = channel::<()>();
+  let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    let Ok(event) = res else { return };
+    if event.kind.is_access() { return; }
+    if event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str())) {
+      let _ = tx.send(());
+    }
+  })
+  .map_err(|e| napi::Error::from_reason(format!("Failed to create watcher: {}", e)))?;
+  watcher.watch(&parent, RecursiveMode::NonRecursive)
+    .map_err(|e| napi::Error::from_reason(format!("Failed to watch {}: {}", parent.display(), e)))?;
+
+  thread::spawn(move || {
+    debounce_events(rx, debounce, |_: &mut (), ()| true, |()| {
+      let exists = target.is_file();
+      tsfn.call(
+        FileChangedEvent {
+          event: "file-changed".to_string(),
+          path: path.clone(),
+          exists,
+          mtime_ms: if exists { get_file_mtime_ms(&target) as f64 } else { 0.0 },
+        },
+        ThreadsafeFunctionCallMode::NonBlocking,
+      );
+    });
+  });
+
+  let id = NEXT_WATCHER_ID.fetch_add(1, Ordering::Relaxed);
+  watchers().lock().unwrap_or_else(|e| e.into_inner()).insert(id, watcher);
+  Ok(id)
+}
+
+/// Stop a watcher started by `watchFile`. Returns false for unknown ids.
+#[napi(js_name = "unwatchFile")]
+pub fn unwatch_file(id: u32) -> bool {
+  unwatch_paths(id)
+}
@@ -0,0 +1,477 @@
+// ── Wwise hashes ─────────────────────────────────────────────────────────────
+// Wwise identifies events and banks by a 32-bit FNV-1 hash of the lowercased
+// name. Sound banks only carry the IDs (plus bank names in the STID section),
+// while bins reference events by name, so hash extraction collects both sides
+// and records the names whose hash matches an ID found in a bank. The result
+// goes to `hashes.wwise.txt` and the `wwise` table of hashes.lmdb. The same
+// bank parsing backs the per-champion audio event export.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use heed::types::{Bytes, Str};
+use ltk_wad::Wad;
+use memmap2::Mmap;
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::chunk_decode::decompress_chunk;
+use crate::game::find_champion_wad;
+use crate::paths::write_retrying;
+use crate::threads::run_cpu;
+use crate::{get_or_open_env, scan_bin_asset_paths, unique_chunks};
+
+pub(crate) use quartz_core::hash_db::{read_wwise_hashes, WWISE_HASH_FILE, WWISE_TABLE as WWISE_DB};
+
+/// HIRC object type of an Event.
+const HIRC_EVENT: u8 = 4;
+
+/// Event name prefixes League uses; names like these are kept even when the
+/// bank declaring them isn't in the same WAD.
+const EVENT_PREFIXES: &[&str] = &["play_", "stop_", "pause_", "resume_", "set_", "mute_", "unmute_"];
+
+pub(crate) fn wwise_id(name: &str) -> u32 {
+  let mut h: u32 = 0x811c9dc5;
+  for b in name.bytes().map(|b| b.to_ascii_lowercase()) {
+    h = h.wrapping_mul(0x01000193);
+    h ^= b as u32;
+  }
+  h
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+  data.get(pos..pos + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+pub(crate) struct HircObject<'a> {
+  pub kind: u8,
+  pub id: u32,
+  /// Object data after the ID.
+  pub body: &'a [u8],
+}
+
+pub(crate) struct Bank<'a> {
+  pub version: u32,
+  pub id: u32,
+  /// HIRC objects in bank order.
+  pub objects: Vec<HircObject<'a>>,
+  /// Bank names from the STID section.
+  pub names: Vec<(u32, String)>,
+}
+
+/// Sections of a `.bnk` this tool cares about; `None` for anything else.
+pub(crate) fn parse_bnk(data: &[u8]) -> Option<Bank<'_>> {
+  if !data.starts_with(b"BKHD") { return None; }
+  let mut bank = Bank { version: 0, id: 0, objects: Vec::new(), names: Vec::new() };
+  let mut pos = 0usize;
+  while pos + 8 <= data.len() {
+    let tag = &data[pos..pos + 4];
+    let Some(size) = read_u32(data, pos + 4) else { break };
+    let start = pos + 8;
+    let end = start.saturating_add(size as usize).min(data.len());
+    let section = &data[start..end];
+    match tag {
+      b"BKHD" => {
+        bank.version = read_u32(section, 0).unwrap_or(0);
+        bank.id = read_u32(section, 4).unwrap_or(0);
+      }
+      b"HIRC" => {
+        let count = read_u32(section, 0).unwrap_or(0);
+        let mut p = 4usize;
+        for _ in 0..count {
+          let (Some(&kind), Some(len)) = (section.get(p), read_u32(section, p + 1)) else { break };
+          let Some(object) = section.get(p + 5..p + 5 + len as usize) else { break };
+          if let Some(id) = read_u32(object, 0) {
+            bank.objects.push(HircObject { kind, id, body: &object[4..] });
+          }
+          p += 5 + len as usize;
+        }
+      }
+      b"STID" => {
+        let count = read_u32(section, 4).unwrap_or(0);
+        let mut p = 8usize;
+        for _ in 0..count {
+          let (Some(id), Some(&len)) = (read_u32(section, p), section.get(p + 4)) else { break };
+          let Some(raw) = section.get(p + 5..p + 5 + len as usize) else { break };
+          if let Ok(name) = std::str::from_utf8(raw) { bank.names.push((id, name.to_string())); }
+          p += 5 + len as usize;
+        }
+      }
+      _ => {}
+    }
+    pos = end;
+  }
+  Some(bank)
+}
+
+/// Event and bank IDs declared by a `.bnk`, plus the bank names its STID
+/// section spells out.
+fn scan_bnk(data: &[u8], ids: &mut HashSet<u32>, names: &mut HashMap<u32, String>) {
+  let Some(bank) = parse_bnk(data) else { return };
+  ids.insert(bank.id);
+  ids.extend(bank.objects.iter().filter(|o| o.kind == HIRC_EVENT).map(|o| o.id));
+  for (id, name) in bank.names {
+    ids.insert(id);
+    names.insert(id, name);
+  }
+}
+
+/// Identifier-like strings (letters, digits, underscores) in a bin: event
+/// names are stored as plain strings, so every candidate is hashed and
+/// matched against the bank IDs later.
+fn scan_bin_names(data: &[u8], out: &mut HashMap<u32, String>) {
+  if !data.starts_with(b"PROP") && !data.starts_with(b"PTCH") { return; }
+  let mut i = 0usize;
+  while i + 2 <= data.len() {
+    let len = u16::from_le_bytes([data[i], data[i + 1]]) as usize;
+    if (4..=128).contains(&len) {
+      if let Some(slice) = data.get(i + 2..i + 2 + len) {
+        if slice.contains(&b'_') && slice.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'_') {
+          // All ASCII, checked above.
+          let name = String::from_utf8_lossy(slice).into_owned();
+          out.entry(wwise_id(&name)).or_insert(name);
+          i += 2 + len;
+          continue;
+        }
+      }
+    }
+    i += 1;
+  }
+  // Bank IDs are the hash of the bank file name without extension.
+  for path in scan_bin_asset_paths(data) {
+    if !path.ends_with(".bnk") && !path.ends_with(".wpk") { continue; }
+    let file = path.rsplit('/').next().unwrap_or(&path);
+    let stem = file.rsplit_once('.').map(|(s, _)| s).unwrap_or(file);
+    out.entry(wwise_id(stem)).or_insert_with(|| stem.to_string());
+  }
+}
+
+/// Audio names and IDs gathered from a WAD's chunks.
+#[derive(Default)]
+pub(crate) struct WwiseScan {
+  ids: HashSet<u32>,
+  /// Names spelled out by banks (STID); always correct.
+  bank_names: HashMap<u32, String>,
+  /// Candidate names from bins, keyed by their Wwise ID.
+  candidates: HashMap<u32, String>,
+}
+
+impl WwiseScan {
+  pub(crate) fn scan(&mut self, data: &[u8]) {
+    scan_bnk(data, &mut self.ids, &mut self.bank_names);
+    scan_bin_names(data, &mut self.candidates);
+  }
+
+  pub(crate) fn merge(mut self, other: WwiseScan) -> WwiseScan {
+    self.ids.extend(other.ids);
+    for (k, v) in other.bank_names { self.bank_names.entry(k).or_insert(v); }
+    for (k, v) in other.candidates { self.candidates.entry(k).or_insert(v); }
+    self
+  }
+
+  /// Names known to be Wwise events or banks: declared by a bank, matching an
+  /// ID a bank declares, or following League's event naming.
+  pub(crate) fn resolved(self) -> HashMap<u32, String> {
+    let WwiseScan { ids, mut bank_names, candidates } = self;
+    for (id, name) in candidates {
+      let lower = name.to_ascii_lowercase();
+      if ids.contains(&id) || EVENT_PREFIXES.iter().any(|p| lower.starts_with(p)) {
+        bank_names.entry(id).or_insert(name);
+      }
+    }
+    bank_names
+  }
+}
+
+/// Merge newly found names into `hash_dir/hashes.wwise.txt`; existing entries win.
+pub(crate) fn save_wwise_hashes(hash_dir: &Path, found: &HashMap<u32, String>) -> Result<(), String> {
+  let path = hash_dir.join(WWISE_HASH_FILE);
+  let mut all = read_wwise_hashes(&path);
+  for (k, v) in found { all.entry(*k).or_insert_with(|| v.clone()); }
+  let mut entries: Vec<_> = all.iter().collect();
+  entries.sort_by(|a, b| a.1.cmp(b.1));
+  let mut out = String::with_capacity(entries.len() * 40);
+  for (id, name) in entries {
+    let _ = writeln!(out, "{:08x} {}", id, name);
+  }
+  fs::write(&path, out.as_bytes()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Known names for Wwise IDs from the hash dir: the LMDB `wwise` table, or
+/// `hashes.wwise.txt` when buildHashDb hasn't picked the file up yet.
+pub(crate) fn lookup_wwise_names(hash_dir: &str, ids: &[u32]) -> Vec<Option<String>> {
+  let env_opt = get_or_open_env(hash_dir);
+  let db_ctx = env_opt.as_deref().and_then(|env| {
+    let rtxn = env.read_txn().ok()?;
+    let db = env.open_database::<Bytes, Str>(&rtxn, Some(WWISE_DB)).ok()??;
+    Some((rtxn, db))
+  });
+  if let Some((rtxn, db)) = db_ctx {
+    return ids.iter().map(|id| db.get(&rtxn, &id.to_be_bytes()[..]).ok().flatten().map(str::to_string)).collect();
+  }
+  let text = read_wwise_hashes(&Path::new(hash_dir).join(WWISE_HASH_FILE));
+  ids.iter().map(|id| text.get(id).cloned()).collect()
+}
+
+// ── resolveWwiseHashes ───────────────────────────────────────────────────────
+
+fn parse_wwise_id(s: &str) -> Option<u32> {
+  let s = s.trim();
+  match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+    None if s.bytes().all(|b| b.is_ascii_digit()) => s.parse().ok(),
+    None => u32::from_str_radix(s, 16).ok(),
+  }
+}
+
+/// Resolve Wwise event/bank IDs (decimal or hex strings, as they appear in
+/// bins and bank dumps) to names. Unknown IDs come back unchanged.
+#[napi(js_name = "resolveWwiseHashes")]
+pub fn resolve_wwise_hashes(ids: Vec<String>, hash_dir: String) -> Vec<String> {
+  let parsed: Vec<Option<u32>> = ids.iter().map(|s| parse_wwise_id(s)).collect();
+  let known: Vec<u32> = parsed.iter().flatten().copied().collect();
+  let mut names = lookup_wwise_names(&hash_dir, &known).into_iter();
+  ids
+    .into_iter()
+    .zip(parsed)
+    .map(|(raw, id)| match id {
+      Some(_) => names.next().flatten().unwrap_or(raw),
+      None => raw,
+    })
+    .collect()
+}
+
+// ── exportAudioEvents ────────────────────────────────────────────────────────
+// Event -> actions -> played object -> (containers ->) sounds -> WEM IDs.
+// Container layouts change between Wwise versions, so their children are found
+// by looking for IDs of objects written before them in the same bank: Wwise
+// writes a bank's hierarchy children-first, which keeps a container's parent
+// (written after it) out of the match.
+
+const HIRC_SOUND: u8 = 2;
+const HIRC_ACTION: u8 = 3;
+const HIRC_MUSIC_TRACK: u8 = 11;
+/// High byte of the Play action types (0x0403 Play, ...).
+const ACTION_PLAY: u16 = 0x04;
+/// Last bank version storing an event's action count as a plain u32.
+const LAST_U32_ACTION_COUNT: u32 = 122;
+
+/// Wwise variable-length integer: 7 bits per byte, high bit set on all but the last.
+fn read_var(data: &[u8], pos: &mut usize) -> Option<u32> {
+  let mut value = 0u32;
+  loop {
+    let b = *data.get(*pos)?;
+    *pos += 1;
+    value = (value << 7) | (b & 0x7f) as u32;
+    if b & 0x80 == 0 { return Some(value); }
+  }
+}
+
+fn event_actions(version: u32, body: &[u8]) -> Vec<u32> {
+  let mut pos = 0usize;
+  let count = if version <= LAST_U32_ACTION_COUNT {
+    pos = 4;
+    read_u32(body, 0)
+  } else {
+    read_var(body, &mut pos)
+  };
+  (0..count.unwrap_or(0) as usize).map_while(|i| read_u32(body, pos + i * 4)).collect()
+}
+
+struct Hierarchy<'a> {
+  banks: Vec<Bank<'a>>,
+  /// Object ID -> (bank, index in the bank's HIRC).
+  objects: HashMap<u32, (usize, usize)>,
+}
+
+impl Hierarchy<'_> {
+  fn object(&self, id: u32) -> Option<(usize, usize, &HircObject<'_>)> {
+    let (b, i) = *self.objects.get(&id)?;
+    Some((b, i, &self.banks[b].objects[i]))
+  }
+
+  fn wems(&self, id: u32, memo: &mut HashMap<u32, Vec<u32>>) -> Vec<u32> {
+    if let Some(w) = memo.get(&id) { return w.clone(); }
+    // Placeholder guards against cycles in malformed banks.
+    memo.insert(id, Vec::new());
+    let Some((b, index, object)) = self.object(id) else { return Vec::new() };
+    let mut out = Vec::new();
+    match object.kind {
+      HIRC_SOUND => out.extend(read_u32(object.body, 5)),
+      HIRC_MUSIC_TRACK => {
+        let count = read_u32(object.body, 1).unwrap_or(0) as usize;
+        out.extend((0..count).map_while(|i| read_u32(object.body, 5 + i * 14 + 5)));
+      }
+      HIRC_EVENT | HIRC_ACTION => {}
+      _ => {
+        let mut children = Vec::new();
+        for pos in 0..object.body.len().saturating_sub(3) {
+          let Some(candidate) = read_u32(object.body, pos) else { break };
+          if let Some(&(cb, ci)) = self.objects.get(&candidate) {
+            if cb == b && ci < index && !children.contains(&candidate) { children.push(candidate); }
+          }
+        }
+        for child in children { out.extend(self.wems(child, memo)); }
+      }
+    }
+    out.sort_unstable();
+    out.dedup();
+    memo.insert(id, out.clone());
+    out
+  }
+}
+
+pub(crate) struct AudioEventRow {
+  pub event: Option<String>,
+  pub event_id: u32,
+  pub bank: Option<String>,
+  pub bank_id: u32,
+  /// WEM IDs played, sorted.
+  pub wems: Vec<u32>,
+}
+
+fn csv_field(s: &str) -> String {
+  if s.contains([',', '"', '\n']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_string() }
+}
+
+#[napi(object)]
+pub struct ExportAudioEventsResult {
+  pub success: bool,
+  pub error: Option<String>,
+  #[napi(js_name = "eventCount")]
+  pub event_count: u32,
+  /// Events whose name couldn't be resolved; listed by ID.
+  #[napi(js_name = "unnamedCount")]
+  pub unnamed_count: u32,
+}
+
+/// Audio names (from bins) and raw banks of one WAD.
+fn scan_wad_audio(wad_path: &Path) -> Result<(WwiseScan, Vec<Vec<u8>>), String> {
+  let file = fs::File::open(wad_path).map_err(|e| format!("Failed to open {}: {}", wad_path.display(), e))?;
+  let mmap = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to mmap {}: {}", wad_path.display(), e))?;
+  let wad = Wad::mount(Cursor::new(&mmap[..])).map_err(|e| format!("Failed to mount {}: {}", wad_path.display(), e))?;
+  let (chunks, _) = unique_chunks(wad.chunks());
+  let wad_data = &mmap[..];
+
+  type Found = (WwiseScan, Vec<Vec<u8>>);
+  Ok(run_cpu(|| chunks
+    .par_iter()
+    .fold(
+      || (WwiseScan::default(), Vec::new()),
+      |(mut scan, mut bnks): Found, chunk| {
+        let Ok(data) = decompress_chunk(wad_data, chunk) else { return (scan, bnks) };
+        scan.scan(&data);
+        if data.starts_with(b"BKHD") { bnks.push(data); }
+        (scan, bnks)
+      },
+    )
+    .reduce(
+      || (WwiseScan::default(), Vec::new()),
+      |(sa, mut ba): Found, (sb, bb)| {
+        ba.extend(bb);
+        (sa.merge(sb), ba)
+      },
+    )))
+}
+
+/// Events declared by the banks in `event_wads`, named from the bins of those
+/// and `name_wads` (VO banks ship in locale WADs, the bins naming their events
+/// in the champion WAD) and, failing that, the hash dir.
+pub(crate) fn audio_events(event_wads: &[&Path], name_wads: &[&Path], hash_dir: Option<&str>) -> Result<Vec<AudioEventRow>, String> {
+  let mut scan = WwiseScan::default();
+  let mut bnks = Vec::new();
+  for wad in event_wads {
+    let (s, b) = scan_wad_audio(wad)?;
+    scan = scan.merge(s);
+    bnks.extend(b);
+  }
+  for wad in name_wads {
+    scan = scan.merge(scan_wad_audio(wad)?.0);
+  }
+
+  let banks: Vec<Bank> = bnks.iter().filter_map(|d| parse_bnk(d)).collect();
+  let mut objects = HashMap::new();
+  for (b, bank) in banks.iter().enumerate() {
+    for (i, object) in bank.objects.iter().enumerate() { objects.entry(object.id).or_insert((b, i)); }
+  }
+  let hierarchy = Hierarchy { banks, objects };
+
+  let mut memo = HashMap::new();
+  let mut rows = Vec::new();
+  for bank in &hierarchy.banks {
+    for event in bank.objects.iter().filter(|o| o.kind == HIRC_EVENT) {
+      let mut wems = Vec::new();
+      for action in event_actions(bank.version, event.body) {
+        let Some((_, _, action)) = hierarchy.object(action) else { continue };
+        let (Some(kind), Some(target)) = (action.body.get(..2), read_u32(action.body, 2)) else { continue };
+        if u16::from_le_bytes([kind[0], kind[1]]) >> 8 == ACTION_PLAY {
+          wems.extend(hierarchy.wems(target, &mut memo));
+        }
+      }
+      wems.sort_unstable();
+      wems.dedup();
+      rows.push(AudioEventRow { event: None, event_id: event.id, bank: None, bank_id: bank.id, wems });
+    }
+  }
+
+  let mut names = scan.resolved();
+  if let Some(dir) = hash_dir {
+    let missing: Vec<u32> = rows
+      .iter()
+      .flat_map(|r| [r.event_id, r.bank_id])
+      .filter(|id| !names.contains_key(id))
+      .collect();
+    for (id, name) in missing.iter().zip(lookup_wwise_names(dir, &missing)) {
+      if let Some(name) = name { names.insert(*id, name); }
+    }
+  }
+  for row in &mut rows {
+    row.event = names.get(&row.event_id).cloned();
+    row.bank = names.get(&row.bank_id).cloned();
+  }
+  rows.sort_by(|a, b| (&a.bank, &a.event, a.event_id).cmp(&(&b.bank, &b.event, b.event_id)));
+  Ok(rows)
+}
+
+fn export_events(champion: &str, league: &Path, out_csv: &Path, hash_dir: Option<&str>) -> Result<(u32, u32), String> {
+  let wad_path = find_champion_wad(league, champion)
+    .ok_or_else(|| format!("Champion WAD not found for {}", champion))?;
+  let rows = audio_events(&[&wad_path], &[], hash_dir)?;
+
+  let mut out = String::from("event,eventId,bank,wemIds\n");
+  for row in &rows {
+    let wems: Vec<String> = row.wems.iter().map(u32::to_string).collect();
+    let _ = writeln!(
+      out,
+      "{},{},{},{}",
+      csv_field(row.event.as_deref().unwrap_or("")),
+      row.event_id,
+      csv_field(&row.bank.clone().unwrap_or_else(|| row.bank_id.to_string())),
+      wems.join(" "),
+    );
+  }
+  if let Some(parent) = out_csv.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+  }
+  write_retrying(out_csv, out.as_bytes()).map_err(|e| format!("Failed to write {}: {}", out_csv.display(), e))?;
+  let unnamed = rows.iter().filter(|r| r.event.is_none()).count() as u32;
+  Ok((rows.len() as u32, unnamed))
+}
+
+/// Write a CSV of a champion's audio events: name, ID, declaring bank and the
+/// WEM IDs each event plays, so sounds can be traced to the events that use them.
+#[napi(js_name = "exportAudioEvents")]
+pub fn export_audio_events(
+  champion: String,
+  league_path: String,
+  out_csv: String,
+  hash_dir: Option<String>,
+) -> ExportAudioEventsResult {
+  match export_events(&champion, Path::new(&league_path), Path::new(&out_csv), hash_dir.as_deref()) {
+    Ok((event_count, unnamed_count)) => ExportAudioEventsResult { success: true, error: None, event_count, unnamed_count },
+    Err(e) => ExportAudioEventsResult { success: false, error: Some(e), event_count: 0, unnamed_count: 0 },
+  }
+}
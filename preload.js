@@ -85,4 +85,11 @@ window.electronAPI = {
     decodeToDataUrl: (filePath) => ipcRenderer.invoke('texture:decodeToDataUrl', { filePath }),
   },
 
+  temp: {
+    /** Reserve a managed temp path under `category` (removed on exit). */
+    create: (category, options = {}) => ipcRenderer.invoke('temp:create', { category, ...options }),
+    /** Delete a managed temp file or folder early. */
+    release: (filePath) => ipcRenderer.invoke('temp:release', { path: filePath }),
+  },
+
 };
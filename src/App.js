@@ -182,6 +182,25 @@ const QuartzInteropBridge = () => {
     const onCheckNow = () => consume();
     ipcRenderer.on('interop:check-now', onCheckNow);
 
+    // "Open with Quartz": bins and ritobin text go to the bin editor, WADs to the WAD explorer.
+    const onOpenFile = (_event, payload) => {
+      const filePath = payload?.path;
+      if (!filePath) return;
+      if (payload.kind === 'wad') {
+        window.__QUARTZ_PENDING_WAD_OPEN = filePath;
+        const emit = () => window.dispatchEvent(new CustomEvent('quartz-open-wad', { detail: { path: filePath } }));
+        if ((window.location.hash || '#/') !== '#/wad-explorer') {
+          navigate('/wad-explorer');
+          setTimeout(emit, 220);
+        } else {
+          emit();
+        }
+        return;
+      }
+      processHandoff({ target_app: 'quartz', bin_path: filePath, mode: 'bineditor', action: 'open-bin' });
+    };
+    ipcRenderer.on('app:open-file', onOpenFile);
+
     consume();
     const timer = setInterval(consume, 5000);
 
@@ -190,6 +209,7 @@ const QuartzInteropBridge = () => {
       clearInterval(timer);
       clearTimeout(watchDebounce);
       ipcRenderer.removeListener('interop:check-now', onCheckNow);
+      ipcRenderer.removeListener('app:open-file', onOpenFile);
       try {
         watcher?.close();
       } catch {}
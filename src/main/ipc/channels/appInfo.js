@@ -7,6 +7,7 @@ function registerAppInfoChannels({
   LOG_DIR,
   LOG_FILE,
   logToFile,
+  portableMode,
 }) {
   ipcMain.handle('get-log-file-path', () => {
     return LOG_FILE;
@@ -31,6 +32,10 @@ function registerAppInfoChannels({
     return app.getPath('userData');
   });
 
+  ipcMain.handle('get-portable-mode', () => {
+    return portableMode || { portable: false, dataDir: null };
+  });
+
   ipcMain.handle('getAppPath', () => {
     return app.getAppPath();
   });
@@ -1,3 +1,5 @@
+const { tryLoadNativeWadIndexer } = require('./wadBumpath');
+
 function registerAudioChannels({
   ipcMain,
   app,
@@ -56,25 +58,16 @@ function registerAudioChannels({
       doReq(url);
     });
 
-    // Helper: download a single file to disk
-    const downloadFile = (url, destPath) => new Promise((resolve, reject) => {
-      fs.mkdirSync(path.dirname(destPath), { recursive: true });
-      const file = fs.createWriteStream(destPath);
-      file.on('error', (err) => { try { file.destroy(); } catch (_) { } reject(err); });
-      const doReq = (u, hops = 0) => {
-        if (hops > 5) return reject(new Error('Too many redirects'));
-        require('https').get(u, { headers: { 'User-Agent': 'Quartz-App' } }, (res) => {
-          if (res.statusCode >= 300 && res.statusCode < 400 && res.headers.location) {
-            res.resume();
-            return doReq(res.headers.location, hops + 1);
-          }
-          if (res.statusCode !== 200) { res.resume(); return reject(new Error(`HTTP ${res.statusCode}`)); }
-          res.pipe(file);
-          file.on('finish', () => file.close(resolve));
-        }).on('error', reject);
-      };
-      doReq(url);
-    });
+    // Helper: download a single file to disk via the native downloader
+    // (retries, resume from .part, proxy settings)
+    const downloadFile = async (url, destPath) => {
+      const nativeAddon = tryLoadNativeWadIndexer();
+      if (!nativeAddon || typeof nativeAddon.downloadFileAsync !== 'function') {
+        throw new Error('Native addon unavailable — rebuild wad_indexer');
+      }
+      const result = await nativeAddon.downloadFileAsync(url, destPath);
+      if (!result?.success) throw new Error(result?.error || `Failed to download ${url}`);
+    };
 
     try {
       fs.mkdirSync(AUDIO_TOOLS_ROOT, { recursive: true });
@@ -0,0 +1,164 @@
+// "Open with Quartz" file associations, one toggle per extension.
+// Each extension gets its own ProgId under HKCU\Software\Classes and is listed in
+// the extension's OpenWithProgids, so Quartz shows up in "Open with" without
+// taking over the user's default app. Launches pass `--open-file "<path>"`.
+const FILE_ASSOCIATIONS = {
+  bin: { extension: '.bin', progId: 'Quartz.Bin', description: 'League bin file' },
+  py: { extension: '.py', progId: 'Quartz.Ritobin', description: 'Ritobin text file' },
+  // Windows sees .wad.client as the .client extension.
+  wadClient: { extension: '.client', progId: 'Quartz.Wad', description: 'League WAD archive' },
+};
+
+function registerFileAssociationChannels({
+  ipcMain,
+  exec,
+  app,
+  path,
+  processRef,
+  isDev,
+  logToFile,
+}) {
+  const execRegistryCommand = (command) => {
+    return new Promise((resolve, reject) => {
+      exec(command, (error, stdout, stderr) => {
+        if (error) {
+          logToFile(`Registry command failed: ${command}`, 'ERROR');
+          logToFile(`Error: ${error.message}`, 'ERROR');
+          reject(error);
+        } else {
+          resolve({ stdout, stderr });
+        }
+      });
+    });
+  };
+
+  const launchCommand = () => {
+    const appPath = app.getAppPath();
+    const appExe = processRef.execPath;
+    return isDev
+      ? `\\"${appExe}\\" \\"${appPath}\\" --open-file \\"%1\\"`
+      : `\\"${appExe}\\" --open-file \\"%1\\"`;
+  };
+
+  const iconPath = () => {
+    const appPath = app.getAppPath();
+    return isDev
+      ? path.join(appPath, 'public', 'divinelab.ico')
+      : path.join(path.dirname(appPath), 'divinelab.ico');
+  };
+
+  const isRegistered = async ({ extension, progId }) => {
+    try {
+      await execRegistryCommand(`reg query "HKCU\\Software\\Classes\\${extension}\\OpenWithProgids" /v "${progId}"`);
+      return true;
+    } catch {
+      return false;
+    }
+  };
+
+  const register = async ({ extension, progId, description }) => {
+    const root = `HKCU\\Software\\Classes\\${progId}`;
+    const commands = [
+      `reg add "${root}" /ve /d "${description}" /f`,
+      `reg add "${root}\\DefaultIcon" /ve /d "${iconPath()}" /f`,
+      `reg add "${root}\\shell\\open" /v "FriendlyAppName" /d "Quartz" /f`,
+      `reg add "${root}\\shell\\open\\command" /ve /d "${launchCommand()}" /f`,
+      `reg add "HKCU\\Software\\Classes\\${extension}\\OpenWithProgids" /v "${progId}" /t REG_NONE /f`,
+    ];
+    for (const command of commands) {
+      await execRegistryCommand(command);
+    }
+  };
+
+  const unregister = async ({ extension, progId }) => {
+    const commands = [
+      `reg delete "HKCU\\Software\\Classes\\${extension}\\OpenWithProgids" /v "${progId}" /f`,
+      `reg delete "HKCU\\Software\\Classes\\${progId}" /f`,
+    ];
+    for (const command of commands) {
+      try {
+        await execRegistryCommand(command);
+      } catch (error) {
+        logToFile(`Registry delete warning (key may not exist): ${error.message}`, 'WARN');
+      }
+    }
+  };
+
+  const getStatus = async () => {
+    const status = {};
+    for (const [key, association] of Object.entries(FILE_ASSOCIATIONS)) {
+      status[key] = await isRegistered(association);
+    }
+    return status;
+  };
+
+  // Re-point registered associations at the current executable after a reinstall.
+  const refreshFileAssociationsIfStale = async () => {
+    if (processRef.platform !== 'win32') return;
+    for (const association of Object.values(FILE_ASSOCIATIONS)) {
+      if (!(await isRegistered(association))) continue;
+      try {
+        const result = await execRegistryCommand(`reg query "HKCU\\Software\\Classes\\${association.progId}\\shell\\open\\command" /ve`);
+        if (result.stdout.includes(processRef.execPath)) continue;
+      } catch {
+        // Missing command key: re-register below.
+      }
+      logToFile(`File association ${association.progId} is stale — re-registering`, 'INFO');
+      try {
+        await register(association);
+      } catch (error) {
+        logToFile(`Failed to refresh file association ${association.progId}: ${error.message}`, 'ERROR');
+      }
+    }
+  };
+
+  ipcMain.handle('fileAssociations:get', async () => {
+    if (processRef.platform !== 'win32') {
+      return { success: false, error: 'File associations only supported on Windows', associations: {} };
+    }
+    return { success: true, associations: await getStatus() };
+  });
+
+  // toggles: { bin?: boolean, py?: boolean, wadClient?: boolean }; omitted keys are left as they are.
+  ipcMain.handle('fileAssociations:set', async (_event, toggles = {}) => {
+    if (processRef.platform !== 'win32') {
+      return { success: false, error: 'File associations only supported on Windows', associations: {} };
+    }
+    try {
+      for (const [key, enabled] of Object.entries(toggles || {})) {
+        const association = FILE_ASSOCIATIONS[key];
+        if (!association || typeof enabled !== 'boolean') continue;
+        if (enabled) {
+          await register(association);
+        } else {
+          await unregister(association);
+        }
+        logToFile(`File association ${association.extension} ${enabled ? 'registered' : 'unregistered'}`, 'INFO');
+      }
+      return { success: true, associations: await getStatus() };
+    } catch (error) {
+      logToFile(`Failed to update file associations: ${error.message}`, 'ERROR');
+      return { success: false, error: error.message, associations: await getStatus() };
+    }
+  });
+
+  ipcMain.handle('fileAssociations:unregisterAll', async () => {
+    if (processRef.platform !== 'win32') {
+      return { success: false, error: 'File associations only supported on Windows', associations: {} };
+    }
+    for (const association of Object.values(FILE_ASSOCIATIONS)) {
+      await unregister(association);
+    }
+    logToFile('All file associations unregistered', 'INFO');
+    return { success: true, associations: await getStatus() };
+  });
+
+  return {
+    refreshFileAssociationsIfStale,
+  };
+}
+
+module.exports = {
+  FILE_ASSOCIATIONS,
+  registerFileAssociationChannels,
+};
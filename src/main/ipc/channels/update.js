@@ -6,6 +6,9 @@ function registerUpdateChannels({
   autoUpdater,
   logToFile,
   checkUpdatesViaGitHubAPI,
+  getUpdateSettings,
+  setUpdateChannel,
+  setBackgroundCheck,
   shell,
   getCachedUpdateInfo,
 }) {
@@ -131,6 +134,33 @@ function registerUpdateChannels({
     }
   });
 
+  ipcMain.handle('update:get-settings', async () => {
+    try {
+      return { success: true, ...getUpdateSettings() };
+    } catch (error) {
+      return { success: false, error: error.message };
+    }
+  });
+
+  ipcMain.handle('update:set-channel', async (_event, channel) => {
+    try {
+      return { success: true, ...setUpdateChannel(channel) };
+    } catch (error) {
+      logToFile(`Failed to set update channel: ${error.message}`, 'ERROR');
+      return { success: false, error: error.message };
+    }
+  });
+
+  // options: { enabled?: boolean, intervalHours?: number }
+  ipcMain.handle('update:set-background-check', async (_event, options) => {
+    try {
+      return { success: true, ...setBackgroundCheck(options) };
+    } catch (error) {
+      logToFile(`Failed to configure background update checks: ${error.message}`, 'ERROR');
+      return { success: false, error: error.message };
+    }
+  });
+
   ipcMain.handle('update:install', async () => {
     try {
       if (isDev || !app.isPackaged) {
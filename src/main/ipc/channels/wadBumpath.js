@@ -1251,11 +1251,10 @@ function registerWadBumpathChannels({
 
   // ---------------------------------------------------------------------------
   // wad:readBinAsText — read a .bin chunk from a WAD and return it as ritobin
-  // text (fake-python format). Writes to a temp file, converts via native addon,
-  // reads output, then cleans up.
+  // text (fake-python format). Writes to a managed temp file, converts via native
+  // addon, reads output, then releases the temp files.
   // ---------------------------------------------------------------------------
   ipcMain.handle('wad:readBinAsText', async (_event, data) => {
-    const os = require('os');
     let fd = null;
     let tempBin = null;
     let tempPy = null;
@@ -1289,18 +1288,22 @@ function registerWadBumpathChannels({
       const payload = chunk.data ? Buffer.from(chunk.data) : Buffer.alloc(0);
       if (payload.length === 0) return { error: 'Chunk payload is empty' };
 
-      // Write to temp .bin file
-      const tmpDir = os.tmpdir();
-      const uid = `wadbin_${Date.now()}_${Math.random().toString(36).slice(2)}`;
-      tempBin = path.join(tmpDir, `${uid}.bin`);
-      tempPy  = path.join(tmpDir, `${uid}.py`);
-      nodeFs.writeFileSync(tempBin, payload);
-
-      // Convert via native addon
       const nativeAddon = tryLoadNativeWadIndexer();
       if (!nativeAddon || typeof nativeAddon.binToPy !== 'function') {
         return { error: 'Native addon unavailable — rebuild wad_indexer' };
       }
+
+      // Write to a managed temp .bin file (removed on exit even if we crash here)
+      const binTemp = nativeAddon.createTempFile('wad-bins', null, 'bin');
+      const pyTemp = nativeAddon.createTempFile('wad-bins', null, 'py');
+      if (!binTemp.success || !pyTemp.success) {
+        return { error: binTemp.error || pyTemp.error };
+      }
+      tempBin = binTemp.path;
+      tempPy = pyTemp.path;
+      nodeFs.writeFileSync(tempBin, payload);
+
+      // Convert via native addon
       let converted = false;
       try {
         let hashDir = null;
@@ -1325,9 +1328,31 @@ function registerWadBumpathChannels({
       return { error: e.message };
     } finally {
       if (fd) await fd.close().catch(() => {});
-      try { if (tempBin && nodeFs.existsSync(tempBin)) nodeFs.unlinkSync(tempBin); } catch (_) {}
-      try { if (tempPy  && nodeFs.existsSync(tempPy))  nodeFs.unlinkSync(tempPy);  } catch (_) {}
+      const nativeAddon = tryLoadNativeWadIndexer();
+      if (tempBin) nativeAddon?.releaseTempFile(tempBin);
+      if (tempPy) nativeAddon?.releaseTempFile(tempPy);
+    }
+  });
+
+  // ---------------------------------------------------------------------------
+  // temp:create / temp:release — managed temp paths for renderer features
+  // (model previews). They live in the main process's temp session, so they are
+  // removed on exit and by orphan cleanup after a crash.
+  // ---------------------------------------------------------------------------
+  ipcMain.handle('temp:create', async (_event, data) => {
+    const nativeAddon = tryLoadNativeWadIndexer();
+    if (!nativeAddon || typeof nativeAddon.createTempFile !== 'function') {
+      return { success: false, error: 'Native addon unavailable — rebuild wad_indexer' };
+    }
+    return nativeAddon.createTempFile(String(data?.category || ''), data?.name ?? null, data?.extension ?? null);
+  });
+
+  ipcMain.handle('temp:release', async (_event, data) => {
+    const nativeAddon = tryLoadNativeWadIndexer();
+    if (!nativeAddon || typeof nativeAddon.releaseTempFile !== 'function') {
+      return { success: false, error: 'Native addon unavailable — rebuild wad_indexer' };
     }
+    return nativeAddon.releaseTempFile(String(data?.path || ''));
   });
 
   // ---------------------------------------------------------------------------
@@ -6,6 +6,7 @@ function registerAppLifecycleHandlers({
   createWindow,
   clearTextureCacheOnQuit,
   cleanupMeiFolders,
+  cleanupSessionTempFiles,
   clearSavedBinPaths,
   getQuitState,
   setQuitState,
@@ -81,6 +82,10 @@ function registerAppLifecycleHandlers({
     }
   });
 
+  app.on('will-quit', () => {
+    cleanupSessionTempFiles();
+  });
+
   app.on('activate', () => {
     if (BrowserWindow.getAllWindows().length === 0) {
       createWindow();
@@ -1,10 +1,20 @@
-function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https, logToFile }) {
+function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https, logToFile, loadPrefs, savePrefs }) {
   let updateWindow = null;
   let cachedUpdateInfo = null;
+  let backgroundCheckTimer = null;
+  let updaterActive = false;
+  const UPDATE_CHANNELS = ['stable', 'beta'];
+  const DEFAULT_CHECK_INTERVAL_HOURS = 6;
+  const MIN_CHECK_INTERVAL_HOURS = 1;
+  // setInterval delays above 2^31-1 ms (~596h) fire immediately, so cap well below that.
+  const MAX_CHECK_INTERVAL_HOURS = 168;
   const UPDATE_REPOS = [
     { owner: 'LeagueToolkit', repo: 'Quartz' },
   ];
 
+  // `npm run deploy` publishes the installer's .blockmap next to latest.yml, so
+  // electron-updater fetches only the changed blocks and falls back to the full
+  // installer when there is no blockmap or the patch fails.
   autoUpdater.autoDownload = false;
   autoUpdater.autoInstallOnAppQuit = true;
 
@@ -20,6 +30,42 @@ function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https,
     autoUpdater.updateConfigPath = null;
   }
 
+  // Update preferences (stored in preferences.json):
+  //   UpdateChannel             'stable' | 'beta' (beta also offers GitHub pre-releases)
+  //   AutoCheckUpdates          background checks on startup and every interval (default on)
+  //   UpdateCheckIntervalHours  hours between background checks (default 6, 1 to 168)
+  function getUpdateSettings() {
+    const prefs = loadPrefs();
+    const channel = UPDATE_CHANNELS.includes(prefs.UpdateChannel) ? prefs.UpdateChannel : 'stable';
+    const hours = Number(prefs.UpdateCheckIntervalHours);
+    return {
+      channel,
+      autoCheck: prefs.AutoCheckUpdates !== false,
+      intervalHours: Number.isFinite(hours) && hours > 0
+        ? Math.min(MAX_CHECK_INTERVAL_HOURS, Math.max(MIN_CHECK_INTERVAL_HOURS, hours))
+        : DEFAULT_CHECK_INTERVAL_HOURS,
+    };
+  }
+
+  function applyUpdateChannel(channel) {
+    autoUpdater.allowPrerelease = channel === 'beta';
+    // Going back to stable from a beta must be allowed to offer the lower stable version.
+    autoUpdater.allowDowngrade = channel === 'stable' && app.getVersion().includes('-');
+  }
+
+  function setUpdateChannel(channel) {
+    if (!UPDATE_CHANNELS.includes(channel)) {
+      throw new Error(`Unknown update channel: ${channel}`);
+    }
+    const prefs = loadPrefs();
+    prefs.UpdateChannel = channel;
+    savePrefs(prefs);
+    applyUpdateChannel(channel);
+    cachedUpdateInfo = null;
+    logToFile(`Update channel set to ${channel}`, 'INFO');
+    return getUpdateSettings();
+  }
+
   function setUpdateWindow(win) {
     updateWindow = win;
   }
@@ -34,21 +80,30 @@ function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https,
 
   async function checkUpdatesViaGitHubAPI() {
     const compareVersions = (v1, v2) => {
-      const parts1 = v1.split('.').map(Number);
-      const parts2 = v2.split('.').map(Number);
+      const parts1 = v1.split('-')[0].split('.').map(Number);
+      const parts2 = v2.split('-')[0].split('.').map(Number);
       for (let i = 0; i < Math.max(parts1.length, parts2.length); i++) {
         const a = parts1[i] || 0;
         const b = parts2[i] || 0;
         if (a < b) return -1;
         if (a > b) return 1;
       }
+      // Same numbers: a pre-release (2.7.0-beta.1) comes before the release (2.7.0).
+      const pre1 = v1.includes('-');
+      const pre2 = v2.includes('-');
+      if (pre1 !== pre2) return pre1 ? -1 : 1;
       return 0;
     };
 
+    const includePrerelease = getUpdateSettings().channel === 'beta';
+
     const fetchLatestFromRepo = (owner, repo) => new Promise((resolve, reject) => {
       const options = {
         hostname: 'api.github.com',
-        path: `/repos/${owner}/${repo}/releases/latest`,
+        // /releases/latest never returns pre-releases; the beta channel takes the newest of the list.
+        path: includePrerelease
+          ? `/repos/${owner}/${repo}/releases?per_page=10`
+          : `/repos/${owner}/${repo}/releases/latest`,
         method: 'GET',
         headers: {
           'User-Agent': 'Quartz-App',
@@ -67,7 +122,12 @@ function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https,
             return;
           }
           try {
-            const release = JSON.parse(data);
+            const parsed = JSON.parse(data);
+            const release = Array.isArray(parsed) ? parsed.find((r) => !r.draft) : parsed;
+            if (!release) {
+              reject(new Error(`${owner}/${repo} has no releases`));
+              return;
+            }
             resolve({
               owner,
               repo,
@@ -163,6 +223,8 @@ function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https,
     }
 
     logToFile('Setting up auto-updater', 'INFO');
+    updaterActive = true;
+    applyUpdateChannel(getUpdateSettings().channel);
 
     autoUpdater.on('checking-for-update', () => {
       logToFile('Checking for update...', 'INFO');
@@ -233,44 +295,90 @@ function createAutoUpdaterService({ autoUpdater, app, isDev, processRef, https,
       }
     });
 
-    setTimeout(() => {
-      try {
-        logToFile('Checking for updates...', 'INFO');
-        logToFile(`Current version: ${app.getVersion()}`, 'INFO');
-        logToFile(`isDev: ${isDev}, isPackaged: ${app.isPackaged}`, 'INFO');
-
-        if (enableInDev) {
-          autoUpdater.setFeedURL({
-            provider: 'github',
-            owner: 'LeagueToolkit',
-            repo: 'Quartz',
-          });
-          autoUpdater.forceDevUpdateConfig = true;
-          logToFile('Calling checkForUpdatesAndNotify (dev mode)...', 'INFO');
-          autoUpdater.checkForUpdatesAndNotify().catch((err) => {
-            logToFile(`Update check error: ${err.message}`, 'ERROR');
-            logToFile(`Stack trace: ${err.stack}`, 'ERROR');
-          });
-        } else {
-          logToFile('Production mode - checking for updates via electron-updater', 'INFO');
-          autoUpdater.checkForUpdates().catch((err) => {
-            logToFile(`Update check failed: ${err.message}`, 'ERROR');
-            logToFile('Trying fallback GitHub API check...', 'INFO');
-            checkUpdatesViaGitHubAPI().catch((fallbackErr) => {
-              logToFile(`Fallback check failed: ${fallbackErr.message}`, 'ERROR');
-            });
+    const { autoCheck } = getUpdateSettings();
+    if (autoCheck) {
+      setTimeout(runUpdateCheck, 3000);
+    } else {
+      logToFile('Background update checks are disabled in preferences', 'INFO');
+    }
+    scheduleBackgroundChecks();
+  }
+
+  function runUpdateCheck() {
+    const enableInDev = processRef.env.ENABLE_AUTO_UPDATER === 'true';
+    try {
+      logToFile('Checking for updates...', 'INFO');
+      logToFile(`Current version: ${app.getVersion()}`, 'INFO');
+      logToFile(`isDev: ${isDev}, isPackaged: ${app.isPackaged}`, 'INFO');
+
+      if (enableInDev) {
+        autoUpdater.setFeedURL({
+          provider: 'github',
+          owner: 'LeagueToolkit',
+          repo: 'Quartz',
+        });
+        autoUpdater.forceDevUpdateConfig = true;
+        logToFile('Calling checkForUpdatesAndNotify (dev mode)...', 'INFO');
+        autoUpdater.checkForUpdatesAndNotify().catch((err) => {
+          logToFile(`Update check error: ${err.message}`, 'ERROR');
+          logToFile(`Stack trace: ${err.stack}`, 'ERROR');
+        });
+      } else {
+        logToFile('Production mode - checking for updates via electron-updater', 'INFO');
+        autoUpdater.checkForUpdates().catch((err) => {
+          logToFile(`Update check failed: ${err.message}`, 'ERROR');
+          logToFile('Trying fallback GitHub API check...', 'INFO');
+          checkUpdatesViaGitHubAPI().catch((fallbackErr) => {
+            logToFile(`Fallback check failed: ${fallbackErr.message}`, 'ERROR');
           });
-        }
-      } catch (err) {
-        logToFile(`Failed to check for updates: ${err.message}`, 'ERROR');
-        logToFile(`Stack trace: ${err.stack}`, 'ERROR');
+        });
+      }
+    } catch (err) {
+      logToFile(`Failed to check for updates: ${err.message}`, 'ERROR');
+      logToFile(`Stack trace: ${err.stack}`, 'ERROR');
+    }
+  }
+
+  // Re-reads the preferences, so call it again after changing them.
+  function scheduleBackgroundChecks() {
+    if (backgroundCheckTimer) {
+      clearInterval(backgroundCheckTimer);
+      backgroundCheckTimer = null;
+    }
+    const { autoCheck, intervalHours } = getUpdateSettings();
+    if (!autoCheck) return;
+    backgroundCheckTimer = setInterval(() => {
+      logToFile('Background update check', 'INFO');
+      runUpdateCheck();
+    }, intervalHours * 60 * 60 * 1000);
+    logToFile(`Background update checks every ${intervalHours}h`, 'INFO');
+  }
+
+  function setBackgroundCheck({ enabled, intervalHours } = {}) {
+    const prefs = loadPrefs();
+    if (typeof enabled === 'boolean') {
+      prefs.AutoCheckUpdates = enabled;
+    }
+    if (intervalHours !== undefined) {
+      const hours = Number(intervalHours);
+      if (!Number.isFinite(hours) || hours < MIN_CHECK_INTERVAL_HOURS || hours > MAX_CHECK_INTERVAL_HOURS) {
+        throw new Error(`Update check interval must be between ${MIN_CHECK_INTERVAL_HOURS} and ${MAX_CHECK_INTERVAL_HOURS} hours`);
       }
-    }, 3000);
+      prefs.UpdateCheckIntervalHours = hours;
+    }
+    savePrefs(prefs);
+    if (updaterActive) {
+      scheduleBackgroundChecks();
+    }
+    return getUpdateSettings();
   }
 
   return {
     setupAutoUpdater,
     checkUpdatesViaGitHubAPI,
+    getUpdateSettings,
+    setUpdateChannel,
+    setBackgroundCheck,
     setUpdateWindow,
     getUpdateWindow,
     getCachedUpdateInfo,
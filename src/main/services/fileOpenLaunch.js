@@ -0,0 +1,80 @@
+// Routes files opened through "Open with Quartz" (`--open-file <path>`) to the
+// renderer as `app:open-file` { path, kind }, where kind is 'bin', 'py' or 'wad'.
+function createFileOpenLaunchService({ fs, logToFile, getMainWindow }) {
+  let pendingOpenPath = null;
+
+  const log = (message, level = 'INFO') => {
+    try {
+      logToFile(message, level);
+    } catch (_) {}
+  };
+
+  const fileKind = (filePath) => {
+    const lower = String(filePath || '').toLowerCase();
+    if (lower.endsWith('.bin')) return 'bin';
+    if (lower.endsWith('.py')) return 'py';
+    if (lower.endsWith('.wad.client') || lower.endsWith('.wad')) return 'wad';
+    return null;
+  };
+
+  const extractOpenPath = (args = []) => {
+    const list = Array.isArray(args) ? args : [];
+    const flagIdx = list.indexOf('--open-file');
+    if (flagIdx === -1) return null;
+    // In dev, Electron may inject Chromium flags after our custom flag.
+    const target = list.slice(flagIdx + 1).find((arg) => typeof arg === 'string' && fileKind(arg));
+    if (target) log(`[FileOpenLaunch] detected --open-file target: ${target}`, 'INFO');
+    return target || null;
+  };
+
+  const sendOpenToRenderer = (targetPath) => {
+    if (!targetPath || !fs.existsSync(targetPath)) {
+      log(`[FileOpenLaunch] send skipped: file does not exist: ${targetPath}`, 'WARN');
+      return false;
+    }
+    const win = getMainWindow();
+    if (!win || win.isDestroyed()) {
+      log('[FileOpenLaunch] send deferred: main window missing/destroyed', 'WARN');
+      return false;
+    }
+
+    const payload = { path: targetPath, kind: fileKind(targetPath) };
+    const send = () => {
+      try {
+        win.webContents.send('app:open-file', payload);
+      } catch (e) {
+        log(`[FileOpenLaunch] send failed: ${e.message}`, 'ERROR');
+      }
+    };
+    if (win.webContents.isLoading()) {
+      win.webContents.once('did-finish-load', send);
+    } else {
+      send();
+    }
+    return true;
+  };
+
+  return {
+    initFromStartupArgs(args) {
+      pendingOpenPath = extractOpenPath(args);
+      return pendingOpenPath;
+    },
+    handleSecondInstanceArgs(args) {
+      const targetPath = extractOpenPath(args);
+      if (targetPath && !sendOpenToRenderer(targetPath)) {
+        pendingOpenPath = targetPath;
+      }
+      return targetPath;
+    },
+    flushPendingOnReady() {
+      if (!pendingOpenPath) return;
+      const initialPath = pendingOpenPath;
+      pendingOpenPath = null;
+      if (!sendOpenToRenderer(initialPath)) {
+        setTimeout(() => sendOpenToRenderer(initialPath), 1200);
+      }
+    },
+  };
+}
+
+module.exports = { createFileOpenLaunchService };
@@ -0,0 +1,36 @@
+const PORTABLE_FLAG = 'portable.flag';
+const PORTABLE_DATA_DIR = 'data';
+
+/**
+ * Folder the user launched Quartz from. electron-builder's portable target
+ * extracts to a temp dir and reports the real location in PORTABLE_EXECUTABLE_DIR.
+ */
+function getExecutableDir({ app, path, processRef }) {
+  if (processRef.env.PORTABLE_EXECUTABLE_DIR) return processRef.env.PORTABLE_EXECUTABLE_DIR;
+  if (!app.isPackaged) return app.getAppPath();
+  return path.dirname(processRef.execPath);
+}
+
+/**
+ * Portable mode: when `portable.flag` sits next to the executable, keep all
+ * app data (preferences, logs, hashes, caches) in `./data` beside it instead
+ * of AppData. Must run before anything reads `userData` or APPDATA, i.e.
+ * before the logger and prefs store are created.
+ */
+function applyPortableMode({ app, fs, path, processRef }) {
+  const exeDir = getExecutableDir({ app, path, processRef });
+  if (!fs.existsSync(path.join(exeDir, PORTABLE_FLAG))) {
+    return { portable: false, dataDir: null };
+  }
+
+  const dataDir = path.join(exeDir, PORTABLE_DATA_DIR);
+  fs.mkdirSync(dataDir, { recursive: true });
+  app.setPath('userData', path.join(dataDir, 'Quartz'));
+  app.setPath('sessionData', path.join(dataDir, 'Quartz', 'session'));
+  // FrogTools (hashes, ritobin) is resolved from APPDATA throughout the app,
+  // so point it at the portable folder too; child processes inherit this.
+  processRef.env.APPDATA = dataDir;
+  return { portable: true, dataDir };
+}
+
+module.exports = { applyPortableMode, PORTABLE_FLAG };
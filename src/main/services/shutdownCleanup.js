@@ -1,4 +1,4 @@
-function createShutdownCleanup({ fs, path, logToFile }) {
+function createShutdownCleanup({ fs, path, logToFile, loadNativeAddon }) {
   let textureCacheCleared = false;
 
   function clearTextureCacheOnQuit() {
@@ -121,9 +121,39 @@ function createShutdownCleanup({ fs, path, logToFile }) {
     }
   }
 
+  // Managed temp files (previews, open-from-WAD bins, ...) live in a
+  // per-process session under {temp}/quartz, owned by the native addon.
+  function runNativeTempCleanup(fnName, label) {
+    try {
+      const nativeAddon = loadNativeAddon?.();
+      if (!nativeAddon || typeof nativeAddon[fnName] !== 'function') {
+        return;
+      }
+      const result = nativeAddon[fnName]();
+      if (!result?.success) {
+        logToFile(`${label} failed: ${result?.error}`, 'WARN');
+      } else if (result.removedCount > 0) {
+        const freedMB = (result.freedBytes / (1024 * 1024)).toFixed(2);
+        logToFile(`${label}: removed ${result.removedCount} item(s), freed ${freedMB} MB`, 'INFO');
+      }
+    } catch (error) {
+      logToFile(`Error during ${label.toLowerCase()}: ${error.message}`, 'ERROR');
+    }
+  }
+
+  function cleanupOrphanTempFiles() {
+    runNativeTempCleanup('cleanupOrphanTempFiles', 'Orphan temp cleanup');
+  }
+
+  function cleanupSessionTempFiles() {
+    runNativeTempCleanup('cleanupTempFiles', 'Session temp cleanup');
+  }
+
   return {
     clearTextureCacheOnQuit,
     cleanupMeiFolders,
+    cleanupOrphanTempFiles,
+    cleanupSessionTempFiles,
   };
 }
 
@@ -585,7 +585,8 @@ export default function BinEditorV2() {
             const mode = String(handoff.mode || 'paint').toLowerCase();
             const action = String(handoff.action || 'open-bin').toLowerCase();
             if (mode !== 'bineditor') return;
-            if (!handoff.bin_path.toLowerCase().endsWith('.bin')) return;
+            const lowerPath = handoff.bin_path.toLowerCase();
+            if (!lowerPath.endsWith('.bin') && !lowerPath.endsWith('.py')) return;
 
             const isReload = action === 'reload-bin';
             const isSameFile = binPath && String(binPath).toLowerCase() === String(handoff.bin_path).toLowerCase();
@@ -142,7 +142,10 @@ const ModernSettings = () => {
     updateError,
     handleCheckForUpdates,
     handleDownloadUpdate,
-    handleInstallUpdate
+    handleInstallUpdate,
+    updateSettings,
+    handleSetUpdateChannel,
+    handleSetBackgroundCheck
   } = useUpdateSettings();
   const [highlightUpdateSection, setHighlightUpdateSection] = useState(false);
   const updateSectionRef = useRef(null);
@@ -174,7 +177,10 @@ const ModernSettings = () => {
   const {
     contextMenuEnabled,
     contextMenuLoading,
-    handleToggleContextMenu
+    handleToggleContextMenu,
+    fileAssociations,
+    fileAssociationsLoading,
+    handleToggleFileAssociation
   } = useWindowsIntegrationSettings();
 
 
@@ -1845,6 +1851,9 @@ const ModernSettings = () => {
             handleCheckForUpdates={handleCheckForUpdates}
             handleDownloadUpdate={handleDownloadUpdate}
             handleInstallUpdate={handleInstallUpdate}
+            updateSettings={updateSettings}
+            handleSetUpdateChannel={handleSetUpdateChannel}
+            handleSetBackgroundCheck={handleSetBackgroundCheck}
           />
         );
       case 'windowsIntegration':
@@ -1853,6 +1862,9 @@ const ModernSettings = () => {
             contextMenuEnabled={contextMenuEnabled}
             handleToggleContextMenu={handleToggleContextMenu}
             contextMenuLoading={contextMenuLoading}
+            fileAssociations={fileAssociations}
+            fileAssociationsLoading={fileAssociationsLoading}
+            handleToggleFileAssociation={handleToggleFileAssociation}
             windowsIntegrationSectionRef={windowsIntegrationSectionRef}
             highlightWindowsIntegrationSection={highlightWindowsIntegrationSection}
           />
@@ -1,6 +1,16 @@
 import React from 'react';
 import { Download, AlertTriangle, Check, RefreshCw, Upload, FolderOpen } from 'lucide-react';
-import { FormGroup, StatusBadge, Button, ToggleSwitch, InputWithButton } from '../SettingsPrimitives';
+import { FormGroup, StatusBadge, Button, ToggleSwitch, InputWithButton, CustomSelect } from '../SettingsPrimitives';
+
+const UPDATE_INTERVAL_OPTIONS = [
+  { value: 1, label: 'Every hour' },
+  { value: 3, label: 'Every 3 hours' },
+  { value: 6, label: 'Every 6 hours' },
+  { value: 12, label: 'Every 12 hours' },
+  { value: 24, label: 'Every day' },
+  { value: 72, label: 'Every 3 days' },
+  { value: 168, label: 'Every week' },
+];
 
 const ToolsSection = ({
   settings,
@@ -20,7 +30,10 @@ const ToolsSection = ({
   updateError,
   handleCheckForUpdates,
   handleDownloadUpdate,
-  handleInstallUpdate
+  handleInstallUpdate,
+  updateSettings,
+  handleSetUpdateChannel,
+  handleSetBackgroundCheck
 }) => {
   return (
     <div style={{ display: 'flex', flexDirection: 'column', gap: '20px' }}>
@@ -193,6 +206,40 @@ const ToolsSection = ({
               </Button>
             )}
           </div>
+
+          <div style={{ display: 'flex', flexDirection: 'column', gap: '12px', marginTop: '16px', paddingTop: '16px', borderTop: '1px solid var(--settings-card-border, rgba(255, 255, 255, 0.08))' }}>
+            <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '12px' }}>
+              <span style={{ fontSize: '13px', color: 'var(--text)' }}>Update channel</span>
+              <div style={{ minWidth: '180px' }}>
+                <CustomSelect
+                  value={updateSettings.channel}
+                  onChange={(value) => handleSetUpdateChannel(value)}
+                  options={[
+                    { value: 'stable', label: 'Stable' },
+                    { value: 'beta', label: 'Beta' },
+                  ]}
+                />
+              </div>
+            </div>
+
+            <ToggleSwitch
+              label="Check for updates in the background"
+              checked={updateSettings.autoCheck}
+              onChange={(checked) => handleSetBackgroundCheck({ enabled: checked })}
+            />
+
+            <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', gap: '12px' }}>
+              <span style={{ fontSize: '13px', color: 'var(--text)', opacity: updateSettings.autoCheck ? 1 : 0.5 }}>Check interval</span>
+              <div style={{ minWidth: '180px' }}>
+                <CustomSelect
+                  value={updateSettings.intervalHours}
+                  onChange={(value) => handleSetBackgroundCheck({ intervalHours: value })}
+                  options={UPDATE_INTERVAL_OPTIONS}
+                  disabled={!updateSettings.autoCheck}
+                />
+              </div>
+            </div>
+          </div>
         </div>
       </FormGroup>
     </div>
@@ -6,6 +6,9 @@ const WindowsIntegrationSection = ({
   contextMenuEnabled,
   handleToggleContextMenu,
   contextMenuLoading,
+  fileAssociations = {},
+  fileAssociationsLoading,
+  handleToggleFileAssociation,
   windowsIntegrationSectionRef,
   highlightWindowsIntegrationSection
 }) => {
@@ -68,6 +71,42 @@ const WindowsIntegrationSection = ({
         </div>
       </FormGroup>
 
+      <FormGroup
+        label="Open With Quartz"
+        description="List Quartz in the Explorer \"Open with\" menu for these file types"
+      >
+        <div style={{ background: 'rgba(255, 255, 255, 0.02)', border: '1px solid rgba(255, 255, 255, 0.06)', borderRadius: '8px', padding: '16px', display: 'flex', flexDirection: 'column', gap: '12px' }}>
+          {[
+            { key: 'bin', label: '.bin', detail: 'Opens in the Bin Editor' },
+            { key: 'py', label: '.py (ritobin)', detail: 'Opens the ritobin text in the Bin Editor' },
+            { key: 'wadClient', label: '.wad.client', detail: 'Opens in the WAD Explorer' },
+          ].map(({ key, label, detail }) => (
+            <div key={key} style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between' }}>
+              <div style={{ flex: 1 }}>
+                <div style={{ fontSize: '13px', color: 'var(--text)', fontWeight: '600', marginBottom: '4px' }}>
+                  {label}
+                </div>
+                <div style={{ fontSize: '12px', color: 'var(--text-2)', opacity: 0.7 }}>
+                  {detail}
+                </div>
+              </div>
+              <ToggleSwitch
+                label=""
+                checked={!!fileAssociations[key]}
+                onChange={(enabled) => handleToggleFileAssociation?.(key, enabled)}
+              />
+            </div>
+          ))}
+
+          {fileAssociationsLoading && (
+            <div style={{ padding: '8px', background: 'rgba(255, 255, 255, 0.05)', borderRadius: '4px', fontSize: '12px', color: 'var(--text-2)', textAlign: 'center' }}>
+              <RefreshCw size={14} style={{ animation: 'spin 1s linear infinite', marginRight: '6px' }} />
+              Updating registry...
+            </div>
+          )}
+        </div>
+      </FormGroup>
+
       <FormGroup label="About Windows Integration" description="How the context menu works">
         <div style={{ background: 'rgba(255, 255, 255, 0.02)', border: '1px solid rgba(255, 255, 255, 0.06)', borderRadius: '8px', padding: '16px', fontSize: '12px', color: 'var(--text-2)', lineHeight: '1.6' }}>
           <p style={{ margin: '0 0 8px 0' }}>
@@ -77,7 +116,7 @@ const WindowsIntegrationSection = ({
             <strong style={{ color: 'var(--accent)' }}>Privacy:</strong> Only modifies your user registry (HKCU). No admin rights required.
           </p>
           <p style={{ margin: '0' }}>
-            <strong style={{ color: 'var(--accent)' }}>Uninstall:</strong> Simply toggle off to remove all registry entries.
+            <strong style={{ color: 'var(--accent)' }}>Uninstall:</strong> Simply toggle off to remove all registry entries. "Open with" entries are removed per file type.
           </p>
         </div>
       </FormGroup>
@@ -6,6 +6,7 @@ const useUpdateSettings = () => {
   const [newVersion, setNewVersion] = useState('');
   const [updateProgress, setUpdateProgress] = useState({ percent: 0, transferred: 0, total: 0 });
   const [updateError, setUpdateError] = useState('');
+  const [updateSettings, setUpdateSettings] = useState({ channel: 'stable', autoCheck: true, intervalHours: 6 });
 
   useEffect(() => {
     if (!window.require) return undefined;
@@ -51,6 +52,11 @@ const useUpdateSettings = () => {
         if (versionResult.success) {
           setCurrentVersion(versionResult.version);
         }
+        const settingsResult = await ipcRenderer.invoke('update:get-settings');
+        if (settingsResult.success) {
+          const { channel, autoCheck, intervalHours } = settingsResult;
+          setUpdateSettings({ channel, autoCheck, intervalHours });
+        }
       } catch (error) {
         console.error('Error getting version:', error);
       }
@@ -121,6 +127,30 @@ const useUpdateSettings = () => {
     }
   }, []);
 
+  const applyUpdateSettingsResult = useCallback((result, fallbackError) => {
+    if (result.success) {
+      const { channel, autoCheck, intervalHours } = result;
+      setUpdateSettings({ channel, autoCheck, intervalHours });
+    } else {
+      setUpdateError(result.error || fallbackError);
+    }
+    return result.success;
+  }, []);
+
+  const handleSetUpdateChannel = useCallback(async (channel) => {
+    if (!window.require) return false;
+    const { ipcRenderer } = window.require('electron');
+    const result = await ipcRenderer.invoke('update:set-channel', channel);
+    return applyUpdateSettingsResult(result, 'Failed to change update channel');
+  }, [applyUpdateSettingsResult]);
+
+  const handleSetBackgroundCheck = useCallback(async (options) => {
+    if (!window.require) return false;
+    const { ipcRenderer } = window.require('electron');
+    const result = await ipcRenderer.invoke('update:set-background-check', options);
+    return applyUpdateSettingsResult(result, 'Failed to change background update checks');
+  }, [applyUpdateSettingsResult]);
+
   return {
     updateStatus,
     currentVersion,
@@ -129,7 +159,10 @@ const useUpdateSettings = () => {
     updateError,
     handleCheckForUpdates,
     handleDownloadUpdate,
-    handleInstallUpdate
+    handleInstallUpdate,
+    updateSettings,
+    handleSetUpdateChannel,
+    handleSetBackgroundCheck
   };
 };
 
@@ -3,6 +3,8 @@ import { useCallback, useEffect, useState } from 'react';
 const useWindowsIntegrationSettings = () => {
   const [contextMenuEnabled, setContextMenuEnabled] = useState(false);
   const [contextMenuLoading, setContextMenuLoading] = useState(false);
+  const [fileAssociations, setFileAssociations] = useState({ bin: false, py: false, wadClient: false });
+  const [fileAssociationsLoading, setFileAssociationsLoading] = useState(false);
 
   useEffect(() => {
     const checkContextMenuStatus = async () => {
@@ -17,7 +19,20 @@ const useWindowsIntegrationSettings = () => {
       }
     };
 
+    const checkFileAssociations = async () => {
+      if (!window.require) return;
+
+      try {
+        const { ipcRenderer } = window.require('electron');
+        const result = await ipcRenderer.invoke('fileAssociations:get');
+        if (result.success) setFileAssociations((prev) => ({ ...prev, ...result.associations }));
+      } catch (error) {
+        console.error('Error checking file associations:', error);
+      }
+    };
+
     checkContextMenuStatus();
+    checkFileAssociations();
   }, []);
 
   const handleToggleContextMenu = useCallback(async (enabled) => {
@@ -56,10 +71,34 @@ const useWindowsIntegrationSettings = () => {
     }
   }, []);
 
+  const handleToggleFileAssociation = useCallback(async (key, enabled) => {
+    if (!window.require) {
+      console.error('File associations require Electron');
+      return;
+    }
+
+    setFileAssociationsLoading(true);
+    try {
+      const { ipcRenderer } = window.require('electron');
+      const result = await ipcRenderer.invoke('fileAssociations:set', { [key]: enabled });
+      if (!result.success) {
+        console.error('Failed to update file association:', result.error);
+      }
+      setFileAssociations((prev) => ({ ...prev, ...(result.associations || {}) }));
+    } catch (error) {
+      console.error('Error toggling file association:', error);
+    } finally {
+      setFileAssociationsLoading(false);
+    }
+  }, []);
+
   return {
     contextMenuEnabled,
     contextMenuLoading,
-    handleToggleContextMenu
+    handleToggleContextMenu,
+    fileAssociations,
+    fileAssociationsLoading,
+    handleToggleFileAssociation
   };
 };
 
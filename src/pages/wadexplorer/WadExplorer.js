@@ -520,10 +520,7 @@ function FileDetailPanel({ selectedNode, hashPath, wadData }) {
     const dir = tempDirRef.current;
     if (!dir) return;
     tempDirRef.current = null;
-    try {
-      const fs = window.require?.('fs');
-      if (fs) fs.rm(dir, { recursive: true, force: true }, () => { });
-    } catch (_) { }
+    window.electronAPI?.temp?.release?.(dir).catch(() => { });
   }, []);
 
   // Clean up temp dir on unmount
@@ -668,12 +665,13 @@ function FileDetailPanel({ selectedNode, hashPath, wadData }) {
         .filter((f, idx, arr) => arr.findIndex((x) => x.path === f.path) === idx);
 
       const fs = window.require?.('fs');
-      const os = window.require?.('os');
       const path = window.require?.('path');
-      if (!fs || !os || !path) throw new Error('Node fs/path unavailable');
+      if (!fs || !path) throw new Error('Node fs/path unavailable');
 
       cleanupTempDir();
-      const rootDir = path.join(os.tmpdir(), 'quartz-wad-preview', String(Date.now()));
+      const temp = await window.electronAPI?.temp?.create?.('previews');
+      if (!temp?.success) throw new Error(temp?.error || 'Could not create preview temp folder');
+      const rootDir = temp.path;
       tempDirRef.current = rootDir;
       await fs.promises.mkdir(rootDir, { recursive: true });
 
@@ -1127,6 +1125,25 @@ export default function WadExplorer() {
     loadSingleWad(wadFile.path);
   }, [loadSingleWad, hashPath]);
 
+  // WADs opened from Explorer ("Open with Quartz") arrive as quartz-open-wad.
+  useEffect(() => {
+    const openWad = async (wadPath) => {
+      // The pending path is consumed once, whether it arrives on mount or by event.
+      if (!wadPath || window.__QUARTZ_PENDING_WAD_OPEN !== wadPath) return;
+      window.__QUARTZ_PENDING_WAD_OPEN = null;
+      if (hashPath) {
+        setIsPrimeLoading(true);
+        await window.electronAPI?.hashtable?.primeWad?.(hashPath)?.catch(() => { });
+        setIsPrimeLoading(false);
+      }
+      loadSingleWad(wadPath);
+    };
+    const handleOpenWad = (event) => openWad(event?.detail?.path);
+    window.addEventListener('quartz-open-wad', handleOpenWad);
+    openWad(window.__QUARTZ_PENDING_WAD_OPEN);
+    return () => window.removeEventListener('quartz-open-wad', handleOpenWad);
+  }, [loadSingleWad, hashPath]);
+
   const handleGamePathBlur = useCallback((e) => {
     const val = e.target.value.trim();
     if (val) {
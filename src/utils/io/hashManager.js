@@ -127,57 +127,23 @@ function isAutoSyncFresh(maxAgeMinutes = 30) {
 }
 
 /**
- * Download a file from URL
+ * Download a file from URL through the native downloader (retries, resume,
+ * proxy settings).
  * @param {string} url - URL to download from
  * @param {string} filePath - Local file path to save to
- * @param {Function} progressCallback - Optional progress callback
  * @returns {Promise<void>}
  */
-function downloadFile(url, filePath, progressCallback = null) {
-  return new Promise((resolve, reject) => {
-    const protocol = url.startsWith('https:') ? https : http;
-    const file = fs.createWriteStream(filePath);
-
-    protocol.get(url, (response) => {
-      if (response.statusCode === 301 || response.statusCode === 302) {
-        // Handle redirect
-        file.close();
-        fs.unlinkSync(filePath);
-        return downloadFile(response.headers.location, filePath, progressCallback)
-          .then(resolve)
-          .catch(reject);
-      }
-
-      if (response.statusCode !== 200) {
-        file.close();
-        fs.unlinkSync(filePath);
-        return reject(new Error(`HTTP ${response.statusCode}: ${response.statusMessage}`));
-      }
-
-      const totalSize = parseInt(response.headers['content-length'], 10);
-      let downloadedSize = 0;
-
-      response.on('data', (chunk) => {
-        downloadedSize += chunk.length;
-        if (progressCallback && totalSize) {
-          progressCallback(downloadedSize, totalSize, url);
-        }
-      });
-
-      response.pipe(file);
-
-      file.on('finish', () => {
-        file.close();
-        resolve(response.headers || {});
-      });
-    }).on('error', (err) => {
-      file.close();
-      if (fs.existsSync(filePath)) {
-        fs.unlinkSync(filePath);
-      }
-      reject(err);
-    });
-  });
+async function downloadFile(url, filePath) {
+  // Loaded lazily: this module is also required before the addon is needed.
+  const { tryLoadNativeWadIndexer } = require('../../main/ipc/channels/wadBumpath');
+  const nativeAddon = tryLoadNativeWadIndexer();
+  if (!nativeAddon || typeof nativeAddon.downloadFileAsync !== 'function') {
+    throw new Error('Native addon unavailable — rebuild wad_indexer');
+  }
+  const result = await nativeAddon.downloadFileAsync(url, filePath);
+  if (!result?.success) {
+    throw new Error(result?.error || `Failed to download ${url}`);
+  }
 }
 
 function readHashesMeta(hashDir) {
@@ -306,13 +272,13 @@ async function downloadHashes(progressCallback = null) {
           progressCallback(`Downloading ${filename}...`, i + 1, HASH_FILES.length + 2);
         }
 
-        const headers = await downloadFile(url, filePath);
+        await downloadFile(url, filePath);
         const after = localFileState(filePath);
         downloaded.push(filename);
         meta[filename] = {
           url,
-          etag: headers?.etag || remote?.etag || '',
-          lastModified: headers?.['last-modified'] || remote?.lastModified || '',
+          etag: remote?.etag || '',
+          lastModified: remote?.lastModified || '',
           lastCheckedAt: new Date().toISOString(),
           localMtimeMs: after?.mtimeMs || 0,
           localSize: after?.size || 0,
@@ -374,14 +340,14 @@ async function downloadHashes(progressCallback = null) {
         progressCallback('Downloading hashes.game.txt (part 1/2)...', HASH_FILES.length + 1, HASH_FILES.length + 2);
       }
       // Download part 0
-      const part0Headers = await downloadFile(GAME_HASH_PART_URLS[0], tempPart0);
+      await downloadFile(GAME_HASH_PART_URLS[0], tempPart0);
 
       if (progressCallback) {
         progressCallback('Downloading hashes.game.txt (part 2/2)...', HASH_FILES.length + 2, HASH_FILES.length + 2);
       }
 
       // Download part 1
-      const part1Headers = await downloadFile(GAME_HASH_PART_URLS[1], tempPart1);
+      await downloadFile(GAME_HASH_PART_URLS[1], tempPart1);
 
       // Combine parts
       const part0Data = fs.readFileSync(tempPart0);
@@ -400,12 +366,12 @@ async function downloadHashes(progressCallback = null) {
         localMtimeMs: gameAfter?.mtimeMs || 0,
         localSize: gameAfter?.size || 0,
         part0: {
-          etag: part0Headers?.etag || p0Remote?.etag || '',
-          lastModified: part0Headers?.['last-modified'] || p0Remote?.lastModified || '',
+          etag: p0Remote?.etag || '',
+          lastModified: p0Remote?.lastModified || '',
         },
         part1: {
-          etag: part1Headers?.etag || p1Remote?.etag || '',
-          lastModified: part1Headers?.['last-modified'] || p1Remote?.lastModified || '',
+          etag: p1Remote?.etag || '',
+          lastModified: p1Remote?.lastModified || '',
         },
       };
     } catch (error) {